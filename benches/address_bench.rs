@@ -0,0 +1,155 @@
+#![feature(test)]
+extern crate rosc;
+extern crate test;
+
+use self::test::Bencher;
+
+use rosc::address::{Matcher, MatcherCache, OscAddress, PatternSet};
+
+const PATTERNS: usize = 50;
+const MESSAGES: usize = 100_000;
+
+/// Builds a stream of `(pattern, address)` pairs drawn from a fixed set of distinct patterns,
+/// similar to what a server receiving patterns from several controllers would see.
+fn message_stream() -> Vec<(String, OscAddress)> {
+    (0..MESSAGES)
+        .map(|i| {
+            let n = (i * 7) % PATTERNS;
+            let pattern = format!("/mixer/channel{}/*/{{gain,pan}}", n);
+            let address = OscAddress::new(format!("/mixer/channel{}/strip/gain", n)).unwrap();
+            (pattern, address)
+        })
+        .collect()
+}
+
+#[bench]
+fn bench_match_compile_every_time(b: &mut Bencher) {
+    let messages = message_stream();
+    b.iter(|| {
+        messages
+            .iter()
+            .filter(|(pattern, address)| Matcher::new(pattern).unwrap().match_address(address))
+            .count()
+    });
+}
+
+#[bench]
+fn bench_match_matcher_cache(b: &mut Bencher) {
+    let messages = message_stream();
+    let mut cache = MatcherCache::new(PATTERNS);
+    b.iter(|| {
+        messages
+            .iter()
+            .filter(|(pattern, address)| {
+                cache
+                    .get_or_compile(pattern)
+                    .unwrap()
+                    .match_address(address)
+            })
+            .count()
+    });
+}
+
+const LONG_ADDRESS: &str = "/show/scenes/act2/cue17/fixtures/moving/spot3/intensity";
+
+fn bench_long_address(b: &mut Bencher, pattern: &str) {
+    let matcher = Matcher::new(pattern).unwrap();
+    let address = OscAddress::new(LONG_ADDRESS.to_string()).unwrap();
+    // Differs from the address only in its last character
+    let other = OscAddress::new(LONG_ADDRESS.replace("intensity", "intensitx")).unwrap();
+    b.iter(|| {
+        (0..100)
+            .filter(|_| {
+                matcher.match_address(test::black_box(&address))
+                    && !matcher.match_address(test::black_box(&other))
+            })
+            .count()
+    });
+}
+
+#[bench]
+fn bench_match_long_string_eq_baseline(b: &mut Bencher) {
+    let pattern = LONG_ADDRESS.to_string();
+    let address = LONG_ADDRESS.to_string();
+    let other = LONG_ADDRESS.replace("intensity", "intensitx");
+    b.iter(|| {
+        (0..100)
+            .filter(|_| {
+                *test::black_box(&address) == pattern && *test::black_box(&other) != pattern
+            })
+            .count()
+    });
+}
+
+#[bench]
+fn bench_match_long_no_wildcards(b: &mut Bencher) {
+    bench_long_address(b, LONG_ADDRESS);
+}
+
+#[bench]
+fn bench_match_long_one_wildcard(b: &mut Bencher) {
+    bench_long_address(b, "/show/scenes/act2/cue17/fixtures/moving/*/intensity");
+}
+
+#[bench]
+fn bench_match_long_many_wildcards(b: &mut Bencher) {
+    bench_long_address(
+        b,
+        "/show/*/act?/cue[0-9]*/fixtures/{moving,static}/*/intensity",
+    );
+}
+
+/// 500 patterns, as registered by a server for 50 mixer channels with 10 parameters each, half
+/// of them with wildcards, and a few catch-all patterns.
+fn dispatch_patterns() -> Vec<String> {
+    let mut patterns: Vec<String> = (0..490)
+        .map(|i| {
+            let (channel, param) = (i / 10, i % 10);
+            if param % 2 == 0 {
+                format!("/mixer/channel{}/param{}", channel, param)
+            } else {
+                format!("/mixer/channel{}/*/param{}", channel, param)
+            }
+        })
+        .collect();
+    patterns.extend((0..10).map(|i| format!("/*/channel{}/param?", i)));
+    patterns
+}
+
+fn dispatch_addresses() -> Vec<OscAddress> {
+    (0..100)
+        .map(|i| OscAddress::new(format!("/mixer/channel{}/param{}", i % 50, i % 10)).unwrap())
+        .collect()
+}
+
+#[bench]
+fn bench_dispatch_500_matchers(b: &mut Bencher) {
+    let matchers: Vec<Matcher> = dispatch_patterns()
+        .iter()
+        .map(|pattern| Matcher::new(pattern).unwrap())
+        .collect();
+    let addresses = dispatch_addresses();
+    b.iter(|| {
+        addresses
+            .iter()
+            .map(|address| {
+                matchers
+                    .iter()
+                    .filter(|matcher| matcher.match_address(test::black_box(address)))
+                    .count()
+            })
+            .sum::<usize>()
+    });
+}
+
+#[bench]
+fn bench_dispatch_500_pattern_set(b: &mut Bencher) {
+    let set = PatternSet::from_iter(dispatch_patterns()).unwrap();
+    let addresses = dispatch_addresses();
+    b.iter(|| {
+        addresses
+            .iter()
+            .map(|address| set.matches(test::black_box(address)).count())
+            .sum::<usize>()
+    });
+}
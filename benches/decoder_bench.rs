@@ -15,3 +15,38 @@ fn bench_decode(b: &mut Bencher) {
     ];
     b.iter(|| rosc::decoder::decode_udp(&raw_msg).unwrap());
 }
+
+fn wide_message() -> Vec<u8> {
+    let args = (0..100)
+        .map(|i| {
+            if i % 2 == 0 {
+                rosc::OscType::Float(i as f32)
+            } else {
+                rosc::OscType::String(format!("argument {}", i))
+            }
+        })
+        .collect();
+    rosc::encoder::encode(&rosc::OscPacket::Message(rosc::OscMessage {
+        addr: "/wide".to_string(),
+        args,
+    }))
+    .unwrap()
+}
+
+#[bench]
+fn bench_decode_sparse_full(b: &mut Bencher) {
+    let raw_msg = wide_message();
+    b.iter(|| match rosc::decoder::decode_udp(&raw_msg).unwrap().1 {
+        rosc::OscPacket::Message(msg) => (msg.args[10].clone(), msg.args[90].clone()),
+        rosc::OscPacket::Bundle(_) => unreachable!(),
+    });
+}
+
+#[bench]
+fn bench_decode_sparse_view(b: &mut Bencher) {
+    let raw_msg = wide_message();
+    b.iter(|| {
+        let view = rosc::decoder::MessageView::new(&raw_msg).unwrap();
+        (view.arg(10).unwrap(), view.arg(90).unwrap())
+    });
+}
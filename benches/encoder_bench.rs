@@ -0,0 +1,109 @@
+#![feature(test)]
+extern crate rosc;
+extern crate test;
+
+use rosc::{OscBundle, OscMessage, OscPacket, OscType};
+use std::time::{Duration, Instant};
+
+use self::test::Bencher;
+
+fn bundle() -> OscPacket {
+    OscPacket::Bundle(OscBundle {
+        timetag: (0, 1).into(),
+        content: (0..1000)
+            .map(|i| {
+                OscPacket::Message(OscMessage {
+                    addr: "/OSCILLATORS/OSC2/ADSR/x".to_string(),
+                    args: vec![OscType::Float(0.1234567), OscType::Int(i)],
+                })
+            })
+            .collect(),
+    })
+}
+
+#[bench]
+fn bench_encode_bundle(b: &mut Bencher) {
+    let packet = bundle();
+    b.iter(|| rosc::encoder::encode(&packet).unwrap());
+}
+
+#[bench]
+fn bench_encode_bundle_with_deadline(b: &mut Bencher) {
+    let packet = bundle();
+    let mut out = Vec::new();
+    b.iter(|| {
+        out.clear();
+        let deadline = Instant::now() + Duration::from_secs(1);
+        rosc::encoder::encode_with_deadline(&packet, &mut out, || Instant::now() < deadline)
+            .unwrap()
+    });
+}
+
+#[bench]
+fn bench_encode_bundle_with_capacity_hint(b: &mut Bencher) {
+    let packet = bundle();
+    let size = rosc::encoder::encode(&packet).unwrap().len();
+    b.iter(|| rosc::encoder::encode_with_capacity(&packet, size).unwrap());
+}
+
+#[bench]
+fn bench_encode_bundle_without_capacity_hint(b: &mut Bencher) {
+    let packet = bundle();
+    b.iter(|| rosc::encoder::encode_with_capacity(&packet, 0).unwrap());
+}
+
+#[bench]
+fn bench_encode_numeric_arrays(b: &mut Bencher) {
+    let array = |content: Vec<OscType>| OscType::Array(rosc::OscArray { content });
+    let packet = OscPacket::Message(OscMessage {
+        addr: "/spectrum".to_string(),
+        args: vec![
+            array((0..1024).map(|i| OscType::Float(i as f32)).collect()),
+            array((0..256).map(OscType::Int).collect()),
+            array((0..256).map(|i| OscType::Double(i as f64)).collect()),
+        ],
+    });
+    b.iter(|| rosc::encoder::encode(&packet).unwrap());
+}
+
+fn fader_values(i: i32) -> [OscType; 3] {
+    [
+        OscType::Int(i % 16),
+        OscType::Float(i as f32 / 1000.0),
+        OscType::String("main".to_string()),
+    ]
+}
+
+#[bench]
+fn bench_send_loop_full_encode(b: &mut Bencher) {
+    let mut out = Vec::new();
+    b.iter(|| {
+        for i in 0..1000 {
+            out.clear();
+            let packet = OscPacket::Message(OscMessage {
+                addr: "/mixer/channel/fader".to_string(),
+                args: fader_values(i).to_vec(),
+            });
+            rosc::encoder::encode_into(&packet, &mut out).unwrap();
+        }
+    });
+}
+
+#[bench]
+fn bench_send_loop_prepared(b: &mut Bencher) {
+    use rosc::encoder::PreparedMessage;
+    use rosc::TypeTag;
+
+    let prepared = PreparedMessage::new(
+        "/mixer/channel/fader",
+        &[TypeTag::Int, TypeTag::Float, TypeTag::String],
+    )
+    .unwrap();
+    let mut out = Vec::new();
+    b.iter(|| {
+        for i in 0..1000 {
+            out.clear();
+            prepared.encode_values(&fader_values(i), &mut out).unwrap();
+        }
+    });
+}
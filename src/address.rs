@@ -1,32 +1,41 @@
 use crate::errors::OscError;
+use crate::types::{OscMessage, SpecProfile};
 
 use alloc::string::{String, ToString};
 use alloc::vec::Vec;
 use core::fmt::{Display, Formatter};
 use nom::branch::alt;
-use nom::bytes::complete::{is_a, is_not, tag, take, take_while1, take_while_m_n};
+use nom::bytes::complete::{is_a, tag, take_while1};
 use nom::character::complete::{char, satisfy};
 use nom::combinator::{all_consuming, complete, opt, recognize, verify};
-use nom::error::{ErrorKind, ParseError};
 use nom::multi::{many1, separated_list1};
 use nom::sequence::{delimited, pair, separated_pair};
 use nom::{IResult, Parser};
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
+use std::convert::TryFrom;
 use std::iter::FromIterator;
+use std::ops::Deref;
+use std::sync::Arc;
 
 /// A valid OSC method address.
 ///
 /// A valid OSC address begins with a `/` and contains at least a method name, e.g. `/tempo`.
 /// A plain address must not include any of the following characters `#*,/?[]{}`, since they're reserved for OSC address patterns.
-#[derive(Clone, Debug)]
-pub struct OscAddress(String);
+///
+/// The address is validated once on construction and stored in an `Arc<str>`, so it can be cloned
+/// cheaply and passed around without being checked again.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct OscAddress(Arc<str>);
 
 impl OscAddress {
     pub fn new(address: String) -> Result<Self, OscError> {
-        match verify_address(&address) {
-            Ok(_) => Ok(OscAddress(address)),
-            Err(e) => Err(e),
-        }
+        verify_address(&address)?;
+        Ok(OscAddress(address.into()))
+    }
+
+    /// Returns the address as a string slice.
+    pub fn as_str(&self) -> &str {
+        &self.0
     }
 }
 
@@ -36,12 +45,117 @@ impl Display for OscAddress {
     }
 }
 
+/// A valid OSC address pattern, see [`Matcher::new`] for the supported syntax.
+///
+/// Like [`OscAddress`], the pattern is validated once on construction and is cheap to clone.
+/// Since it dereferences to `str`, it can be passed to [`Matcher::new`] directly.
+///
+/// # Examples
+///
+/// ```
+/// use rosc::address::{Matcher, OscPattern};
+/// use std::convert::TryFrom;
+///
+/// let pattern = OscPattern::try_from("/oscillator/[0-9]/frequency").unwrap();
+/// let matcher = Matcher::new(&pattern).unwrap();
+/// assert_eq!(matcher.pattern, &*pattern);
+///
+/// OscPattern::try_from("oscillator").expect_err("pattern does not start with a slash");
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct OscPattern(Arc<str>);
+
+impl OscPattern {
+    pub fn new(pattern: String) -> Result<Self, OscError> {
+        verify_address_pattern(&pattern)?;
+        Ok(OscPattern(pattern.into()))
+    }
+
+    /// Returns the pattern as a string slice.
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+impl Display for OscPattern {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+/// Implements the conversions shared by the validated address types.
+macro_rules! validated_str_impl {
+    ($($name:ident),*) => {
+        $(
+        impl TryFrom<String> for $name {
+            type Error = OscError;
+
+            fn try_from(s: String) -> Result<Self, OscError> {
+                $name::new(s)
+            }
+        }
+
+        impl TryFrom<&str> for $name {
+            type Error = OscError;
+
+            fn try_from(s: &str) -> Result<Self, OscError> {
+                $name::new(s.to_string())
+            }
+        }
+
+        impl Deref for $name {
+            type Target = str;
+
+            fn deref(&self) -> &str {
+                &self.0
+            }
+        }
+
+        impl AsRef<str> for $name {
+            fn as_ref(&self) -> &str {
+                &self.0
+            }
+        }
+
+        impl From<$name> for OscMessage {
+            fn from(address: $name) -> OscMessage {
+                OscMessage::from(address.as_str())
+            }
+        }
+        )*
+    }
+}
+validated_str_impl!(OscAddress, OscPattern);
+
+/// Which version of the OSC address pattern syntax a [`Matcher`] accepts.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum PatternSyntax {
+    /// The syntax of OSC 1.0, which is what [`Matcher::new`] accepts.
+    #[default]
+    Osc10,
+    /// The syntax of OSC 1.1, which adds the path-traversing wildcard `//`. It matches any number
+    /// of address parts, e.g. `//volume` matches `/volume` as well as `/mixer/1/volume`.
+    Osc11,
+}
+
+impl PatternSyntax {
+    /// Returns the syntax allowed by `profile`. Only [`SpecProfile::Osc11`] allows `//`.
+    pub fn for_profile(profile: SpecProfile) -> Self {
+        match profile {
+            SpecProfile::Osc11 => PatternSyntax::Osc11,
+            SpecProfile::Osc10Strict | SpecProfile::Permissive => PatternSyntax::Osc10,
+        }
+    }
+}
+
 /// With a Matcher OSC method addresses can be [matched](Matcher::match_address) against an OSC address pattern.
 /// Refer to the OSC specification for details about OSC address spaces: <http://opensoundcontrol.org/spec-1_0.html#osc-address-spaces-and-osc-addresses>
 #[derive(Clone, Debug)]
 pub struct Matcher {
     pub pattern: String,
     pattern_parts: Vec<AddressPatternComponent>,
+    // The pattern has no wildcards, classes or choices, so it only matches itself
+    literal: bool,
 }
 
 impl Matcher {
@@ -70,14 +184,49 @@ impl Matcher {
     /// Matcher::new("").expect_err("address does not start with a slash");
     /// ```
     pub fn new(pattern: &str) -> Result<Self, OscError> {
-        verify_address_pattern(pattern)?;
-        let mut match_fn = all_consuming(many1(map_address_pattern_component));
-        let (_, pattern_parts) =
-            match_fn(pattern).map_err(|err| OscError::BadAddressPattern(err.to_string()))?;
+        Self::with_syntax(pattern, PatternSyntax::Osc10)
+    }
+
+    /// Like [`new`](Matcher::new), but accepting address patterns of the given syntax.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rosc::address::{Matcher, OscAddress, PatternSyntax};
+    ///
+    /// let matcher = Matcher::with_syntax("//volume", PatternSyntax::Osc11).unwrap();
+    /// assert!(matcher.match_address(&OscAddress::new(String::from("/mixer/1/volume")).unwrap()));
+    /// Matcher::with_syntax("//volume", PatternSyntax::Osc10).expect_err("'//' is OSC 1.1");
+    /// ```
+    pub fn with_syntax(pattern: &str, syntax: PatternSyntax) -> Result<Self, OscError> {
+        let pattern_parts = match syntax {
+            PatternSyntax::Osc10 => {
+                verify_address_pattern(pattern)?;
+                parse_pattern_components(pattern)?
+            }
+            PatternSyntax::Osc11 => {
+                // Each `//` stands for a slash, followed by any number of address parts. The
+                // pattern without them must be a valid OSC 1.0 pattern.
+                verify_address_pattern(&pattern.replace("//", "/"))?;
+                let mut parts = Vec::new();
+                for (i, piece) in pattern.split("//").enumerate() {
+                    if i > 0 {
+                        parts.push(AddressPatternComponent::PathTraversal);
+                    }
+                    if !piece.is_empty() {
+                        parts.extend(parse_pattern_components(piece)?);
+                    }
+                }
+                parts
+            }
+        };
 
         Ok(Matcher {
             pattern: pattern.into(),
-            pattern_parts,
+            literal: pattern_parts
+                .iter()
+                .all(|part| matches!(part, AddressPatternComponent::Tag(_))),
+            pattern_parts: coalesce_tags(pattern_parts),
         })
     }
 
@@ -94,35 +243,380 @@ impl Matcher {
     /// assert!(matcher.match_address(&OscAddress::new(String::from("/oscillator/8/phase")).unwrap()));
     /// assert_eq!(matcher.match_address(&OscAddress::new(String::from("/oscillator/4/detune")).unwrap()), false);
     /// ```
+    #[inline]
     pub fn match_address(&self, address: &OscAddress) -> bool {
-        // Trivial case
-        if address.0 == self.pattern {
-            return true;
+        // Trivial case, which is the only one for patterns without any wildcards
+        if self.literal {
+            return *address.0 == self.pattern;
         }
+        self.match_parts(address)
+    }
 
-        let mut remainder = address.0.as_str();
-        let mut iter = self.pattern_parts.iter().peekable();
-
-        while let Some(part) = iter.next() {
-            // Match the the address component by component
-            let result = match part {
-                AddressPatternComponent::Tag(s) => match_literally(remainder, s),
-                AddressPatternComponent::WildcardSingle => match_wildcard_single(remainder),
-                AddressPatternComponent::Wildcard(l) => {
-                    match_wildcard(remainder, *l, iter.peek().copied())
+    /// Matches the address by tracking every position in it that the pattern components seen so
+    /// far can end at, like an NFA. Each component is applied to all positions in a single pass,
+    /// so matching takes time linear in the address length for every component, without any
+    /// backtracking, no matter how the pattern is built.
+    fn match_parts(&self, address: &OscAddress) -> bool {
+        // Valid addresses only contain ASCII characters, so bytes and characters coincide
+        let address = address.as_bytes();
+        // The reachable positions, in ascending order and without duplicates
+        let mut reachable = Vec::with_capacity(address.len() + 1);
+        let mut next = Vec::with_capacity(address.len() + 1);
+        reachable.push(0);
+
+        for part in &self.pattern_parts {
+            next.clear();
+            match part {
+                AddressPatternComponent::Tag(s) => {
+                    let s = s.as_bytes();
+                    next.extend(
+                        reachable
+                            .iter()
+                            .filter(|&&pos| address[pos..].starts_with(s))
+                            .map(|pos| pos + s.len()),
+                    );
+                }
+                AddressPatternComponent::Choice(choices) => {
+                    for choice in choices.iter().map(|choice| choice.as_bytes()) {
+                        next.extend(
+                            reachable
+                                .iter()
+                                .filter(|&&pos| address[pos..].starts_with(choice))
+                                .map(|pos| pos + choice.len()),
+                        );
+                    }
+                    if next.len() > 1 {
+                        next.sort_unstable();
+                        next.dedup();
+                    }
                 }
-                AddressPatternComponent::CharacterClass(cc) => match_character_class(remainder, cc),
-                AddressPatternComponent::Choice(s) => match_choice(remainder, s),
-            };
-
-            remainder = match result {
-                Ok((i, _)) => i,
-                Err(_) => return false, // Component didn't match, goodbye
-            };
+                AddressPatternComponent::WildcardSingle => {
+                    next.extend(
+                        reachable
+                            .iter()
+                            .filter(|&&pos| pos < address.len() && address[pos] != b'/')
+                            .map(|pos| pos + 1),
+                    );
+                }
+                AddressPatternComponent::Wildcard(minimum_length) => {
+                    // Any number of characters up to the end of the address part. The ranges of
+                    // later positions start later, so only their new positions need to be added.
+                    let mut part_end = None;
+                    for &pos in &reachable {
+                        let part_end = match part_end {
+                            Some(end) if end >= pos => end,
+                            _ => *part_end.insert(
+                                address[pos..]
+                                    .iter()
+                                    .position(|&b| b == b'/')
+                                    .map_or(address.len(), |len| pos + len),
+                            ),
+                        };
+                        let start = match next.last() {
+                            Some(&last) => (pos + minimum_length).max(last + 1),
+                            None => pos + minimum_length,
+                        };
+                        next.extend(start..=part_end);
+                    }
+                }
+                AddressPatternComponent::PathTraversal => {
+                    // A slash, followed by any number of address parts, so every position
+                    // following a slash after the first reachable slash
+                    if let Some(&pos) = reachable
+                        .iter()
+                        .find(|&&pos| address.get(pos) == Some(&b'/'))
+                    {
+                        next.extend(
+                            (pos + 1..=address.len()).filter(|&next| address[next - 1] == b'/'),
+                        );
+                    }
+                }
+                AddressPatternComponent::CharacterClass(cc) => {
+                    // One or more characters of the class. Like for wildcards, the runs of later
+                    // positions overlap those of earlier ones, so scanning resumes after them.
+                    for &pos in &reachable {
+                        let mut end = match next.last() {
+                            Some(&last) if last > pos => last,
+                            _ => pos,
+                        };
+                        while end < address.len() && cc.contains(address[end]) {
+                            end += 1;
+                            next.push(end);
+                        }
+                    }
+                }
+            }
+
+            core::mem::swap(&mut reachable, &mut next);
+            if reachable.is_empty() {
+                return false;
+            }
         }
 
         // Address is only matched if it was consumed entirely
-        remainder.is_empty()
+        reachable.last() == Some(&address.len())
+    }
+}
+
+/// A size-bounded cache of compiled [`Matcher`]s, keyed by their address pattern.
+///
+/// Compiling an address pattern is expensive, so applications that receive patterns at runtime
+/// (e.g. in the address of incoming messages) should look them up here instead of calling
+/// [`Matcher::new`] for every message. Once the cache is full, the least recently used matcher is
+/// evicted to make room for a new one.
+///
+/// Looking up a pattern that is already cached does not allocate. Apart from hashing the pattern
+/// and compiling it on a miss, lookups and evictions take constant time. The cache requires
+/// `&mut self` for lookups, so it can be shared between threads by wrapping it in a `Mutex`.
+///
+/// # Examples
+///
+/// ```
+/// use rosc::address::{MatcherCache, OscAddress};
+///
+/// let mut cache = MatcherCache::new(64);
+/// let address = OscAddress::new(String::from("/oscillator/1/frequency")).unwrap();
+///
+/// let matcher = cache.get_or_compile("/oscillator/[0-9]/*").unwrap();
+/// assert!(matcher.match_address(&address));
+/// assert_eq!(cache.len(), 1);
+/// ```
+#[derive(Clone, Debug)]
+pub struct MatcherCache {
+    capacity: usize,
+    index: HashMap<String, usize>,
+    entries: Vec<CacheEntry>,
+    // Slots of the most and least recently used entries, which are linked in order of use
+    newest: usize,
+    oldest: usize,
+}
+
+#[derive(Clone, Debug)]
+struct CacheEntry {
+    matcher: Matcher,
+    newer: usize,
+    older: usize,
+}
+
+/// Marks the end of the list of cache entries.
+const NO_SLOT: usize = usize::MAX;
+
+impl MatcherCache {
+    /// Creates an empty cache holding at most `capacity` compiled matchers.
+    ///
+    /// A `capacity` of zero is treated as one, since the cache needs to hold on to the matcher it
+    /// returns.
+    pub fn new(capacity: usize) -> Self {
+        let capacity = capacity.max(1);
+        MatcherCache {
+            capacity,
+            index: HashMap::with_capacity(capacity),
+            entries: Vec::with_capacity(capacity),
+            newest: NO_SLOT,
+            oldest: NO_SLOT,
+        }
+    }
+
+    /// Returns the compiled matcher for `pattern`, compiling and caching it first if necessary.
+    ///
+    /// An error is returned if `pattern` is not a valid address pattern. Invalid patterns are
+    /// not cached.
+    pub fn get_or_compile(&mut self, pattern: &str) -> Result<&Matcher, OscError> {
+        let slot = match self.index.get(pattern) {
+            Some(&slot) => {
+                self.unlink(slot);
+                slot
+            }
+            None => self.insert(Matcher::new(pattern)?),
+        };
+
+        self.link_newest(slot);
+        Ok(&self.entries[slot].matcher)
+    }
+
+    /// Returns the maximum number of matchers held by this cache.
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// Returns the number of matchers currently held by this cache.
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Returns `true` if a compiled matcher for `pattern` is currently cached.
+    pub fn contains(&self, pattern: &str) -> bool {
+        self.index.contains_key(pattern)
+    }
+
+    /// Returns `true` if the cache holds no matchers.
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Removes all cached matchers.
+    pub fn clear(&mut self) {
+        self.index.clear();
+        self.entries.clear();
+        self.newest = NO_SLOT;
+        self.oldest = NO_SLOT;
+    }
+
+    /// Stores a freshly compiled matcher, evicting the least recently used one if the cache is
+    /// full, and returns the slot it was stored in. The slot is not linked into the order of use.
+    fn insert(&mut self, matcher: Matcher) -> usize {
+        let entry = CacheEntry {
+            matcher,
+            newer: NO_SLOT,
+            older: NO_SLOT,
+        };
+
+        let slot = if self.entries.len() < self.capacity {
+            self.entries.push(entry);
+            self.entries.len() - 1
+        } else {
+            let slot = self.oldest;
+            self.unlink(slot);
+            let evicted = core::mem::replace(&mut self.entries[slot], entry);
+            self.index.remove(&evicted.matcher.pattern);
+            slot
+        };
+
+        self.index
+            .insert(self.entries[slot].matcher.pattern.clone(), slot);
+        slot
+    }
+
+    /// Removes `slot` from the order of use.
+    fn unlink(&mut self, slot: usize) {
+        let CacheEntry { newer, older, .. } = self.entries[slot];
+        match newer {
+            NO_SLOT => self.newest = older,
+            newer => self.entries[newer].older = older,
+        }
+        match older {
+            NO_SLOT => self.oldest = newer,
+            older => self.entries[older].newer = newer,
+        }
+    }
+
+    /// Links the unlinked `slot` in as the most recently used entry.
+    fn link_newest(&mut self, slot: usize) {
+        self.entries[slot].newer = NO_SLOT;
+        self.entries[slot].older = self.newest;
+        match self.newest {
+            NO_SLOT => self.oldest = slot,
+            newest => self.entries[newest].newer = slot,
+        }
+        self.newest = slot;
+    }
+}
+
+/// A set of address patterns compiled up front, for finding all patterns that match an address.
+///
+/// Patterns are grouped by their literal prefix, the path components before the first one
+/// containing a wildcard, class or choice. An address is then only matched against the patterns
+/// whose literal prefix it starts with, so dispatching to hundreds of patterns doesn't cost
+/// hundreds of matches.
+///
+/// # Examples
+///
+/// ```
+/// use rosc::address::{OscAddress, PatternSet};
+///
+/// let set = PatternSet::from_iter(&["/mixer/*/gain", "/mixer/1/{gain,pan}", "/fx/*"]).unwrap();
+/// let address = OscAddress::new(String::from("/mixer/1/gain")).unwrap();
+/// assert_eq!(set.matches(&address).collect::<Vec<_>>(), vec![0, 1]);
+///
+/// let errors = PatternSet::from_iter(&["/ok", "bad", "/also/[bad"]).unwrap_err();
+/// assert_eq!(errors.iter().map(|(i, _)| *i).collect::<Vec<_>>(), vec![1, 2]);
+/// ```
+#[derive(Clone, Debug)]
+pub struct PatternSet {
+    matchers: Vec<Matcher>,
+    root: PrefixNode,
+}
+
+/// A node of the trie of literal prefixes, keyed by path component.
+#[derive(Clone, Debug, Default)]
+struct PrefixNode {
+    children: HashMap<String, PrefixNode>,
+    // The indices of the patterns whose literal prefix ends at this node
+    patterns: Vec<usize>,
+}
+
+impl PatternSet {
+    /// Compiles all `patterns`, which are identified by their index in the iteration order.
+    ///
+    /// If any pattern is invalid, the index and error of every invalid pattern is returned
+    /// instead.
+    pub fn from_iter<I>(patterns: I) -> Result<Self, Vec<(usize, OscError)>>
+    where
+        I: IntoIterator,
+        I::Item: AsRef<str>,
+    {
+        let mut matchers = Vec::new();
+        let mut errors = Vec::new();
+        for (i, pattern) in patterns.into_iter().enumerate() {
+            match Matcher::new(pattern.as_ref()) {
+                Ok(matcher) => matchers.push(matcher),
+                Err(err) => errors.push((i, err)),
+            }
+        }
+        if !errors.is_empty() {
+            return Err(errors);
+        }
+
+        let mut root = PrefixNode::default();
+        for (i, matcher) in matchers.iter().enumerate() {
+            let parts: Vec<&str> = matcher.pattern[1..].split('/').collect();
+            // The last component is never part of the prefix, so that each pattern is checked
+            // against addresses of any length
+            let mut node = &mut root;
+            for part in parts[..parts.len() - 1]
+                .iter()
+                .take_while(|part| !part.contains(|c: char| "?*[]{}".contains(c)))
+            {
+                node = node.children.entry(part.to_string()).or_default();
+            }
+            node.patterns.push(i);
+        }
+
+        Ok(PatternSet { matchers, root })
+    }
+
+    /// Returns the indices of all patterns matching `address`, in ascending order.
+    pub fn matches(&self, address: &OscAddress) -> impl Iterator<Item = usize> {
+        let mut matching = Vec::new();
+        let mut node = Some(&self.root);
+        let mut parts = address[1..].split('/');
+        while let Some(current) = node {
+            matching.extend(
+                current
+                    .patterns
+                    .iter()
+                    .copied()
+                    .filter(|&i| self.matchers[i].match_address(address)),
+            );
+            node = parts.next().and_then(|part| current.children.get(part));
+        }
+        matching.sort_unstable();
+        matching.into_iter()
+    }
+
+    /// Returns the number of patterns in the set.
+    pub fn len(&self) -> usize {
+        self.matchers.len()
+    }
+
+    /// Returns `true` if the set holds no patterns.
+    pub fn is_empty(&self) -> bool {
+        self.matchers.is_empty()
+    }
+
+    /// Returns the compiled matcher of the pattern with the given index.
+    pub fn get(&self, index: usize) -> Option<&Matcher> {
+        self.matchers.get(index)
     }
 }
 
@@ -240,6 +734,18 @@ impl CharacterClass {
             }
         }
     }
+
+    /// Returns `true` if the address character `c` is a member of this class. Negated classes
+    /// never contain `/`.
+    fn contains(&self, c: u8) -> bool {
+        // All characters of a class are address characters, and thereby ASCII
+        let listed = self.characters.as_bytes().contains(&c);
+        if self.negated {
+            c != b'/' && !listed
+        } else {
+            listed
+        }
+    }
 }
 
 #[derive(Clone, Debug)]
@@ -249,6 +755,16 @@ enum AddressPatternComponent {
     WildcardSingle,
     CharacterClass(CharacterClass),
     Choice(Vec<String>),
+    // The OSC 1.1 `//`, see `PatternSyntax::Osc11`
+    PathTraversal,
+}
+
+/// Parses a pattern, or a piece of one between `//`, into its components.
+fn parse_pattern_components(pattern: &str) -> Result<Vec<AddressPatternComponent>, OscError> {
+    let mut match_fn = all_consuming(many1(map_address_pattern_component));
+    let (_, pattern_parts) =
+        match_fn(pattern).map_err(|err| OscError::BadAddressPattern(err.to_string()))?;
+    Ok(pattern_parts)
 }
 
 fn map_address_pattern_component(input: &str) -> IResult<&str, AddressPatternComponent> {
@@ -256,7 +772,8 @@ fn map_address_pattern_component(input: &str) -> IResult<&str, AddressPatternCom
         // Anything that's alphanumeric gets matched literally
         take_while1(is_address_character)
             .map(|s: &str| AddressPatternComponent::Tag(String::from(s))),
-        // Slashes must be seperated into their own tag for the non-greedy implementation of wildcards
+        // Slashes are matched literally as well, they are joined with the surrounding literals
+        // by `coalesce_tags`
         char('/').map(|c: char| AddressPatternComponent::Tag(c.to_string())),
         tag("?").map(|_| AddressPatternComponent::WildcardSingle),
         // Combinations of wildcards are a bit tricky.
@@ -273,92 +790,18 @@ fn map_address_pattern_component(input: &str) -> IResult<&str, AddressPatternCom
     ))(input)
 }
 
-fn match_literally<'a>(input: &'a str, pattern: &str) -> IResult<&'a str, &'a str> {
-    tag(pattern)(input)
-}
-
-fn match_wildcard_single(input: &str) -> IResult<&str, &str> {
-    take_while_m_n(1, 1, is_address_character)(input)
-}
-
-fn match_character_class<'a>(
-    input: &'a str,
-    character_class: &'a CharacterClass,
-) -> IResult<&'a str, &'a str> {
-    if character_class.negated {
-        is_not(character_class.characters.as_str())(input)
-    } else {
-        is_a(character_class.characters.as_str())(input)
-    }
-}
-
-/// Sequentially try all tags from choice element until one matches or return an error
-/// Example choice element: '{foo,bar}'
-/// It will get parsed into a vector containing the strings "foo" and "bar", which are then matched
-fn match_choice<'a>(input: &'a str, choices: &[String]) -> IResult<&'a str, &'a str> {
-    for choice in choices {
-        if let Ok((i, o)) = tag::<_, _, nom::error::Error<&str>>(choice.as_str())(input) {
-            return Ok((i, o));
-        }
-    }
-    Err(nom::Err::Error(nom::error::Error::from_error_kind(
-        input,
-        ErrorKind::Tag,
-    )))
-}
-
-/// Match Wildcard '*' by either consuming the rest of the part, or, if it's not the last component
-/// in the part, by looking ahead and matching the next component
-fn match_wildcard<'a>(
-    input: &'a str,
-    minimum_length: usize,
-    next: Option<&AddressPatternComponent>,
-) -> IResult<&'a str, &'a str> {
-    // If the next component is a '/', there are no more components in the current part and it can be wholly consumed
-    let next = next.filter(|&part| match part {
-        AddressPatternComponent::Tag(s) => s != "/",
-        _ => true,
-    });
-    match next {
-        // No next component, consume all allowed characters until end or next '/'
-        None => verify(take_while1(is_address_character), |s: &str| {
-            s.len() >= minimum_length
-        })(input),
-        // There is another element in this part, so logic gets a bit more complicated
-        Some(component) => {
-            // Wildcards can only match within the current address part, discard the rest
-            let address_part = match input.split_once('/') {
-                Some((p, _)) => p,
-                None => input,
-            };
-
-            // Attempt to find the latest matching occurrence of the next pattern component
-            // This is a greedy wildcard implementation
-            let mut longest: usize = 0;
-            for i in 0..address_part.len() {
-                let (_, substring) = input.split_at(i);
-                let result: IResult<_, _, nom::error::Error<&str>> = match component {
-                    AddressPatternComponent::Tag(s) => match_literally(substring, s.as_str()),
-                    AddressPatternComponent::CharacterClass(cc) => {
-                        match_character_class(substring, cc)
-                    }
-                    AddressPatternComponent::Choice(s) => match_choice(substring, s),
-                    // These two cases are prevented from happening by map_address_pattern_component
-                    AddressPatternComponent::WildcardSingle => {
-                        panic!("Single wildcard ('?') must not follow wildcard ('*')")
-                    }
-                    AddressPatternComponent::Wildcard(_) => {
-                        panic!("Double wildcards must be condensed into one")
-                    }
-                };
-
-                if result.is_ok() {
-                    longest = i
-                }
+/// Joins runs of literal components, so that they are matched with a single comparison.
+fn coalesce_tags(parts: Vec<AddressPatternComponent>) -> Vec<AddressPatternComponent> {
+    let mut coalesced: Vec<AddressPatternComponent> = Vec::with_capacity(parts.len());
+    for part in parts {
+        match (coalesced.last_mut(), part) {
+            (Some(AddressPatternComponent::Tag(run)), AddressPatternComponent::Tag(s)) => {
+                run.push_str(&s)
             }
-            verify(take(longest), |s: &str| s.len() >= minimum_length)(input)
+            (_, part) => coalesced.push(part),
         }
     }
+    coalesced
 }
 
 /// Verify that an address is valid
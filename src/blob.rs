@@ -0,0 +1,122 @@
+use crate::alloc::vec::Vec;
+use crate::errors::OscError;
+use crate::types::Result;
+
+use byteorder::{BigEndian, ByteOrder, LittleEndian};
+
+/// Interprets a blob as tightly packed big-endian `f32` samples.
+///
+/// An error is returned if the length of the blob is not a multiple of 4.
+///
+/// # Example
+///
+/// ```
+/// use rosc::blob;
+///
+/// let samples = blob::blob_as_f32_be(&[0x3f, 0x80, 0x00, 0x00, 0xbf, 0x00, 0x00, 0x00]).unwrap();
+/// assert_eq!(samples, vec![1.0, -0.5]);
+/// ```
+pub fn blob_as_f32_be(blob: &[u8]) -> Result<Vec<f32>> {
+    iter_f32_be(blob).map(Iterator::collect)
+}
+
+/// Interprets a blob as tightly packed little-endian `f32` samples.
+///
+/// An error is returned if the length of the blob is not a multiple of 4.
+pub fn blob_as_f32_le(blob: &[u8]) -> Result<Vec<f32>> {
+    iter_f32_le(blob).map(Iterator::collect)
+}
+
+/// Interprets a blob as tightly packed big-endian `i16` samples, e.g. 16 bit PCM audio.
+///
+/// An error is returned if the length of the blob is not a multiple of 2.
+pub fn blob_as_i16_be(blob: &[u8]) -> Result<Vec<i16>> {
+    iter_i16_be(blob).map(Iterator::collect)
+}
+
+/// Interprets a blob as tightly packed little-endian `i16` samples, e.g. 16 bit PCM audio.
+///
+/// An error is returned if the length of the blob is not a multiple of 2.
+pub fn blob_as_i16_le(blob: &[u8]) -> Result<Vec<i16>> {
+    iter_i16_le(blob).map(Iterator::collect)
+}
+
+/// Returns an iterator over the big-endian `f32` samples of a blob without copying it.
+///
+/// An error is returned if the length of the blob is not a multiple of 4.
+pub fn iter_f32_be(blob: &[u8]) -> Result<impl Iterator<Item = f32> + '_> {
+    samples(blob, 4, BigEndian::read_f32)
+}
+
+/// Returns an iterator over the little-endian `f32` samples of a blob without copying it.
+///
+/// An error is returned if the length of the blob is not a multiple of 4.
+pub fn iter_f32_le(blob: &[u8]) -> Result<impl Iterator<Item = f32> + '_> {
+    samples(blob, 4, LittleEndian::read_f32)
+}
+
+/// Returns an iterator over the big-endian `i16` samples of a blob without copying it.
+///
+/// An error is returned if the length of the blob is not a multiple of 2.
+pub fn iter_i16_be(blob: &[u8]) -> Result<impl Iterator<Item = i16> + '_> {
+    samples(blob, 2, BigEndian::read_i16)
+}
+
+/// Returns an iterator over the little-endian `i16` samples of a blob without copying it.
+///
+/// An error is returned if the length of the blob is not a multiple of 2.
+pub fn iter_i16_le(blob: &[u8]) -> Result<impl Iterator<Item = i16> + '_> {
+    samples(blob, 2, LittleEndian::read_i16)
+}
+
+/// Packs `f32` samples into a big-endian blob.
+///
+/// # Example
+///
+/// ```
+/// use rosc::{blob, OscType};
+///
+/// let arg = OscType::Blob(blob::blob_from_f32_be(&[1.0, -0.5]));
+/// assert_eq!(blob::blob_as_f32_be(&arg.blob().unwrap()).unwrap(), vec![1.0, -0.5]);
+/// ```
+pub fn blob_from_f32_be(samples: &[f32]) -> Vec<u8> {
+    let mut blob = vec![0u8; samples.len() * 4];
+    BigEndian::write_f32_into(samples, &mut blob);
+    blob
+}
+
+/// Packs `f32` samples into a little-endian blob.
+pub fn blob_from_f32_le(samples: &[f32]) -> Vec<u8> {
+    let mut blob = vec![0u8; samples.len() * 4];
+    LittleEndian::write_f32_into(samples, &mut blob);
+    blob
+}
+
+/// Packs `i16` samples into a big-endian blob.
+pub fn blob_from_i16_be(samples: &[i16]) -> Vec<u8> {
+    let mut blob = vec![0u8; samples.len() * 2];
+    BigEndian::write_i16_into(samples, &mut blob);
+    blob
+}
+
+/// Packs `i16` samples into a little-endian blob.
+pub fn blob_from_i16_le(samples: &[i16]) -> Vec<u8> {
+    let mut blob = vec![0u8; samples.len() * 2];
+    LittleEndian::write_i16_into(samples, &mut blob);
+    blob
+}
+
+fn samples<'a, T: 'a>(
+    blob: &'a [u8],
+    size: usize,
+    read: fn(&[u8]) -> T,
+) -> Result<impl Iterator<Item = T> + 'a> {
+    if !blob.len().is_multiple_of(size) {
+        return Err(OscError::BadArg(format!(
+            "Blob length {} is not a multiple of the sample size {}",
+            blob.len(),
+            size
+        )));
+    }
+    Ok(blob.chunks_exact(size).map(read))
+}
@@ -0,0 +1,146 @@
+use std::ops::{Deref, DerefMut};
+use std::sync::{Arc, Mutex};
+
+/// A pool of equally sized byte buffers for receiving packets.
+///
+/// Allocating a fresh buffer for every received packet is wasteful, while sharing a single buffer
+/// prevents decoding several packets concurrently. A `BufferPool` hands out [`PooledBuffer`]s
+/// which return to the pool when they are dropped, so that they can be reused.
+///
+/// The pool never blocks: if no idle buffer is available, a new one is allocated. At most
+/// `max_buffers` idle buffers are kept, any further returned buffers are freed. The pool is cheap
+/// to clone, and all clones share the same buffers, so it can be used from several threads.
+///
+/// Decoded packets own their data, but the remainder slice returned alongside them borrows from
+/// the buffer, so a buffer only returns to the pool once that borrow has ended.
+///
+/// # Example
+///
+/// ```
+/// use rosc::buffer::BufferPool;
+/// use rosc::{decoder, encoder, OscMessage, OscPacket};
+///
+/// let pool = BufferPool::new(4, decoder::MTU);
+///
+/// let mut buf = pool.get();
+/// // Receive into the buffer, e.g. using `UdpSocket::recv`
+/// let packet = encoder::encode(&OscPacket::Message(OscMessage::from("/ping"))).unwrap();
+/// buf[..packet.len()].copy_from_slice(&packet);
+///
+/// let (_, decoded) = decoder::decode_udp(&buf[..packet.len()]).unwrap();
+/// drop(buf);
+/// assert_eq!(pool.idle_count(), 1);
+/// ```
+#[derive(Clone, Debug)]
+pub struct BufferPool {
+    shared: Arc<Shared>,
+}
+
+#[derive(Debug)]
+struct Shared {
+    idle: Mutex<Vec<Vec<u8>>>,
+    max_buffers: usize,
+    buffer_size: usize,
+}
+
+impl BufferPool {
+    /// Creates an empty pool keeping up to `max_buffers` idle buffers of `buffer_size` bytes.
+    ///
+    /// Buffers are allocated lazily, the first time they are needed.
+    pub fn new(max_buffers: usize, buffer_size: usize) -> Self {
+        BufferPool {
+            shared: Arc::new(Shared {
+                idle: Mutex::new(Vec::with_capacity(max_buffers)),
+                max_buffers,
+                buffer_size,
+            }),
+        }
+    }
+
+    /// Returns the maximum number of idle buffers kept by the pool.
+    pub fn max_buffers(&self) -> usize {
+        self.shared.max_buffers
+    }
+
+    /// Returns the size of the buffers handed out by the pool.
+    pub fn buffer_size(&self) -> usize {
+        self.shared.buffer_size
+    }
+
+    /// Returns the number of idle buffers currently in the pool.
+    pub fn idle_count(&self) -> usize {
+        self.shared.idle().len()
+    }
+
+    /// Takes a buffer of [`buffer_size`](BufferPool::buffer_size) bytes from the pool, or
+    /// allocates a new one if the pool is exhausted.
+    ///
+    /// The content of a reused buffer is not cleared.
+    pub fn get(&self) -> PooledBuffer {
+        let buf = self
+            .shared
+            .idle()
+            .pop()
+            .unwrap_or_else(|| vec![0u8; self.shared.buffer_size]);
+        PooledBuffer {
+            buf,
+            shared: Arc::clone(&self.shared),
+        }
+    }
+}
+
+impl Shared {
+    fn idle(&self) -> std::sync::MutexGuard<'_, Vec<Vec<u8>>> {
+        // The idle list is always consistent, so a panic while it was locked doesn't matter
+        self.idle.lock().unwrap_or_else(|err| err.into_inner())
+    }
+}
+
+/// A buffer taken from a [`BufferPool`], which returns to the pool when dropped.
+///
+/// It dereferences to a `Vec<u8>` of the pool's buffer size. The vector may be truncated, e.g.
+/// to the size of a received packet, or even grown; its length is restored before it is reused.
+#[derive(Debug)]
+pub struct PooledBuffer {
+    buf: Vec<u8>,
+    shared: Arc<Shared>,
+}
+
+impl PooledBuffer {
+    /// Detaches the buffer from its pool, so it won't be returned to it.
+    pub fn into_inner(mut self) -> Vec<u8> {
+        let buf = std::mem::take(&mut self.buf);
+        // Dropping an empty buffer doesn't return it to the pool
+        drop(self);
+        buf
+    }
+}
+
+impl Deref for PooledBuffer {
+    type Target = Vec<u8>;
+
+    fn deref(&self) -> &Vec<u8> {
+        &self.buf
+    }
+}
+
+impl DerefMut for PooledBuffer {
+    fn deref_mut(&mut self) -> &mut Vec<u8> {
+        &mut self.buf
+    }
+}
+
+impl Drop for PooledBuffer {
+    fn drop(&mut self) {
+        if self.buf.capacity() < self.shared.buffer_size {
+            return;
+        }
+
+        let mut idle = self.shared.idle();
+        if idle.len() < self.shared.max_buffers {
+            let mut buf = std::mem::take(&mut self.buf);
+            buf.resize(self.shared.buffer_size, 0);
+            idle.push(buf);
+        }
+    }
+}
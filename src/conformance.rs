@@ -0,0 +1,161 @@
+use crate::alloc::string::ToString;
+use crate::decoder;
+use crate::encoder;
+use crate::errors::OscError;
+use crate::types::{
+    OscArray, OscBundle, OscColor, OscMessage, OscMidiMessage, OscPacket, OscTime, OscType,
+};
+
+use core::fmt::{self, Display};
+
+/// The encoding of the packet returned by `golden_packet`, written by hand from the OSC
+/// specification rather than by the encoder.
+#[rustfmt::skip]
+const GOLDEN_BYTES: [u8; 120] = [
+    // "#bundle"
+    0x23, 0x62, 0x75, 0x6e, 0x64, 0x6c, 0x65, 0x00,
+    // Time tag
+    0x01, 0x02, 0x03, 0x04, 0x05, 0x06, 0x07, 0x08,
+    // Element size, "/self/test"
+    0x00, 0x00, 0x00, 0x64, 0x2f, 0x73, 0x65, 0x6c,
+    0x66, 0x2f, 0x74, 0x65, 0x73, 0x74, 0x00, 0x00,
+    // ",ifsbthdcrmTFNI[if]"
+    0x2c, 0x69, 0x66, 0x73, 0x62, 0x74, 0x68, 0x64,
+    0x63, 0x72, 0x6d, 0x54, 0x46, 0x4e, 0x49, 0x5b,
+    0x69, 0x66, 0x5d, 0x00,
+    // Int -2, Float -1.5
+    0xff, 0xff, 0xff, 0xfe, 0xbf, 0xc0, 0x00, 0x00,
+    // String "osc"
+    0x6f, 0x73, 0x63, 0x00,
+    // Blob of 5 bytes
+    0x00, 0x00, 0x00, 0x05, 0x01, 0x02, 0x03, 0x04,
+    0x05, 0x00, 0x00, 0x00,
+    // Time
+    0x89, 0xab, 0xcd, 0xef, 0x01, 0x23, 0x45, 0x67,
+    // Long
+    0x01, 0x02, 0x03, 0x04, 0x05, 0x06, 0x07, 0x08,
+    // Double -0.75
+    0xbf, 0xe8, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+    // Char 'λ', Color, Midi
+    0x00, 0x00, 0x03, 0xbb, 0x01, 0x02, 0x03, 0x04,
+    0x05, 0x06, 0x07, 0x08,
+    // Array of Int 7 and Float 0.5
+    0x00, 0x00, 0x00, 0x07, 0x3f, 0x00, 0x00, 0x00,
+];
+
+fn golden_packet() -> OscPacket {
+    OscPacket::Bundle(OscBundle {
+        timetag: OscTime::from((0x0102_0304, 0x0506_0708)),
+        content: vec![OscPacket::Message(OscMessage {
+            addr: "/self/test".to_string(),
+            args: vec![
+                OscType::Int(-2),
+                OscType::Float(-1.5),
+                OscType::String("osc".to_string()),
+                OscType::Blob(vec![1, 2, 3, 4, 5]),
+                OscType::Time(OscTime::from((0x89ab_cdef, 0x0123_4567))),
+                OscType::Long(0x0102_0304_0506_0708),
+                OscType::Double(-0.75),
+                OscType::Char('λ'),
+                OscType::Color(OscColor {
+                    red: 1,
+                    green: 2,
+                    blue: 3,
+                    alpha: 4,
+                }),
+                OscType::Midi(OscMidiMessage {
+                    port: 5,
+                    status: 6,
+                    data1: 7,
+                    data2: 8,
+                }),
+                OscType::Bool(true),
+                OscType::Bool(false),
+                OscType::Nil,
+                OscType::Inf,
+                OscType::Array(OscArray {
+                    content: vec![OscType::Int(7), OscType::Float(0.5)],
+                }),
+            ],
+        })],
+    })
+}
+
+/// Checks that packets are encoded and decoded correctly on this machine.
+///
+/// A built-in bundle using every argument type, including the endian-sensitive ones, is encoded
+/// and compared against golden bytes written from the OSC specification, and the golden bytes are
+/// decoded and compared against the bundle. This is cheap enough to run at startup, e.g. to catch
+/// miscompilation or endianness issues on unusual hardware.
+///
+/// # Example
+///
+/// ```
+/// rosc::self_test().expect("OSC codec is broken on this machine");
+/// ```
+pub fn self_test() -> Result<(), SelfTestFailure> {
+    let packet = golden_packet();
+
+    let encoded = encoder::encode(&packet).map_err(SelfTestFailure::Encode)?;
+    if encoded[..] != GOLDEN_BYTES[..] {
+        let offset = encoded
+            .iter()
+            .zip(GOLDEN_BYTES.iter())
+            .position(|(actual, expected)| actual != expected)
+            .unwrap_or_else(|| encoded.len().min(GOLDEN_BYTES.len()));
+        return Err(SelfTestFailure::EncodedMismatch {
+            offset,
+            expected: GOLDEN_BYTES.get(offset).cloned(),
+            actual: encoded.get(offset).cloned(),
+        });
+    }
+
+    let (_, decoded) = decoder::decode_udp(&GOLDEN_BYTES).map_err(SelfTestFailure::Decode)?;
+    if decoded != packet {
+        return Err(SelfTestFailure::DecodedMismatch { decoded });
+    }
+
+    Ok(())
+}
+
+/// The reason a [`self_test`] failed.
+#[derive(Debug)]
+pub enum SelfTestFailure {
+    /// Encoding the built-in packet failed.
+    Encode(OscError),
+    /// The encoded packet differs from the golden bytes, first at `offset`. `expected` or
+    /// `actual` is `None` if the respective bytes end at `offset`.
+    EncodedMismatch {
+        offset: usize,
+        expected: Option<u8>,
+        actual: Option<u8>,
+    },
+    /// Decoding the golden bytes failed.
+    Decode(OscError),
+    /// Decoding the golden bytes produced a different packet.
+    DecodedMismatch { decoded: OscPacket },
+}
+
+impl Display for SelfTestFailure {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            SelfTestFailure::Encode(err) => write!(f, "encoding the test packet failed: {}", err),
+            SelfTestFailure::EncodedMismatch {
+                offset,
+                expected,
+                actual,
+            } => write!(
+                f,
+                "encoded test packet differs at offset {}: expected {:?}, got {:?}",
+                offset, expected, actual
+            ),
+            SelfTestFailure::Decode(err) => write!(f, "decoding the test packet failed: {}", err),
+            SelfTestFailure::DecodedMismatch { decoded } => {
+                write!(f, "decoded test packet differs: {:?}", decoded)
+            }
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for SelfTestFailure {}
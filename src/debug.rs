@@ -0,0 +1,567 @@
+#[cfg(feature = "std")]
+use crate::address::{Matcher, OscAddress};
+use crate::alloc::{
+    string::{String, ToString},
+    vec::Vec,
+};
+use crate::decoder;
+use crate::encoder::{self, pad_to_4, BUNDLE_TAG};
+#[cfg(feature = "std")]
+use crate::errors::OscError;
+#[cfg(feature = "std")]
+use crate::types::OscArray;
+use crate::types::{OscPacket, OscType};
+
+use byteorder::{BigEndian, ByteOrder};
+use core::fmt::{self, Write};
+
+/// Renders an encoded packet as a hex dump annotated with its OSC structure.
+///
+/// Each row shows the offset and up to four bytes in hex, followed by a description of the
+/// address, type tags, argument or bundle field those bytes belong to. Arguments without any
+/// data, like `true` or `nil`, get a row without bytes. Arguments are numbered from 0, elements
+/// of arrays are numbered within them, e.g. `arg2[0]`. The contents of bundle elements are
+/// indented.
+///
+/// The packet is decoded as far as possible. At the first byte that cannot be decoded, the error
+/// is noted and the rest of the bytes are dumped without annotations.
+///
+/// # Example
+///
+/// ```
+/// use rosc::{debug, encoder, OscMessage, OscPacket, OscType};
+///
+/// let bytes = encoder::encode(&OscPacket::Message(OscMessage {
+///     addr: "/foo".to_string(),
+///     args: vec![OscType::Int(42)],
+/// }))
+/// .unwrap();
+///
+/// assert_eq!(
+///     debug::annotated_dump(&bytes),
+///     "0000  2f 66 6f 6f  addr '/foo' + 4 pad\n\
+///      0004  00 00 00 00\n\
+///      0008  2c 69 00 00  tags ',i' + 2 pad\n\
+///      000c  00 00 00 2a  arg0 i32 = 42\n"
+/// );
+/// ```
+pub fn annotated_dump(bytes: &[u8]) -> String {
+    let mut dump = String::new();
+    // Writing to a `String` never fails
+    let _ = write_annotated_dump(&mut dump, bytes);
+    dump
+}
+
+/// Like [`annotated_dump`], but writing the dump to `out`.
+pub fn write_annotated_dump<W: Write>(out: &mut W, bytes: &[u8]) -> fmt::Result {
+    let mut dump = Dump {
+        bytes,
+        items: Vec::new(),
+    };
+    let failure = match dump.packet(0, bytes.len(), 0) {
+        Ok(end) => {
+            if end < bytes.len() {
+                dump.item(end, bytes.len(), 0, "trailing bytes".to_string());
+            }
+            None
+        }
+        Err(failure) => Some(failure),
+    };
+
+    for item in &dump.items {
+        write_rows(out, bytes, item)?;
+    }
+    if let Some((offset, error)) = failure {
+        write_rows(
+            out,
+            bytes,
+            &Item {
+                start: offset,
+                end: bytes.len(),
+                depth: 0,
+                note: format!("error: {}", error),
+            },
+        )?;
+    }
+    Ok(())
+}
+
+/// Like [`annotated_dump`], but with the arguments of each message passed through `redactor`
+/// first, so that sensitive values don't end up in logs.
+///
+/// If the redactor changes any argument, the dump shows the re-encoded packet instead of
+/// `bytes`. Since a packet that cannot be decoded cannot be redacted either, only the error and
+/// the length of such a packet are written, without any of its bytes.
+///
+/// # Example
+///
+/// ```
+/// use rosc::debug::{self, Redaction, Redactor};
+/// use rosc::{encoder, OscMessage, OscPacket, OscType};
+///
+/// struct HideTokens;
+///
+/// impl Redactor for HideTokens {
+///     fn redact(&self, addr: &str, index: usize, _arg: &OscType) -> Redaction {
+///         if addr == "/login" && index == 1 {
+///             Redaction::Mask
+///         } else {
+///             Redaction::Keep
+///         }
+///     }
+/// }
+///
+/// let bytes = encoder::encode(&OscPacket::Message(OscMessage {
+///     addr: "/login".to_string(),
+///     args: vec!["user".into(), "hunter2".into()],
+/// }))
+/// .unwrap();
+///
+/// let dump = debug::annotated_dump_redacted(&bytes, &HideTokens);
+/// assert!(dump.contains("arg0 str 'user'"));
+/// assert!(dump.contains("arg1 str '***'"));
+/// assert!(!dump.contains("hunter2"));
+/// ```
+pub fn annotated_dump_redacted<R: Redactor + ?Sized>(bytes: &[u8], redactor: &R) -> String {
+    let mut dump = String::new();
+    // Writing to a `String` never fails
+    let _ = write_annotated_dump_redacted(&mut dump, bytes, redactor);
+    dump
+}
+
+/// Like [`annotated_dump_redacted`], but writing the dump to `out`.
+pub fn write_annotated_dump_redacted<W: Write, R: Redactor + ?Sized>(
+    out: &mut W,
+    bytes: &[u8],
+    redactor: &R,
+) -> fmt::Result {
+    let mut packet = match decoder::decode_udp(bytes) {
+        Ok((_, packet)) => packet,
+        Err(error) => {
+            return writeln!(
+                out,
+                "error: {} ({} bytes not shown, as they cannot be redacted)",
+                error,
+                bytes.len()
+            )
+        }
+    };
+    if !redact_in_place(&mut packet, redactor) {
+        return write_annotated_dump(out, bytes);
+    }
+    match encoder::encode(&packet) {
+        Ok(redacted) => write_annotated_dump(out, &redacted),
+        Err(error) => writeln!(out, "error: {}", error),
+    }
+}
+
+/// The string that [`Redaction::Mask`] replaces an argument with.
+pub const MASK: &str = "***";
+
+/// What a [`Redactor`] does with an argument.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Redaction {
+    /// Leave the argument as it is.
+    Keep,
+    /// Replace the argument with the string [`MASK`], whatever its type.
+    Mask,
+    /// Replace the argument with the given value.
+    Replace(OscType),
+}
+
+/// Decides which message arguments to hide before a packet is logged.
+///
+/// The default implementation keeps every argument.
+pub trait Redactor {
+    /// Decides what to do with the argument at `index` of a message sent to `addr`. Arrays are
+    /// passed as a whole, as a single argument.
+    fn redact(&self, addr: &str, index: usize, arg: &OscType) -> Redaction {
+        let _ = (addr, index, arg);
+        Redaction::Keep
+    }
+}
+
+/// A [`Redactor`] that keeps every argument.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct NoRedaction;
+
+impl Redactor for NoRedaction {}
+
+/// A [`Redactor`] masking every string argument of messages whose address matches any of a set
+/// of address patterns.
+///
+/// Strings inside arrays are masked as well, by replacing the array with a copy in which they
+/// are masked.
+///
+/// # Example
+///
+/// ```
+/// use rosc::debug::{PatternRedactor, Redaction, Redactor};
+/// use rosc::OscType;
+///
+/// let redactor = PatternRedactor::new(&["/auth/*", "/session/token"]).unwrap();
+/// let token = OscType::String("secret".to_string());
+/// assert_eq!(redactor.redact("/auth/login", 0, &token), Redaction::Mask);
+/// assert_eq!(redactor.redact("/auth/login", 1, &OscType::Int(3)), Redaction::Keep);
+/// assert_eq!(redactor.redact("/mixer/name", 0, &token), Redaction::Keep);
+/// ```
+#[cfg(feature = "std")]
+#[derive(Clone, Debug)]
+pub struct PatternRedactor {
+    matchers: Vec<Matcher>,
+}
+
+#[cfg(feature = "std")]
+impl PatternRedactor {
+    /// Creates a redactor for the given address patterns.
+    ///
+    /// Returns an error if any of the patterns is invalid.
+    pub fn new(patterns: &[&str]) -> Result<Self, OscError> {
+        let matchers = patterns
+            .iter()
+            .map(|pattern| Matcher::new(pattern))
+            .collect::<Result<_, _>>()?;
+        Ok(PatternRedactor { matchers })
+    }
+
+    fn matches(&self, addr: &str) -> bool {
+        match OscAddress::new(addr.to_string()) {
+            Ok(address) => self
+                .matchers
+                .iter()
+                .any(|matcher| matcher.match_address(&address)),
+            Err(_) => false,
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl Redactor for PatternRedactor {
+    fn redact(&self, addr: &str, _index: usize, arg: &OscType) -> Redaction {
+        match arg {
+            OscType::String(_) if self.matches(addr) => Redaction::Mask,
+            OscType::Array(array) if contains_string(&array.content) && self.matches(addr) => {
+                Redaction::Replace(OscType::Array(OscArray {
+                    content: array.content.iter().map(mask_strings).collect(),
+                }))
+            }
+            _ => Redaction::Keep,
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+fn contains_string(args: &[OscType]) -> bool {
+    args.iter().any(|arg| match arg {
+        OscType::String(_) => true,
+        OscType::Array(array) => contains_string(&array.content),
+        _ => false,
+    })
+}
+
+/// Returns `arg` with any strings in it replaced by [`MASK`].
+#[cfg(feature = "std")]
+fn mask_strings(arg: &OscType) -> OscType {
+    match arg {
+        OscType::String(_) => OscType::String(MASK.to_string()),
+        OscType::Array(array) => OscType::Array(OscArray {
+            content: array.content.iter().map(mask_strings).collect(),
+        }),
+        _ => arg.clone(),
+    }
+}
+
+/// Returns a copy of `packet` with the arguments of all its messages passed through `redactor`.
+///
+/// This can be used to redact packets before logging them in other ways than
+/// [`annotated_dump_redacted`], e.g. with their `Debug` representation.
+pub fn redact_packet<R: Redactor + ?Sized>(packet: &OscPacket, redactor: &R) -> OscPacket {
+    let mut packet = packet.clone();
+    redact_in_place(&mut packet, redactor);
+    packet
+}
+
+/// Redacts the arguments of all messages in `packet`, returning whether anything changed.
+fn redact_in_place<R: Redactor + ?Sized>(packet: &mut OscPacket, redactor: &R) -> bool {
+    match packet {
+        OscPacket::Message(message) => {
+            let mut changed = false;
+            for (index, arg) in message.args.iter_mut().enumerate() {
+                match redactor.redact(&message.addr, index, arg) {
+                    Redaction::Keep => continue,
+                    Redaction::Mask => *arg = OscType::String(MASK.to_string()),
+                    Redaction::Replace(value) => *arg = value,
+                }
+                changed = true;
+            }
+            changed
+        }
+        OscPacket::Bundle(bundle) => bundle.content.iter_mut().fold(false, |changed, packet| {
+            redact_in_place(packet, redactor) | changed
+        }),
+    }
+}
+
+/// A range of bytes and its description.
+struct Item {
+    start: usize,
+    end: usize,
+    depth: usize,
+    note: String,
+}
+
+/// The offset at which decoding failed, and why.
+type Failure = (usize, String);
+
+struct Dump<'a> {
+    bytes: &'a [u8],
+    items: Vec<Item>,
+}
+
+impl<'a> Dump<'a> {
+    fn item(&mut self, start: usize, end: usize, depth: usize, note: String) {
+        self.items.push(Item {
+            start,
+            end,
+            depth,
+            note,
+        });
+    }
+
+    /// Returns the `len` bytes at `at`, or a failure if they extend past `end`.
+    fn take(&self, at: usize, len: usize, end: usize, what: &str) -> Result<&'a [u8], Failure> {
+        if len > end - at {
+            return Err((
+                at,
+                format!("{} needs {} bytes, {} left", what, len, end - at),
+            ));
+        }
+        Ok(&self.bytes[at..at + len])
+    }
+
+    /// Annotates the packet in `start..end`, returning the offset at which it ends.
+    fn packet(&mut self, start: usize, end: usize, depth: usize) -> Result<usize, Failure> {
+        let input = &self.bytes[start..end];
+        if input.is_empty() {
+            Err((start, "empty packet".to_string()))
+        } else if input.starts_with(BUNDLE_TAG) {
+            self.bundle(start, end, depth)
+        } else if input[0] == b'#' {
+            Err((start, "invalid bundle tag".to_string()))
+        } else {
+            self.message(start, end, depth)
+        }
+    }
+
+    fn bundle(&mut self, start: usize, end: usize, depth: usize) -> Result<usize, Failure> {
+        self.item(start, start + 8, depth, "bundle".to_string());
+        let mut at = start + 8;
+
+        let timetag = self.take(at, 8, end, "time tag")?;
+        let note = match (
+            BigEndian::read_u32(&timetag[..4]),
+            BigEndian::read_u32(&timetag[4..]),
+        ) {
+            (0, 1) => "timetag immediate".to_string(),
+            (seconds, fractional) => format!("timetag ({}, {})", seconds, fractional),
+        };
+        self.item(at, at + 8, depth, note);
+        at += 8;
+
+        while at < end {
+            let size = BigEndian::read_u32(self.take(at, 4, end, "bundle element size")?) as usize;
+            if size > end - at - 4 {
+                return Err((
+                    at,
+                    format!(
+                        "bundle element size {} exceeds the {} bytes left",
+                        size,
+                        end - at - 4
+                    ),
+                ));
+            }
+            self.item(at, at + 4, depth, format!("bundle elem size {}", size));
+            at += 4;
+
+            let element_end = at + size;
+            let packet_end = self.packet(at, element_end, depth + 1)?;
+            if packet_end < element_end {
+                self.item(
+                    packet_end,
+                    element_end,
+                    depth + 1,
+                    "unused bytes".to_string(),
+                );
+            }
+            at = element_end;
+        }
+        Ok(at)
+    }
+
+    fn message(&mut self, start: usize, end: usize, depth: usize) -> Result<usize, Failure> {
+        let (tags_start, _) = self.string(start, end, depth, "addr")?;
+        let (tags_end, tags) = self.string(tags_start, end, depth, "tags")?;
+        let tags = match tags.strip_prefix(',') {
+            Some(tags) => tags,
+            None => {
+                self.items.pop();
+                return Err((tags_start, "type tags do not start with ','".to_string()));
+            }
+        };
+        let mut at = tags_end;
+
+        // The index of the current argument, followed by its index within each enclosing array
+        let mut path = vec![0usize];
+        for tag in tags.chars() {
+            let label = arg_label(&path);
+            let (len, note) = match tag {
+                '[' => {
+                    self.item(at, at, depth, format!("{} array start", label));
+                    path.push(0);
+                    continue;
+                }
+                ']' => {
+                    if path.len() < 2 {
+                        return Err((at, "type tag ']' outside of an array".to_string()));
+                    }
+                    path.pop();
+                    self.item(at, at, depth, format!("{} array end", arg_label(&path)));
+                    *path.last_mut().unwrap() += 1;
+                    continue;
+                }
+                's' => {
+                    let (next, _) = self.string(at, end, depth, &label)?;
+                    *path.last_mut().unwrap() += 1;
+                    at = next;
+                    continue;
+                }
+                'b' => {
+                    let len = BigEndian::read_u32(self.take(at, 4, end, "blob size")?) as usize;
+                    let padded = pad_to_4(len);
+                    self.take(at + 4, padded, end, "blob")?;
+                    self.item(at, at + 4, depth, format!("{} blob len {}", label, len));
+                    if padded > 0 {
+                        self.item(
+                            at + 4,
+                            at + 4 + padded,
+                            depth,
+                            format!("{} blob data + {} pad", label, padded - len),
+                        );
+                    }
+                    *path.last_mut().unwrap() += 1;
+                    at += 4 + padded;
+                    continue;
+                }
+                'i' | 'f' | 'c' | 'r' | 'm' => (4, None),
+                'h' | 'd' | 't' => (8, None),
+                'T' => (0, Some("true")),
+                'F' => (0, Some("false")),
+                'N' => (0, Some("nil")),
+                'I' => (0, Some("inf")),
+                _ => return Err((at, format!("unknown type tag '{}'", tag.escape_debug()))),
+            };
+
+            let data = self.take(at, len, end, &label)?;
+            let note = match (note, tag) {
+                (Some(note), _) => note.to_string(),
+                (None, 'i') => format!("i32 = {}", BigEndian::read_i32(data)),
+                (None, 'f') => format!("f32 = {}", BigEndian::read_f32(data)),
+                (None, 'h') => format!("i64 = {}", BigEndian::read_i64(data)),
+                (None, 'd') => format!("f64 = {}", BigEndian::read_f64(data)),
+                (None, 'c') => match core::char::from_u32(BigEndian::read_u32(data)) {
+                    Some(c) => format!("char '{}'", c.escape_debug()),
+                    None => return Err((at, "invalid char".to_string())),
+                },
+                (None, 't') => format!(
+                    "time ({}, {})",
+                    BigEndian::read_u32(&data[..4]),
+                    BigEndian::read_u32(&data[4..])
+                ),
+                (None, 'r') => format!("color {:?}", data),
+                (None, _) => format!("midi {:?}", data),
+            };
+            self.item(at, at + len, depth, format!("{} {}", label, note));
+            *path.last_mut().unwrap() += 1;
+            at += len;
+        }
+
+        if path.len() > 1 {
+            return Err((at, "unterminated array".to_string()));
+        }
+        Ok(at)
+    }
+
+    /// Annotates the null terminated and padded string at `at` as `label`, returning the offset
+    /// after it and the string itself.
+    fn string(
+        &mut self,
+        at: usize,
+        end: usize,
+        depth: usize,
+        label: &str,
+    ) -> Result<(usize, &'a str), Failure> {
+        let bytes = self.bytes;
+        let len = match bytes[at..end].iter().position(|&b| b == 0) {
+            Some(len) => len,
+            None => return Err((at, format!("{} is not null terminated", label))),
+        };
+        let padded = pad_to_4(len + 1);
+        self.take(at, padded, end, label)?;
+        let string = match core::str::from_utf8(&bytes[at..at + len]) {
+            Ok(string) => string,
+            Err(_) => return Err((at, format!("{} is not valid UTF-8", label))),
+        };
+
+        let note = if label.starts_with("arg") {
+            format!(
+                "{} str '{}' + {} pad",
+                label,
+                string.escape_debug(),
+                padded - len
+            )
+        } else {
+            format!(
+                "{} '{}' + {} pad",
+                label,
+                string.escape_debug(),
+                padded - len
+            )
+        };
+        self.item(at, at + padded, depth, note);
+        Ok((at + padded, string))
+    }
+}
+
+fn arg_label(path: &[usize]) -> String {
+    let mut label = format!("arg{}", path[0]);
+    for index in &path[1..] {
+        let _ = write!(label, "[{}]", index);
+    }
+    label
+}
+
+/// Writes the rows of an item, with the description on the first one.
+fn write_rows<W: Write>(out: &mut W, bytes: &[u8], item: &Item) -> fmt::Result {
+    let indent = item.depth * 2;
+    let mut offset = item.start;
+    let mut note = Some(&item.note);
+    loop {
+        let row = &bytes[offset..item.end.min(offset + 4)];
+        write!(out, "{:04x} ", offset)?;
+        for byte in row {
+            write!(out, " {:02x}", byte)?;
+        }
+        match note.take() {
+            Some(note) => {
+                write!(out, "{:1$}", "", (4 - row.len()) * 3 + 2 + indent)?;
+                writeln!(out, "{}", note)?;
+            }
+            None => writeln!(out)?,
+        }
+
+        offset += row.len();
+        if offset >= item.end {
+            return Ok(());
+        }
+    }
+}
@@ -2,13 +2,22 @@ use crate::alloc::{
     string::{String, ToString},
     vec::Vec,
 };
-use crate::errors::OscError;
+use crate::encoder::{self, pad_to_4, BUNDLE_TAG};
+use crate::errors::{ExactnessError, OscError};
+use crate::framing::{self, Framing};
+use crate::signature::TypeSignature;
+use crate::stats::Stats;
 use crate::types::{
-    OscArray, OscBundle, OscColor, OscMessage, OscMidiMessage, OscPacket, OscTime, OscType,
+    NumericByteOrder, OscArray, OscBundle, OscColor, OscMessage, OscMidiMessage, OscPacket,
+    OscTime, OscType, OscTypeRef, SpecProfile, TypeTag,
 };
 
+use byteorder::{BigEndian, ByteOrder};
+use core::cell::RefCell;
+use core::mem;
 use nom::bytes::complete::{take, take_till};
 use nom::combinator::{map, map_parser};
+use nom::error::ErrorKind;
 use nom::multi::many0;
 use nom::number::complete::{be_f32, be_f64, be_i32, be_i64, be_u32};
 use nom::sequence::terminated;
@@ -18,102 +27,1549 @@ use nom::{combinator::map_res, sequence::tuple, Err, IResult};
 /// Common MTU size for ethernet
 pub const MTU: usize = 1536;
 
+/// The maximum number of bundles that may be nested inside each other in a decoded packet.
+///
+/// Bundles are decoded recursively, so this limit prevents a malicious packet from overflowing
+/// the stack.
+pub const MAX_BUNDLE_DEPTH: usize = 64;
+
+/// Options controlling how lenient the decoder is.
+///
+/// The default options follow the OSC specification. Pass options to the `*_with_options`
+/// variants of the decoding functions to accept packets from peers that don't.
+///
+/// # Example
+///
+/// ```
+/// use rosc::decoder::{self, DecodeOptions};
+/// use rosc::OscPacket;
+///
+/// let options = DecodeOptions {
+///     require_leading_slash: false,
+///     ..DecodeOptions::default()
+/// };
+/// // A message with the address "ping" and no arguments
+/// let bytes = b"ping\0\0\0\0,\0\0\0";
+/// assert!(decoder::decode_udp(bytes).is_err());
+///
+/// match decoder::decode_udp_with_options(bytes, &options).unwrap().1 {
+///     OscPacket::Message(msg) => assert_eq!(msg.addr, "ping"),
+///     OscPacket::Bundle(_) => unreachable!(),
+/// }
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DecodeOptions {
+    /// Reject message addresses that don't start with `/`, including empty ones, with an
+    /// [`OscError::BadAddress`]. If disabled, any address not starting with `#` is accepted.
+    ///
+    /// Defaults to `true`.
+    pub require_leading_slash: bool,
+    /// The byte order of numeric argument data. Anything but big-endian is a workaround for
+    /// nonconforming peers, see [`NumericByteOrder`] for what it applies to.
+    ///
+    /// Defaults to [`NumericByteOrder::Big`].
+    pub numeric_byte_order: NumericByteOrder,
+    /// The maximum number of arguments of a message, counting the elements of arrays rather than
+    /// the arrays themselves, like [`OscMessage::total_arg_count`]. Messages with more arguments
+    /// are rejected with an [`OscError::TooManyArgs`].
+    ///
+    /// Arguments like `Nil` or `Bool` have no data, so a message can declare far more arguments
+    /// than its size suggests. The type tags are counted before anything is allocated for the
+    /// arguments, so this limits the memory a single small message can make the decoder use.
+    ///
+    /// Defaults to 65536, far more than any real message needs.
+    pub max_args: usize,
+    /// The type tags accepted in messages, or `None` to accept all type tags supported by this
+    /// crate. Arrays are only accepted if `[` is included. Messages with other type tags are
+    /// rejected with an [`OscError::BadArg`].
+    ///
+    /// Defaults to `None`.
+    pub allowed_type_tags: Option<String>,
+    /// Reject packets with nonzero padding bytes with an [`OscError::BadPacket`].
+    ///
+    /// This is checked by encoding the decoded packet again and comparing it to the input, like
+    /// [`decode_exact`] does, so any other difference to how this crate would encode the packet
+    /// is rejected as well. Bytes following the packet are not checked. [`decode_to_sink`]
+    /// decodes the packet to owned types for this check.
+    ///
+    /// Defaults to `false`.
+    pub strict_padding: bool,
+    /// Insert the time tag of a bundle as a `Time` argument in front of the arguments of every
+    /// message directly contained in it, for tools that treat a leading `t` argument and the time
+    /// tag of a bundle interchangeably.
+    ///
+    /// Messages in nested bundles get the time tag of their innermost bundle, even if an outer
+    /// bundle has a later one. Bundles keep their time tags, and messages that aren't contained
+    /// in a bundle are unchanged. The inserted argument doesn't count towards `max_args` and isn't
+    /// checked against `allowed_type_tags`, and `strict_padding` compares the input to the packet
+    /// without it. With [`decode_to_sink`], it is passed to
+    /// [`on_arg`](DecodeSink::on_arg) directly after
+    /// [`on_message_start`](DecodeSink::on_message_start).
+    ///
+    /// **Encoding a packet decoded this way doesn't give the original packet**, as the inserted
+    /// arguments are encoded like any other.
+    ///
+    /// Defaults to `false`.
+    pub timetag_as_arg: bool,
+    /// The maximum number of bytes the decoded packet may allocate, or `None` for no limit.
+    /// Packets estimated to need more are rejected with an [`OscError::MemoryBudgetExceeded`]
+    /// before anything is allocated for them.
+    ///
+    /// Unlike `max_args`, this limits packets with many strings or blobs, whose owned copies and
+    /// argument vectors can take several times the size of the packet. The estimate is the sum of
+    ///
+    /// - the length of every address, string and blob,
+    /// - `size_of::<OscType>()` for every argument, counting arrays as well as their elements,
+    /// - `size_of::<OscPacket>()` for every element of a bundle.
+    ///
+    /// This ignores allocator overhead, spare capacity and temporary allocations made while
+    /// decoding, so it is a lower bound rather than an exact figure. Checking it takes an extra
+    /// pass over the packet, which [`decode_to_sink`] doesn't make, as it doesn't allocate for
+    /// the packet in the first place.
+    ///
+    /// Defaults to `None`.
+    pub max_owned_bytes: Option<usize>,
+}
+
+impl Default for DecodeOptions {
+    fn default() -> Self {
+        DecodeOptions {
+            require_leading_slash: true,
+            numeric_byte_order: NumericByteOrder::Big,
+            max_args: 65536,
+            allowed_type_tags: None,
+            strict_padding: false,
+            timetag_as_arg: false,
+            max_owned_bytes: None,
+        }
+    }
+}
+
+impl DecodeOptions {
+    /// Returns the default options restricted to what `profile` allows.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use rosc::decoder::DecodeOptions;
+    /// use rosc::SpecProfile;
+    ///
+    /// // OSC 1.0, but tolerating nonzero padding
+    /// let options = DecodeOptions {
+    ///     strict_padding: false,
+    ///     ..DecodeOptions::for_profile(SpecProfile::Osc10Strict)
+    /// };
+    /// assert_eq!(options.allowed_type_tags.as_deref(), Some("ifsb"));
+    /// ```
+    pub fn for_profile(profile: SpecProfile) -> Self {
+        DecodeOptions {
+            allowed_type_tags: profile.allowed_type_tags().map(str::to_string),
+            strict_padding: profile.strict_padding(),
+            ..DecodeOptions::default()
+        }
+    }
+}
+
 /// Takes a bytes slice representing a UDP packet and returns the OSC packet as well as a slice of
 /// any bytes remaining after the OSC packet.
+///
+/// If the input ends within an element of a bundle, [`OscError::NeedMoreData`] is returned, which
+/// tells stream consumers to wait for more data instead of discarding the input as corrupt.
+///
+/// Nonstandard control packets starting with `#`, like `#time`, are rejected with
+/// [`OscError::UnsupportedControlPacket`], so that applications can tell them apart from corrupt
+/// packets and handle them themselves.
+///
+/// ```
+/// use rosc::{decoder, OscError};
+///
+/// match decoder::decode_udp(b"#time\0\0\0") {
+///     Err(OscError::UnsupportedControlPacket(tag)) => assert_eq!(tag, "#time"),
+///     other => panic!("unexpected result {:?}", other),
+/// }
+/// ```
 pub fn decode_udp(msg: &[u8]) -> Result<(&[u8], OscPacket), OscError> {
-    match decode_packet(msg, msg) {
+    decode_udp_with_options(msg, &DecodeOptions::default())
+}
+
+/// Like [`decode_udp`], but using the given decoding options.
+pub fn decode_udp_with_options<'a>(
+    msg: &'a [u8],
+    options: &DecodeOptions,
+) -> Result<(&'a [u8], OscPacket), OscError> {
+    if let Some(budget) = options.max_owned_bytes {
+        check_owned_bytes(msg, budget, options)?;
+    }
+    let result = match decode_packet(msg, msg, 0, options) {
         Ok((remainder, osc_packet)) => Ok((remainder, osc_packet)),
         Err(e) => match e {
             Err::Incomplete(_) => Err(OscError::BadPacket("Incomplete data")),
             Err::Error(e) | Err::Failure(e) => Err(e),
         },
+    };
+    let result = result.and_then(|(remainder, mut osc_packet)| {
+        if options.strict_padding {
+            check_padding(&msg[..msg.len() - remainder.len()], &osc_packet, options)?;
+        }
+        if options.timetag_as_arg {
+            insert_timetag_args(&mut osc_packet);
+        }
+        Ok((remainder, osc_packet))
+    });
+    #[cfg(feature = "tracing")]
+    match &result {
+        Ok((remainder, _)) => {
+            ::tracing::trace!(size = msg.len() - remainder.len(), "decoded packet")
+        }
+        Err(err) => ::tracing::trace!(size = msg.len(), error = %err, "failed to decode packet"),
     }
+    result
 }
 
-/// Takes a bytes slice from a TCP stream (or any stream-based protocol) and returns the first OSC
-/// packet as well as a slice of the bytes remaining after the packet.
-pub fn decode_tcp(msg: &[u8]) -> Result<(&[u8], Option<OscPacket>), OscError> {
-    let (input, osc_packet_length) = match be_u32(msg) {
-        Ok((i, o)) => (i, o),
-        Err(e) => match e {
-            Err::Incomplete(_) => return Err(OscError::BadPacket("Incomplete data")),
-            Err::Error(e) | Err::Failure(e) => return Err(e),
-        },
+/// Rejects packets estimated to allocate more than `budget` bytes, for
+/// `DecodeOptions::max_owned_bytes`. Packets that cannot be decoded are left to the decoder to
+/// reject.
+fn check_owned_bytes(msg: &[u8], budget: usize, options: &DecodeOptions) -> Result<(), OscError> {
+    match owned_bytes_estimate(msg, options) {
+        Some(needed) if needed > budget => Err(OscError::MemoryBudgetExceeded { budget, needed }),
+        _ => Ok(()),
+    }
+}
+
+/// Estimates what decoding the packet at the start of `msg` allocates, as documented for
+/// `DecodeOptions::max_owned_bytes`, or returns `None` if it cannot be decoded.
+pub(crate) fn owned_bytes_estimate(msg: &[u8], options: &DecodeOptions) -> Option<usize> {
+    let mut estimate = OwnedBytesEstimate::default();
+    walk_packet(msg, msg, 0, None, options, &mut Some(&mut estimate)).ok()?;
+    Some(estimate.total)
+}
+
+/// Sums up what decoding a packet allocates, as documented for `DecodeOptions::max_owned_bytes`.
+#[derive(Default)]
+struct OwnedBytesEstimate {
+    total: usize,
+    /// The number of arguments so far of the message and each array being decoded.
+    arg_counts: Vec<usize>,
+    bundle_depth: usize,
+}
+
+impl OwnedBytesEstimate {
+    fn add(&mut self, bytes: usize) {
+        self.total = self.total.saturating_add(bytes);
+    }
+
+    fn add_arg(&mut self) {
+        if let Some(count) = self.arg_counts.last_mut() {
+            *count += 1;
+        }
+    }
+
+    fn add_element(&mut self) {
+        if self.bundle_depth > 0 {
+            self.add(mem::size_of::<OscPacket>());
+        }
+    }
+
+    fn finish_args(&mut self) {
+        let count = self.arg_counts.pop().unwrap_or(0);
+        self.add(count.saturating_mul(mem::size_of::<OscType>()));
+    }
+}
+
+impl DecodeSink for OwnedBytesEstimate {
+    fn on_message_start(&mut self, addr: &str) {
+        self.add_element();
+        self.add(addr.len());
+        self.arg_counts.push(0);
+    }
+
+    fn on_arg(&mut self, arg: OscTypeRef) {
+        self.add_arg();
+        match arg {
+            OscTypeRef::String(s) => self.add(s.len()),
+            OscTypeRef::Blob(b) => self.add(b.len()),
+            _ => (),
+        }
+    }
+
+    fn on_array_start(&mut self) {
+        self.add_arg();
+        self.arg_counts.push(0);
+    }
+
+    fn on_array_end(&mut self) {
+        self.finish_args();
+    }
+
+    fn on_message_end(&mut self) {
+        self.finish_args();
+    }
+
+    fn on_bundle_start(&mut self, _timetag: OscTime) {
+        self.add_element();
+        self.bundle_depth += 1;
+    }
+
+    fn on_bundle_end(&mut self) {
+        self.bundle_depth -= 1;
+    }
+}
+
+/// Inserts the time tag of every bundle of `osc_packet` as the first argument of the messages
+/// directly contained in it, for `DecodeOptions::timetag_as_arg`.
+fn insert_timetag_args(osc_packet: &mut OscPacket) {
+    if let OscPacket::Bundle(bundle) = osc_packet {
+        for element in &mut bundle.content {
+            match element {
+                OscPacket::Message(msg) => msg.insert_arg(0, OscType::Time(bundle.timetag)),
+                OscPacket::Bundle(_) => insert_timetag_args(element),
+            }
+        }
+    }
+}
+
+/// Checks that `bytes` are exactly what encoding `osc_packet`, which was decoded from them, gives,
+/// for `DecodeOptions::strict_padding`.
+fn check_padding(
+    bytes: &[u8],
+    osc_packet: &OscPacket,
+    options: &DecodeOptions,
+) -> Result<(), OscError> {
+    let encode_options = encoder::EncodeOptions {
+        numeric_byte_order: options.numeric_byte_order,
+        ..encoder::EncodeOptions::default()
+    };
+    if encoder::encode_with_options(osc_packet, &encode_options)? != bytes {
+        return Err(OscError::BadPacket("Nonzero padding"));
+    }
+    Ok(())
+}
+
+/// Like [`decode_udp`], but additionally records the size of the decoded packet in `stats`.
+///
+/// Nothing is recorded if the packet could not be decoded.
+pub fn decode_udp_with_stats<'a>(
+    msg: &'a [u8],
+    stats: &mut Stats,
+) -> Result<(&'a [u8], OscPacket), OscError> {
+    let (remainder, osc_packet) = decode_udp(msg)?;
+    stats.record(msg.len() - remainder.len());
+    Ok((remainder, osc_packet))
+}
+
+/// Takes ownership of a byte vector representing a UDP packet and returns the decoded OSC packet.
+///
+/// The decoded packet does not borrow from `bytes`, so this is a convenience for callers who own
+/// the received data and don't want to keep it alive. Any bytes following the OSC packet are
+/// ignored.
+///
+/// # Example
+///
+/// ```
+/// use rosc::{decoder, encoder, OscMessage, OscPacket};
+///
+/// let packet = OscPacket::Message(OscMessage::from("/ping"));
+/// let bytes = encoder::encode(&packet).unwrap();
+/// assert_eq!(decoder::decode_owned(bytes).unwrap(), packet);
+/// ```
+pub fn decode_owned(bytes: Vec<u8>) -> Result<OscPacket, OscError> {
+    decode_udp(&bytes).map(|(_, osc_packet)| osc_packet)
+}
+
+/// Decodes a packet and checks that encoding it again gives exactly the same bytes.
+///
+/// Decoding accepts some packets that the encoder would write differently, e.g. with nonzero
+/// padding bytes or trailing data after the packet. A proxy that forwards decoded packets can use
+/// this to make sure that it doesn't change the bytes of packets it passes through unmodified. The
+/// check also catches inconsistencies between the encoder and decoder.
+///
+/// The packet is re-encoded byte by byte against `bytes`, without building a second buffer, and
+/// the offset of the first byte that differs is reported.
+///
+/// # Example
+///
+/// ```
+/// use rosc::{decoder, ExactnessError};
+///
+/// assert!(decoder::decode_exact(b"/ping\0\0\0,\0\0\0").is_ok());
+/// // Nonzero padding after the address
+/// match decoder::decode_exact(b"/ping\0\xff\0,\0\0\0") {
+///     Err(ExactnessError::Mismatch { offset }) => assert_eq!(offset, 6),
+///     other => panic!("unexpected result {:?}", other),
+/// }
+/// ```
+pub fn decode_exact(bytes: &[u8]) -> Result<OscPacket, ExactnessError> {
+    let (_, osc_packet) = decode_udp(bytes)?;
+    let mut comparison = Comparison {
+        expected: bytes,
+        len: 0,
+        mismatch: None,
     };
+    encoder::write_packet(&mut comparison, &osc_packet);
 
-    if osc_packet_length as usize > msg.len() {
-        return Ok((msg, None));
+    // Trailing bytes that weren't encoded again differ as well
+    match comparison.mismatch {
+        None if comparison.len == bytes.len() => Ok(osc_packet),
+        mismatch => Err(ExactnessError::Mismatch {
+            offset: mismatch.unwrap_or(comparison.len),
+        }),
     }
+}
 
-    match decode_packet(input, msg).map(|(remainder, osc_packet)| (remainder, Some(osc_packet))) {
-        Ok((remainder, osc_packet)) => Ok((remainder, osc_packet)),
-        Err(e) => match e {
-            Err::Incomplete(_) => Err(OscError::BadPacket("Incomplete data")),
-            Err::Error(e) | Err::Failure(e) => Err(e),
-        },
+/// Compares encoded bytes to `expected`, recording the offset of the first difference.
+struct Comparison<'a> {
+    expected: &'a [u8],
+    /// The number of encoded bytes so far.
+    len: usize,
+    mismatch: Option<usize>,
+}
+
+impl encoder::ByteSink for Comparison<'_> {
+    fn put(&mut self, bytes: &[u8]) {
+        if self.mismatch.is_none() {
+            let expected = self.expected.get(self.len..).unwrap_or_default();
+            self.mismatch = match bytes.iter().zip(expected).position(|(a, b)| a != b) {
+                Some(i) => Some(self.len + i),
+                // Encoded bytes beyond the end of `expected`
+                None if bytes.len() > expected.len() => Some(self.expected.len()),
+                None => None,
+            };
+        }
+        self.len += bytes.len();
+    }
+}
+
+/// Decodes a single message into `msg`, reusing the allocations of its address and arguments.
+///
+/// The address and arguments of `msg` are replaced, but their capacity is kept, so that a receive
+/// loop handling single-message datagrams doesn't need to allocate for them once they have grown
+/// large enough. Strings, blobs and arrays within the arguments are still allocated. Bundles are
+/// rejected with an [`OscError::BadMessage`], and any bytes following the message are ignored.
+/// If an error is returned, the contents of `msg` are unspecified.
+///
+/// # Example
+///
+/// ```
+/// use rosc::{decoder, encoder, OscMessage, OscPacket, OscType};
+///
+/// let packet = OscPacket::Message(OscMessage {
+///     addr: "/level".to_string(),
+///     args: vec![OscType::Float(0.5)],
+/// });
+/// let bytes = encoder::encode(&packet).unwrap();
+///
+/// let mut msg = OscMessage::from("/");
+/// decoder::decode_message_into(&bytes, &mut msg).unwrap();
+/// assert_eq!(OscPacket::Message(msg), packet);
+/// ```
+pub fn decode_message_into(bytes: &[u8], msg: &mut OscMessage) -> Result<(), OscError> {
+    let (address, type_tags, args_start) = read_signature(bytes)?;
+    msg.addr.clear();
+    msg.addr.push_str(address);
+
+    let mut args = core::mem::take(&mut msg.args);
+    args.clear();
+    let (_, args) = finish(read_osc_args_into(
+        &bytes[args_start..],
+        bytes,
+        type_tags.chars(),
+        args,
+    ))?;
+    msg.args = args;
+    Ok(())
+}
+
+/// Receives the contents of a packet as it is decoded by [`decode_to_sink`], so that applications
+/// can build their own representation without the owned types of this crate ever being created.
+///
+/// A message is reported as [`on_message_start`](DecodeSink::on_message_start), one
+/// [`on_arg`](DecodeSink::on_arg) per argument and [`on_message_end`](DecodeSink::on_message_end).
+/// The elements of an array are reported between [`on_array_start`](DecodeSink::on_array_start)
+/// and [`on_array_end`](DecodeSink::on_array_end). A bundle is reported as
+/// [`on_bundle_start`](DecodeSink::on_bundle_start), its elements in order and
+/// [`on_bundle_end`](DecodeSink::on_bundle_end).
+pub trait DecodeSink {
+    /// Called at the start of a message, before its arguments.
+    fn on_message_start(&mut self, addr: &str);
+    /// Called for each argument of a message, including the elements of arrays.
+    fn on_arg(&mut self, arg: OscTypeRef);
+    /// Called at the start of an array argument, before its elements.
+    fn on_array_start(&mut self);
+    /// Called after the last element of an array argument.
+    fn on_array_end(&mut self);
+    /// Called after the last argument of a message.
+    fn on_message_end(&mut self);
+    /// Called at the start of a bundle, before its elements.
+    fn on_bundle_start(&mut self, timetag: OscTime);
+    /// Called after the last element of a bundle.
+    fn on_bundle_end(&mut self);
+}
+
+/// Like [`decode_udp`], but passing the contents of the packet to `sink` instead of returning
+/// them, and returning only the bytes remaining after the packet.
+///
+/// The packet is validated before anything is passed to `sink`, so if an error is returned,
+/// `sink` hasn't been called at all. Bundle elements that [`decode_udp`] would drop are skipped
+/// in the same way.
+///
+/// # Example
+///
+/// ```
+/// use rosc::decoder::{self, DecodeSink};
+/// use rosc::{encoder, OscMessage, OscPacket, OscTime, OscTypeRef};
+///
+/// /// Counts the messages of a packet and adds up their float arguments.
+/// #[derive(Default)]
+/// struct FloatSum {
+///     messages: usize,
+///     sum: f32,
+/// }
+///
+/// impl DecodeSink for FloatSum {
+///     fn on_message_start(&mut self, _addr: &str) {
+///         self.messages += 1;
+///     }
+///     fn on_arg(&mut self, arg: OscTypeRef) {
+///         if let OscTypeRef::Float(x) = arg {
+///             self.sum += x;
+///         }
+///     }
+///     fn on_array_start(&mut self) {}
+///     fn on_array_end(&mut self) {}
+///     fn on_message_end(&mut self) {}
+///     fn on_bundle_start(&mut self, _timetag: OscTime) {}
+///     fn on_bundle_end(&mut self) {}
+/// }
+///
+/// let bytes = encoder::encode(&OscPacket::Message(OscMessage {
+///     addr: "/levels".to_string(),
+///     args: vec![0.25f32.into(), 0.5f32.into()],
+/// }))
+/// .unwrap();
+///
+/// let mut sum = FloatSum::default();
+/// decoder::decode_to_sink(&bytes, &mut sum).unwrap();
+/// assert_eq!(sum.messages, 1);
+/// assert_eq!(sum.sum, 0.75);
+/// ```
+pub fn decode_to_sink<'a, S: DecodeSink>(
+    msg: &'a [u8],
+    sink: &mut S,
+) -> Result<&'a [u8], OscError> {
+    decode_to_sink_with_options(msg, &DecodeOptions::default(), sink)
+}
+
+/// Like [`decode_to_sink`], but using the given decoding options.
+pub fn decode_to_sink_with_options<'a, S: DecodeSink>(
+    msg: &'a [u8],
+    options: &DecodeOptions,
+    sink: &mut S,
+) -> Result<&'a [u8], OscError> {
+    if options.strict_padding {
+        decode_udp_with_options(msg, options)?;
+    } else {
+        finish(walk_packet(msg, msg, 0, None, options, &mut None::<&mut S>))?;
+    }
+    finish(walk_packet(msg, msg, 0, None, options, &mut Some(sink)))
+        .map(|(remainder, ())| remainder)
+}
+
+/// Reads only the address of an encoded message, without looking at its type tags or arguments.
+///
+/// Returns `None` if `msg` is a bundle. This is meant for dropping unwanted packets cheaply
+/// before decoding them, see [`filter::AddressFilter`](crate::filter::AddressFilter).
+///
+/// # Example
+///
+/// ```
+/// use rosc::{decoder, encoder, OscMessage, OscPacket};
+///
+/// let bytes = encoder::encode(&OscPacket::Message(OscMessage::from("/ping"))).unwrap();
+/// assert_eq!(decoder::peek_address(&bytes).unwrap(), Some("/ping"));
+/// ```
+pub fn peek_address(msg: &[u8]) -> Result<Option<&str>, OscError> {
+    if msg.is_empty() {
+        return Err(OscError::BadPacket("Empty packet."));
+    }
+    if msg.starts_with(BUNDLE_TAG) {
+        return Ok(None);
+    }
+    if msg[0] == b'#' {
+        return Err(OscError::UnsupportedControlPacket(control_packet_tag(msg)));
+    }
+    let (_, address) = finish(read_osc_str(msg, msg))?;
+    Ok(Some(address))
+}
+
+/// Returns the tag of a control packet, which extends up to the first null byte.
+fn control_packet_tag(packet: &[u8]) -> String {
+    let len = packet.iter().position(|&b| b == 0).unwrap_or(packet.len());
+    String::from_utf8_lossy(&packet[..len]).into_owned()
+}
+
+/// The shape of an encoded bundle, as returned by [`bundle_stats`].
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct BundleStats {
+    /// The number of top-level elements.
+    pub element_count: usize,
+    /// The size of the largest top-level element, excluding its size prefix.
+    pub max_element_size: usize,
+    /// The size of the whole packet, as far as it is covered by the input.
+    pub total_size: usize,
+    /// The number of nested bundle levels, counting the outermost bundle as 1. Nesting deeper
+    /// than [`MAX_BUNDLE_DEPTH`] is not examined, so the result is capped at that value plus one.
+    pub depth_estimate: usize,
+    /// `false` if a size field lied about the remaining input, was 0 or was not a multiple of 4,
+    /// or if the input ended in the middle of a header or size field. Sizes are clamped to the
+    /// input, so the other statistics remain meaningful, but decoding the packet will likely
+    /// fail.
+    pub consistent: bool,
+}
+
+/// Examines the structure of an encoded bundle by following only its size fields, without
+/// decoding any messages.
+///
+/// This is cheap enough to decide whether a packet is worth decoding at all, e.g. to drop
+/// bundles with too many or too large elements. A message is reported as a single element
+/// spanning the whole input, with a depth of 0. An error is only returned for empty input.
+///
+/// # Example
+///
+/// ```
+/// use rosc::decoder;
+/// use rosc::{encoder, OscBundle, OscMessage, OscPacket, OscTime};
+///
+/// let bundle = OscPacket::Bundle(OscBundle {
+///     timetag: OscTime::IMMEDIATE,
+///     content: vec![
+///         OscPacket::Message(OscMessage::from("/a")),
+///         OscPacket::Message(OscMessage::from("/long/address")),
+///     ],
+/// });
+/// let bytes = encoder::encode(&bundle).unwrap();
+///
+/// let stats = decoder::bundle_stats(&bytes).unwrap();
+/// assert_eq!(stats.element_count, 2);
+/// assert_eq!(stats.max_element_size, 20);
+/// assert_eq!(stats.total_size, bytes.len());
+/// assert_eq!(stats.depth_estimate, 1);
+/// assert!(stats.consistent);
+/// ```
+pub fn bundle_stats(bytes: &[u8]) -> Result<BundleStats, OscError> {
+    if bytes.is_empty() {
+        return Err(OscError::BadPacket("Empty packet."));
+    }
+
+    let mut stats = BundleStats {
+        consistent: true,
+        ..BundleStats::default()
+    };
+    if !bytes.starts_with(BUNDLE_TAG) {
+        stats.element_count = 1;
+        stats.max_element_size = bytes.len();
+        stats.total_size = bytes.len();
+        return Ok(stats);
+    }
+
+    let mut elements = Vec::new();
+    stats.total_size = walk_bundle_sizes(bytes, 1, &mut stats, Some(&mut elements));
+    stats.element_count = elements.len();
+    stats.max_element_size = elements.into_iter().max().unwrap_or(0);
+    Ok(stats)
+}
+
+/// Follows the size fields of the bundle at the start of `bytes`, recording the size of each
+/// top-level element in `elements`, and returns the number of bytes the bundle covers.
+fn walk_bundle_sizes(
+    bytes: &[u8],
+    depth: usize,
+    stats: &mut BundleStats,
+    mut elements: Option<&mut Vec<usize>>,
+) -> usize {
+    stats.depth_estimate = stats.depth_estimate.max(depth);
+    // The tag and the time tag
+    if bytes.len() < 16 {
+        stats.consistent = false;
+        return bytes.len();
+    }
+
+    let mut offset = 16;
+    while offset < bytes.len() {
+        let remaining = bytes.len() - offset;
+        if remaining < 4 {
+            stats.consistent = false;
+            return bytes.len();
+        }
+        let declared = BigEndian::read_u32(&bytes[offset..]) as usize;
+        offset += 4;
+
+        let size = declared.min(remaining - 4);
+        if size != declared || size == 0 || !size.is_multiple_of(4) {
+            stats.consistent = false;
+        }
+        if let Some(ref mut elements) = elements {
+            elements.push(size);
+        }
+
+        let element = &bytes[offset..offset + size];
+        if element.starts_with(BUNDLE_TAG) && depth <= MAX_BUNDLE_DEPTH {
+            walk_bundle_sizes(element, depth + 1, stats, None);
+        }
+        offset += size;
+    }
+    offset
+}
+
+/// Takes a bytes slice from a TCP stream (or any stream-based protocol) and returns the first OSC
+/// packet as well as a slice of the bytes remaining after the packet.
+pub fn decode_tcp(msg: &[u8]) -> Result<(&[u8], Option<OscPacket>), OscError> {
+    decode_tcp_with_options(msg, &DecodeOptions::default())
+}
+
+/// Like [`decode_tcp`], but using the given decoding options.
+pub fn decode_tcp_with_options<'a>(
+    msg: &'a [u8],
+    options: &DecodeOptions,
+) -> Result<(&'a [u8], Option<OscPacket>), OscError> {
+    match framing::decode_framed_with_options(msg, Framing::LengthPrefixed, options) {
+        Ok((len, osc_packet)) => Ok((&msg[len..], Some(osc_packet))),
+        // An incomplete size field is an error rather than a reason to wait for more data
+        Err(OscError::NeedMoreData { .. }) if msg.len() < 4 => {
+            Err(OscError::ReadError(ErrorKind::Eof))
+        }
+        Err(OscError::NeedMoreData { .. }) => Ok((msg, None)),
+        Err(err) => Err(err),
     }
 }
 
 /// Takes a bytes slice from a TCP stream (or any stream-based protocol) and returns a vec of all
 /// OSC packets in the slice as well as a slice of the bytes remaining after the last packet.
 pub fn decode_tcp_vec(msg: &[u8]) -> Result<(&[u8], Vec<OscPacket>), OscError> {
-    let mut input = msg;
+    decode_tcp_vec_with_options(msg, &DecodeOptions::default())
+}
+
+/// Like [`decode_tcp_vec`], but using the given decoding options.
+pub fn decode_tcp_vec_with_options<'a>(
+    msg: &'a [u8],
+    options: &DecodeOptions,
+) -> Result<(&'a [u8], Vec<OscPacket>), OscError> {
     let mut osc_packets = vec![];
+    let input = decode_tcp_frames(msg, options, &mut osc_packets)?;
+    Ok((input, osc_packets))
+}
+
+/// Like [`decode_tcp_vec`], but decoding into `out` instead of a new vector, so that a decode loop
+/// can reuse its allocation.
+///
+/// `out` is cleared first, then all complete packets are appended to it and their number is
+/// returned. An incomplete packet at the end of `bytes` is not decoded, use [`decode_tcp_vec`] to
+/// find out where it starts. If an error is returned, `out` holds the packets preceding the
+/// invalid one.
+///
+/// # Example
+///
+/// ```
+/// use rosc::{decoder, encoder, OscMessage, OscPacket};
+///
+/// let packet = OscPacket::Message(OscMessage::from("/ping"));
+/// let mut bytes = vec![];
+/// for _ in 0..2 {
+///     let frame = encoder::encode(&packet).unwrap();
+///     bytes.extend_from_slice(&(frame.len() as u32).to_be_bytes());
+///     bytes.extend_from_slice(&frame);
+/// }
+///
+/// let mut packets = Vec::new();
+/// assert_eq!(decoder::decode_framed_into(&bytes, &mut packets).unwrap(), 2);
+/// assert_eq!(packets, vec![packet.clone(), packet]);
+/// ```
+pub fn decode_framed_into(bytes: &[u8], out: &mut Vec<OscPacket>) -> Result<usize, OscError> {
+    out.clear();
+    decode_tcp_frames(bytes, &DecodeOptions::default(), out)?;
+    Ok(out.len())
+}
 
-    while let (remainder, Some(osc_packet)) = decode_tcp(input)? {
+/// Appends all complete length-prefixed packets of `msg` to `out`, returning the bytes after them.
+fn decode_tcp_frames<'a>(
+    msg: &'a [u8],
+    options: &DecodeOptions,
+    out: &mut Vec<OscPacket>,
+) -> Result<&'a [u8], OscError> {
+    let mut input = msg;
+    while let (remainder, Some(osc_packet)) = decode_tcp_with_options(input, options)? {
         input = remainder;
-        osc_packets.push(osc_packet);
+        out.push(osc_packet);
 
         if remainder.is_empty() {
             break;
         }
     }
+    Ok(input)
+}
 
-    Ok((input, osc_packets))
+/// Returns the offset of the next plausible packet start at or after `from`, for recovering from
+/// corrupt data in a stream.
+///
+/// This is a heuristic and only best-effort: a plausible start is either the bundle tag
+/// `#bundle\0`, or a `/` followed by printable characters, a null terminator with padding up to
+/// a multiple of 4 bytes, and the `,` starting the type tags. A candidate that is cut off by the
+/// end of `bytes` is returned as long as the available bytes agree with it, so that a stream
+/// consumer can wait for the rest. Garbage can happen to look like a packet start, so the result
+/// must still be decoded, and valid packets that don't follow the specification are not found.
+///
+/// See [`LenientReader`] for a reader of length-prefixed streams built on top of this.
+///
+/// # Example
+///
+/// ```
+/// use rosc::{decoder, encoder, OscMessage, OscPacket};
+///
+/// let mut bytes = b"garbage/".to_vec();
+/// bytes.extend(encoder::encode(&OscPacket::Message(OscMessage::from("/ping"))).unwrap());
+/// assert_eq!(decoder::resync(&bytes, 0), Some(8));
+/// ```
+pub fn resync(bytes: &[u8], from: usize) -> Option<usize> {
+    (from..bytes.len()).find(|&offset| is_plausible_packet_start(&bytes[offset..]))
+}
+
+/// Checks whether `bytes` could start with a packet, see [`resync`].
+fn is_plausible_packet_start(bytes: &[u8]) -> bool {
+    match bytes[0] {
+        b'#' => {
+            let len = bytes.len().min(BUNDLE_TAG.len());
+            bytes[..len] == BUNDLE_TAG[..len]
+        }
+        b'/' => {
+            let addr_len = match bytes.iter().position(|&b| !b.is_ascii_graphic()) {
+                Some(addr_len) => addr_len,
+                None => return true,
+            };
+            let type_tags_start = pad_to_4(addr_len + 1);
+            bytes[addr_len..type_tags_start.min(bytes.len())]
+                .iter()
+                .all(|&b| b == 0)
+                && bytes.get(type_tags_start).is_none_or(|&b| b == b',')
+        }
+        _ => false,
+    }
+}
+
+/// A best-effort reader of length-prefixed packets from a stream that may contain corrupt data,
+/// like [`decode_tcp`] but skipping over anything it can't decode.
+///
+/// Received data is appended using [`push`](LenientReader::push), and decoded packets are taken
+/// out using [`next_packet`](LenientReader::next_packet). If a length prefix is implausible or
+/// its packet can't be decoded, the reader looks for the next plausible packet start using
+/// [`resync`] and assumes that the four bytes before it are its length prefix. The number of bytes
+/// that were dropped this way is counted by [`skipped_bytes`](LenientReader::skipped_bytes), and
+/// the number of times data was dropped by [`resyncs`](LenientReader::resyncs).
+///
+/// Like [`resync`], this is a heuristic. Packets can be lost even after the corruption ended, and
+/// garbage that happens to decode is returned as a packet.
+///
+/// # Example
+///
+/// ```
+/// use rosc::decoder::LenientReader;
+/// use rosc::{encoder, OscMessage, OscPacket};
+///
+/// let packet = OscPacket::Message(OscMessage::from("/ping"));
+/// let frame = encoder::encode(&packet).unwrap();
+///
+/// let mut reader = LenientReader::new(1024);
+/// reader.push(b"\xde\xad\xbe\xef");
+/// reader.push(&(frame.len() as u32).to_be_bytes());
+/// reader.push(&frame);
+/// assert_eq!(reader.next_packet(), Some(packet));
+/// assert_eq!(reader.next_packet(), None);
+/// assert_eq!(reader.skipped_bytes(), 4);
+/// assert_eq!(reader.resyncs(), 1);
+/// ```
+#[derive(Clone, Debug)]
+pub struct LenientReader {
+    buf: Vec<u8>,
+    max_packet_size: usize,
+    options: DecodeOptions,
+    skipped: u64,
+    resyncs: u64,
+}
+
+impl LenientReader {
+    /// Creates a reader that treats length prefixes larger than `max_packet_size` as corrupt.
+    ///
+    /// Without such a limit, a corrupt length prefix would make the reader wait for data forever.
+    pub fn new(max_packet_size: usize) -> Self {
+        Self::with_options(max_packet_size, DecodeOptions::default())
+    }
+
+    /// Like [`new`](LenientReader::new), but decoding packets using the given options.
+    pub fn with_options(max_packet_size: usize, options: DecodeOptions) -> Self {
+        LenientReader {
+            buf: Vec::new(),
+            max_packet_size,
+            options,
+            skipped: 0,
+            resyncs: 0,
+        }
+    }
+
+    /// Appends data received from the stream.
+    pub fn push(&mut self, bytes: &[u8]) {
+        self.buf.extend_from_slice(bytes);
+    }
+
+    /// Decodes the next packet, skipping corrupt data before it. Returns `None` if more data is
+    /// needed.
+    pub fn next_packet(&mut self) -> Option<OscPacket> {
+        self.next_frame(|frame, options| {
+            decode_udp_with_options(frame, options).map(|(_, osc_packet)| osc_packet)
+        })
+    }
+
+    /// Like [`next_packet`](LenientReader::next_packet), but passing the contents of the next
+    /// packet to `sink` like [`decode_to_sink`]. Returns `false` if more data is needed.
+    pub fn next_packet_to_sink<S: DecodeSink>(&mut self, sink: &mut S) -> bool {
+        self.next_frame(|frame, options| {
+            decode_to_sink_with_options(frame, options, sink).map(|_| ())
+        })
+        .is_some()
+    }
+
+    /// Decodes the next frame using `decode`, skipping corrupt data before it, including frames
+    /// that `decode` fails on.
+    fn next_frame<T, F>(&mut self, mut decode: F) -> Option<T>
+    where
+        F: FnMut(&[u8], &DecodeOptions) -> Result<T, OscError>,
+    {
+        while self.buf.len() >= 4 {
+            let len = BigEndian::read_u32(&self.buf) as usize;
+            let plausible = len >= 4
+                && len.is_multiple_of(4)
+                && len <= self.max_packet_size
+                && resync(&self.buf[..self.buf.len().min(4 + len)], 4) == Some(4);
+            if plausible {
+                if self.buf.len() < 4 + len {
+                    return None;
+                }
+                if let Ok(decoded) = decode(&self.buf[4..4 + len], &self.options) {
+                    self.buf.drain(..4 + len);
+                    return Some(decoded);
+                }
+            }
+
+            // The length prefix of the next candidate directly precedes it. If there is none, a
+            // packet can only start after the end of the buffer, but its length prefix may have
+            // been received already.
+            let skip = match resync(&self.buf, 5) {
+                Some(offset) => offset - 4,
+                None => self.buf.len() - 4,
+            };
+            if skip == 0 {
+                return None;
+            }
+            self.buf.drain(..skip);
+            self.skipped += skip as u64;
+            self.resyncs += 1;
+        }
+        None
+    }
+
+    /// Returns the total number of bytes that were dropped as corrupt.
+    pub fn skipped_bytes(&self) -> u64 {
+        self.skipped
+    }
+
+    /// Returns the number of times corrupt data was dropped.
+    pub fn resyncs(&self) -> u64 {
+        self.resyncs
+    }
+}
+
+/// The address and type tags of an encoded message, as returned by [`decode_signature`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Signature {
+    /// The address of the message.
+    pub address: String,
+    /// The type tags of the message.
+    pub type_tags: TypeSignature,
+}
+
+impl Signature {
+    /// Returns the type tags of each top-level argument. An array argument consists of multiple
+    /// type tags, including its brackets.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use rosc::decoder::Signature;
+    /// use rosc::signature::TypeSignature;
+    ///
+    /// let signature = Signature {
+    ///     address: "/mixer".to_string(),
+    ///     type_tags: TypeSignature::parse("i[ff]s").unwrap(),
+    /// };
+    /// assert_eq!(signature.arg_type_tags(), vec!["i", "[ff]", "s"]);
+    /// ```
+    pub fn arg_type_tags(&self) -> Vec<&str> {
+        split_arg_type_tags(self.type_tags.as_str())
+    }
+
+    /// Returns the offset at which the argument data of the encoded message begins.
+    fn data_offset(&self) -> usize {
+        pad_to_4(self.address.len() + 1) + pad_to_4(self.type_tags.as_str().len() + 2)
+    }
+}
+
+/// Decodes only the address and type tags of an encoded message, returning them together with
+/// the offset at which the argument data begins.
+///
+/// This allows checking the shape of a message before decoding its arguments, or decoding only
+/// some of them using [`decode_arg_at`]. The type tags can be checked against an expected
+/// signature with [`TypeSignature::is_compatible_with`]. An error is returned if `msg` is not a
+/// message, or if its type tags are not a valid [`TypeSignature`].
+///
+/// # Example
+///
+/// ```
+/// use rosc::{decoder, encoder, OscMessage, OscPacket, OscType};
+///
+/// let bytes = encoder::encode(&OscPacket::Message(OscMessage {
+///     addr: "/mixer/gain".to_string(),
+///     args: vec![OscType::Int(3), OscType::Float(0.5)],
+/// }))
+/// .unwrap();
+///
+/// let (signature, offset) = decoder::decode_signature(&bytes).unwrap();
+/// assert_eq!(signature.address, "/mixer/gain");
+/// assert_eq!(signature.type_tags.as_str(), "if");
+/// assert_eq!(offset, 16);
+/// ```
+pub fn decode_signature(msg: &[u8]) -> Result<(Signature, usize), OscError> {
+    let (address, type_tags, offset) = read_signature(msg)?;
+    let signature = Signature {
+        address: address.to_string(),
+        type_tags: TypeSignature::parse(type_tags)?,
+    };
+    Ok((signature, offset))
+}
+
+/// Decodes only the argument at index `n` of an encoded message, whose signature was decoded
+/// using [`decode_signature`].
+///
+/// The arguments before `n` are skipped based on their size without being decoded. Fixed size
+/// arguments are skipped directly, while strings and blobs need to be scanned. An array counts as
+/// a single argument.
+///
+/// # Example
+///
+/// ```
+/// use rosc::{decoder, encoder, OscMessage, OscPacket, OscType};
+///
+/// let bytes = encoder::encode(&OscPacket::Message(OscMessage {
+///     addr: "/track".to_string(),
+///     args: vec!["name".into(), OscType::Blob(vec![1, 2, 3]), OscType::Float(0.5)],
+/// }))
+/// .unwrap();
+///
+/// let (signature, _) = decoder::decode_signature(&bytes).unwrap();
+/// assert_eq!(decoder::decode_arg_at(&bytes, &signature, 2).unwrap(), OscType::Float(0.5));
+/// ```
+pub fn decode_arg_at(msg: &[u8], signature: &Signature, n: usize) -> Result<OscType, OscError> {
+    let arg_type_tags = signature.arg_type_tags();
+    let type_tags = match arg_type_tags.get(n) {
+        Some(type_tags) => *type_tags,
+        None => {
+            return Err(OscError::BadArg(format!(
+                "Message has no argument at index {}",
+                n
+            )))
+        }
+    };
+
+    let mut input = msg
+        .get(signature.data_offset()..)
+        .ok_or(OscError::BadMessage(
+            "Message is shorter than its signature",
+        ))?;
+    for tag in arg_type_tags[..n].iter().flat_map(|tags| tags.chars()) {
+        input = finish(skip_osc_arg(input, tag))?.0;
+    }
+
+    read_top_level_arg(input, msg, type_tags)
+}
+
+/// A lazily decoded view of an encoded message.
+///
+/// Only the address and type tags are decoded when the view is created, and both are borrowed
+/// from the encoded message. Arguments are decoded individually on access. The offsets of the
+/// arguments are cached while skipping over them, so that every argument is only ever skipped
+/// once, no matter how often or in which order the arguments are accessed.
+///
+/// # Example
+///
+/// ```
+/// use rosc::decoder::MessageView;
+/// use rosc::{encoder, OscMessage, OscPacket, OscType};
+///
+/// let bytes = encoder::encode(&OscPacket::Message(OscMessage {
+///     addr: "/track".to_string(),
+///     args: vec!["name".into(), OscType::Blob(vec![1, 2, 3]), OscType::Float(0.5)],
+/// }))
+/// .unwrap();
+///
+/// let view = MessageView::new(&bytes).unwrap();
+/// assert_eq!(view.addr(), "/track");
+/// assert_eq!(view.tags(), "sbf");
+/// assert_eq!(view.arg_count(), 3);
+/// assert_eq!(view.arg(2).unwrap(), OscType::Float(0.5));
+/// ```
+#[derive(Clone, Debug)]
+pub struct MessageView<'a> {
+    msg: &'a [u8],
+    addr: &'a str,
+    tags: &'a str,
+    arg_tags: Vec<&'a str>,
+    /// The offsets of the arguments whose position is known so far, starting with the first one.
+    offsets: RefCell<Vec<usize>>,
+}
+
+impl<'a> MessageView<'a> {
+    /// Creates a view of the encoded message `msg`, decoding only its address and type tags.
+    ///
+    /// An error is returned under the same conditions as by [`decode_signature`].
+    pub fn new(msg: &'a [u8]) -> Result<Self, OscError> {
+        let (addr, tags, offset) = read_signature(msg)?;
+        Ok(MessageView {
+            msg,
+            addr,
+            tags,
+            arg_tags: split_arg_type_tags(tags),
+            offsets: RefCell::new(vec![offset]),
+        })
+    }
+
+    /// Returns the address of the message.
+    pub fn addr(&self) -> &'a str {
+        self.addr
+    }
+
+    /// Returns the type tags of the message, without the leading `,`.
+    pub fn tags(&self) -> &'a str {
+        self.tags
+    }
+
+    /// Returns the number of arguments of the message. An array counts as a single argument.
+    pub fn arg_count(&self) -> usize {
+        self.arg_tags.len()
+    }
+
+    /// Decodes the argument at index `n`.
+    ///
+    /// The first access to an argument skips over all arguments before it whose offsets aren't
+    /// known yet. Any further access to it, or to an argument before it, decodes it directly.
+    pub fn arg(&self, n: usize) -> Result<OscType, OscError> {
+        let type_tags = match self.arg_tags.get(n) {
+            Some(type_tags) => *type_tags,
+            None => {
+                return Err(OscError::BadArg(format!(
+                    "Message has no argument at index {}",
+                    n
+                )))
+            }
+        };
+
+        let mut offsets = self.offsets.borrow_mut();
+        while offsets.len() <= n {
+            let known = offsets.len() - 1;
+            let mut input = &self.msg[offsets[known]..];
+            for tag in self.arg_tags[known].chars() {
+                input = finish(skip_osc_arg(input, tag))?.0;
+            }
+            offsets.push(self.msg.offset(input));
+        }
+        let input = &self.msg[offsets[n]..];
+        drop(offsets);
+
+        read_top_level_arg(input, self.msg, type_tags)
+    }
+
+    /// Decodes all arguments, returning an owned message.
+    pub fn to_message(&self) -> Result<OscMessage, OscError> {
+        Ok(OscMessage {
+            addr: self.addr.to_string(),
+            args: (0..self.arg_count())
+                .map(|n| self.arg(n))
+                .collect::<Result<_, _>>()?,
+        })
+    }
+}
+
+/// Reads the address and type tags of a message, returning them together with the offset at
+/// which the argument data begins.
+fn read_signature(msg: &[u8]) -> Result<(&str, &str, usize), OscError> {
+    let (input, address) = finish(read_osc_str(msg, msg))?;
+    if !address.starts_with('/') {
+        return Err(OscError::BadMessage("Packet is not a message"));
+    }
+    let (input, type_tags) = finish(read_osc_str(input, msg))?;
+    let type_tags = match type_tags.strip_prefix(',') {
+        Some(type_tags) => type_tags,
+        None => return Err(OscError::BadMessage("Type tags must start with ','")),
+    };
+    check_array_brackets(type_tags)?;
+
+    Ok((address, type_tags, msg.offset(input)))
+}
+
+/// Checks that the array brackets in type tags are balanced.
+fn check_array_brackets(type_tags: &str) -> Result<(), OscError> {
+    let mut depth = 0usize;
+    for tag in type_tags.chars() {
+        match tag {
+            '[' => depth += 1,
+            ']' => {
+                depth = depth
+                    .checked_sub(1)
+                    .ok_or(OscError::BadMessage("Encountered ] outside array"))?
+            }
+            _ => (),
+        }
+    }
+    if depth != 0 {
+        return Err(OscError::BadMessage("Unterminated array"));
+    }
+    Ok(())
+}
+
+/// Splits type tags into those of each top-level argument, see [`Signature::arg_type_tags`].
+fn split_arg_type_tags(type_tags: &str) -> Vec<&str> {
+    let mut args = Vec::new();
+    let mut start = 0;
+    let mut depth = 0usize;
+    for (i, tag) in type_tags.char_indices() {
+        match tag {
+            '[' => depth += 1,
+            ']' => depth = depth.saturating_sub(1),
+            _ => (),
+        }
+        if depth == 0 {
+            args.push(&type_tags[start..i + tag.len_utf8()]);
+            start = i + tag.len_utf8();
+        }
+    }
+    args
+}
+
+/// Reads a single top-level argument, which is an array if `type_tags` starts with `[`.
+fn read_top_level_arg(
+    input: &[u8],
+    original_input: &[u8],
+    type_tags: &str,
+) -> Result<OscType, OscError> {
+    if type_tags.starts_with('[') {
+        let (_, mut args) = finish(read_osc_args(
+            input,
+            original_input,
+            format!(",{}", type_tags),
+        ))?;
+        Ok(args.remove(0))
+    } else {
+        let tag = type_tags.chars().next().unwrap_or_default();
+        finish(read_osc_arg(input, original_input, tag)).map(|(_, arg)| arg)
+    }
+}
+
+/// Skips over the data of an argument with the type tag `tag`.
+fn skip_osc_arg(input: &[u8], tag: char) -> IResult<&[u8], (), OscError> {
+    let size = match TypeTag::from_char(tag) {
+        Some(TypeTag::String) => match input.iter().position(|&b| b == 0) {
+            Some(len) => pad_to_4(len + 1),
+            None => return Err(nom::Err::Error(OscError::BadString("Unterminated string"))),
+        },
+        Some(TypeTag::Blob) => {
+            let (_, size) = be_u32(input)?;
+            4 + pad_to_4(size as usize)
+        }
+        // Only strings and blobs vary in size
+        Some(tag) => tag.fixed_payload_size().unwrap_or_default(),
+        None => {
+            return Err(nom::Err::Error(OscError::BadArg(format!(
+                "Type tag \"{}\" is not implemented!",
+                tag
+            ))))
+        }
+    };
+    map(take(size), |_| ())(input)
+}
+
+/// Converts the result of a parser into the result type of the public decoding functions.
+fn finish<T>(result: IResult<&[u8], T, OscError>) -> Result<(&[u8], T), OscError> {
+    result.map_err(|e| match e {
+        Err::Incomplete(_) => OscError::BadPacket("Incomplete data"),
+        Err::Error(e) | Err::Failure(e) => e,
+    })
+}
+
+/// Like `decode_packet`, but passing the contents of the packet to `sink`. Without a sink, the
+/// packet is only validated. A message is passed `timetag` as its first argument, see
+/// `DecodeOptions::timetag_as_arg`.
+fn walk_packet<'a, S: DecodeSink>(
+    input: &'a [u8],
+    original_input: &'a [u8],
+    depth: usize,
+    timetag: Option<OscTime>,
+    options: &DecodeOptions,
+    sink: &mut Option<&mut S>,
+) -> IResult<&'a [u8], (), OscError> {
+    if let Some(input) = strip_bundle_tag(input)? {
+        return walk_bundle(input, original_input, depth + 1, options, sink);
+    }
+    let (mut input, (addr, tags)) = read_message_header(input, original_input, options)?;
+
+    if let Some(sink) = sink {
+        sink.on_message_start(addr);
+        if let Some(timetag) = timetag {
+            sink.on_arg(OscTypeRef::Time(timetag));
+        }
+    }
+    for tag in tags.chars() {
+        match tag {
+            '[' => {
+                if let Some(sink) = sink {
+                    sink.on_array_start();
+                }
+            }
+            ']' => {
+                if let Some(sink) = sink {
+                    sink.on_array_end();
+                }
+            }
+            _ => {
+                let (remainder, mut arg) = read_osc_arg_ref(input, original_input, tag)?;
+                input = remainder;
+                if options.numeric_byte_order == NumericByteOrder::Little {
+                    arg = arg.swap_numeric_bytes();
+                }
+                if let Some(sink) = sink {
+                    sink.on_arg(arg);
+                }
+            }
+        }
+    }
+    if let Some(sink) = sink {
+        sink.on_message_end();
+    }
+    Ok((input, ()))
+}
+
+/// Like `decode_bundle`, but passing the contents of the bundle to `sink`.
+fn walk_bundle<'a, S: DecodeSink>(
+    input: &'a [u8],
+    original_input: &'a [u8],
+    depth: usize,
+    options: &DecodeOptions,
+    sink: &mut Option<&mut S>,
+) -> IResult<&'a [u8], (), OscError> {
+    if depth > MAX_BUNDLE_DEPTH {
+        return Err(nom::Err::Failure(OscError::BadBundle(format!(
+            "Bundles nested deeper than {} levels",
+            MAX_BUNDLE_DEPTH
+        ))));
+    }
+
+    let (mut input, timetag) = read_time_tag(input)?;
+    if let Some(sink) = sink {
+        sink.on_bundle_start(timetag);
+    }
+    let arg_timetag = options.timetag_as_arg.then_some(timetag);
+    loop {
+        // Like `many0` in `decode_bundle`, an element failing with an error rather than a
+        // failure ends the bundle. Elements are validated before they are passed to the sink, so
+        // that it never sees the start of an element that is then dropped.
+        let remainder = match walk_bundle_element(
+            input,
+            original_input,
+            depth,
+            arg_timetag,
+            options,
+            &mut None::<&mut S>,
+        ) {
+            Ok((remainder, ())) => remainder,
+            Err(nom::Err::Error(_)) => break,
+            Err(err) => return Err(err),
+        };
+        if sink.is_some() {
+            walk_bundle_element(input, original_input, depth, arg_timetag, options, sink)?;
+        }
+        input = remainder;
+    }
+    if let Some(sink) = sink {
+        sink.on_bundle_end();
+    }
+    Ok((input, ()))
+}
+
+/// Like `read_bundle_element`, but passing the contents of the element to `sink`.
+fn walk_bundle_element<'a, S: DecodeSink>(
+    input: &'a [u8],
+    original_input: &'a [u8],
+    depth: usize,
+    timetag: Option<OscTime>,
+    options: &DecodeOptions,
+    sink: &mut Option<&mut S>,
+) -> IResult<&'a [u8], (), OscError> {
+    if !input.is_empty() && input.len() < 4 {
+        return Err(nom::Err::Failure(OscError::NeedMoreData {
+            needed: 4 - input.len(),
+        }));
+    }
+    let (input, elem_size) = be_u32(input)?;
+    if elem_size as usize > input.len() {
+        return Err(nom::Err::Failure(OscError::NeedMoreData {
+            needed: elem_size as usize - input.len(),
+        }));
+    }
+
+    if elem_size == 0 {
+        return Err(nom::Err::Failure(empty_bundle_element()));
+    }
+
+    let (element, remainder) = input.split_at(elem_size as usize);
+    walk_packet(element, original_input, depth, timetag, options, sink)?;
+    Ok((remainder, ()))
 }
 
 fn decode_packet<'a>(
     input: &'a [u8],
     original_input: &'a [u8],
+    depth: usize,
+    options: &DecodeOptions,
 ) -> IResult<&'a [u8], OscPacket, OscError> {
+    if let Some(input) = strip_bundle_tag(input)? {
+        return decode_bundle(input, original_input, depth + 1, options);
+    }
+    let (input, (addr, type_tags)) = read_message_header(input, original_input, options)?;
+    decode_message(addr, type_tags, input, original_input, options)
+}
+
+/// Checks the start of a packet, returning the input after the bundle tag if it is a bundle, or
+/// `None` if it is a message.
+fn strip_bundle_tag(input: &[u8]) -> Result<Option<&[u8]>, nom::Err<OscError>> {
     if input.is_empty() {
         return Err(nom::Err::Error(OscError::BadPacket("Empty packet.")));
     }
 
-    let (input, addr) = read_osc_string(input, original_input)?;
+    // Only exactly "#bundle\0" starts a bundle, anything else starting with '#' is a control
+    // packet, even if its tag isn't a valid OSC string
+    if let Some(input) = input.strip_prefix(&BUNDLE_TAG[..]) {
+        return Ok(Some(input));
+    }
+    if input[0] == b'#' {
+        return Err(nom::Err::Error(OscError::UnsupportedControlPacket(
+            control_packet_tag(input),
+        )));
+    }
+    Ok(None)
+}
 
-    match addr.chars().next() {
-        Some('/') => decode_message(addr, input, original_input),
-        Some('#') if &addr == "#bundle" => decode_bundle(input, original_input),
-        _ => Err(nom::Err::Error(OscError::BadPacket(
-            "Invalid message address or bundle tag",
+/// Reads the address and the type tags of a message and checks them against `options`. The type
+/// tags are returned without the leading `,`, and their array brackets are balanced.
+fn read_message_header<'a>(
+    input: &'a [u8],
+    original_input: &'a [u8],
+    options: &DecodeOptions,
+) -> IResult<&'a [u8], (&'a str, &'a str), OscError> {
+    let (input, addr) = read_osc_str(input, original_input)?;
+    if !addr.starts_with('/') && options.require_leading_slash {
+        return Err(nom::Err::Error(OscError::BadAddress(format!(
+            "Message address must start with '/': {:?}",
+            addr
+        ))));
+    }
+
+    let (input, type_tags) = read_osc_str(input, original_input)?;
+    let mut tags = type_tags.chars();
+    tags.next();
+    let tags = tags.as_str();
+    let (count, _) = count_arg_tags(tags.chars());
+    if count > options.max_args {
+        // A failure rather than an error, so that an enclosing bundle doesn't just stop reading
+        // its elements at this point
+        return Err(nom::Err::Failure(OscError::TooManyArgs {
+            count,
+            max: options.max_args,
+        }));
+    }
+    check_allowed_type_tags(tags.chars(), options).map_err(nom::Err::Failure)?;
+    check_array_brackets(tags).map_err(nom::Err::Error)?;
+    Ok((input, (addr, tags)))
+}
+
+/// Checks type tags without the leading `,` against `DecodeOptions::allowed_type_tags`. Like
+/// `max_args`, violations are meant to be failures, so that enclosing bundles don't just drop the
+/// message.
+fn check_allowed_type_tags<I: Iterator<Item = char>>(
+    mut type_tags: I,
+    options: &DecodeOptions,
+) -> Result<(), OscError> {
+    let allowed = match options.allowed_type_tags {
+        Some(ref allowed) => allowed,
+        None => return Ok(()),
+    };
+    match type_tags.find(|&tag| tag != ']' && !allowed.contains(tag)) {
+        Some(tag) => Err(OscError::BadArg(format!(
+            "Type tag \"{}\" is not allowed by the decode options",
+            tag
         ))),
+        None => Ok(()),
     }
 }
 
+/// Reads the arguments of a message whose header has been read by `read_message_header`.
 fn decode_message<'a>(
-    addr: String,
+    addr: &str,
+    type_tags: &str,
     input: &'a [u8],
     original_input: &'a [u8],
+    options: &DecodeOptions,
 ) -> IResult<&'a [u8], OscPacket, OscError> {
-    let (input, type_tags) = read_osc_string(input, original_input)?;
-
-    if type_tags.len() > 1 {
-        let (input, args) = read_osc_args(input, original_input, type_tags)?;
-        Ok((input, OscPacket::Message(OscMessage { addr, args })))
-    } else {
-        Ok((input, OscPacket::Message(OscMessage { addr, args: vec![] })))
+    let (_, top_level) = count_arg_tags(type_tags.chars());
+    let (input, args) = read_osc_args_into(
+        input,
+        original_input,
+        type_tags.chars(),
+        Vec::with_capacity(top_level),
+    )?;
+    let mut packet = OscPacket::Message(OscMessage {
+        addr: addr.to_string(),
+        args,
+    });
+    if options.numeric_byte_order == NumericByteOrder::Little {
+        packet.swap_numeric_bytes();
     }
+    Ok((input, packet))
 }
 
 fn decode_bundle<'a>(
     input: &'a [u8],
     original_input: &'a [u8],
+    depth: usize,
+    options: &DecodeOptions,
 ) -> IResult<&'a [u8], OscPacket, OscError> {
+    if depth > MAX_BUNDLE_DEPTH {
+        // A failure rather than an error, so that the enclosing bundles don't just stop reading
+        // their elements at this point
+        return Err(nom::Err::Failure(OscError::BadBundle(format!(
+            "Bundles nested deeper than {} levels",
+            MAX_BUNDLE_DEPTH
+        ))));
+    }
+
     let (input, (timetag, content)) = tuple((
         read_time_tag,
-        many0(|input| read_bundle_element(input, original_input)),
+        many0(|input| read_bundle_element(input, original_input, depth, options)),
     ))(input)?;
+    osc_trace!(depth, "decoded bundle with {} elements", content.len());
 
     Ok((input, OscPacket::Bundle(OscBundle { timetag, content })))
 }
@@ -121,46 +1577,105 @@ fn decode_bundle<'a>(
 fn read_bundle_element<'a>(
     input: &'a [u8],
     original_input: &'a [u8],
+    depth: usize,
+    options: &DecodeOptions,
 ) -> IResult<&'a [u8], OscPacket, OscError> {
+    // Failures rather than errors, so that the enclosing bundle doesn't just stop reading its
+    // elements at this point
+    if !input.is_empty() && input.len() < 4 {
+        return Err(nom::Err::Failure(OscError::NeedMoreData {
+            needed: 4 - input.len(),
+        }));
+    }
     let (input, elem_size) = be_u32(input)?;
+    if elem_size as usize > input.len() {
+        return Err(nom::Err::Failure(OscError::NeedMoreData {
+            needed: elem_size as usize - input.len(),
+        }));
+    }
 
-    map_parser(
-        move |input| {
-            take(elem_size)(input).map_err(|_: nom::Err<OscError>| {
-                nom::Err::Error(OscError::BadBundle(
-                    "Bundle shorter than expected!".to_string(),
-                ))
-            })
-        },
-        |input| decode_packet(input, original_input),
-    )(input)
+    // Even an empty message has an address, so this isn't just an empty element
+    if elem_size == 0 {
+        return Err(nom::Err::Failure(empty_bundle_element()));
+    }
+
+    map_parser(take(elem_size), |input| {
+        decode_packet(input, original_input, depth, options)
+    })(input)
+}
+
+fn empty_bundle_element() -> OscError {
+    OscError::BadBundle("Bundle element has a length of 0".to_string())
 }
 
 fn read_osc_string<'a>(
     input: &'a [u8],
     original_input: &'a [u8],
 ) -> IResult<&'a [u8], String, OscError> {
+    map(|input| read_osc_str(input, original_input), str::to_string)(input)
+}
+
+/// Like `read_osc_string`, but borrowing the string from the input.
+fn read_osc_str<'a>(
+    input: &'a [u8],
+    original_input: &'a [u8],
+) -> IResult<&'a [u8], &'a str, OscError> {
     map_res(
         terminated(
             take_till(|c| c == 0u8),
             pad_to_32_bit_boundary(original_input),
         ),
         |str_buf: &'a [u8]| {
-            String::from_utf8(str_buf.into())
-                .map_err(OscError::StringError)
-                .map(|s| s.trim_matches(0u8 as char).to_string())
+            core::str::from_utf8(str_buf)
+                .map_err(|_| OscError::StringError(String::from_utf8(str_buf.into()).unwrap_err()))
         },
     )(input)
 }
 
 fn read_osc_args<'a>(
-    mut input: &'a [u8],
+    input: &'a [u8],
     original_input: &'a [u8],
     raw_type_tags: String,
 ) -> IResult<&'a [u8], Vec<OscType>, OscError> {
-    let type_tags: Vec<char> = raw_type_tags.chars().skip(1).collect();
+    let (_, top_level) = count_arg_tags(raw_type_tags.chars().skip(1));
+    let args = Vec::with_capacity(top_level);
+    read_osc_args_into(input, original_input, raw_type_tags.chars().skip(1), args)
+}
+
+/// Counts the arguments declared by type tags without the leading `,`. Returns the total number,
+/// which counts the elements of arrays rather than the arrays, and the number of top-level
+/// arguments, which counts each array as one.
+fn count_arg_tags<I: Iterator<Item = char>>(type_tags: I) -> (usize, usize) {
+    let (mut count, mut top_level) = (0, 0);
+    let mut depth = 0usize;
+    for tag in type_tags {
+        match tag {
+            '[' => {
+                if depth == 0 {
+                    top_level += 1;
+                }
+                depth += 1;
+            }
+            ']' => depth = depth.saturating_sub(1),
+            _ => {
+                count += 1;
+                if depth == 0 {
+                    top_level += 1;
+                }
+            }
+        }
+    }
+    (count, top_level)
+}
 
-    let mut args: Vec<OscType> = Vec::with_capacity(type_tags.len());
+/// Like `read_osc_args`, but appending the arguments to `args` and taking the type tags without
+/// the leading `,`.
+fn read_osc_args_into<'a, I: Iterator<Item = char>>(
+    mut input: &'a [u8],
+    original_input: &'a [u8],
+    type_tags: I,
+    mut args: Vec<OscType>,
+) -> IResult<&'a [u8], Vec<OscType>, OscError> {
     let mut stack: Vec<Vec<OscType>> = Vec::new();
     for tag in type_tags {
         if tag == '[' {
@@ -195,26 +1710,66 @@ fn read_osc_arg<'a>(
     original_input: &'a [u8],
     tag: char,
 ) -> IResult<&'a [u8], OscType, OscError> {
-    match tag {
-        'f' => map(be_f32, OscType::Float)(input),
-        'd' => map(be_f64, OscType::Double)(input),
-        'i' => map(be_i32, OscType::Int)(input),
-        'h' => map(be_i64, OscType::Long)(input),
-        's' => read_osc_string(input, original_input)
+    match TypeTag::from_char(tag) {
+        Some(TypeTag::Float) => map(be_f32, OscType::Float)(input),
+        Some(TypeTag::Double) => map(be_f64, OscType::Double)(input),
+        Some(TypeTag::Int) => map(be_i32, OscType::Int)(input),
+        Some(TypeTag::Long) => map(be_i64, OscType::Long)(input),
+        Some(TypeTag::String) => read_osc_string(input, original_input)
             .map(|(remainder, string)| (remainder, OscType::String(string))),
-        't' => read_time_tag(input).map(|(remainder, time)| (remainder, OscType::Time(time))),
-        'b' => read_blob(input, original_input),
-        'r' => read_osc_color(input),
-        'T' => Ok((input, true.into())),
-        'F' => Ok((input, false.into())),
-        'N' => Ok((input, OscType::Nil)),
-        'I' => Ok((input, OscType::Inf)),
-        'c' => read_char(input),
-        'm' => read_midi_message(input),
-        _ => Err(nom::Err::Error(OscError::BadArg(format!(
-            "Type tag \"{}\" is not implemented!",
-            tag
-        )))),
+        Some(TypeTag::Time) => {
+            read_time_tag(input).map(|(remainder, time)| (remainder, OscType::Time(time)))
+        }
+        Some(TypeTag::Blob) => read_blob(input),
+        Some(TypeTag::Color) => read_osc_color(input),
+        Some(TypeTag::True) => Ok((input, true.into())),
+        Some(TypeTag::False) => Ok((input, false.into())),
+        Some(TypeTag::Nil) => Ok((input, OscType::Nil)),
+        Some(TypeTag::Inf) => Ok((input, OscType::Inf)),
+        Some(TypeTag::Char) => read_char(input),
+        Some(TypeTag::Midi) => read_midi_message(input),
+        Some(TypeTag::ArrayStart) | Some(TypeTag::ArrayEnd) | None => Err(nom::Err::Error(
+            OscError::BadArg(format!("Type tag \"{}\" is not implemented!", tag)),
+        )),
+    }
+}
+
+/// Like `read_osc_arg`, but borrowing strings and blobs from the input.
+fn read_osc_arg_ref<'a>(
+    input: &'a [u8],
+    original_input: &'a [u8],
+    tag: char,
+) -> IResult<&'a [u8], OscTypeRef<'a>, OscError> {
+    match TypeTag::from_char(tag) {
+        Some(TypeTag::String) => map(
+            |input| read_osc_str(input, original_input),
+            OscTypeRef::String,
+        )(input),
+        Some(TypeTag::Blob) => {
+            let (input, size) = be_u32(input)?;
+            let padding = (4 - size % 4) % 4;
+            map(terminated(take(size), take(padding)), OscTypeRef::Blob)(input)
+        }
+        _ => {
+            let (input, arg) = read_osc_arg(input, original_input, tag)?;
+            let arg = match arg {
+                OscType::Int(x) => OscTypeRef::Int(x),
+                OscType::Float(x) => OscTypeRef::Float(x),
+                OscType::Time(x) => OscTypeRef::Time(x),
+                OscType::Long(x) => OscTypeRef::Long(x),
+                OscType::Double(x) => OscTypeRef::Double(x),
+                OscType::Char(x) => OscTypeRef::Char(x),
+                OscType::Color(x) => OscTypeRef::Color(x),
+                OscType::Midi(x) => OscTypeRef::Midi(x),
+                OscType::Bool(x) => OscTypeRef::Bool(x),
+                OscType::Nil => OscTypeRef::Nil,
+                OscType::Inf => OscTypeRef::Inf,
+                OscType::String(_) | OscType::Blob(_) | OscType::Array(_) => {
+                    unreachable!("strings and blobs are read above, arrays aren't single tags")
+                }
+            };
+            Ok((input, arg))
+        }
     }
 }
 
@@ -228,16 +1783,15 @@ fn read_char(input: &[u8]) -> IResult<&[u8], OscType, OscError> {
     })(input)
 }
 
-fn read_blob<'a>(
-    input: &'a [u8],
-    original_input: &'a [u8],
-) -> IResult<&'a [u8], OscType, OscError> {
+fn read_blob(input: &[u8]) -> IResult<&[u8], OscType, OscError> {
     let (input, size) = be_u32(input)?;
+    // Unlike strings, blobs are not null terminated, so a blob whose size is already a multiple
+    // of 4 is not followed by any padding.
+    let padding = (4 - size % 4) % 4;
 
-    map(
-        terminated(take(size), pad_to_32_bit_boundary(original_input)),
-        |blob| OscType::Blob(blob.into()),
-    )(input)
+    map(terminated(take(size), take(padding)), |blob: &[u8]| {
+        OscType::Blob(blob.into())
+    })(input)
 }
 
 fn read_time_tag(input: &[u8]) -> IResult<&[u8], OscTime, OscError> {
@@ -0,0 +1,126 @@
+use crate::encoder;
+use crate::types::{Fnv1a, OscPacket};
+
+use std::collections::{HashMap, VecDeque};
+use std::net::SocketAddr;
+use std::time::{Duration, Instant};
+
+type Key = (u64, Option<SocketAddr>);
+
+/// A window of recently seen packets, used to suppress duplicates such as retransmissions.
+///
+/// Packets are keyed by a hash of their encoded form and, optionally, the address they were
+/// received from. Packets sharing a key are compared byte by byte, so that only packets which
+/// encode identically count as duplicates even if their hashes collide.
+///
+/// A packet is remembered until `window` has passed since it was first seen, or until `capacity`
+/// newer packets were inserted, whichever happens first. Both checks are amortized O(1).
+///
+/// # Examples
+///
+/// ```
+/// use rosc::dedup::Window;
+/// use rosc::{OscMessage, OscPacket};
+/// use std::time::{Duration, Instant};
+///
+/// let mut window = Window::new(Duration::from_millis(100), 64);
+/// let packet = OscPacket::Message(OscMessage::from("/transport/play"));
+///
+/// let now = Instant::now();
+/// assert!(!window.check_and_insert(&packet, None, now));
+/// assert!(window.check_and_insert(&packet, None, now + Duration::from_millis(10)));
+/// assert!(!window.check_and_insert(&packet, None, now + Duration::from_millis(200)));
+/// ```
+#[derive(Clone, Debug)]
+pub struct Window {
+    window: Duration,
+    capacity: usize,
+    /// The keys of the remembered packets and when they were seen, oldest first.
+    order: VecDeque<(Key, Instant)>,
+    /// The encoded remembered packets by key, oldest first.
+    seen: HashMap<Key, VecDeque<Vec<u8>>>,
+}
+
+impl Window {
+    /// Creates an empty window remembering packets for `window`, and at most `capacity` of them.
+    pub fn new(window: Duration, capacity: usize) -> Self {
+        Window {
+            window,
+            capacity,
+            order: VecDeque::with_capacity(capacity),
+            seen: HashMap::new(),
+        }
+    }
+
+    /// Returns the number of packets currently remembered.
+    pub fn len(&self) -> usize {
+        self.order.len()
+    }
+
+    /// Returns `true` if no packets are remembered.
+    pub fn is_empty(&self) -> bool {
+        self.order.is_empty()
+    }
+
+    /// Returns `true` if `packet` was already seen from `source` within the window before `now`.
+    /// Otherwise, the packet is remembered and `false` is returned.
+    ///
+    /// A repeated packet does not extend the time it is remembered for, so a packet that is
+    /// resent continuously is let through once per window. Packets that cannot be encoded are
+    /// never considered duplicates.
+    pub fn check_and_insert(
+        &mut self,
+        packet: &OscPacket,
+        source: Option<SocketAddr>,
+        now: Instant,
+    ) -> bool {
+        self.expire(now);
+
+        let bytes = match encoder::encode(packet) {
+            Ok(bytes) => bytes,
+            Err(_) => return false,
+        };
+        let mut hash = Fnv1a::new();
+        hash.write(&bytes);
+        let key = (hash.finish(), source);
+        if let Some(candidates) = self.seen.get(&key) {
+            if candidates.contains(&bytes) {
+                return true;
+            }
+        }
+
+        if self.capacity == 0 {
+            return false;
+        }
+        if self.order.len() == self.capacity {
+            self.evict_oldest();
+        }
+        self.order.push_back((key, now));
+        self.seen.entry(key).or_default().push_back(bytes);
+        false
+    }
+
+    fn expire(&mut self, now: Instant) {
+        while let Some(&(_, seen_at)) = self.order.front() {
+            if now.saturating_duration_since(seen_at) <= self.window {
+                break;
+            }
+            self.evict_oldest();
+        }
+    }
+
+    fn evict_oldest(&mut self) {
+        let (key, _) = match self.order.pop_front() {
+            Some(entry) => entry,
+            None => return,
+        };
+        // Packets are remembered in the same order in both collections, so the oldest packet
+        // overall is also the oldest one with its key
+        if let Some(candidates) = self.seen.get_mut(&key) {
+            candidates.pop_front();
+            if candidates.is_empty() {
+                self.seen.remove(&key);
+            }
+        }
+    }
+}
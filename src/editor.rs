@@ -0,0 +1,192 @@
+use crate::alloc::{string::String, vec::Vec};
+use crate::errors::OscError;
+use crate::types::{OscMessage, OscPacket, OscTime, OscType};
+
+use core::mem;
+
+/// Edits a packet, recording the edits as an overlay and only building the edited packet in
+/// [`finish`](Self::finish).
+///
+/// Edits that set a value to what it already is in the original packet don't count, so a
+/// transform that only sometimes changes a packet can tell whether it did with
+/// [`is_changed`](Self::is_changed).
+///
+/// The editor takes ownership of the packet and moves everything that wasn't edited into the
+/// result, so e.g. a large blob isn't copied when another argument is changed. Arguments are
+/// owned values, so a packet that is only borrowed has to be cloned to be edited.
+///
+/// # Examples
+///
+/// ```
+/// use rosc::{OscMessage, OscPacket, OscPacketEditor, OscType};
+///
+/// let packet = OscPacket::Message(OscMessage {
+///     addr: "/fader/1".to_string(),
+///     args: vec![OscType::Float(0.5)],
+/// });
+///
+/// let mut editor = OscPacketEditor::new(packet);
+/// editor.set_arg(0, OscType::Float(0.5)).unwrap();
+/// assert!(!editor.is_changed());
+///
+/// editor.set_addr("/fader/2").unwrap();
+/// assert!(editor.is_changed());
+/// match editor.finish() {
+///     OscPacket::Message(msg) => assert_eq!(msg.addr, "/fader/2"),
+///     OscPacket::Bundle(_) => unreachable!(),
+/// }
+/// ```
+#[derive(Clone, Debug)]
+pub struct OscPacketEditor {
+    original: OscPacket,
+    addr: Option<String>,
+    args: Vec<(usize, OscType)>,
+    timetag: Option<OscTime>,
+    elements: Vec<(usize, OscPacketEditor)>,
+}
+
+impl OscPacketEditor {
+    /// Creates an editor for `packet`.
+    pub fn new(packet: OscPacket) -> Self {
+        OscPacketEditor {
+            original: packet,
+            addr: None,
+            args: Vec::new(),
+            timetag: None,
+            elements: Vec::new(),
+        }
+    }
+
+    /// Returns `true` if any edit so far changed the packet.
+    pub fn is_changed(&self) -> bool {
+        self.addr.is_some()
+            || !self.args.is_empty()
+            || self.timetag.is_some()
+            || self
+                .elements
+                .iter()
+                .any(|(_, element)| element.is_changed())
+    }
+
+    /// Sets the address of the message.
+    ///
+    /// An error is returned if the packet is a bundle.
+    pub fn set_addr<S: Into<String>>(&mut self, addr: S) -> Result<&mut Self, OscError> {
+        let addr = addr.into();
+        match self.original {
+            OscPacket::Message(ref msg) if msg.addr == addr => self.addr = None,
+            OscPacket::Message(_) => self.addr = Some(addr),
+            OscPacket::Bundle(_) => return Err(OscError::BadPacket("Packet is not a message")),
+        }
+        Ok(self)
+    }
+
+    /// Sets the argument at index `n` of the message.
+    ///
+    /// An error is returned if the packet is a bundle, or if the message has no argument at
+    /// index `n`.
+    pub fn set_arg(&mut self, n: usize, arg: OscType) -> Result<&mut Self, OscError> {
+        let unchanged = match self.original {
+            OscPacket::Message(ref msg) => match msg.args.get(n) {
+                Some(current) => *current == arg,
+                None => {
+                    return Err(OscError::BadArg(format!(
+                        "Message has no argument at index {}",
+                        n
+                    )))
+                }
+            },
+            OscPacket::Bundle(_) => return Err(OscError::BadPacket("Packet is not a message")),
+        };
+
+        let existing = self.args.iter().position(|(index, _)| *index == n);
+        match (existing, unchanged) {
+            (Some(i), true) => {
+                self.args.swap_remove(i);
+            }
+            (Some(i), false) => self.args[i].1 = arg,
+            (None, true) => (),
+            (None, false) => self.args.push((n, arg)),
+        }
+        Ok(self)
+    }
+
+    /// Sets the time tag of the bundle.
+    ///
+    /// An error is returned if the packet is a message.
+    pub fn set_timetag(&mut self, timetag: OscTime) -> Result<&mut Self, OscError> {
+        match self.original {
+            OscPacket::Bundle(ref bundle) if bundle.timetag == timetag => self.timetag = None,
+            OscPacket::Bundle(_) => self.timetag = Some(timetag),
+            OscPacket::Message(_) => return Err(OscError::BadPacket("Packet is not a bundle")),
+        }
+        Ok(self)
+    }
+
+    /// Edits the element at index `n` of the bundle using `edit`, which receives an editor for
+    /// the element.
+    ///
+    /// Editing the same element again continues with the same editor. An error is returned if
+    /// the packet is a message, if the bundle has no element at index `n`, or if `edit` fails.
+    /// Edits made before `edit` failed are kept.
+    pub fn edit_element<F>(&mut self, n: usize, edit: F) -> Result<&mut Self, OscError>
+    where
+        F: FnOnce(&mut OscPacketEditor) -> Result<(), OscError>,
+    {
+        let i = match self.elements.iter().position(|(index, _)| *index == n) {
+            Some(i) => i,
+            None => {
+                let element = match self.original {
+                    // Move the element out, so that it is moved into the result as well
+                    OscPacket::Bundle(ref mut bundle) => bundle.content.get_mut(n).map(|element| {
+                        let placeholder = OscPacket::Message(OscMessage {
+                            addr: String::new(),
+                            args: Vec::new(),
+                        });
+                        OscPacketEditor::new(mem::replace(element, placeholder))
+                    }),
+                    OscPacket::Message(_) => {
+                        return Err(OscError::BadPacket("Packet is not a bundle"))
+                    }
+                };
+                match element {
+                    Some(element) => {
+                        self.elements.push((n, element));
+                        self.elements.len() - 1
+                    }
+                    None => {
+                        return Err(OscError::BadBundle(format!(
+                            "Bundle has no element at index {}",
+                            n
+                        )))
+                    }
+                }
+            }
+        };
+        edit(&mut self.elements[i].1).map(|()| self)
+    }
+
+    /// Returns the edited packet.
+    pub fn finish(self) -> OscPacket {
+        match self.original {
+            OscPacket::Message(mut msg) => {
+                if let Some(addr) = self.addr {
+                    msg.addr = addr;
+                }
+                for (n, arg) in self.args {
+                    msg.args[n] = arg;
+                }
+                OscPacket::Message(msg)
+            }
+            OscPacket::Bundle(mut bundle) => {
+                if let Some(timetag) = self.timetag {
+                    bundle.timetag = timetag;
+                }
+                for (n, element) in self.elements {
+                    bundle.content[n] = element.finish();
+                }
+                OscPacket::Bundle(bundle)
+            }
+        }
+    }
+}
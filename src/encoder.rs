@@ -1,11 +1,21 @@
 use crate::alloc::{
+    boxed::Box,
     string::{String, ToString},
     vec::Vec,
 };
 use crate::errors::OscError;
-use crate::types::{OscBundle, OscMessage, OscPacket, OscTime, OscType, Result};
+use crate::stats::Stats;
+use crate::types::{
+    NumericByteOrder, OscBundle, OscMessage, OscPacket, OscTime, OscType, Result, SpecProfile,
+    TypeTag,
+};
 
 use byteorder::{BigEndian, ByteOrder};
+use core::borrow::Borrow;
+use core::fmt;
+use core::mem::MaybeUninit;
+use core::ops::Range;
+use core::ptr;
 
 /// Takes a reference to an OSC packet and returns
 /// a byte vector on success. If the packet was invalid
@@ -25,21 +35,1141 @@ use byteorder::{BigEndian, ByteOrder};
 /// assert!(encoder::encode(&packet).is_ok())
 /// ```
 pub fn encode(packet: &OscPacket) -> Result<Vec<u8>> {
+    let bytes = match *packet {
+        OscPacket::Message(ref msg) => encode_message(msg)?,
+        OscPacket::Bundle(ref bundle) => encode_bundle(bundle)?,
+    };
+    osc_trace!(
+        size = bytes.len(),
+        "encoded {} of {} bytes",
+        packet_kind(packet),
+        bytes.len()
+    );
+    Ok(bytes)
+}
+
+/// Like [`encode`], but first checks that the packet will be decoded as the packet it is.
+///
+/// A decoder treats anything starting with `#` as a bundle or another non-message packet, so an
+/// [`OscError::BadAddress`] is returned if the address of any message in `packet` starts with
+/// `#`.
+///
+/// # Example
+///
+/// ```
+/// use rosc::{encoder, OscMessage, OscPacket};
+///
+/// let packet = OscPacket::Message(OscMessage::from("#bundle"));
+/// assert!(encoder::encode(&packet).is_ok());
+/// assert!(encoder::encode_validated(&packet).is_err());
+/// ```
+pub fn encode_validated(packet: &OscPacket) -> Result<Vec<u8>> {
+    packet.flatten().try_for_each(|(_, msg)| {
+        if msg.addr.starts_with('#') {
+            return Err(OscError::BadAddress(format!(
+                "Message address must not start with '#': {}",
+                msg.addr
+            )));
+        }
+        Ok(())
+    })?;
+    encode(packet)
+}
+
+/// Returns what kind of packet `packet` is, for diagnostics.
+#[cfg(feature = "tracing")]
+fn packet_kind(packet: &OscPacket) -> &'static str {
+    match packet {
+        OscPacket::Message(_) => "message",
+        OscPacket::Bundle(_) => "bundle",
+    }
+}
+
+/// Like [`encode`], but starts from a buffer with a capacity of `hint` bytes.
+///
+/// If the size of encoded packets is roughly known, e.g. from [`Stats`], a good hint avoids
+/// reallocating the buffer while it grows. The buffer still grows beyond `hint` if needed, so a
+/// hint that is too small only costs performance, while one that is too large wastes memory.
+///
+/// # Example
+///
+/// ```
+/// use rosc::{encoder, OscMessage, OscPacket};
+///
+/// let packet = OscPacket::Message(OscMessage::from("/ping"));
+/// let bytes = encoder::encode_with_capacity(&packet, 64).unwrap();
+/// assert_eq!(bytes, encoder::encode(&packet).unwrap());
+/// assert!(bytes.capacity() >= 64);
+/// ```
+pub fn encode_with_capacity(packet: &OscPacket, hint: usize) -> Result<Vec<u8>> {
+    let mut out = Vec::with_capacity(hint);
+    let mut deadline = Deadline {
+        check: || true,
+        packets: 0,
+    };
+    encode_packet_checked(packet, &mut out, &mut deadline)?;
+    Ok(out)
+}
+
+/// Like [`encode`], but appends the encoded packet to `out`, returning the number of bytes
+/// appended.
+///
+/// If an error is returned, `out` may contain a partially encoded packet after its original
+/// content.
+///
+/// # Example
+///
+/// ```
+/// use rosc::{encoder, OscMessage, OscPacket};
+///
+/// let packet = OscPacket::Message(OscMessage::from("/ping"));
+/// let mut out = vec![];
+/// assert_eq!(encoder::encode_into(&packet, &mut out).unwrap(), 12);
+/// assert_eq!(out, encoder::encode(&packet).unwrap());
+/// ```
+pub fn encode_into(packet: &OscPacket, out: &mut Vec<u8>) -> Result<usize> {
+    encode_with_deadline(packet, out, || true)
+}
+
+/// Options controlling how packets are encoded.
+///
+/// The default options follow the OSC specification, so [`encode_with_options`] only needs to be
+/// used to talk to peers that don't. See [`DecodeOptions`](crate::decoder::DecodeOptions) for
+/// the decoding side.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct EncodeOptions {
+    /// The byte order of numeric argument data. Anything but big-endian is a workaround for
+    /// nonconforming peers, see [`NumericByteOrder`] for what it applies to.
+    ///
+    /// Defaults to [`NumericByteOrder::Big`].
+    pub numeric_byte_order: NumericByteOrder,
+    /// The type tags that may be encoded, or `None` to allow all of them. Arrays are only allowed
+    /// if `[` is included. Packets with other arguments are rejected with an
+    /// [`OscError::BadArg`].
+    ///
+    /// Defaults to `None`.
+    pub allowed_type_tags: Option<String>,
+}
+
+impl EncodeOptions {
+    /// Returns the default options restricted to what `profile` allows.
+    pub fn for_profile(profile: SpecProfile) -> Self {
+        EncodeOptions {
+            allowed_type_tags: profile.allowed_type_tags().map(str::to_string),
+            ..EncodeOptions::default()
+        }
+    }
+}
+
+/// Like [`encode`], but using the given encoding options.
+///
+/// # Example
+///
+/// ```
+/// use rosc::decoder::{self, DecodeOptions};
+/// use rosc::encoder::{self, EncodeOptions};
+/// use rosc::{NumericByteOrder, OscMessage, OscPacket, OscType};
+///
+/// let packet = OscPacket::Message(OscMessage {
+///     addr: "/level".to_string(),
+///     args: vec![OscType::Int(1)],
+/// });
+/// let encode_options = EncodeOptions {
+///     numeric_byte_order: NumericByteOrder::Little,
+///     ..EncodeOptions::default()
+/// };
+/// let bytes = encoder::encode_with_options(&packet, &encode_options).unwrap();
+/// assert_eq!(bytes[12..], [1, 0, 0, 0]);
+///
+/// let decode_options = DecodeOptions {
+///     numeric_byte_order: NumericByteOrder::Little,
+///     ..DecodeOptions::default()
+/// };
+/// let decoded = decoder::decode_udp_with_options(&bytes, &decode_options).unwrap().1;
+/// assert_eq!(decoded, packet);
+/// ```
+pub fn encode_with_options(packet: &OscPacket, options: &EncodeOptions) -> Result<Vec<u8>> {
+    if let Some(ref allowed) = options.allowed_type_tags {
+        check_allowed_type_tags(packet, allowed)?;
+    }
+    match options.numeric_byte_order {
+        NumericByteOrder::Big => encode(packet),
+        NumericByteOrder::Little => {
+            // Big-endian encoding of the byte swapped values yields the little-endian data
+            let mut swapped = packet.clone();
+            swapped.swap_numeric_bytes();
+            encode(&swapped)
+        }
+    }
+}
+
+/// Checks the arguments of every message of `packet` against `EncodeOptions::allowed_type_tags`.
+fn check_allowed_type_tags(packet: &OscPacket, allowed: &str) -> Result<()> {
+    fn check_args(args: &[OscType], allowed: &str) -> Result<()> {
+        for arg in args {
+            let tag = TypeTag::of(arg).to_char();
+            if !allowed.contains(tag) {
+                return Err(OscError::BadArg(format!(
+                    "Type tag \"{}\" is not allowed by the encode options",
+                    tag
+                )));
+            }
+            if let OscType::Array(array) = arg {
+                check_args(&array.content, allowed)?;
+            }
+        }
+        Ok(())
+    }
+
+    packet
+        .flatten()
+        .try_for_each(|(_, msg)| check_args(&msg.args, allowed))
+}
+
+/// Like [`encode`], but additionally records the size of the encoded packet in `stats`.
+///
+/// Nothing is recorded if the packet could not be encoded.
+pub fn encode_with_stats(packet: &OscPacket, stats: &mut Stats) -> Result<Vec<u8>> {
+    let bytes = encode(packet)?;
+    stats.record(bytes.len());
+    Ok(bytes)
+}
+
+/// The number of packets [`encode_with_deadline`] encodes between two calls of its `check`
+/// closure.
+pub const DEADLINE_CHECK_INTERVAL: usize = 32;
+
+/// Like [`encode`], but periodically asks `check` whether encoding should go on, and appends the
+/// encoded packet to `out`.
+///
+/// This is meant for real-time threads which would rather drop a packet than exceed their time
+/// budget encoding an unexpectedly large bundle. `check` is called before the first packet and
+/// then once every [`DEADLINE_CHECK_INTERVAL`] packets, where both messages and bundles (at any
+/// nesting level) count as a packet. Arguments and bytes are not counted, so the overhead is
+/// negligible for typical packets.
+///
+/// If `check` returns `false`, encoding stops with an [`OscError::Aborted`]. In that case, and
+/// for any other error, `out` may contain a partially encoded packet after its original content.
+/// Truncate it to its previous length if it is reused. Use [`encode_into_slice`] instead if all
+/// or nothing should be written.
+///
+/// # Example
+///
+/// ```
+/// use rosc::{encoder, OscMessage, OscPacket};
+/// use std::time::{Duration, Instant};
+///
+/// let packet = OscPacket::Message(OscMessage::from("/ping"));
+/// let deadline = Instant::now() + Duration::from_micros(250);
+///
+/// let mut out = Vec::new();
+/// match encoder::encode_with_deadline(&packet, &mut out, || Instant::now() < deadline) {
+///     Ok(len) => assert_eq!(len, out.len()),
+///     Err(_) => out.clear(),
+/// }
+/// ```
+pub fn encode_with_deadline<F>(packet: &OscPacket, out: &mut Vec<u8>, check: F) -> Result<usize>
+where
+    F: FnMut() -> bool,
+{
+    let start = out.len();
+    let mut deadline = Deadline { check, packets: 0 };
+    deadline.tick()?;
+    encode_packet_checked(packet, out, &mut deadline)?;
+    let size = out.len() - start;
+    osc_trace!(size, "encoded {} of {} bytes", packet_kind(packet), size);
+    Ok(size)
+}
+
+struct Deadline<F> {
+    check: F,
+    packets: usize,
+}
+
+impl<F: FnMut() -> bool> Deadline<F> {
+    /// Counts a packet about to be encoded, calling `check` if it is due.
+    fn tick(&mut self) -> Result<()> {
+        let due = self.packets.is_multiple_of(DEADLINE_CHECK_INTERVAL);
+        self.packets += 1;
+        if due && !(self.check)() {
+            return Err(OscError::Aborted);
+        }
+        Ok(())
+    }
+}
+
+fn encode_packet_checked<F>(
+    packet: &OscPacket,
+    out: &mut Vec<u8>,
+    deadline: &mut Deadline<F>,
+) -> Result<()>
+where
+    F: FnMut() -> bool,
+{
     match *packet {
-        OscPacket::Message(ref msg) => encode_message(msg),
-        OscPacket::Bundle(ref bundle) => encode_bundle(bundle),
+        OscPacket::Message(ref msg) => out.extend(encode_message(msg)?),
+        OscPacket::Bundle(ref bundle) => {
+            encode_string_into("#bundle", out);
+            out.extend(encode_time_tag(bundle.timetag));
+            for packet in &bundle.content {
+                deadline.tick()?;
+                // Reserve room for the size and fill it in once the element is encoded
+                let size_pos = out.len();
+                out.extend([0u8; 4]);
+                encode_packet_checked(packet, out, deadline)?;
+                let size = out.len() - size_pos - 4;
+                BigEndian::write_u32(&mut out[size_pos..size_pos + 4], size as u32);
+            }
+        }
+    }
+    Ok(())
+}
+
+/// An element of a [`LazyBundleEncoder`], which appends an encoded packet to its argument.
+type LazyElement<'a> = Box<dyn FnOnce(&mut Vec<u8>) -> Result<usize> + 'a>;
+
+/// Encodes a bundle whose elements are produced on demand while it is being encoded, rather than
+/// held in an [`OscBundle`].
+///
+/// Each element is a closure that appends one encoded packet to the buffer it is given, e.g.
+/// using [`encode_into`] or [`encode_message_iter`]. The closures are called in order once the
+/// bundle header has been written, and the size of each element is filled in afterwards, so
+/// only the output buffer and the closures themselves are held in memory. Elements that are
+/// already packets can be mixed in using [`add`](LazyBundleEncoder::add).
+///
+/// # Example
+///
+/// ```
+/// use rosc::encoder::{self, LazyBundleEncoder};
+/// use rosc::{OscBundle, OscMessage, OscPacket, OscTime, OscType};
+///
+/// let mut bundle = LazyBundleEncoder::new(OscTime::IMMEDIATE);
+/// for channel in 0..2 {
+///     bundle.add_lazy(move |out| {
+///         let addr = format!("/ch/{}/level", channel);
+///         let msg = OscPacket::Message(OscMessage {
+///             addr,
+///             args: vec![OscType::Float(0.5)],
+///         });
+///         encoder::encode_into(&msg, out)
+///     });
+/// }
+/// let bytes = bundle.encode().unwrap();
+///
+/// let eager = OscPacket::Bundle(OscBundle {
+///     timetag: OscTime::IMMEDIATE,
+///     content: (0..2)
+///         .map(|channel| {
+///             OscPacket::Message(OscMessage {
+///                 addr: format!("/ch/{}/level", channel),
+///                 args: vec![OscType::Float(0.5)],
+///             })
+///         })
+///         .collect(),
+/// });
+/// assert_eq!(bytes, encoder::encode(&eager).unwrap());
+/// ```
+pub struct LazyBundleEncoder<'a> {
+    timetag: OscTime,
+    elements: Vec<LazyElement<'a>>,
+}
+
+impl<'a> LazyBundleEncoder<'a> {
+    /// Creates an encoder for an empty bundle with the given time tag.
+    pub fn new(timetag: OscTime) -> Self {
+        LazyBundleEncoder {
+            timetag,
+            elements: Vec::new(),
+        }
+    }
+
+    /// Adds an element that is encoded by calling `element` with the output buffer.
+    ///
+    /// `element` must append exactly one encoded packet to the buffer. Its return value is
+    /// ignored, the size of the element is taken from the buffer instead. Encoding the bundle
+    /// fails with an [`OscError::BadBundle`] if nothing or a number of bytes that isn't a
+    /// multiple of 4 is appended.
+    pub fn add_lazy<F>(&mut self, element: F) -> &mut Self
+    where
+        F: FnOnce(&mut Vec<u8>) -> Result<usize> + 'a,
+    {
+        self.elements.push(Box::new(element));
+        self
+    }
+
+    /// Adds an element that is already a packet.
+    pub fn add(&mut self, packet: OscPacket) -> &mut Self {
+        self.add_lazy(move |out| encode_into(&packet, out))
+    }
+
+    /// Returns the number of elements added so far.
+    pub fn len(&self) -> usize {
+        self.elements.len()
+    }
+
+    /// Returns `true` if no elements have been added.
+    pub fn is_empty(&self) -> bool {
+        self.elements.is_empty()
+    }
+
+    /// Encodes the bundle, appending it to `out` and returning the number of bytes appended.
+    ///
+    /// If an error is returned, `out` may contain a partially encoded bundle after its original
+    /// content.
+    pub fn encode_into(self, out: &mut Vec<u8>) -> Result<usize> {
+        let start = out.len();
+        encode_string_into("#bundle", out);
+        out.extend(encode_time_tag(self.timetag));
+        for element in self.elements {
+            // Reserve room for the size and fill it in once the element is encoded
+            let size_pos = out.len();
+            out.extend([0u8; 4]);
+            element(out)?;
+            let size = out.len() - size_pos - 4;
+            if size == 0 || !size.is_multiple_of(4) {
+                return Err(OscError::BadBundle(format!(
+                    "Lazy element of {} bytes is not an encoded packet",
+                    size
+                )));
+            }
+            BigEndian::write_u32(&mut out[size_pos..size_pos + 4], size as u32);
+        }
+        Ok(out.len() - start)
+    }
+
+    /// Encodes the bundle into a new byte vector.
+    pub fn encode(self) -> Result<Vec<u8>> {
+        let mut out = Vec::new();
+        self.encode_into(&mut out)?;
+        Ok(out)
+    }
+}
+
+impl fmt::Debug for LazyBundleEncoder<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("LazyBundleEncoder")
+            .field("timetag", &self.timetag)
+            .field("elements", &self.elements.len())
+            .finish()
+    }
+}
+
+/// Encodes an OSC packet and writes it to `writer`, returning the number of bytes written.
+///
+/// The packet is encoded completely before anything is written, so `writer` doesn't need to
+/// support seeking, even for bundles whose element sizes precede the elements. If the packet
+/// can't be encoded, an error of kind [`InvalidInput`](std::io::ErrorKind::InvalidInput) wrapping
+/// the [`OscError`] is returned and nothing is written. Otherwise, errors of `writer` are passed
+/// on, in which case the packet may have been written partially.
+///
+/// # Example
+///
+/// ```
+/// use rosc::{encoder, OscMessage, OscPacket};
+/// use std::io::BufWriter;
+///
+/// let packet = OscPacket::Message(OscMessage::from("/ping"));
+/// let mut writer = BufWriter::new(Vec::new());
+/// let len = encoder::encode_to_writer(&packet, &mut writer).unwrap();
+/// assert_eq!(writer.into_inner().unwrap(), encoder::encode(&packet).unwrap());
+/// # assert_eq!(len, 12);
+/// ```
+#[cfg(feature = "std")]
+pub fn encode_to_writer<W: std::io::Write>(
+    packet: &OscPacket,
+    mut writer: W,
+) -> std::io::Result<usize> {
+    let bytes =
+        encode(packet).map_err(|err| std::io::Error::new(std::io::ErrorKind::InvalidInput, err))?;
+    writer.write_all(&bytes)?;
+    Ok(bytes.len())
+}
+
+/// Encodes an OSC packet into a fixed size buffer and returns the number of bytes written.
+///
+/// The packet is written directly into `buf`, without allocating. To leave room for a header of
+/// your own, e.g. for framing, pass the part of the buffer after the header. The size of the
+/// encoded packet is computed first, so if `buf` is too small to hold it, an
+/// [`OscError::BufferTooSmall`] is returned and `buf` is left unchanged.
+///
+/// # Example
+///
+/// ```
+/// use rosc::{OscPacket, OscMessage};
+/// use rosc::encoder;
+///
+/// let packet = OscPacket::Message(OscMessage::from("/ping"));
+/// let mut buf = [0u8; 64];
+///
+/// // Reserve the first 4 bytes for a length prefix
+/// let len = encoder::encode_into_slice(&packet, &mut buf[4..]).unwrap();
+/// buf[..4].copy_from_slice(&(len as u32).to_be_bytes());
+///
+/// assert_eq!(&buf[4..4 + len], &encoder::encode(&packet).unwrap()[..]);
+/// ```
+pub fn encode_into_slice(packet: &OscPacket, buf: &mut [u8]) -> Result<usize> {
+    // SAFETY: `MaybeUninit<u8>` has the same layout as `u8`, and `encode_into_uninit` only ever
+    // writes initialized bytes, so `buf` stays initialized.
+    let buf = unsafe {
+        core::slice::from_raw_parts_mut(buf.as_mut_ptr() as *mut MaybeUninit<u8>, buf.len())
+    };
+    encode_into_uninit(packet, buf).map(|bytes| bytes.len())
+}
+
+/// Encodes an OSC packet into a possibly uninitialized buffer and returns the initialized prefix
+/// holding the encoded packet.
+///
+/// This is useful when the destination is a frame handed out by a networking layer which would
+/// otherwise have to be zeroed before it could be used as a `&mut [u8]`.
+///
+/// The packet is written directly into `buf`, without allocating. Only the returned prefix of
+/// `buf` is initialized by this function. The rest of `buf` is left untouched, and no byte of
+/// `buf` is ever read. The size of the encoded packet is computed first, so if `buf` is too small
+/// to hold it, an [`OscError::BufferTooSmall`] is returned and `buf` is not written to at all.
+///
+/// # Example
+///
+/// ```
+/// use rosc::{OscPacket, OscMessage};
+/// use rosc::encoder;
+/// use std::mem::MaybeUninit;
+///
+/// let packet = OscPacket::Message(OscMessage::from("/ping"));
+/// let mut frame = [MaybeUninit::<u8>::uninit(); 64];
+///
+/// let bytes = encoder::encode_into_uninit(&packet, &mut frame).unwrap();
+/// assert_eq!(bytes, &encoder::encode(&packet).unwrap()[..]);
+/// ```
+pub fn encode_into_uninit<'a>(
+    packet: &OscPacket,
+    buf: &'a mut [MaybeUninit<u8>],
+) -> Result<&'a mut [u8]> {
+    let needed = encoded_len(packet);
+    if needed > buf.len() {
+        return Err(OscError::BufferTooSmall {
+            needed,
+            available: buf.len(),
+        });
+    }
+
+    let mut writer = UninitWriter {
+        buf: &mut buf[..needed],
+        len: 0,
+    };
+    write_packet(&mut writer, packet);
+    debug_assert_eq!(writer.len, needed);
+    osc_trace!(
+        size = needed,
+        "encoded {} of {} bytes",
+        packet_kind(packet),
+        needed
+    );
+    Ok(writer.into_init())
+}
+
+/// Returns the number of bytes `packet` takes up once encoded.
+fn encoded_len(packet: &OscPacket) -> usize {
+    fn type_tag_count(arg: &OscType) -> usize {
+        match *arg {
+            OscType::Array(ref array) => {
+                2 + array.content.iter().map(type_tag_count).sum::<usize>()
+            }
+            _ => 1,
+        }
+    }
+
+    match *packet {
+        OscPacket::Message(ref msg) => {
+            // The type tags are preceded by a `,` and followed by a null terminator
+            let type_tags_len = 2 + msg.args.iter().map(type_tag_count).sum::<usize>();
+            pad_to_4(msg.addr.len() + 1)
+                + pad_to_4(type_tags_len)
+                + msg.args.iter().map(arg_data_len).sum::<usize>()
+        }
+        OscPacket::Bundle(ref bundle) => {
+            BUNDLE_TAG.len()
+                + 8
+                + bundle
+                    .content
+                    .iter()
+                    .map(|packet| 4 + encoded_len(packet))
+                    .sum::<usize>()
+        }
+    }
+}
+
+/// Receives the bytes of an encoded packet from [`write_packet`], in order.
+pub(crate) trait ByteSink {
+    fn put(&mut self, bytes: &[u8]);
+
+    fn put_zeros(&mut self, count: usize) {
+        for _ in 0..count {
+            self.put(&[0]);
+        }
+    }
+}
+
+/// Encodes `packet` into `sink`, producing the same bytes as [`encode`] without buffering any of
+/// them. Nested bundles are walked once more for each level to compute their sizes up front.
+pub(crate) fn write_packet<S: ByteSink>(sink: &mut S, packet: &OscPacket) {
+    match *packet {
+        OscPacket::Message(ref msg) => {
+            write_padded(sink, msg.addr.as_bytes());
+            sink.put(b",");
+            let mut type_tags_len = 1;
+            for arg in &msg.args {
+                type_tags_len += write_type_tag(sink, arg);
+            }
+            sink.put_zeros(pad_to_4(type_tags_len + 1) - type_tags_len);
+            for arg in &msg.args {
+                write_arg_data(sink, arg);
+            }
+        }
+        OscPacket::Bundle(ref bundle) => {
+            sink.put(BUNDLE_TAG);
+            write_time(sink, bundle.timetag);
+            for packet in &bundle.content {
+                sink.put(&(encoded_len(packet) as u32).to_be_bytes());
+                write_packet(sink, packet);
+            }
+        }
+    }
+}
+
+/// Writes `bytes` followed by a null terminator and padding, like [`encode_string`].
+fn write_padded<S: ByteSink>(sink: &mut S, bytes: &[u8]) {
+    sink.put(bytes);
+    sink.put_zeros(pad_to_4(bytes.len() + 1) - bytes.len());
+}
+
+/// Writes the type tags of `arg`, returning how many were written.
+fn write_type_tag<S: ByteSink>(sink: &mut S, arg: &OscType) -> usize {
+    match *arg {
+        OscType::Array(ref array) => {
+            sink.put(b"[");
+            let mut count = 2;
+            for arg in &array.content {
+                count += write_type_tag(sink, arg);
+            }
+            sink.put(b"]");
+            count
+        }
+        _ => {
+            sink.put(&[TypeTag::of(arg).to_char() as u8]);
+            1
+        }
+    }
+}
+
+fn write_arg_data<S: ByteSink>(sink: &mut S, arg: &OscType) {
+    match *arg {
+        OscType::Int(x) => sink.put(&x.to_be_bytes()),
+        OscType::Long(x) => sink.put(&x.to_be_bytes()),
+        OscType::Float(x) => sink.put(&x.to_be_bytes()),
+        OscType::Double(x) => sink.put(&x.to_be_bytes()),
+        OscType::Char(x) => sink.put(&(x as u32).to_be_bytes()),
+        OscType::String(ref x) => write_padded(sink, x.as_bytes()),
+        OscType::Blob(ref x) => {
+            sink.put(&(x.len() as i32).to_be_bytes());
+            sink.put(x);
+            sink.put_zeros(pad_to_4(x.len()) - x.len());
+        }
+        OscType::Time(time) => write_time(sink, time),
+        OscType::Midi(ref x) => sink.put(&[x.port, x.status, x.data1, x.data2]),
+        OscType::Color(ref x) => sink.put(&[x.red, x.green, x.blue, x.alpha]),
+        OscType::Bool(_) | OscType::Nil | OscType::Inf => (),
+        OscType::Array(ref array) => {
+            for arg in &array.content {
+                write_arg_data(sink, arg);
+            }
+        }
+    }
+}
+
+fn write_time<S: ByteSink>(sink: &mut S, time: OscTime) {
+    sink.put(&time.seconds.to_be_bytes());
+    sink.put(&time.fractional.to_be_bytes());
+}
+
+/// A [`ByteSink`] writing into a buffer which is known to be large enough, as computed by
+/// [`encoded_len`]. Writing past the end of the buffer panics.
+struct UninitWriter<'a> {
+    buf: &'a mut [MaybeUninit<u8>],
+    /// The number of bytes at the start of `buf` that have been written.
+    len: usize,
+}
+
+impl<'a> UninitWriter<'a> {
+    /// Returns the written bytes.
+    fn into_init(self) -> &'a mut [u8] {
+        // SAFETY: the first `len` elements of `buf` have been written, so they are initialized,
+        // and `MaybeUninit<u8>` has the same layout as `u8`.
+        unsafe { core::slice::from_raw_parts_mut(self.buf.as_mut_ptr() as *mut u8, self.len) }
+    }
+}
+
+impl ByteSink for UninitWriter<'_> {
+    fn put(&mut self, bytes: &[u8]) {
+        let dst = &mut self.buf[self.len..self.len + bytes.len()];
+        // SAFETY: `dst` holds exactly `bytes.len()` elements, and `MaybeUninit<u8>` has the same
+        // layout as `u8`. `bytes` can't overlap `dst`, which is borrowed mutably.
+        unsafe {
+            ptr::copy_nonoverlapping(bytes.as_ptr(), dst.as_mut_ptr() as *mut u8, bytes.len());
+        }
+        self.len += bytes.len();
+    }
+
+    fn put_zeros(&mut self, count: usize) {
+        for byte in &mut self.buf[self.len..self.len + count] {
+            *byte = MaybeUninit::new(0);
+        }
+        self.len += count;
+    }
+}
+
+/// The location of the parts of an encoded packet, as returned by [`encode_with_layout`].
+///
+/// All ranges are byte offsets into the encoded packet.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum PacketLayout {
+    Message(MessageLayout),
+    Bundle(BundleLayout),
+}
+
+/// The location of the parts of an encoded message.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MessageLayout {
+    /// The address, including its null terminator and padding.
+    pub address_range: Range<usize>,
+    /// The type tag string, including the leading `,`, null terminator and padding.
+    pub type_tags_range: Range<usize>,
+    /// The data of each argument. Arguments without data, like booleans, have an empty range,
+    /// and the range of an array covers the data of all its elements.
+    pub arg_ranges: Vec<Range<usize>>,
+}
+
+impl MessageLayout {
+    /// Returns the range of the data of all arguments, which directly follows the type tags and
+    /// extends to the end of the message. It is empty if no argument has any data.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use rosc::encoder::{self, PacketLayout};
+    /// use rosc::{OscMessage, OscPacket, OscType};
+    ///
+    /// let packet = OscPacket::Message(OscMessage {
+    ///     addr: "/xy".to_string(),
+    ///     args: vec![OscType::Float(0.25), OscType::Float(0.75)],
+    /// });
+    /// let (bytes, layout) = encoder::encode_with_layout(&packet).unwrap();
+    /// match layout {
+    ///     PacketLayout::Message(layout) => {
+    ///         assert_eq!(layout.arg_data_range(), 8..16);
+    ///         assert_eq!(&bytes[layout.type_tags_range], b",ff\0");
+    ///     }
+    ///     PacketLayout::Bundle(_) => unreachable!(),
+    /// }
+    /// ```
+    pub fn arg_data_range(&self) -> Range<usize> {
+        let start = self.type_tags_range.end;
+        let end = self.arg_ranges.last().map_or(start, |range| range.end);
+        start..end
+    }
+}
+
+/// The location of the parts of an encoded bundle.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct BundleLayout {
+    /// The time tag of the bundle, which is always `8..16`.
+    pub timetag_range: Range<usize>,
+    /// Each top-level element of the bundle, excluding its size prefix. Each range holds a
+    /// complete encoded packet which can be decoded or sent on its own.
+    pub element_ranges: Vec<Range<usize>>,
+}
+
+/// Like [`encode`], but additionally returns where the parts of the packet are located in the
+/// encoded bytes.
+///
+/// This allows building scatter-gather sends, forwarding single elements of a bundle without
+/// decoding it, or patching values in place right before sending.
+///
+/// # Example
+///
+/// ```
+/// use rosc::encoder::{self, PacketLayout};
+/// use rosc::{decoder, OscMessage, OscPacket, OscTime};
+///
+/// let msg = OscMessage::from("/play");
+/// let packet = msg.clone().at(OscTime::from((3_900_000_000, 0)));
+/// let (bytes, layout) = encoder::encode_with_layout(&packet).unwrap();
+///
+/// match layout {
+///     PacketLayout::Bundle(bundle) => {
+///         let element = &bytes[bundle.element_ranges[0].clone()];
+///         assert_eq!(decoder::decode_udp(element).unwrap().1, OscPacket::Message(msg));
+///     }
+///     PacketLayout::Message(_) => unreachable!(),
+/// }
+/// ```
+pub fn encode_with_layout(packet: &OscPacket) -> Result<(Vec<u8>, PacketLayout)> {
+    let bytes = encode(packet)?;
+    let layout = match *packet {
+        OscPacket::Message(ref msg) => {
+            let address_end = pad_to_4(msg.addr.len() + 1);
+            let data_len: usize = msg.args.iter().map(arg_data_len).sum();
+            let data_start = bytes.len() - data_len;
+
+            let mut arg_ranges = Vec::with_capacity(msg.args.len());
+            let mut pos = data_start;
+            for arg in &msg.args {
+                let end = pos + arg_data_len(arg);
+                arg_ranges.push(pos..end);
+                pos = end;
+            }
+
+            PacketLayout::Message(MessageLayout {
+                address_range: 0..address_end,
+                type_tags_range: address_end..data_start,
+                arg_ranges,
+            })
+        }
+        OscPacket::Bundle(ref bundle) => {
+            let mut element_ranges = Vec::with_capacity(bundle.content.len());
+            let mut pos = 16;
+            for _ in &bundle.content {
+                let size = BigEndian::read_u32(&bytes[pos..pos + 4]) as usize;
+                element_ranges.push(pos + 4..pos + 4 + size);
+                pos += 4 + size;
+            }
+
+            PacketLayout::Bundle(BundleLayout {
+                timetag_range: 8..16,
+                element_ranges,
+            })
+        }
+    };
+    Ok((bytes, layout))
+}
+
+/// Returns the number of bytes the data of an encoded argument takes up.
+fn arg_data_len(arg: &OscType) -> usize {
+    match *arg {
+        OscType::Int(_)
+        | OscType::Float(_)
+        | OscType::Char(_)
+        | OscType::Midi(_)
+        | OscType::Color(_) => 4,
+        OscType::Long(_) | OscType::Double(_) | OscType::Time(_) => 8,
+        OscType::String(ref s) => pad_to_4(s.len() + 1),
+        OscType::Blob(ref b) => 4 + pad_to_4(b.len()),
+        OscType::Bool(_) | OscType::Nil | OscType::Inf => 0,
+        OscType::Array(ref array) => array.content.iter().map(arg_data_len).sum(),
+    }
+}
+
+/// The tag starting every encoded bundle, including its null terminator.
+pub(crate) const BUNDLE_TAG: &[u8; 8] = b"#bundle\0";
+
+/// Overwrites the time tag of an encoded bundle in place.
+///
+/// This allows encoding a bundle ahead of time and stamping it right before it is sent. Only the
+/// 8 bytes of the time tag are written, the rest of `buf` stays untouched. An
+/// [`OscError::BadBundle`] is returned if `buf` does not start with an encoded bundle header, in
+/// which case nothing is written.
+///
+/// # Example
+///
+/// ```
+/// use rosc::{decoder, encoder, OscMessage, OscTime};
+///
+/// let packet = OscMessage::from("/play").at(OscTime::from((0, 1)));
+/// let mut bytes = encoder::encode(&packet).unwrap();
+///
+/// let send_time = OscTime::from((3_900_000_000, 0));
+/// encoder::patch_timetag(&mut bytes, send_time).unwrap();
+/// assert_eq!(decoder::decode_udp(&bytes).unwrap().1, OscMessage::from("/play").at(send_time));
+/// ```
+pub fn patch_timetag(buf: &mut [u8], new_time: OscTime) -> Result<()> {
+    check_bundle_header(buf)?;
+    buf[8..16].copy_from_slice(&encode_time_tag(new_time));
+    Ok(())
+}
+
+/// Like [`patch_timetag`], but additionally overwrites the time tags of all nested bundles.
+///
+/// Nested bundles are found by walking the size fields of the bundle elements. The whole buffer
+/// is validated before anything is written, so if an error is returned, e.g. because an element
+/// size exceeds its enclosing bundle, `buf` is left unchanged. Bundles nested deeper than
+/// [`MAX_BUNDLE_DEPTH`](crate::decoder::MAX_BUNDLE_DEPTH) levels are rejected as well.
+pub fn patch_timetag_recursive(buf: &mut [u8], new_time: OscTime) -> Result<()> {
+    check_bundle_header(buf)?;
+
+    // Find the offsets of all bundles first, without recursion
+    let mut bundles = Vec::new();
+    let mut pending = vec![(0..buf.len(), 1)];
+    while let Some((range, depth)) = pending.pop() {
+        if depth > crate::decoder::MAX_BUNDLE_DEPTH {
+            return Err(OscError::BadBundle(format!(
+                "Bundles nested deeper than {} levels",
+                crate::decoder::MAX_BUNDLE_DEPTH
+            )));
+        }
+        bundles.push(range.start);
+
+        let mut pos = range.start + 16;
+        while pos < range.end {
+            let size = match buf.get(pos..pos + 4) {
+                Some(size) if pos + 4 <= range.end => BigEndian::read_u32(size) as usize,
+                _ => {
+                    return Err(OscError::BadBundle(
+                        "Bundle element size is truncated".to_string(),
+                    ))
+                }
+            };
+            let element = pos + 4..pos + 4 + size;
+            if element.end > range.end {
+                return Err(OscError::BadBundle(format!(
+                    "Bundle element of {} bytes at offset {} exceeds its bundle",
+                    size, pos
+                )));
+            }
+            if buf[element.clone()].starts_with(BUNDLE_TAG) {
+                check_bundle_header(&buf[element.clone()])?;
+                pending.push((element.clone(), depth + 1));
+            }
+            pos = element.end;
+        }
+    }
+
+    let timetag = encode_time_tag(new_time);
+    for start in bundles {
+        buf[start + 8..start + 16].copy_from_slice(&timetag);
+    }
+    Ok(())
+}
+
+fn check_bundle_header(buf: &[u8]) -> Result<()> {
+    if buf.len() < 16 || !buf.starts_with(BUNDLE_TAG) {
+        return Err(OscError::BadBundle(
+            "Buffer does not start with an encoded bundle".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+/// Describes the encoding constraints of a particular peer.
+///
+/// Many OSC implementations only support a subset of the protocol. Rather than checking each
+/// constraint separately before sending, the constraints of a peer can be collected in a
+/// `PeerProfile` and applied by [`encode_for_peer`]. The default profile imposes no constraints,
+/// so encoding with it is equivalent to [`encode`].
+///
+/// A few presets for common peers are provided, which can be adjusted by changing their fields.
+///
+/// # Example
+///
+/// ```
+/// use rosc::encoder::{self, PeerProfile};
+/// use rosc::{OscMessage, OscPacket, OscType};
+///
+/// let profile = PeerProfile {
+///     max_packet_size: Some(1024),
+///     ..PeerProfile::liblo()
+/// };
+/// let packet = OscPacket::Message(OscMessage {
+///     addr: "/color".to_string(),
+///     args: vec![OscType::Int(255)],
+/// });
+/// assert!(encoder::encode_for_peer(&packet, &profile).is_ok());
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct PeerProfile {
+    /// The type tags the peer understands, or `None` if it understands all of them. Arrays are
+    /// only allowed if `[` is included.
+    pub allowed_type_tags: Option<String>,
+    /// Replace every array argument by its elements before encoding, for peers which don't
+    /// support arrays.
+    pub flatten_arrays: bool,
+    /// Only allow ASCII characters in addresses and string arguments.
+    pub ascii_only: bool,
+    /// The maximum size of an encoded packet in bytes, or `None` if there is no limit.
+    pub max_packet_size: Option<usize>,
+}
+
+impl PeerProfile {
+    /// A peer supporting only what the OSC 1.0 specification requires: `i`, `f`, `s` and `b`
+    /// arguments with ASCII strings. Arrays are flattened.
+    pub fn strict_osc_1_0() -> Self {
+        PeerProfile {
+            allowed_type_tags: Some("ifsb".to_string()),
+            flatten_arrays: true,
+            ascii_only: true,
+            max_packet_size: None,
+        }
+    }
+
+    /// A peer using [liblo](http://liblo.sourceforge.net/), which supports most of the
+    /// nonstandard type tags except for colors and arrays. Arrays are flattened.
+    pub fn liblo() -> Self {
+        PeerProfile {
+            allowed_type_tags: Some("ifsbhtdcmTFNI".to_string()),
+            flatten_arrays: true,
+            ascii_only: false,
+            max_packet_size: None,
+        }
+    }
+
+    /// A small embedded device only handling `i`, `f` and `s` arguments with ASCII strings in
+    /// packets of at most 512 bytes. Arrays are flattened.
+    pub fn minimal_embedded() -> Self {
+        PeerProfile {
+            allowed_type_tags: Some("ifs".to_string()),
+            flatten_arrays: true,
+            ascii_only: true,
+            max_packet_size: Some(512),
+        }
+    }
+}
+
+/// Encodes a packet for a peer with the constraints described by `profile`.
+///
+/// The profile is applied in the following order:
+///
+/// 1. If `flatten_arrays` is set, array arguments are replaced by their elements.
+/// 2. The type tags and, if `ascii_only` is set, the addresses and strings of the (flattened)
+///    packet are checked. An [`OscError::BadArg`] or [`OscError::BadString`] is returned for the
+///    first violation.
+/// 3. The packet is encoded and its size is checked against `max_packet_size`, returning an
+///    [`OscError::BadPacket`] if it is exceeded.
+pub fn encode_for_peer(packet: &OscPacket, profile: &PeerProfile) -> Result<Vec<u8>> {
+    let flattened;
+    let packet = if profile.flatten_arrays {
+        flattened = flatten_arrays(packet);
+        &flattened
+    } else {
+        packet
+    };
+
+    check_peer_constraints(packet, profile)?;
+
+    let bytes = encode(packet)?;
+    match profile.max_packet_size {
+        Some(max) if bytes.len() > max => Err(OscError::BadPacket(
+            "Encoded packet exceeds the maximum packet size of the peer",
+        )),
+        _ => Ok(bytes),
+    }
+}
+
+fn flatten_arrays(packet: &OscPacket) -> OscPacket {
+    fn flatten_into(args: &[OscType], flat: &mut Vec<OscType>) {
+        for arg in args {
+            match arg {
+                OscType::Array(array) => flatten_into(&array.content, flat),
+                _ => flat.push(arg.clone()),
+            }
+        }
+    }
+
+    match packet {
+        OscPacket::Message(msg) => {
+            let mut args = Vec::with_capacity(msg.args.len());
+            flatten_into(&msg.args, &mut args);
+            OscPacket::Message(OscMessage {
+                addr: msg.addr.clone(),
+                args,
+            })
+        }
+        OscPacket::Bundle(bundle) => OscPacket::Bundle(OscBundle {
+            timetag: bundle.timetag,
+            content: bundle.content.iter().map(flatten_arrays).collect(),
+        }),
+    }
+}
+
+fn check_peer_constraints(packet: &OscPacket, profile: &PeerProfile) -> Result<()> {
+    fn check_args(args: &[OscType], profile: &PeerProfile) -> Result<()> {
+        for arg in args {
+            let tag = TypeTag::of(arg).to_char();
+            if let Some(ref allowed) = profile.allowed_type_tags {
+                if !allowed.contains(tag) {
+                    return Err(OscError::BadArg(format!(
+                        "Type tag \"{}\" is not supported by the peer",
+                        tag
+                    )));
+                }
+            }
+            match arg {
+                OscType::String(s) if profile.ascii_only && !s.is_ascii() => {
+                    return Err(OscError::BadString(
+                        "Non-ASCII strings are not supported by the peer",
+                    ))
+                }
+                OscType::Array(array) => check_args(&array.content, profile)?,
+                _ => (),
+            }
+        }
+        Ok(())
+    }
+
+    match packet {
+        OscPacket::Message(msg) => {
+            if profile.ascii_only && !msg.addr.is_ascii() {
+                return Err(OscError::BadString(
+                    "Non-ASCII addresses are not supported by the peer",
+                ));
+            }
+            check_args(&msg.args, profile)
+        }
+        OscPacket::Bundle(bundle) => bundle
+            .content
+            .iter()
+            .try_for_each(|packet| check_peer_constraints(packet, profile)),
     }
 }
 
 fn encode_message(msg: &OscMessage) -> Result<Vec<u8>> {
+    encode_message_iter(&msg.addr, &msg.args)
+}
+
+/// Encodes a message with the address `addr` whose arguments are produced by `args`, without
+/// first collecting them into an `OscMessage`.
+///
+/// The type tags and the argument data are accumulated in separate buffers while `args` is
+/// iterated, so the iterator is only consumed once and does not need to be `Clone` or know its
+/// length up front. The result is identical to encoding an `OscMessage` with the same address
+/// and arguments. Both owned arguments and references to arguments are accepted.
+///
+/// An error is returned if `addr` starts with `#`, since the encoded message would be mistaken
+/// for a bundle by a decoder.
+///
+/// # Example
+///
+/// ```
+/// use rosc::{OscMessage, OscPacket, OscType};
+/// use rosc::encoder;
+///
+/// let bytes = encoder::encode_message_iter("/meter", (0..4).map(OscType::Int)).unwrap();
+/// let msg = OscMessage {
+///     addr: "/meter".to_string(),
+///     args: (0..4).map(OscType::Int).collect(),
+/// };
+/// assert_eq!(bytes, encoder::encode(&OscPacket::Message(msg)).unwrap());
+/// ```
+pub fn encode_message_iter<I>(addr: &str, args: I) -> Result<Vec<u8>>
+where
+    I: IntoIterator,
+    I::Item: Borrow<OscType>,
+{
     let mut msg_bytes: Vec<u8> = Vec::new();
 
-    msg_bytes.extend(encode_string(msg.addr.clone()));
+    encode_string_into(addr, &mut msg_bytes);
     let mut type_tags: Vec<char> = vec![','];
     let mut arg_bytes: Vec<u8> = Vec::new();
 
-    for arg in &msg.args {
-        let (bytes, tags): (Option<Vec<u8>>, String) = encode_arg(arg)?;
+    for arg in args {
+        let (bytes, tags): (Option<Vec<u8>>, String) = encode_arg(arg.borrow())?;
 
         type_tags.extend(tags.chars());
         if let Some(data) = bytes {
@@ -47,16 +1177,127 @@ fn encode_message(msg: &OscMessage) -> Result<Vec<u8>> {
         }
     }
 
-    msg_bytes.extend(encode_string(type_tags.into_iter().collect::<String>()));
+    encode_string_into(&type_tags.into_iter().collect::<String>(), &mut msg_bytes);
     if !arg_bytes.is_empty() {
         msg_bytes.extend(arg_bytes);
     }
     Ok(msg_bytes)
 }
 
+/// A message whose address and type tags are encoded once, for sending the same kind of message
+/// with new values over and over.
+///
+/// Only the argument data is encoded by [`encode_values`](PreparedMessage::encode_values), which
+/// appends the cached address and type tags in front of it. The result is identical to encoding
+/// an `OscMessage` with the same address and arguments.
+///
+/// # Example
+///
+/// ```
+/// use rosc::encoder::{self, PreparedMessage};
+/// use rosc::{OscMessage, OscPacket, OscType, TypeTag};
+///
+/// let fader = PreparedMessage::new("/fader", &[TypeTag::Int, TypeTag::Float]).unwrap();
+/// let mut out = Vec::new();
+/// for level in [0.25, 0.5] {
+///     out.clear();
+///     let values = [OscType::Int(1), OscType::Float(level)];
+///     fader.encode_values(&values, &mut out).unwrap();
+///     let msg = OscMessage {
+///         addr: "/fader".to_string(),
+///         args: values.to_vec(),
+///     };
+///     assert_eq!(out, encoder::encode(&OscPacket::Message(msg)).unwrap());
+/// }
+///
+/// // The values must match the type tags
+/// assert!(fader.encode_values(&[OscType::Int(1)], &mut out).is_err());
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PreparedMessage {
+    /// The encoded address and type tags.
+    prefix: Vec<u8>,
+    /// The type tags, without the leading `,`.
+    type_tags: String,
+}
+
+impl PreparedMessage {
+    /// Encodes the address and the type tags of the message. Arrays are described by
+    /// [`TypeTag::ArrayStart`] and [`TypeTag::ArrayEnd`] around the type tags of their elements.
+    ///
+    /// An error is returned if the array brackets are unbalanced.
+    pub fn new(addr: &str, type_tags: &[TypeTag]) -> Result<Self> {
+        let mut depth = 0usize;
+        for tag in type_tags {
+            match tag {
+                TypeTag::ArrayStart => depth += 1,
+                TypeTag::ArrayEnd => {
+                    depth = depth
+                        .checked_sub(1)
+                        .ok_or(OscError::BadMessage("Encountered ] outside array"))?
+                }
+                _ => (),
+            }
+        }
+        if depth != 0 {
+            return Err(OscError::BadMessage("Unterminated array"));
+        }
+
+        let mut type_tags: String = core::iter::once(',')
+            .chain(type_tags.iter().map(|tag| tag.to_char()))
+            .collect();
+        let mut prefix = Vec::new();
+        encode_string_into(addr, &mut prefix);
+        encode_string_into(&type_tags, &mut prefix);
+        type_tags.remove(0);
+        Ok(PreparedMessage { prefix, type_tags })
+    }
+
+    /// Appends the message with the arguments `values` to `out`, returning the number of bytes
+    /// appended.
+    ///
+    /// An [`OscError::BadArg`] is returned, and nothing is appended, if the types of `values`
+    /// don't match the type tags given to [`new`](PreparedMessage::new).
+    pub fn encode_values(&self, values: &[OscType], out: &mut Vec<u8>) -> Result<usize> {
+        let mut tags = self.type_tags.chars();
+        if !values_match_tags(values, &mut tags) || tags.next().is_some() {
+            return Err(OscError::BadArg(format!(
+                "Values don't match the type tags \",{}\"",
+                self.type_tags
+            )));
+        }
+
+        let start = out.len();
+        out.extend_from_slice(&self.prefix);
+        for value in values {
+            match encode_arg(value) {
+                Ok((Some(data), _)) => out.extend(data),
+                Ok((None, _)) => (),
+                Err(err) => {
+                    out.truncate(start);
+                    return Err(err);
+                }
+            }
+        }
+        Ok(out.len() - start)
+    }
+}
+
+/// Consumes the type tags of `values` from `tags`, returning whether they match.
+fn values_match_tags(values: &[OscType], tags: &mut core::str::Chars) -> bool {
+    values.iter().all(|value| match value {
+        OscType::Array(array) => {
+            tags.next() == Some(TypeTag::ArrayStart.to_char())
+                && values_match_tags(&array.content, tags)
+                && tags.next() == Some(TypeTag::ArrayEnd.to_char())
+        }
+        _ => tags.next() == Some(TypeTag::of(value).to_char()),
+    })
+}
+
 fn encode_bundle(bundle: &OscBundle) -> Result<Vec<u8>> {
     let mut bundle_bytes: Vec<u8> = Vec::new();
-    bundle_bytes.extend(encode_string("#bundle".to_string()).into_iter());
+    encode_string_into("#bundle", &mut bundle_bytes);
 
     match encode_arg(&OscType::Time(bundle.timetag))? {
         (Some(x), _) => {
@@ -92,56 +1333,52 @@ fn encode_bundle(bundle: &OscBundle) -> Result<Vec<u8>> {
 }
 
 fn encode_arg(arg: &OscType) -> Result<(Option<Vec<u8>>, String)> {
-    match *arg {
+    let bytes = match *arg {
         OscType::Int(ref x) => {
             let mut bytes = vec![0u8; 4];
             BigEndian::write_i32(&mut bytes, *x);
-            Ok((Some(bytes), "i".into()))
+            Some(bytes)
         }
         OscType::Long(ref x) => {
             let mut bytes = vec![0u8; 8];
             BigEndian::write_i64(&mut bytes, *x);
-            Ok((Some(bytes), "h".into()))
+            Some(bytes)
         }
         OscType::Float(ref x) => {
             let mut bytes = vec![0u8; 4];
             BigEndian::write_f32(&mut bytes, *x);
-            Ok((Some(bytes), "f".into()))
+            Some(bytes)
         }
         OscType::Double(ref x) => {
             let mut bytes = vec![0u8; 8];
             BigEndian::write_f64(&mut bytes, *x);
-            Ok((Some(bytes), "d".into()))
+            Some(bytes)
         }
         OscType::Char(ref x) => {
             let mut bytes = vec![0u8; 4];
             BigEndian::write_u32(&mut bytes, *x as u32);
-            Ok((Some(bytes), "c".into()))
+            Some(bytes)
         }
-        OscType::String(ref x) => Ok((Some(encode_string(x.clone())), "s".into())),
+        OscType::String(ref x) => Some(encode_string(x.clone())),
         OscType::Blob(ref x) => {
-            let padded_blob_length: usize = pad(x.len() as u64) as usize;
+            let padded_blob_length = pad_to_4(x.len());
             let mut bytes = vec![0u8; 4 + padded_blob_length];
             // write length
             BigEndian::write_i32(&mut bytes[..4], x.len() as i32);
             for (i, v) in x.iter().enumerate() {
                 bytes[i + 4] = *v;
             }
-            Ok((Some(bytes), "b".into()))
+            Some(bytes)
         }
-        OscType::Time(time) => Ok((Some(encode_time_tag(time)), "t".into())),
-        OscType::Midi(ref x) => Ok((Some(vec![x.port, x.status, x.data1, x.data2]), "m".into())),
-        OscType::Color(ref x) => Ok((Some(vec![x.red, x.green, x.blue, x.alpha]), "r".into())),
-        OscType::Bool(ref x) => {
-            if *x {
-                Ok((None, "T".into()))
-            } else {
-                Ok((None, "F".into()))
-            }
-        }
-        OscType::Nil => Ok((None, "N".into())),
-        OscType::Inf => Ok((None, "I".into())),
+        OscType::Time(time) => Some(encode_time_tag(time)),
+        OscType::Midi(ref x) => Some(vec![x.port, x.status, x.data1, x.data2]),
+        OscType::Color(ref x) => Some(vec![x.red, x.green, x.blue, x.alpha]),
+        OscType::Bool(_) | OscType::Nil | OscType::Inf => None,
         OscType::Array(ref x) => {
+            if let Some((bytes, type_tags)) = encode_numeric_array(&x.content) {
+                return Ok((Some(bytes), type_tags));
+            }
+
             let mut bytes = vec![0u8; 0];
             let mut type_tags = String::from("[");
             for v in x.content.iter() {
@@ -157,8 +1394,42 @@ fn encode_arg(arg: &OscType) -> Result<(Option<Vec<u8>>, String)> {
                 }
             }
             type_tags.push(']');
-            Ok((Some(bytes), type_tags))
+            return Ok((Some(bytes), type_tags));
         }
+    };
+    Ok((bytes, TypeTag::of(arg).to_char().to_string()))
+}
+
+/// Encodes an array consisting only of `Int`, `Float`, `Long` or `Double` arguments of the same
+/// type in bulk, rather than element by element. Returns `None` for any other array, including
+/// empty ones.
+fn encode_numeric_array(content: &[OscType]) -> Option<(Vec<u8>, String)> {
+    macro_rules! bulk {
+        ($variant:ident, $ty:ty, $size:expr, $write:ident, $tag:expr) => {{
+            let mut values: Vec<$ty> = Vec::with_capacity(content.len());
+            for arg in content {
+                match *arg {
+                    OscType::$variant(x) => values.push(x),
+                    _ => return None,
+                }
+            }
+            let mut bytes = vec![0u8; values.len() * $size];
+            BigEndian::$write(&values, &mut bytes);
+
+            let mut type_tags = String::with_capacity(values.len() + 2);
+            type_tags.push('[');
+            type_tags.extend(core::iter::repeat_n($tag.to_char(), values.len()));
+            type_tags.push(']');
+            Some((bytes, type_tags))
+        }};
+    }
+
+    match content.first()? {
+        OscType::Int(_) => bulk!(Int, i32, 4, write_i32_into, TypeTag::Int),
+        OscType::Float(_) => bulk!(Float, f32, 4, write_f32_into, TypeTag::Float),
+        OscType::Long(_) => bulk!(Long, i64, 8, write_i64_into, TypeTag::Long),
+        OscType::Double(_) => bulk!(Double, f64, 8, write_f64_into, TypeTag::Double),
+        _ => None,
     }
 }
 
@@ -166,29 +1437,37 @@ fn encode_arg(arg: &OscType) -> Result<(Option<Vec<u8>>, String)> {
 /// adds null bytes until the length of the result is a
 /// multiple of 4.
 pub fn encode_string<S: Into<String>>(s: S) -> Vec<u8> {
-    let mut bytes: Vec<u8> = s.into().as_bytes().into();
-    bytes.push(0u8);
-    pad_bytes(&mut bytes);
+    let s = s.into();
+    let mut bytes = Vec::with_capacity(pad_to_4(s.len() + 1));
+    encode_string_into(&s, &mut bytes);
     bytes
 }
 
-fn pad_bytes(bytes: &mut Vec<u8>) {
-    let padded_lengh = pad(bytes.len() as u64);
-    while bytes.len() < padded_lengh as usize {
-        bytes.push(0u8)
-    }
-}
-
-/// Returns the position padded to 4 bytes.
+/// Like [`encode_string`], but appends the encoded string to `out`, returning the number of
+/// bytes appended.
 ///
 /// # Example
 ///
 /// ```
 /// use rosc::encoder;
 ///
-/// let pos: u64 = 10;
-/// assert_eq!(12u64, encoder::pad(pos))
+/// let mut out = vec![];
+/// assert_eq!(encoder::encode_string_into("/ping", &mut out), 8);
+/// assert_eq!(out, encoder::encode_string("/ping"));
 /// ```
+pub fn encode_string_into(s: &str, out: &mut Vec<u8>) -> usize {
+    let padded_len = pad_to_4(s.len() + 1);
+    out.reserve(padded_len);
+    out.extend_from_slice(s.as_bytes());
+    // The null terminator and padding
+    out.resize(out.len() + padded_len - s.len(), 0);
+    padded_len
+}
+
+/// Returns the position padded to 4 bytes.
+///
+/// Use [`pad_to_4`] instead, which works on `usize` and in constant expressions.
+#[deprecated(since = "0.10.0", note = "use `pad_to_4` instead")]
 pub fn pad(pos: u64) -> u64 {
     match pos % 4 {
         0 => pos,
@@ -196,6 +1475,46 @@ pub fn pad(pos: u64) -> u64 {
     }
 }
 
+/// Returns `len` rounded up to the next multiple of 4, the alignment of all OSC data.
+///
+/// This can be used in constant expressions, e.g. to size buffers for known messages.
+///
+/// # Panics
+///
+/// If the result doesn't fit in a `usize`, this panics in debug builds and wraps around in
+/// release builds, like any arithmetic overflow. In constant expressions, it fails to compile
+/// instead. See [`checked_pad_to_4`] for a variant returning `None`.
+///
+/// # Example
+///
+/// ```
+/// use rosc::encoder;
+///
+/// // "/ping" with its null terminator, followed by the type tags ","
+/// const PING_SIZE: usize = encoder::pad_to_4("/ping".len() + 1) + encoder::pad_to_4(2);
+/// assert_eq!(PING_SIZE, 12);
+/// assert!(encoder::is_aligned_4(PING_SIZE));
+/// ```
+pub const fn pad_to_4(len: usize) -> usize {
+    match len % 4 {
+        0 => len,
+        d => len + (4 - d),
+    }
+}
+
+/// Like [`pad_to_4`], but returns `None` if the result doesn't fit in a `usize`.
+pub const fn checked_pad_to_4(len: usize) -> Option<usize> {
+    match len.checked_add(3) {
+        Some(len) => Some(len & !3),
+        None => None,
+    }
+}
+
+/// Returns `true` if `len` is a multiple of 4, the alignment of all OSC data.
+pub const fn is_aligned_4(len: usize) -> bool {
+    len.is_multiple_of(4)
+}
+
 fn encode_time_tag(time: OscTime) -> Vec<u8> {
     let mut bytes = vec![0u8; 8];
     BigEndian::write_u32(&mut bytes[..4], time.seconds);
@@ -204,9 +1523,30 @@ fn encode_time_tag(time: OscTime) -> Vec<u8> {
 }
 
 #[test]
+#[allow(deprecated)]
 fn test_pad() {
     assert_eq!(4, pad(4));
     assert_eq!(8, pad(5));
     assert_eq!(8, pad(6));
     assert_eq!(8, pad(7));
 }
+
+#[test]
+fn test_pad_to_4() {
+    assert_eq!(0, pad_to_4(0));
+    assert_eq!(4, pad_to_4(1));
+    assert_eq!(4, pad_to_4(4));
+    assert_eq!(8, pad_to_4(5));
+    assert_eq!(usize::MAX - 3, pad_to_4(usize::MAX - 3));
+    assert_eq!(usize::MAX - 3, pad_to_4(usize::MAX - 6));
+
+    assert_eq!(Some(8), checked_pad_to_4(7));
+    assert_eq!(Some(usize::MAX - 3), checked_pad_to_4(usize::MAX - 3));
+    assert_eq!(None, checked_pad_to_4(usize::MAX - 2));
+    assert_eq!(None, checked_pad_to_4(usize::MAX));
+
+    assert!(is_aligned_4(0));
+    assert!(is_aligned_4(usize::MAX - 3));
+    assert!(!is_aligned_4(usize::MAX));
+    assert!(!is_aligned_4(6));
+}
@@ -20,6 +20,46 @@ pub enum OscError {
     BadAddressPattern(String),
     BadAddress(String),
     RegexError(String),
+    BufferTooSmall {
+        needed: usize,
+        available: usize,
+    },
+    /// The input ended within a bundle element, but was valid up to that point. At least
+    /// `needed` more bytes are required, so a stream consumer should read more data rather than
+    /// discard what it has.
+    NeedMoreData {
+        needed: usize,
+    },
+    /// The packet starts with `#`, but isn't a bundle. Some implementations send such
+    /// nonstandard control packets, e.g. `#time`. The tag, the string up to the first null byte,
+    /// is included so that applications can handle them themselves.
+    UnsupportedControlPacket(String),
+    /// A message declares `count` arguments in its type tags, more than the `max` allowed by
+    /// [`DecodeOptions::max_args`](crate::decoder::DecodeOptions::max_args).
+    TooManyArgs {
+        count: usize,
+        max: usize,
+    },
+    /// Decoding a packet would allocate an estimated `needed` bytes, more than the `budget`
+    /// allowed by [`DecodeOptions::max_owned_bytes`](crate::decoder::DecodeOptions::max_owned_bytes).
+    MemoryBudgetExceeded {
+        budget: usize,
+        needed: usize,
+    },
+    /// A message has `found` arguments, but [`FromOscArgs`](crate::FromOscArgs) expected
+    /// `expected`.
+    WrongArgCount {
+        expected: usize,
+        found: usize,
+    },
+    /// The argument at `index` is a `found`, but [`FromOscArgs`](crate::FromOscArgs) expected a
+    /// `expected`. Both are names as returned by [`OscType::variant_name`](crate::OscType::variant_name).
+    WrongArgType {
+        index: usize,
+        expected: &'static str,
+        found: &'static str,
+    },
+    Aborted,
     Unimplemented,
 }
 
@@ -37,6 +77,44 @@ impl fmt::Display for OscError {
             OscError::BadAddressPattern(msg) => write!(f, "bad OSC address pattern: {}", msg),
             OscError::BadAddress(msg) => write!(f, "bad OSC address: {}", msg),
             OscError::RegexError(msg) => write!(f, "OSC address pattern regex error: {}", msg),
+            OscError::BufferTooSmall { needed, available } => write!(
+                f,
+                "output buffer too small: {} bytes needed, {} available",
+                needed, available
+            ),
+            OscError::NeedMoreData { needed } => {
+                write!(
+                    f,
+                    "incomplete OSC packet: at least {} more bytes needed",
+                    needed
+                )
+            }
+            OscError::UnsupportedControlPacket(tag) => {
+                write!(f, "unsupported OSC control packet: {:?}", tag)
+            }
+            OscError::TooManyArgs { count, max } => write!(
+                f,
+                "OSC message has {} arguments, more than the maximum of {}",
+                count, max
+            ),
+            OscError::MemoryBudgetExceeded { budget, needed } => write!(
+                f,
+                "decoding the OSC packet needs an estimated {} bytes, more than the budget of {}",
+                needed, budget
+            ),
+            OscError::WrongArgCount { expected, found } => {
+                write!(f, "expected {} OSC arguments, found {}", expected, found)
+            }
+            OscError::WrongArgType {
+                index,
+                expected,
+                found,
+            } => write!(
+                f,
+                "OSC argument {}: expected {}, found {}",
+                index, expected, found
+            ),
+            OscError::Aborted => write!(f, "encoding aborted"),
             OscError::Unimplemented => write!(f, "unimplemented"),
         }
     }
@@ -74,3 +152,43 @@ impl error::Error for OscError {
         }
     }
 }
+
+/// Returned by [`decode_exact`](crate::decoder::decode_exact) if a packet doesn't survive a
+/// round trip through the decoder and encoder unchanged.
+#[derive(Debug)]
+pub enum ExactnessError {
+    /// The packet could not be decoded, or its decoded form could not be encoded again.
+    Invalid(OscError),
+    /// Encoding the decoded packet gives different bytes, first differing at `offset`. If one
+    /// encoding is a prefix of the other, `offset` is the length of the shorter one.
+    Mismatch { offset: usize },
+}
+
+impl fmt::Display for ExactnessError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ExactnessError::Invalid(err) => write!(f, "{}", err),
+            ExactnessError::Mismatch { offset } => write!(
+                f,
+                "re-encoded OSC packet differs from the original at offset {}",
+                offset
+            ),
+        }
+    }
+}
+
+impl From<OscError> for ExactnessError {
+    fn from(err: OscError) -> Self {
+        ExactnessError::Invalid(err)
+    }
+}
+
+#[cfg(feature = "std")]
+impl error::Error for ExactnessError {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        match self {
+            ExactnessError::Invalid(ref err) => Some(err),
+            ExactnessError::Mismatch { .. } => None,
+        }
+    }
+}
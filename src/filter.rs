@@ -0,0 +1,245 @@
+use crate::address::{Matcher, OscAddress};
+use crate::decoder::{self, DecodeOptions, MAX_BUNDLE_DEPTH};
+use crate::encoder::BUNDLE_TAG;
+use crate::errors::OscError;
+use crate::types::{OscBundle, OscPacket, OscTime, OscType};
+
+use byteorder::{BigEndian, ByteOrder};
+use std::borrow::Cow;
+use std::mem;
+
+/// Whether an address passed an [`AddressFilter`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Verdict {
+    Allow,
+    Deny,
+}
+
+/// An allow and deny list of address patterns, checked before packets are decoded.
+///
+/// An address is allowed if it matches any pattern of the allow list, or if the allow list is
+/// empty, and doesn't match any pattern of the deny list. Strings that aren't valid addresses are
+/// always denied.
+///
+/// [`decode_udp_filtered`] uses the filter to drop messages after reading only their address, so
+/// a denied message costs no more than that, no matter how many arguments it carries.
+///
+/// # Examples
+///
+/// ```
+/// use rosc::filter::{AddressFilter, Verdict};
+///
+/// let filter = AddressFilter::new(&["/mixer/*"], &["/mixer/master"]).unwrap();
+/// assert_eq!(filter.check("/mixer/1"), Verdict::Allow);
+/// assert_eq!(filter.check("/mixer/master"), Verdict::Deny);
+/// assert_eq!(filter.check("/admin/shutdown"), Verdict::Deny);
+/// ```
+#[derive(Clone, Debug)]
+pub struct AddressFilter {
+    allow: Vec<Matcher>,
+    deny: Vec<Matcher>,
+}
+
+impl AddressFilter {
+    /// Creates a filter from lists of allowed and denied address patterns.
+    ///
+    /// An error is returned if any of the patterns is not a valid address pattern.
+    pub fn new(allow: &[&str], deny: &[&str]) -> Result<Self, OscError> {
+        Ok(AddressFilter {
+            allow: allow
+                .iter()
+                .map(|pattern| Matcher::new(pattern))
+                .collect::<Result<_, _>>()?,
+            deny: deny
+                .iter()
+                .map(|pattern| Matcher::new(pattern))
+                .collect::<Result<_, _>>()?,
+        })
+    }
+
+    /// Checks whether messages to `addr` are allowed.
+    pub fn check(&self, addr: &str) -> Verdict {
+        let address = match OscAddress::new(addr.to_string()) {
+            Ok(address) => address,
+            Err(_) => return Verdict::Deny,
+        };
+
+        let allowed = self.allow.is_empty()
+            || self
+                .allow
+                .iter()
+                .any(|matcher| matcher.match_address(&address));
+        if allowed
+            && !self
+                .deny
+                .iter()
+                .any(|matcher| matcher.match_address(&address))
+        {
+            Verdict::Allow
+        } else {
+            Verdict::Deny
+        }
+    }
+}
+
+/// Like [`decoder::decode_udp`], but dropping messages denied by `filter` before decoding them.
+///
+/// Returns `None` if the packet is a denied message. The elements of bundles are checked one by
+/// one, and denied ones are skipped using their size field, so the bundle is returned without
+/// them. Only the address of a denied message is read, so errors in its type tags or arguments
+/// are not detected. Any bytes following the packet are ignored.
+///
+/// # Example
+///
+/// ```
+/// use rosc::filter::{self, AddressFilter};
+/// use rosc::{encoder, OscMessage, OscPacket};
+///
+/// let filter = AddressFilter::new(&["/public/*"], &[]).unwrap();
+/// let bytes = encoder::encode(&OscPacket::Message(OscMessage::from("/admin/reset"))).unwrap();
+/// assert_eq!(filter::decode_udp_filtered(&bytes, &filter).unwrap(), None);
+/// ```
+pub fn decode_udp_filtered(
+    msg: &[u8],
+    filter: &AddressFilter,
+) -> Result<Option<OscPacket>, OscError> {
+    decode_udp_filtered_with_options(msg, filter, &DecodeOptions::default())
+}
+
+/// Like [`decode_udp_filtered`], but using the given decoding options.
+///
+/// With [`timetag_as_arg`](DecodeOptions::timetag_as_arg), allowed messages get the time tag of
+/// their innermost bundle inserted just like with [`decoder::decode_udp_with_options`].
+///
+/// [`max_owned_bytes`](DecodeOptions::max_owned_bytes) applies to the allowed messages of the
+/// packet together, denied ones don't count. Each allowed message is estimated before it is
+/// decoded, and the packet is rejected once the sum exceeds the budget, with the sum so far as
+/// the needed bytes.
+pub fn decode_udp_filtered_with_options(
+    msg: &[u8],
+    filter: &AddressFilter,
+    options: &DecodeOptions,
+) -> Result<Option<OscPacket>, OscError> {
+    let mut budget = Budget {
+        max: options.max_owned_bytes,
+        used: 0,
+    };
+    // The budget is checked here instead of for each message on its own
+    let options = match options.max_owned_bytes {
+        Some(_) => Cow::Owned(DecodeOptions {
+            max_owned_bytes: None,
+            ..options.clone()
+        }),
+        None => Cow::Borrowed(options),
+    };
+    decode_filtered(msg, filter, &options, 0, None, &mut budget)
+}
+
+/// The bytes allocated so far for the allowed messages of a packet, for
+/// `DecodeOptions::max_owned_bytes`.
+struct Budget {
+    max: Option<usize>,
+    used: usize,
+}
+
+impl Budget {
+    fn charge(&mut self, bytes: usize) -> Result<(), OscError> {
+        self.used = self.used.saturating_add(bytes);
+        match self.max {
+            Some(budget) if self.used > budget => Err(OscError::MemoryBudgetExceeded {
+                budget,
+                needed: self.used,
+            }),
+            _ => Ok(()),
+        }
+    }
+}
+
+fn decode_filtered(
+    msg: &[u8],
+    filter: &AddressFilter,
+    options: &DecodeOptions,
+    depth: usize,
+    timetag: Option<OscTime>,
+    budget: &mut Budget,
+) -> Result<Option<OscPacket>, OscError> {
+    // Elements of bundles are stored in the bundle's vector of packets
+    let element_size = if depth > 0 {
+        mem::size_of::<OscPacket>()
+    } else {
+        0
+    };
+    match decoder::peek_address(msg)? {
+        Some(addr) => match filter.check(addr) {
+            Verdict::Allow => {
+                if budget.max.is_some() {
+                    if let Some(estimate) = decoder::owned_bytes_estimate(msg, options) {
+                        let inserted = timetag.map_or(0, |_| mem::size_of::<OscType>());
+                        budget.charge(estimate + inserted + element_size)?;
+                    }
+                }
+                let (_, mut osc_packet) = decoder::decode_udp_with_options(msg, options)?;
+                if let (Some(timetag), OscPacket::Message(msg)) = (timetag, &mut osc_packet) {
+                    msg.insert_arg(0, OscType::Time(timetag));
+                }
+                Ok(Some(osc_packet))
+            }
+            Verdict::Deny => Ok(None),
+        },
+        None => {
+            budget.charge(element_size)?;
+            decode_filtered_bundle(&msg[BUNDLE_TAG.len()..], filter, options, depth + 1, budget)
+                .map(|bundle| Some(OscPacket::Bundle(bundle)))
+        }
+    }
+}
+
+fn decode_filtered_bundle(
+    mut input: &[u8],
+    filter: &AddressFilter,
+    options: &DecodeOptions,
+    depth: usize,
+    budget: &mut Budget,
+) -> Result<OscBundle, OscError> {
+    if depth > MAX_BUNDLE_DEPTH {
+        return Err(OscError::BadBundle(format!(
+            "Bundles nested deeper than {} levels",
+            MAX_BUNDLE_DEPTH
+        )));
+    }
+    if input.len() < 8 {
+        return Err(OscError::BadBundle("Missing time tag".to_string()));
+    }
+    let timetag = OscTime::from((
+        BigEndian::read_u32(&input[..4]),
+        BigEndian::read_u32(&input[4..8]),
+    ));
+    input = &input[8..];
+
+    let mut content = Vec::new();
+    while !input.is_empty() {
+        if input.len() < 4 {
+            return Err(OscError::NeedMoreData {
+                needed: 4 - input.len(),
+            });
+        }
+        let size = BigEndian::read_u32(input) as usize;
+        input = &input[4..];
+        if size > input.len() {
+            return Err(OscError::NeedMoreData {
+                needed: size - input.len(),
+            });
+        }
+
+        let (element, rest) = input.split_at(size);
+        let element_timetag = options.timetag_as_arg.then_some(timetag);
+        if let Some(osc_packet) =
+            decode_filtered(element, filter, options, depth, element_timetag, budget)?
+        {
+            content.push(osc_packet);
+        }
+        input = rest;
+    }
+
+    Ok(OscBundle { timetag, content })
+}
@@ -0,0 +1,257 @@
+use crate::alloc::vec::Vec;
+use crate::decoder::{self, DecodeOptions};
+use crate::encoder;
+use crate::errors::OscError;
+use crate::types::{OscPacket, Result, SpecProfile};
+
+use byteorder::{BigEndian, ByteOrder};
+
+/// The SLIP byte that ends (and starts) a packet.
+const SLIP_END: u8 = 0xc0;
+/// The SLIP byte that starts an escape sequence.
+const SLIP_ESC: u8 = 0xdb;
+/// Follows `SLIP_ESC` to stand for `SLIP_END`.
+const SLIP_ESC_END: u8 = 0xdc;
+/// Follows `SLIP_ESC` to stand for `SLIP_ESC`.
+const SLIP_ESC_ESC: u8 = 0xdd;
+
+/// How packets are delimited on a transport.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Framing {
+    /// A packet per datagram without any framing, as used over UDP. This is what
+    /// [`encoder::encode`] and [`decoder::decode_udp`] do.
+    Datagram,
+    /// Each packet is preceded by its size as a 32-bit big-endian integer, as used over TCP by
+    /// OSC 1.0. This is the framing of [`decoder::decode_tcp`].
+    LengthPrefixed,
+    /// Packets are delimited by SLIP ([RFC 1055](https://tools.ietf.org/html/rfc1055)) end
+    /// bytes, as used over serial lines and TCP by OSC 1.1. Packets are encoded with an end
+    /// byte on both sides, and any number of end bytes between packets is accepted.
+    Slip,
+}
+
+impl Framing {
+    /// Returns the framing that `profile` expects on streams: length prefixes for OSC 1.0 and
+    /// SLIP for OSC 1.1. [`SpecProfile::Permissive`] uses length prefixes like
+    /// [`decoder::decode_tcp`].
+    pub fn for_profile(profile: SpecProfile) -> Self {
+        match profile {
+            SpecProfile::Osc10Strict | SpecProfile::Permissive => Framing::LengthPrefixed,
+            SpecProfile::Osc11 => Framing::Slip,
+        }
+    }
+}
+
+/// Encodes a packet with the given framing.
+///
+/// # Example
+///
+/// ```
+/// use rosc::framing::{self, Framing};
+/// use rosc::{encoder, OscMessage, OscPacket};
+///
+/// let packet = OscPacket::Message(OscMessage::from("/ping"));
+/// let bytes = framing::encode_framed(&packet, Framing::LengthPrefixed).unwrap();
+/// assert_eq!(bytes[..4], [0, 0, 0, 12]);
+/// assert_eq!(bytes[4..], encoder::encode(&packet).unwrap()[..]);
+/// ```
+pub fn encode_framed(packet: &OscPacket, framing: Framing) -> Result<Vec<u8>> {
+    match framing {
+        Framing::Datagram => encoder::encode(packet),
+        Framing::LengthPrefixed => {
+            let mut out = vec![0u8; 4];
+            let size = encoder::encode_into(packet, &mut out)?;
+            BigEndian::write_u32(&mut out[..4], size as u32);
+            Ok(out)
+        }
+        Framing::Slip => {
+            let bytes = encoder::encode(packet)?;
+            let mut out = Vec::with_capacity(bytes.len() + 2);
+            out.push(SLIP_END);
+            for byte in bytes {
+                match byte {
+                    SLIP_END => out.extend([SLIP_ESC, SLIP_ESC_END]),
+                    SLIP_ESC => out.extend([SLIP_ESC, SLIP_ESC_ESC]),
+                    _ => out.push(byte),
+                }
+            }
+            out.push(SLIP_END);
+            Ok(out)
+        }
+    }
+}
+
+/// Decodes the first packet of `bytes` with the given framing, returning the number of bytes it
+/// took up together with the packet.
+///
+/// The rest of `bytes` can be passed to another call to decode the next packet of a stream. If
+/// `bytes` ends before the first packet does, [`OscError::NeedMoreData`] is returned, so that
+/// stream consumers can wait for more data. Datagrams can't be split, so `bytes` is decoded like
+/// by [`decoder::decode_udp`], which returns the bytes following a message as its remainder.
+///
+/// # Example
+///
+/// ```
+/// use rosc::framing::{self, Framing};
+/// use rosc::{OscMessage, OscPacket};
+///
+/// let ping = OscPacket::Message(OscMessage::from("/ping"));
+/// let pong = OscPacket::Message(OscMessage::from("/pong"));
+/// let mut stream = framing::encode_framed(&ping, Framing::Slip).unwrap();
+/// stream.extend(framing::encode_framed(&pong, Framing::Slip).unwrap());
+///
+/// let (len, packet) = framing::decode_framed(&stream, Framing::Slip).unwrap();
+/// assert_eq!(packet, ping);
+/// let (_, packet) = framing::decode_framed(&stream[len..], Framing::Slip).unwrap();
+/// assert_eq!(packet, pong);
+/// ```
+pub fn decode_framed(bytes: &[u8], framing: Framing) -> Result<(usize, OscPacket)> {
+    decode_framed_with_options(bytes, framing, &DecodeOptions::default())
+}
+
+/// Like [`decode_framed`], but using the given decoding options.
+pub fn decode_framed_with_options(
+    bytes: &[u8],
+    framing: Framing,
+    options: &DecodeOptions,
+) -> Result<(usize, OscPacket)> {
+    match framing {
+        Framing::Datagram => decoder::decode_udp_with_options(bytes, options)
+            .map(|(remainder, osc_packet)| (bytes.len() - remainder.len(), osc_packet)),
+        Framing::LengthPrefixed => {
+            if bytes.len() < 4 {
+                return Err(OscError::NeedMoreData {
+                    needed: 4 - bytes.len(),
+                });
+            }
+            let size = BigEndian::read_u32(bytes) as usize;
+            let frame = &bytes[4..];
+            if size > frame.len() {
+                return Err(OscError::NeedMoreData {
+                    needed: size - frame.len(),
+                });
+            }
+            decode_frame(&frame[..size], options).map(|osc_packet| (4 + size, osc_packet))
+        }
+        Framing::Slip => {
+            let start = bytes
+                .iter()
+                .position(|&b| b != SLIP_END)
+                .ok_or(OscError::NeedMoreData { needed: 1 })?;
+            let len = bytes[start..]
+                .iter()
+                .position(|&b| b == SLIP_END)
+                .ok_or(OscError::NeedMoreData { needed: 1 })?;
+            let frame = slip_unescape(&bytes[start..start + len])?;
+            decode_frame(&frame, options).map(|osc_packet| (start + len + 1, osc_packet))
+        }
+    }
+}
+
+/// Returns the number of bytes to skip after [`decode_framed`] failed on `bytes` with an error
+/// other than [`OscError::NeedMoreData`], so that decoding can resume at the next plausible frame
+/// instead of giving up on the whole stream.
+///
+/// * [`Framing::Datagram`]: the whole datagram is skipped.
+/// * [`Framing::Slip`]: the corrupt frame is skipped, up to the end byte that ends it.
+/// * [`Framing::LengthPrefixed`]: the length prefix of the corrupt frame can't be trusted, so the
+///   following offsets are scanned for a plausible one instead: a multiple of 4 followed by what
+///   [`decoder::resync`] considers the start of a packet. At most `max_scan` bytes are skipped.
+///   Scanning stops before the last 4 bytes, which are kept until more data arrives.
+///
+/// At least one byte is skipped if `bytes` isn't empty. Like [`decoder::resync`], this is a
+/// heuristic, so packets following the corruption can be lost.
+///
+/// # Example
+///
+/// ```
+/// use rosc::framing::{self, Framing};
+/// use rosc::{OscMessage, OscPacket};
+///
+/// let packet = OscPacket::Message(OscMessage::from("/ping"));
+/// let mut stream = framing::encode_framed(&packet, Framing::LengthPrefixed).unwrap();
+/// // Corrupt the length prefix of the first frame
+/// stream[0] = 0xff;
+/// stream.extend(framing::encode_framed(&packet, Framing::LengthPrefixed).unwrap());
+///
+/// let mut bytes = &stream[..];
+/// let mut resyncs = 0;
+/// let mut decoded = vec![];
+/// while !bytes.is_empty() {
+///     match framing::decode_framed(bytes, Framing::LengthPrefixed) {
+///         Ok((len, packet)) => {
+///             decoded.push(packet);
+///             bytes = &bytes[len..];
+///         }
+///         Err(_) => {
+///             resyncs += 1;
+///             bytes = &bytes[framing::resume_hint(bytes, Framing::LengthPrefixed, 1024)..];
+///         }
+///     }
+/// }
+/// assert_eq!(decoded, vec![packet]);
+/// assert_eq!(resyncs, 1);
+/// ```
+pub fn resume_hint(bytes: &[u8], framing: Framing, max_scan: usize) -> usize {
+    let skip = match framing {
+        Framing::Datagram => bytes.len(),
+        Framing::LengthPrefixed => {
+            let end = max_scan.min(bytes.len().saturating_sub(4));
+            (1..=end)
+                .find(|&offset| plausible_length_prefix(&bytes[offset..]))
+                .unwrap_or(end)
+        }
+        Framing::Slip => {
+            let start = bytes
+                .iter()
+                .position(|&b| b != SLIP_END)
+                .unwrap_or(bytes.len());
+            bytes[start..]
+                .iter()
+                .position(|&b| b == SLIP_END)
+                .map_or(bytes.len(), |len| start + len)
+        }
+    };
+    skip.max(1).min(bytes.len())
+}
+
+/// Returns whether `bytes` starts with a length prefix that is followed by what looks like the
+/// start of a packet of that size.
+fn plausible_length_prefix(bytes: &[u8]) -> bool {
+    if bytes.len() < 5 {
+        return false;
+    }
+    let size = BigEndian::read_u32(bytes) as usize;
+    size >= 8
+        && size.is_multiple_of(4)
+        && decoder::resync(&bytes[..bytes.len().min(4 + size)], 4) == Some(4)
+}
+
+/// Decodes a packet that is known to be complete, so that running out of data is an error in the
+/// packet rather than a reason to wait for more.
+fn decode_frame(frame: &[u8], options: &DecodeOptions) -> Result<OscPacket> {
+    match decoder::decode_udp_with_options(frame, options) {
+        Ok((_, osc_packet)) => Ok(osc_packet),
+        Err(OscError::NeedMoreData { .. }) => Err(OscError::BadPacket(
+            "Bundle element exceeds the end of its frame",
+        )),
+        Err(err) => Err(err),
+    }
+}
+
+fn slip_unescape(escaped: &[u8]) -> Result<Vec<u8>> {
+    let mut frame = Vec::with_capacity(escaped.len());
+    let mut bytes = escaped.iter();
+    while let Some(&byte) = bytes.next() {
+        if byte != SLIP_ESC {
+            frame.push(byte);
+            continue;
+        }
+        match bytes.next() {
+            Some(&SLIP_ESC_END) => frame.push(SLIP_END),
+            Some(&SLIP_ESC_ESC) => frame.push(SLIP_ESC),
+            _ => return Err(OscError::BadPacket("Invalid SLIP escape sequence")),
+        }
+    }
+    Ok(frame)
+}
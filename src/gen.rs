@@ -0,0 +1,342 @@
+use crate::address::Matcher;
+use crate::errors::OscError;
+use crate::types::{OscBundle, OscMessage, OscPacket, OscTime, OscType, TypeTag};
+
+use std::f64::consts::PI;
+
+/// The characters used for generated strings and for wildcards in address patterns unless
+/// another charset is given.
+pub const ALPHANUMERIC: &str = "abcdefghijklmnopqrstuvwxyzABCDEFGHIJKLMNOPQRSTUVWXYZ0123456789";
+
+/// The distribution of generated `Float` and `Double` arguments.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum FloatDist {
+    /// Uniformly distributed in `min..max`.
+    Uniform { min: f64, max: f64 },
+    /// Normally distributed around `mean`.
+    Normal { mean: f64, std_dev: f64 },
+}
+
+/// How to generate a single argument.
+#[derive(Clone, Debug, PartialEq)]
+pub enum ArgSpec {
+    /// An `Int` in `min..=max`.
+    Int { min: i32, max: i32 },
+    /// A `Long` in `min..=max`.
+    Long { min: i64, max: i64 },
+    /// A `Float` following the given distribution.
+    Float(FloatDist),
+    /// A `Double` following the given distribution.
+    Double(FloatDist),
+    /// A `String` of `min_len..=max_len` characters picked from `charset`.
+    String {
+        min_len: usize,
+        max_len: usize,
+        charset: String,
+    },
+    /// A `Blob` of `min_len..=max_len` random bytes.
+    Blob { min_len: usize, max_len: usize },
+    /// A random `Bool`.
+    Bool,
+    /// Always the given argument.
+    Fixed(OscType),
+}
+
+impl ArgSpec {
+    /// Returns a spec for strings of `min_len..=max_len` [alphanumeric](ALPHANUMERIC) characters.
+    pub fn alphanumeric(min_len: usize, max_len: usize) -> Self {
+        ArgSpec::String {
+            min_len,
+            max_len,
+            charset: ALPHANUMERIC.to_string(),
+        }
+    }
+
+    /// Returns the type tag of the arguments generated by this spec, or `None` if it depends on
+    /// the generated value, as for `Bool`.
+    pub fn type_tag(&self) -> Option<char> {
+        match self {
+            ArgSpec::Int { .. } => Some(TypeTag::Int.to_char()),
+            ArgSpec::Long { .. } => Some(TypeTag::Long.to_char()),
+            ArgSpec::Float(_) => Some(TypeTag::Float.to_char()),
+            ArgSpec::Double(_) => Some(TypeTag::Double.to_char()),
+            ArgSpec::String { .. } => Some(TypeTag::String.to_char()),
+            ArgSpec::Blob { .. } => Some(TypeTag::Blob.to_char()),
+            ArgSpec::Bool
+            | ArgSpec::Fixed(OscType::Bool(_))
+            | ArgSpec::Fixed(OscType::Array(_)) => None,
+            ArgSpec::Fixed(arg) => Some(TypeTag::of(arg).to_char()),
+        }
+    }
+}
+
+struct Entry {
+    address: String,
+    args: Vec<ArgSpec>,
+}
+
+/// A generator of random, but valid messages and bundles, e.g. for load testing.
+///
+/// The generator is configured with entries, each consisting of an address or address pattern
+/// and a spec for every argument. Each generated message follows a randomly picked entry. If the
+/// entry's address is a pattern, a random address matching it is generated: `?` and `*` are
+/// replaced by [alphanumeric](ALPHANUMERIC) characters, and character classes and alternatives
+/// by one of their options.
+///
+/// The generator is seeded explicitly and produces the same sequence of messages for the same
+/// seed and entries on every platform, so failing load tests can be reproduced. The random
+/// numbers are not suitable for anything security related.
+///
+/// # Examples
+///
+/// ```
+/// use rosc::gen::{ArgSpec, FloatDist, Generator};
+///
+/// let mut gen = Generator::from_seed(42);
+/// gen.entry(
+///     "/mixer/[1-8]/gain",
+///     vec![ArgSpec::Float(FloatDist::Uniform { min: 0.0, max: 1.0 })],
+/// )
+/// .unwrap();
+///
+/// let msg = gen.message();
+/// assert!(msg.addr.starts_with("/mixer/"));
+/// assert!(msg.args[0].clone().float().unwrap() < 1.0);
+/// ```
+pub struct Generator {
+    rng: SplitMix64,
+    entries: Vec<Entry>,
+}
+
+impl Generator {
+    /// Creates a generator without any entries, seeded with `seed`.
+    pub fn from_seed(seed: u64) -> Self {
+        Generator {
+            rng: SplitMix64(seed),
+            entries: Vec::new(),
+        }
+    }
+
+    /// Adds an entry generating messages to `address` with arguments following `args`.
+    ///
+    /// An error is returned if `address` is not a valid address pattern, or if a spec has an
+    /// empty range or charset.
+    pub fn entry<S: Into<String>>(
+        &mut self,
+        address: S,
+        args: Vec<ArgSpec>,
+    ) -> Result<&mut Self, OscError> {
+        let address = address.into();
+        Matcher::new(&address)?;
+        for spec in &args {
+            let valid = match spec {
+                ArgSpec::Int { min, max } => min <= max,
+                ArgSpec::Long { min, max } => min <= max,
+                ArgSpec::Float(dist) | ArgSpec::Double(dist) => match *dist {
+                    FloatDist::Uniform { min, max } => min <= max,
+                    FloatDist::Normal { std_dev, .. } => std_dev >= 0.0,
+                },
+                ArgSpec::String {
+                    min_len,
+                    max_len,
+                    charset,
+                } => min_len <= max_len && !charset.is_empty(),
+                ArgSpec::Blob { min_len, max_len } => min_len <= max_len,
+                ArgSpec::Bool | ArgSpec::Fixed(_) => true,
+            };
+            if !valid {
+                return Err(OscError::BadArg(format!(
+                    "Invalid argument spec {:?}",
+                    spec
+                )));
+            }
+        }
+
+        self.entries.push(Entry { address, args });
+        Ok(self)
+    }
+
+    /// Generates a message following a randomly picked entry.
+    ///
+    /// # Panics
+    ///
+    /// Panics if no entries were added.
+    pub fn message(&mut self) -> OscMessage {
+        assert!(!self.entries.is_empty(), "Generator has no entries");
+        let index = self.rng.below(self.entries.len() as u64) as usize;
+
+        let Generator { rng, entries } = self;
+        let entry = &entries[index];
+        OscMessage {
+            addr: expand_pattern(rng, &entry.address),
+            args: entry
+                .args
+                .iter()
+                .map(|spec| generate_arg(rng, spec))
+                .collect(),
+        }
+    }
+
+    /// Generates a bundle with the time tag `timetag` containing `len` messages.
+    ///
+    /// # Panics
+    ///
+    /// Panics if no entries were added.
+    pub fn bundle(&mut self, timetag: OscTime, len: usize) -> OscBundle {
+        OscBundle {
+            timetag,
+            content: (0..len)
+                .map(|_| OscPacket::Message(self.message()))
+                .collect(),
+        }
+    }
+}
+
+impl Iterator for Generator {
+    type Item = OscMessage;
+
+    /// Generates a message, see [`message`](Generator::message).
+    fn next(&mut self) -> Option<OscMessage> {
+        Some(self.message())
+    }
+}
+
+fn generate_arg(rng: &mut SplitMix64, spec: &ArgSpec) -> OscType {
+    match spec {
+        ArgSpec::Int { min, max } => {
+            OscType::Int(rng.in_range(i64::from(*min), i64::from(*max)) as i32)
+        }
+        ArgSpec::Long { min, max } => OscType::Long(rng.in_range(*min, *max)),
+        ArgSpec::Float(dist) => OscType::Float(rng.float(dist) as f32),
+        ArgSpec::Double(dist) => OscType::Double(rng.float(dist)),
+        ArgSpec::String {
+            min_len,
+            max_len,
+            charset,
+        } => {
+            let charset: Vec<char> = charset.chars().collect();
+            let len = rng.in_range(*min_len as i64, *max_len as i64);
+            OscType::String((0..len).map(|_| rng.pick(&charset)).collect())
+        }
+        ArgSpec::Blob { min_len, max_len } => {
+            let len = rng.in_range(*min_len as i64, *max_len as i64);
+            OscType::Blob((0..len).map(|_| rng.next() as u8).collect())
+        }
+        ArgSpec::Bool => OscType::Bool(rng.next() & 1 == 1),
+        ArgSpec::Fixed(arg) => arg.clone(),
+    }
+}
+
+/// Generates a random address matching `pattern`, which must be a valid address pattern.
+fn expand_pattern(rng: &mut SplitMix64, pattern: &str) -> String {
+    let alphanumeric: Vec<char> = ALPHANUMERIC.chars().collect();
+    let pattern: Vec<char> = pattern.chars().collect();
+    let mut address = String::with_capacity(pattern.len());
+
+    let mut i = 0;
+    while i < pattern.len() {
+        match pattern[i] {
+            '?' => address.push(rng.pick(&alphanumeric)),
+            '*' => {
+                for _ in 0..rng.in_range(1, 8) {
+                    address.push(rng.pick(&alphanumeric));
+                }
+            }
+            '[' => {
+                let end = i + pattern[i..].iter().position(|&c| c == ']').unwrap();
+                let class = &pattern[i + 1..end];
+                let (negated, class) = match class.split_first() {
+                    Some(('!', rest)) => (true, rest),
+                    _ => (false, class),
+                };
+
+                let mut members = Vec::new();
+                let mut j = 0;
+                while j < class.len() {
+                    if j + 2 < class.len() && class[j + 1] == '-' {
+                        // Like the matcher, ignore characters not allowed in addresses
+                        members.extend(
+                            (class[j]..=class[j + 2]).filter(|c| !" #*,/?[]{}".contains(*c)),
+                        );
+                        j += 3;
+                    } else {
+                        members.push(class[j]);
+                        j += 1;
+                    }
+                }
+                if negated {
+                    members = alphanumeric
+                        .iter()
+                        .cloned()
+                        .filter(|c| !members.contains(c))
+                        .collect();
+                }
+                if members.is_empty() {
+                    // Only possible for a negated class excluding all alphanumeric characters
+                    members.push('_');
+                }
+                address.push(rng.pick(&members));
+                i = end;
+            }
+            '{' => {
+                let end = i + pattern[i..].iter().position(|&c| c == '}').unwrap();
+                let choices: String = pattern[i + 1..end].iter().collect();
+                let choices: Vec<&str> = choices.split(',').collect();
+                address.push_str(choices[rng.below(choices.len() as u64) as usize]);
+                i = end;
+            }
+            c => address.push(c),
+        }
+        i += 1;
+    }
+    address
+}
+
+/// The SplitMix64 generator, which is tiny, fast and good enough for generating test traffic.
+struct SplitMix64(u64);
+
+impl SplitMix64 {
+    fn next(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+
+    /// Returns a number in `0..n`, which must not be zero.
+    fn below(&mut self, n: u64) -> u64 {
+        // Widening multiplication, the bias is negligible for the ranges used here
+        ((u128::from(self.next()) * u128::from(n)) >> 64) as u64
+    }
+
+    /// Returns a number in `min..=max`.
+    fn in_range(&mut self, min: i64, max: i64) -> i64 {
+        let span = max.wrapping_sub(min) as u64;
+        match span.checked_add(1) {
+            Some(n) => min.wrapping_add(self.below(n) as i64),
+            None => self.next() as i64,
+        }
+    }
+
+    /// Returns a number in `0.0..1.0`.
+    fn unit(&mut self) -> f64 {
+        (self.next() >> 11) as f64 / (1u64 << 53) as f64
+    }
+
+    fn float(&mut self, dist: &FloatDist) -> f64 {
+        match *dist {
+            FloatDist::Uniform { min, max } => min + (max - min) * self.unit(),
+            FloatDist::Normal { mean, std_dev } => {
+                // Box-Muller transform, avoiding the logarithm of zero
+                let u1 = 1.0 - self.unit();
+                let u2 = self.unit();
+                mean + std_dev * (-2.0 * u1.ln()).sqrt() * (2.0 * PI * u2).cos()
+            }
+        }
+    }
+
+    fn pick<T: Copy>(&mut self, choices: &[T]) -> T {
+        choices[self.below(choices.len() as u64) as usize]
+    }
+}
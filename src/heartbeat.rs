@@ -0,0 +1,181 @@
+use crate::types::{OscMessage, OscType};
+
+use std::time::{Duration, Instant};
+
+/// The address used for heartbeat pings unless another one is configured.
+pub const DEFAULT_ADDRESS: &str = "/rosc/ping";
+
+/// How far behind the newest observed ping a ping may be to count as reordered rather than as a
+/// restart of the peer.
+pub const MAX_REORDER: i64 = 32;
+
+/// Liveness detection based on periodic OSC pings.
+///
+/// A `Heartbeat` does not spawn any threads or read any clocks by itself. Instead, it is driven
+/// from the application's own event loop by passing in the current time:
+///
+/// - On the sending side, [`due_ping`](Heartbeat::due_ping) is called regularly and returns a
+///   ping message whenever one should be sent.
+/// - On the receiving side, every incoming message is passed to
+///   [`observe`](Heartbeat::observe), and [`is_alive`](Heartbeat::is_alive) tells whether the
+///   peer has sent a ping within the configured timeout.
+///
+/// Each ping carries a monotonically increasing `Long` sequence number. This lets the receiving
+/// side count pings that never arrived ([`missed_count`](Heartbeat::missed_count)) and detect
+/// when the peer restarted ([`restart_count`](Heartbeat::restart_count)), because its sequence
+/// numbers start over.
+///
+/// Since pings are usually sent over UDP, they may be duplicated or arrive out of order.
+/// Duplicates are ignored, and a ping arriving late, at most [`MAX_REORDER`] sequence numbers
+/// after a newer one, is no longer counted as missed. Only a ping with sequence number 0, or
+/// one further behind, is taken as a restart.
+///
+/// # Examples
+///
+/// ```
+/// use rosc::heartbeat::Heartbeat;
+/// use std::time::{Duration, Instant};
+///
+/// let mut sender = Heartbeat::new(Duration::from_secs(1), Duration::from_secs(3));
+/// let mut receiver = Heartbeat::new(Duration::from_secs(1), Duration::from_secs(3));
+///
+/// let start = Instant::now();
+/// let ping = sender.due_ping(start).expect("first ping is due immediately");
+/// assert!(sender.due_ping(start).is_none());
+///
+/// assert!(receiver.observe(&ping, start));
+/// assert!(receiver.is_alive(start + Duration::from_secs(2)));
+/// assert!(!receiver.is_alive(start + Duration::from_secs(4)));
+/// ```
+#[derive(Clone, Debug)]
+pub struct Heartbeat {
+    address: String,
+    interval: Duration,
+    timeout: Duration,
+    next_ping: Option<Instant>,
+    next_sequence: i64,
+    last_seen: Option<Instant>,
+    last_sequence: Option<i64>,
+    /// Which of the last 64 sequence numbers up to `last_sequence` were received, with bit `n`
+    /// standing for `last_sequence - n`.
+    recent: u64,
+    missed: u64,
+    restarts: u64,
+}
+
+impl Heartbeat {
+    /// Creates a heartbeat using the [default address](DEFAULT_ADDRESS), sending a ping every
+    /// `interval` and considering the peer dead once no ping was received for `timeout`.
+    pub fn new(interval: Duration, timeout: Duration) -> Self {
+        Heartbeat::with_address(DEFAULT_ADDRESS, interval, timeout)
+    }
+
+    /// Creates a heartbeat like [`new`](Heartbeat::new), but using a custom ping address.
+    pub fn with_address<S: Into<String>>(
+        address: S,
+        interval: Duration,
+        timeout: Duration,
+    ) -> Self {
+        Heartbeat {
+            address: address.into(),
+            interval,
+            timeout,
+            next_ping: None,
+            next_sequence: 0,
+            last_seen: None,
+            last_sequence: None,
+            recent: 0,
+            missed: 0,
+            restarts: 0,
+        }
+    }
+
+    /// Returns the address pings are sent to.
+    pub fn address(&self) -> &str {
+        &self.address
+    }
+
+    /// Returns a ping message if one is due at `now`, or `None` otherwise.
+    ///
+    /// The first ping is due immediately, every following one `interval` after the previous one.
+    pub fn due_ping(&mut self, now: Instant) -> Option<OscMessage> {
+        if let Some(next_ping) = self.next_ping {
+            if now < next_ping {
+                return None;
+            }
+        }
+
+        let sequence = self.next_sequence;
+        self.next_sequence += 1;
+        self.next_ping = Some(now + self.interval);
+        Some(OscMessage {
+            addr: self.address.clone(),
+            args: vec![OscType::Long(sequence)],
+        })
+    }
+
+    /// Inspects a received message and records it if it is a ping.
+    ///
+    /// Returns `true` if `msg` was a ping, so that the caller can skip further processing of it.
+    pub fn observe(&mut self, msg: &OscMessage, now: Instant) -> bool {
+        if msg.addr != self.address {
+            return false;
+        }
+        let sequence = match msg.args.first() {
+            Some(OscType::Long(sequence)) => *sequence,
+            _ => return false,
+        };
+
+        self.last_seen = Some(now);
+        let last_sequence = match self.last_sequence {
+            Some(last_sequence) => last_sequence,
+            None => {
+                self.recent = 1;
+                self.last_sequence = Some(sequence);
+                return true;
+            }
+        };
+        let distance = sequence.abs_diff(last_sequence);
+        if sequence > last_sequence {
+            self.missed = self.missed.saturating_add(distance - 1);
+            self.recent = if distance < 64 {
+                (self.recent << distance) | 1
+            } else {
+                1
+            };
+            self.last_sequence = Some(sequence);
+        } else if distance > 0 && (sequence == 0 || distance > MAX_REORDER as u64) {
+            // Sequence numbers started over, so the peer restarted
+            self.restarts += 1;
+            self.recent = 1;
+            self.last_sequence = Some(sequence);
+        } else {
+            // A duplicate, or a late ping that was counted as missed when a newer one arrived
+            let bit = 1 << distance;
+            if self.recent & bit == 0 {
+                self.recent |= bit;
+                self.missed = self.missed.saturating_sub(1);
+            }
+        }
+        true
+    }
+
+    /// Returns `true` if a ping was received no longer than `timeout` before `now`.
+    pub fn is_alive(&self, now: Instant) -> bool {
+        match self.last_seen {
+            Some(last_seen) => now.saturating_duration_since(last_seen) <= self.timeout,
+            None => false,
+        }
+    }
+
+    /// Returns the number of pings that were never received, based on gaps in the sequence
+    /// numbers of the observed pings.
+    pub fn missed_count(&self) -> u64 {
+        self.missed
+    }
+
+    /// Returns the number of times the peer was detected to have restarted.
+    pub fn restart_count(&self) -> u64 {
+        self.restarts
+    }
+}
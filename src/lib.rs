@@ -13,21 +13,77 @@ extern crate std as core;
 #[macro_use]
 extern crate std as alloc;
 
+#[cfg(feature = "base64")]
+extern crate base64;
 extern crate byteorder;
+#[cfg(feature = "midly")]
+extern crate midly;
 extern crate nom;
+#[cfg(feature = "tracing")]
+extern crate tracing;
 
+/// Macros for constructing messages and bundles.
+#[macro_use]
+mod macros;
+/// A runtime self-test of the encoder and decoder.
+mod conformance;
+/// Copy-on-write editing of packets.
+mod editor;
 /// Crate specific error types.
 mod errors;
 /// OSC data types, see [OSC 1.0 specification](https://opensoundcontrol.stanford.edu/spec-1_0.html) for details.
 mod types;
 
+pub use crate::conformance::{self_test, SelfTestFailure};
+pub use crate::editor::OscPacketEditor;
+/// Items used by the macros of this crate. Not part of the public API.
+#[doc(hidden)]
+pub mod __private {
+    pub use crate::alloc::string::String;
+    pub use crate::alloc::vec::Vec;
+    pub use crate::macros::approx_diff;
+    pub use core::convert::AsRef;
+}
 pub use crate::errors::*;
 pub use crate::types::*;
 
 /// Address checking and matching methods
 #[cfg(feature = "std")]
 pub mod address;
+/// Helpers for interpreting blob arguments as typed sample data.
+pub mod blob;
+/// A pool of reusable receive buffers.
+#[cfg(feature = "std")]
+pub mod buffer;
+/// Annotated hex dumps of encoded packets, for debugging.
+pub mod debug;
 /// Provides a decoding method for OSC packets.
 pub mod decoder;
+/// Suppression of duplicate packets, e.g. from retransmissions.
+#[cfg(feature = "std")]
+pub mod dedup;
 /// Encodes an `OscPacket` to a byte vector.
 pub mod encoder;
+/// Dropping packets by address before decoding them.
+#[cfg(feature = "std")]
+pub mod filter;
+/// Encoding and decoding packets with the framings of different transports.
+pub mod framing;
+/// Random generation of valid messages, e.g. for load testing.
+#[cfg(feature = "std")]
+pub mod gen;
+/// Liveness detection using periodic OSC pings.
+#[cfg(feature = "std")]
+pub mod heartbeat;
+/// Conversions between MIDI arguments and `midly` live events.
+#[cfg(feature = "midly")]
+pub mod midi;
+/// Namespace query messages following the original OSC query proposal.
+pub mod query;
+/// Splitting message streams into typed per-address value streams.
+#[cfg(feature = "std")]
+pub mod route;
+/// Comparing type signatures of messages for compatibility.
+pub mod signature;
+/// Opt-in statistics about encoded and decoded packets.
+pub mod stats;
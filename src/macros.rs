@@ -0,0 +1,237 @@
+use crate::alloc::string::String;
+use crate::types::{FloatTolerance, OscMessage};
+
+/// Creates an [`OscMessage`](crate::OscMessage) from an address and a list of arguments.
+///
+/// Each argument is converted using the `From` implementations of
+/// [`OscType`](crate::OscType), so its Rust type determines the argument type. Where that would
+/// pick the wrong type, an argument can be wrapped to choose the type explicitly:
+///
+/// | Syntax      | Argument                                     |
+/// |-------------|----------------------------------------------|
+/// | `int(x)`    | `Int(x as i32)`                              |
+/// | `long(x)`   | `Long(x as i64)`                             |
+/// | `float(x)`  | `Float(x as f32)`                            |
+/// | `double(x)` | `Double(x as f64)`                           |
+/// | `string(x)` | `String(x.into())`                           |
+/// | `blob(x)`   | `Blob` holding a copy of `x`, e.g. a `&[u8]` |
+/// | `nil`       | `Nil`                                        |
+/// | `inf`       | `Inf`                                        |
+///
+/// # Examples
+///
+/// ```
+/// #[macro_use]
+/// extern crate rosc;
+///
+/// use rosc::{OscMessage, OscType};
+///
+/// # fn main() {
+/// let msg = osc_msg!("/synth/freq", 440.0f32, 1, "sine", float(880), blob([1, 2]), nil);
+/// assert_eq!(
+///     msg,
+///     OscMessage {
+///         addr: "/synth/freq".to_string(),
+///         args: vec![
+///             OscType::Float(440.0),
+///             OscType::Int(1),
+///             OscType::String("sine".to_string()),
+///             OscType::Float(880.0),
+///             OscType::Blob(vec![1, 2]),
+///             OscType::Nil,
+///         ],
+///     }
+/// );
+/// # }
+/// ```
+///
+/// Arguments without a conversion to `OscType` are rejected at compile time:
+///
+/// ```compile_fail
+/// #[macro_use]
+/// extern crate rosc;
+///
+/// # fn main() {
+/// let msg = osc_msg!("/counter", 1u64);
+/// # }
+/// ```
+///
+/// ```compile_fail
+/// #[macro_use]
+/// extern crate rosc;
+///
+/// # fn main() {
+/// let msg = osc_msg!("/freq", float("440"));
+/// # }
+/// ```
+#[macro_export]
+macro_rules! osc_msg {
+    ($addr:expr $(, $($args:tt)*)?) => {
+        $crate::OscMessage {
+            addr: $crate::__private::String::from($addr),
+            args: $crate::__osc_args!([] $($($args)*)?),
+        }
+    };
+}
+
+/// Creates an [`OscBundle`](crate::OscBundle) from a time tag and a list of elements.
+///
+/// The time tag can be anything convertible into an [`OscTime`](crate::OscTime), and the
+/// elements anything convertible into an [`OscPacket`](crate::OscPacket), i.e. messages,
+/// bundles and packets.
+///
+/// # Examples
+///
+/// ```
+/// #[macro_use]
+/// extern crate rosc;
+///
+/// use rosc::{OscBundle, OscPacket, OscTime};
+///
+/// # fn main() {
+/// let bundle = osc_bundle![(0, 1);
+///     osc_msg!("/play"),
+///     osc_bundle![OscTime::from((3_900_000_000, 0)); osc_msg!("/stop")],
+/// ];
+/// assert_eq!(bundle.content.len(), 2);
+/// # }
+/// ```
+///
+/// Elements which aren't packets are rejected at compile time:
+///
+/// ```compile_fail
+/// #[macro_use]
+/// extern crate rosc;
+///
+/// # fn main() {
+/// let bundle = osc_bundle![(0, 1); "/play"];
+/// # }
+/// ```
+#[macro_export]
+macro_rules! osc_bundle {
+    ($time:expr; $($element:expr),* $(,)?) => {
+        $crate::OscBundle {
+            timetag: $crate::OscTime::from($time),
+            content: $crate::__private::Vec::from([$($crate::OscPacket::from($element)),*]),
+        }
+    };
+}
+
+/// Asserts that two [`OscMessage`](crate::OscMessage)s are equal, allowing their floating point
+/// arguments to differ within a [`FloatTolerance`](crate::FloatTolerance), see
+/// [`OscMessage::approx_eq`](crate::OscMessage::approx_eq).
+///
+/// On failure, the panic message names the first differing argument and both of its values,
+/// followed by both messages.
+///
+/// # Examples
+///
+/// ```
+/// #[macro_use]
+/// extern crate rosc;
+///
+/// use rosc::FloatTolerance;
+///
+/// # fn main() {
+/// let tol = FloatTolerance {
+///     absolute: 1e-3,
+///     relative: 0.0,
+/// };
+/// assert_osc_approx_eq!(osc_msg!("/level", 0.5001f32), osc_msg!("/level", 0.5f32), tol);
+/// # }
+/// ```
+#[macro_export]
+macro_rules! assert_osc_approx_eq {
+    ($left:expr, $right:expr, $tol:expr $(,)?) => {
+        match (&$left, &$right) {
+            (left, right) => {
+                if let Some(diff) = $crate::__private::approx_diff(left, right, $tol) {
+                    panic!(
+                        "assertion `left ≈ right` failed: {}\n  left: {:?}\n right: {:?}",
+                        diff, left, right
+                    )
+                }
+            }
+        }
+    };
+}
+
+/// Describes the first difference between two messages that aren't approximately equal, for
+/// [`assert_osc_approx_eq!`].
+pub fn approx_diff(left: &OscMessage, right: &OscMessage, tol: FloatTolerance) -> Option<String> {
+    if left.addr != right.addr {
+        return Some(format!(
+            "addresses differ: {:?} vs {:?}",
+            left.addr, right.addr
+        ));
+    }
+    if let Some(i) = (0..left.args.len().min(right.args.len()))
+        .find(|&i| !left.args[i].approx_eq(&right.args[i], tol))
+    {
+        return Some(format!(
+            "argument {} differs: {:?} vs {:?}",
+            i, left.args[i], right.args[i]
+        ));
+    }
+    if left.args.len() != right.args.len() {
+        return Some(format!(
+            "argument counts differ: {} vs {}",
+            left.args.len(),
+            right.args.len()
+        ));
+    }
+    None
+}
+
+/// Converts the arguments of [`osc_msg!`] into a vector of `OscType`s.
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __osc_args {
+    ([$($done:expr),*]) => {
+        $crate::__private::Vec::<$crate::OscType>::from([$($done),*])
+    };
+    ([$($done:expr),*] int($value:expr) $(, $($rest:tt)*)?) => {
+        $crate::__osc_args!([$($done,)* $crate::OscType::Int($value as i32)] $($($rest)*)?)
+    };
+    ([$($done:expr),*] long($value:expr) $(, $($rest:tt)*)?) => {
+        $crate::__osc_args!([$($done,)* $crate::OscType::Long($value as i64)] $($($rest)*)?)
+    };
+    ([$($done:expr),*] float($value:expr) $(, $($rest:tt)*)?) => {
+        $crate::__osc_args!([$($done,)* $crate::OscType::Float($value as f32)] $($($rest)*)?)
+    };
+    ([$($done:expr),*] double($value:expr) $(, $($rest:tt)*)?) => {
+        $crate::__osc_args!([$($done,)* $crate::OscType::Double($value as f64)] $($($rest)*)?)
+    };
+    ([$($done:expr),*] string($value:expr) $(, $($rest:tt)*)?) => {
+        $crate::__osc_args!(
+            [$($done,)* $crate::OscType::String($crate::__private::String::from($value))]
+            $($($rest)*)?
+        )
+    };
+    ([$($done:expr),*] blob($value:expr) $(, $($rest:tt)*)?) => {
+        $crate::__osc_args!(
+            [$($done,)* $crate::OscType::Blob($crate::__private::Vec::from(
+                $crate::__private::AsRef::<[u8]>::as_ref(&$value),
+            ))]
+            $($($rest)*)?
+        )
+    };
+    ([$($done:expr),*] nil $(, $($rest:tt)*)?) => {
+        $crate::__osc_args!([$($done,)* $crate::OscType::Nil] $($($rest)*)?)
+    };
+    ([$($done:expr),*] inf $(, $($rest:tt)*)?) => {
+        $crate::__osc_args!([$($done,)* $crate::OscType::Inf] $($($rest)*)?)
+    };
+    ([$($done:expr),*] $value:expr $(, $($rest:tt)*)?) => {
+        $crate::__osc_args!([$($done,)* $crate::OscType::from($value)] $($($rest)*)?)
+    };
+}
+
+/// Emits a trace-level event using `tracing` if the `tracing` feature is enabled. Otherwise, the
+/// event and its arguments are compiled out entirely.
+macro_rules! osc_trace {
+    ($($arg:tt)+) => {
+        #[cfg(feature = "tracing")]
+        ::tracing::trace!($($arg)+);
+    };
+}
@@ -0,0 +1,149 @@
+use crate::alloc::string::ToString;
+use crate::errors::OscError;
+use crate::types::OscMidiMessage;
+
+use core::convert::TryFrom;
+use midly::live::LiveEvent;
+use midly::num::{u14, u4, u7};
+use midly::{MidiMessage, PitchBend};
+
+impl OscMidiMessage {
+    /// Converts a `midly` live event into a MIDI argument sent to `port`.
+    ///
+    /// Only channel voice messages fit into the status and two data bytes of a MIDI argument, so
+    /// an [`OscError::BadArg`] is returned for system common (including SysEx) and realtime
+    /// events. Messages with a single data byte, like program changes, leave `data2` zero.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// extern crate midly;
+    /// extern crate rosc;
+    ///
+    /// use midly::live::LiveEvent;
+    /// use midly::MidiMessage;
+    /// use rosc::OscMidiMessage;
+    ///
+    /// let event = LiveEvent::Midi {
+    ///     channel: 2.into(),
+    ///     message: MidiMessage::NoteOn {
+    ///         key: 60.into(),
+    ///         vel: 100.into(),
+    ///     },
+    /// };
+    /// let msg = OscMidiMessage::from_live_event(&event, 1).unwrap();
+    /// assert_eq!(
+    ///     msg,
+    ///     OscMidiMessage {
+    ///         port: 1,
+    ///         status: 0x92,
+    ///         data1: 60,
+    ///         data2: 100,
+    ///     }
+    /// );
+    /// assert_eq!(msg.to_live_event().unwrap(), event);
+    /// ```
+    pub fn from_live_event(event: &LiveEvent, port: u8) -> Result<OscMidiMessage, OscError> {
+        let (channel, message) = match *event {
+            LiveEvent::Midi { channel, message } => (channel, message),
+            LiveEvent::Common(_) | LiveEvent::Realtime(_) => {
+                return Err(OscError::BadArg(
+                    "Only MIDI channel voice messages fit into a MIDI argument".to_string(),
+                ))
+            }
+        };
+        let (kind, data1, data2) = match message {
+            MidiMessage::NoteOff { key, vel } => (0x80, key.as_int(), vel.as_int()),
+            MidiMessage::NoteOn { key, vel } => (0x90, key.as_int(), vel.as_int()),
+            MidiMessage::Aftertouch { key, vel } => (0xa0, key.as_int(), vel.as_int()),
+            MidiMessage::Controller { controller, value } => {
+                (0xb0, controller.as_int(), value.as_int())
+            }
+            MidiMessage::ProgramChange { program } => (0xc0, program.as_int(), 0),
+            MidiMessage::ChannelAftertouch { vel } => (0xd0, vel.as_int(), 0),
+            MidiMessage::PitchBend { bend } => {
+                let bend = bend.0.as_int();
+                (0xe0, (bend & 0x7f) as u8, (bend >> 7) as u8)
+            }
+        };
+        Ok(OscMidiMessage {
+            port,
+            status: kind | channel.as_int(),
+            data1,
+            data2,
+        })
+    }
+
+    /// Converts this MIDI argument into a `midly` live event, dropping the port.
+    ///
+    /// An [`OscError::BadArg`] is returned if the status byte isn't that of a channel voice
+    /// message, or if a data byte that the message uses has its top bit set. The unused `data2` of
+    /// messages with a single data byte is ignored.
+    pub fn to_live_event(&self) -> Result<LiveEvent<'static>, OscError> {
+        let data = |byte: u8| {
+            u7::try_from(byte).ok_or_else(|| {
+                OscError::BadArg(format!("MIDI data byte {:#04x} is out of range", byte))
+            })
+        };
+        let message = match self.status >> 4 {
+            0x8 => MidiMessage::NoteOff {
+                key: data(self.data1)?,
+                vel: data(self.data2)?,
+            },
+            0x9 => MidiMessage::NoteOn {
+                key: data(self.data1)?,
+                vel: data(self.data2)?,
+            },
+            0xa => MidiMessage::Aftertouch {
+                key: data(self.data1)?,
+                vel: data(self.data2)?,
+            },
+            0xb => MidiMessage::Controller {
+                controller: data(self.data1)?,
+                value: data(self.data2)?,
+            },
+            0xc => MidiMessage::ProgramChange {
+                program: data(self.data1)?,
+            },
+            0xd => MidiMessage::ChannelAftertouch {
+                vel: data(self.data1)?,
+            },
+            0xe => {
+                let bend = u16::from(data(self.data2)?.as_int()) << 7
+                    | u16::from(data(self.data1)?.as_int());
+                MidiMessage::PitchBend {
+                    bend: PitchBend(u14::new(bend)),
+                }
+            }
+            _ => {
+                return Err(OscError::BadArg(format!(
+                    "MIDI status {:#04x} is not a channel voice message",
+                    self.status
+                )))
+            }
+        };
+        Ok(LiveEvent::Midi {
+            channel: u4::new(self.status & 0x0f),
+            message,
+        })
+    }
+}
+
+/// Converts a live event into a MIDI argument for port 0, see
+/// [`OscMidiMessage::from_live_event`].
+impl<'a> TryFrom<LiveEvent<'a>> for OscMidiMessage {
+    type Error = OscError;
+
+    fn try_from(event: LiveEvent<'a>) -> Result<Self, Self::Error> {
+        OscMidiMessage::from_live_event(&event, 0)
+    }
+}
+
+/// Converts a MIDI argument into a live event, see [`OscMidiMessage::to_live_event`].
+impl<'a> TryFrom<&'a OscMidiMessage> for LiveEvent<'static> {
+    type Error = OscError;
+
+    fn try_from(msg: &'a OscMidiMessage) -> Result<Self, Self::Error> {
+        msg.to_live_event()
+    }
+}
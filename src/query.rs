@@ -0,0 +1,102 @@
+//! Some OSC servers let clients discover their address space. This module implements the
+//! following convention, without the JSON based OSCQuery protocol:
+//!
+//! - A *list request* is a message without arguments whose address is the container to list,
+//!   ending in `/`. Requesting `/` lists the top-level containers and methods.
+//! - The *list reply* is a bundle of messages, or a single message, sent to the address of the
+//!   request. Each argument of each message is a `String` holding the name of a child of the
+//!   container. Children which are containers themselves end in `/`.
+
+use crate::alloc::{
+    string::{String, ToString},
+    vec::Vec,
+};
+use crate::types::{OscBundle, OscMessage, OscPacket, OscTime, OscType};
+
+/// Returns a request to list the top-level namespace of a server, i.e. a message to `/` without
+/// arguments.
+pub fn list_request() -> OscMessage {
+    list_request_for("/")
+}
+
+/// Returns a request to list the children of `container`. A `/` is appended to `container` if
+/// it doesn't end in one already.
+///
+/// # Example
+///
+/// ```
+/// use rosc::query;
+///
+/// assert_eq!(query::list_request_for("/mixer").addr, "/mixer/");
+/// ```
+pub fn list_request_for(container: &str) -> OscMessage {
+    let mut addr = container.to_string();
+    if !addr.ends_with('/') {
+        addr.push('/');
+    }
+    OscMessage { addr, args: vec![] }
+}
+
+/// Returns `true` if `msg` is a list request.
+pub fn is_list_request(msg: &OscMessage) -> bool {
+    msg.addr.starts_with('/') && msg.addr.ends_with('/') && msg.args.is_empty()
+}
+
+/// Builds the reply to the list request `request`, listing `children` in a single message
+/// wrapped in an immediate bundle.
+pub fn list_reply<S: AsRef<str>>(request: &OscMessage, children: &[S]) -> OscPacket {
+    OscPacket::Bundle(OscBundle {
+        timetag: OscTime::IMMEDIATE,
+        content: vec![OscPacket::Message(OscMessage {
+            addr: request.addr.clone(),
+            args: children
+                .iter()
+                .map(|child| OscType::String(child.as_ref().to_string()))
+                .collect(),
+        })],
+    })
+}
+
+/// Parses a list reply into the names of the listed children, in order.
+///
+/// Both a single message and a bundle of messages are accepted, see the [module
+/// documentation](self) for the exact convention. `None` is returned if the packet doesn't
+/// follow it, e.g. because an argument is not a string or a bundle contains a nested bundle.
+///
+/// # Example
+///
+/// ```
+/// use rosc::query;
+///
+/// let request = query::list_request();
+/// let reply = query::list_reply(&request, &["mixer/", "transport/", "tempo"]);
+/// assert_eq!(
+///     query::parse_list_reply(&reply),
+///     Some(vec!["mixer/".to_string(), "transport/".to_string(), "tempo".to_string()])
+/// );
+/// ```
+pub fn parse_list_reply(packet: &OscPacket) -> Option<Vec<String>> {
+    fn parse_message(msg: &OscMessage, children: &mut Vec<String>) -> Option<()> {
+        for arg in &msg.args {
+            match arg {
+                OscType::String(child) => children.push(child.clone()),
+                _ => return None,
+            }
+        }
+        Some(())
+    }
+
+    let mut children = Vec::new();
+    match packet {
+        OscPacket::Message(msg) => parse_message(msg, &mut children)?,
+        OscPacket::Bundle(bundle) => {
+            for element in &bundle.content {
+                match element {
+                    OscPacket::Message(msg) => parse_message(msg, &mut children)?,
+                    OscPacket::Bundle(_) => return None,
+                }
+            }
+        }
+    }
+    Some(children)
+}
@@ -0,0 +1,191 @@
+use crate::address::{Matcher, OscAddress};
+use crate::errors::OscError;
+use crate::types::{OscMessage, OscTime, OscType};
+
+use std::sync::mpsc::{channel, Receiver};
+
+/// Conversion of a single OSC argument into a plain Rust value.
+///
+/// This is implemented using the typed getters of [`OscType`], so no coercion between types takes
+/// place: routing a `Float` argument to an `i32` route fails the conversion.
+pub trait FromArg: Sized {
+    /// Converts `arg`, returning `None` if it has a different type.
+    fn from_arg(arg: OscType) -> Option<Self>;
+}
+
+macro_rules! from_arg_impl {
+    ($(($getter:ident, $ty:ty)),*) => {
+        $(
+        impl FromArg for $ty {
+            fn from_arg(arg: OscType) -> Option<Self> {
+                arg.$getter()
+            }
+        }
+        )*
+    }
+}
+from_arg_impl! {
+    (int, i32),
+    (float, f32),
+    (string, String),
+    (blob, Vec<u8>),
+    (long, i64),
+    (double, f64),
+    (char, char),
+    (bool, bool),
+    (time, OscTime)
+}
+
+impl FromArg for OscType {
+    fn from_arg(arg: OscType) -> Option<Self> {
+        Some(arg)
+    }
+}
+
+struct Route<'a> {
+    matcher: Matcher,
+    deliver: Box<dyn FnMut(OscType) -> bool + 'a>,
+}
+
+/// Splits a stream of messages into typed per-address value streams.
+///
+/// Each route consists of an address pattern and a callback receiving the first argument of every
+/// matching message, converted to the callback's argument type using [`FromArg`]. A message is
+/// delivered to every route it matches. Messages that don't match any route are passed to the
+/// default sink, if any, and counted.
+///
+/// # Examples
+///
+/// ```
+/// use rosc::route::AddressSplitter;
+/// use rosc::{OscMessage, OscType};
+///
+/// let mut faders = vec![];
+/// let mut splitter = AddressSplitter::new();
+/// splitter.route("/fader/*", |value: f32| faders.push(value)).unwrap();
+///
+/// splitter.dispatch(OscMessage {
+///     addr: "/fader/1".to_string(),
+///     args: vec![OscType::Float(0.5)],
+/// });
+/// splitter.dispatch(OscMessage::from("/transport/play"));
+/// assert_eq!(splitter.unmatched_count(), 1);
+///
+/// drop(splitter);
+/// assert_eq!(faders, vec![0.5]);
+/// ```
+#[derive(Default)]
+pub struct AddressSplitter<'a> {
+    routes: Vec<Route<'a>>,
+    default_sink: Option<Box<dyn FnMut(OscMessage) + 'a>>,
+    unmatched: u64,
+    rejected: u64,
+}
+
+impl<'a> AddressSplitter<'a> {
+    /// Creates a splitter without any routes.
+    pub fn new() -> Self {
+        AddressSplitter::default()
+    }
+
+    /// Adds a route delivering the first argument of messages matching `pattern` to `callback`.
+    ///
+    /// An error is returned if `pattern` is not a valid address pattern.
+    pub fn route<T, F>(&mut self, pattern: &str, mut callback: F) -> Result<&mut Self, OscError>
+    where
+        T: FromArg,
+        F: FnMut(T) + 'a,
+    {
+        self.routes.push(Route {
+            matcher: Matcher::new(pattern)?,
+            deliver: Box::new(move |arg| match T::from_arg(arg) {
+                Some(value) => {
+                    callback(value);
+                    true
+                }
+                None => false,
+            }),
+        });
+        Ok(self)
+    }
+
+    /// Adds a route delivering the first argument of messages matching `pattern` to a channel,
+    /// and returns the receiving end of the channel.
+    ///
+    /// Values are dropped silently once the receiver is dropped.
+    pub fn route_to_channel<T>(&mut self, pattern: &str) -> Result<Receiver<T>, OscError>
+    where
+        T: FromArg + 'a,
+    {
+        let (sender, receiver) = channel();
+        self.route(pattern, move |value: T| {
+            let _ = sender.send(value);
+        })?;
+        Ok(receiver)
+    }
+
+    /// Sets the sink receiving all messages that didn't match any route.
+    pub fn default_sink<F>(&mut self, sink: F) -> &mut Self
+    where
+        F: FnMut(OscMessage) + 'a,
+    {
+        self.default_sink = Some(Box::new(sink));
+        self
+    }
+
+    /// Delivers a message to all matching routes, or to the default sink if none match.
+    pub fn dispatch(&mut self, msg: OscMessage) {
+        let address = match OscAddress::new(msg.addr.clone()) {
+            Ok(address) => address,
+            Err(_) => return self.unmatched(msg),
+        };
+
+        let mut matched = false;
+        for route in self
+            .routes
+            .iter_mut()
+            .filter(|route| route.matcher.match_address(&address))
+        {
+            matched = true;
+            let delivered = match msg.args.first() {
+                Some(arg) => (route.deliver)(arg.clone()),
+                None => false,
+            };
+            if !delivered {
+                self.rejected += 1;
+            }
+        }
+
+        if !matched {
+            self.unmatched(msg);
+        }
+    }
+
+    /// Returns the number of messages which didn't match any route.
+    pub fn unmatched_count(&self) -> u64 {
+        self.unmatched
+    }
+
+    /// Returns the number of times a message matched a route, but had no first argument or one
+    /// that couldn't be converted to the route's type.
+    pub fn rejected_count(&self) -> u64 {
+        self.rejected
+    }
+
+    fn unmatched(&mut self, msg: OscMessage) {
+        self.unmatched += 1;
+        if let Some(ref mut sink) = self.default_sink {
+            sink(msg);
+        }
+    }
+}
+
+/// Delivers every message of `messages` using `splitter`, see [`AddressSplitter::dispatch`].
+pub fn split_by_address<I>(messages: I, splitter: &mut AddressSplitter)
+where
+    I: IntoIterator<Item = OscMessage>,
+{
+    for msg in messages {
+        splitter.dispatch(msg);
+    }
+}
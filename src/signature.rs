@@ -0,0 +1,110 @@
+use crate::alloc::string::{String, ToString};
+use crate::errors::OscError;
+use crate::types::TypeTag;
+
+use core::fmt;
+
+/// How strictly [`TypeSignature::is_compatible_with`] compares type tags.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CoercionMode {
+    /// Every type tag must be equal.
+    Exact,
+    /// Type tags of values that can be converted into each other are considered equal:
+    ///
+    /// * The numeric tags `i` (int), `h` (long), `f` (float) and `d` (double), e.g. an int where a
+    ///   float is expected.
+    /// * The boolean tags `T` and `F`.
+    ///
+    /// All other type tags, including array brackets, must still be equal.
+    Relaxed,
+}
+
+/// The validated type tags of a message, for checking a received message against an expected
+/// signature.
+///
+/// # Example
+///
+/// ```
+/// use rosc::signature::{CoercionMode, TypeSignature};
+///
+/// let expected = TypeSignature::parse(",sf").unwrap();
+/// let received = TypeSignature::parse(",si").unwrap();
+/// assert!(!received.is_compatible_with(&expected, CoercionMode::Exact));
+/// assert!(received.is_compatible_with(&expected, CoercionMode::Relaxed));
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct TypeSignature {
+    tags: String,
+}
+
+impl TypeSignature {
+    /// Parses a type tag string, with or without its leading `,`.
+    ///
+    /// An error is returned if it contains a type tag not supported by this crate, or if its
+    /// array brackets are unbalanced.
+    pub fn parse(tags: &str) -> Result<Self, OscError> {
+        let tags = tags.strip_prefix(',').unwrap_or(tags);
+
+        let mut depth = 0usize;
+        for tag in tags.chars() {
+            match TypeTag::from_char(tag) {
+                Some(TypeTag::ArrayStart) => depth += 1,
+                Some(TypeTag::ArrayEnd) => {
+                    depth = depth
+                        .checked_sub(1)
+                        .ok_or(OscError::BadMessage("Encountered ] outside array"))?
+                }
+                Some(_) => (),
+                None => {
+                    return Err(OscError::BadArg(format!(
+                        "Type tag \"{}\" is not implemented!",
+                        tag
+                    )))
+                }
+            }
+        }
+        if depth != 0 {
+            return Err(OscError::BadMessage("Unterminated array"));
+        }
+
+        Ok(TypeSignature {
+            tags: tags.to_string(),
+        })
+    }
+
+    /// Returns the type tags, without the leading `,`.
+    pub fn as_str(&self) -> &str {
+        &self.tags
+    }
+
+    /// Checks whether messages with this signature can be handled where `other` is expected.
+    ///
+    /// Both signatures must have the same number of type tags, and each pair of tags must be
+    /// equal or, depending on `coercion`, convertible. The comparison is symmetric.
+    pub fn is_compatible_with(&self, other: &TypeSignature, coercion: CoercionMode) -> bool {
+        self.tags.len() == other.tags.len()
+            && self
+                .tags
+                .bytes()
+                .zip(other.tags.bytes())
+                .all(|(a, b)| a == b || (coercion == CoercionMode::Relaxed && coercible(a, b)))
+    }
+}
+
+impl fmt::Display for TypeSignature {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, ",{}", self.tags)
+    }
+}
+
+/// Returns whether values of type tags `a` and `b` can be converted into each other.
+fn coercible(a: u8, b: u8) -> bool {
+    fn class(tag: u8) -> Option<u8> {
+        match TypeTag::from_char(tag as char)? {
+            TypeTag::Int | TypeTag::Long | TypeTag::Float | TypeTag::Double => Some(0),
+            TypeTag::True | TypeTag::False => Some(1),
+            _ => None,
+        }
+    }
+    class(a).is_some() && class(a) == class(b)
+}
@@ -0,0 +1,363 @@
+#[cfg(feature = "std")]
+use crate::encoder::pad_to_4;
+#[cfg(feature = "std")]
+use crate::types::{OscMessage, OscType};
+
+#[cfg(feature = "std")]
+use std::collections::{HashMap, VecDeque};
+#[cfg(feature = "std")]
+use std::fmt::Write;
+#[cfg(feature = "std")]
+use std::time::{Duration, Instant};
+
+/// Size statistics of the packets passed through an encode or decode call.
+///
+/// `Stats` is opt-in instrumentation: create one, pass it to
+/// [`decode_udp_with_stats`](crate::decoder::decode_udp_with_stats) or
+/// [`encode_with_stats`](crate::encoder::encode_with_stats) for every packet, and inspect it
+/// whenever convenient, e.g. to tune receive buffer sizes in a long-running server. Only packets
+/// that were successfully decoded or encoded are recorded.
+///
+/// # Example
+///
+/// ```
+/// use rosc::stats::Stats;
+/// use rosc::{decoder, encoder, OscMessage, OscPacket};
+///
+/// let mut stats = Stats::new();
+/// let bytes = encoder::encode(&OscPacket::Message(OscMessage::from("/ping"))).unwrap();
+/// decoder::decode_udp_with_stats(&bytes, &mut stats).unwrap();
+///
+/// assert_eq!(stats.count(), 1);
+/// assert_eq!(stats.max_size(), Some(bytes.len()));
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Stats {
+    count: u64,
+    total_size: u64,
+    min_size: Option<usize>,
+    max_size: Option<usize>,
+}
+
+impl Stats {
+    /// Creates empty statistics.
+    pub fn new() -> Self {
+        Stats::default()
+    }
+
+    /// Records a packet of `size` bytes.
+    pub fn record(&mut self, size: usize) {
+        self.count += 1;
+        self.total_size += size as u64;
+        self.min_size = Some(self.min_size.map_or(size, |min| min.min(size)));
+        self.max_size = Some(self.max_size.map_or(size, |max| max.max(size)));
+    }
+
+    /// Returns the number of recorded packets.
+    pub fn count(&self) -> u64 {
+        self.count
+    }
+
+    /// Returns the combined size of all recorded packets in bytes.
+    pub fn total_size(&self) -> u64 {
+        self.total_size
+    }
+
+    /// Returns the size of the smallest recorded packet, or `None` if none were recorded.
+    pub fn min_size(&self) -> Option<usize> {
+        self.min_size
+    }
+
+    /// Returns the size of the largest recorded packet, or `None` if none were recorded.
+    pub fn max_size(&self) -> Option<usize> {
+        self.max_size
+    }
+
+    /// Resets the statistics as if no packets had been recorded.
+    pub fn reset(&mut self) {
+        *self = Stats::default();
+    }
+}
+
+/// The number of buckets the rate window of [`AddressStats`] is divided into. Rates are accurate
+/// to about one bucket's worth of messages.
+#[cfg(feature = "std")]
+const RATE_BUCKETS: u32 = 16;
+
+/// Per-address statistics of live traffic, e.g. to feed a monitoring dashboard.
+///
+/// For every address, the number of messages and their encoded size are counted, and the rate of
+/// messages over the last `window` is estimated. At most `capacity` addresses are tracked: once
+/// that many are known, recording a new address evicts the one that was seen least recently.
+///
+/// Like the other time-based helpers of this crate, `AddressStats` doesn't read the clock
+/// itself, so the current time is passed to every call.
+///
+/// # Example
+///
+/// ```
+/// use rosc::stats::AddressStats;
+/// use rosc::OscMessage;
+/// use std::time::{Duration, Instant};
+///
+/// let mut stats = AddressStats::new(Duration::from_secs(1), 100);
+/// let start = Instant::now();
+/// for i in 0..50 {
+///     stats.record(&OscMessage::from("/fader"), start + Duration::from_millis(20 * i));
+/// }
+/// stats.record(&OscMessage::from("/button"), start);
+///
+/// let snapshot = stats.snapshot(start + Duration::from_secs(1));
+/// assert_eq!(snapshot[0].address, "/fader");
+/// assert_eq!(snapshot[0].count, 50);
+/// assert_eq!(snapshot[0].bytes, 50 * 12);
+/// assert_eq!(snapshot[1].address, "/button");
+/// assert_eq!(snapshot[1].rate, 0.0);
+/// ```
+#[cfg(feature = "std")]
+#[derive(Clone, Debug)]
+pub struct AddressStats {
+    window: Duration,
+    capacity: usize,
+    addresses: HashMap<String, AddressEntry>,
+}
+
+#[cfg(feature = "std")]
+#[derive(Clone, Debug)]
+struct AddressEntry {
+    count: u64,
+    bytes: u64,
+    last_seen: Instant,
+    /// The start and message count of the buckets within the rate window, oldest first.
+    buckets: VecDeque<(Instant, u64)>,
+}
+
+/// The statistics of a single address, as returned by [`AddressStats::snapshot`].
+#[cfg(feature = "std")]
+#[derive(Clone, Debug, PartialEq)]
+pub struct AddressStatEntry {
+    pub address: String,
+    /// The number of messages recorded.
+    pub count: u64,
+    /// The combined encoded size of the recorded messages in bytes.
+    pub bytes: u64,
+    /// When the last message was recorded.
+    pub last_seen: Instant,
+    /// The estimated number of messages per second over the rate window.
+    pub rate: f64,
+}
+
+#[cfg(feature = "std")]
+impl AddressStats {
+    /// Creates empty statistics estimating rates over `window` and tracking at most `capacity`
+    /// addresses.
+    ///
+    /// A `capacity` of zero is treated as one.
+    pub fn new(window: Duration, capacity: usize) -> Self {
+        AddressStats {
+            window,
+            capacity: capacity.max(1),
+            addresses: HashMap::new(),
+        }
+    }
+
+    /// Records a message received at `now`, counting its encoded size.
+    pub fn record(&mut self, msg: &OscMessage, now: Instant) {
+        self.record_size(&msg.addr, encoded_message_size(msg), now);
+    }
+
+    /// Records a message of `size` bytes to `addr` received at `now`, e.g. if the size of the
+    /// received packet is already known.
+    pub fn record_size(&mut self, addr: &str, size: usize, now: Instant) {
+        if !self.addresses.contains_key(addr) {
+            if self.addresses.len() >= self.capacity {
+                self.evict_least_recently_seen();
+            }
+            self.addresses.insert(
+                addr.to_string(),
+                AddressEntry {
+                    count: 0,
+                    bytes: 0,
+                    last_seen: now,
+                    buckets: VecDeque::with_capacity(RATE_BUCKETS as usize + 1),
+                },
+            );
+        }
+
+        let bucket_len = self.window / RATE_BUCKETS;
+        let entry = self
+            .addresses
+            .get_mut(addr)
+            .expect("address was just inserted");
+        entry.count += 1;
+        entry.bytes += size as u64;
+        entry.last_seen = now;
+        match entry.buckets.back_mut() {
+            Some((start, count)) if now < *start + bucket_len => *count += 1,
+            _ => entry.buckets.push_back((now, 1)),
+        }
+        while let Some(&(start, _)) = entry.buckets.front() {
+            if now.saturating_duration_since(start) < self.window {
+                break;
+            }
+            entry.buckets.pop_front();
+        }
+    }
+
+    /// Returns the statistics of every tracked address as of `now`, highest rate first.
+    /// Addresses with equal rates are ordered by address.
+    pub fn snapshot(&self, now: Instant) -> Vec<AddressStatEntry> {
+        let mut entries: Vec<AddressStatEntry> = self
+            .addresses
+            .iter()
+            .map(|(address, entry)| {
+                let recent: u64 = entry
+                    .buckets
+                    .iter()
+                    .filter(|(start, _)| now.saturating_duration_since(*start) < self.window)
+                    .map(|(_, count)| count)
+                    .sum();
+                AddressStatEntry {
+                    address: address.clone(),
+                    count: entry.count,
+                    bytes: entry.bytes,
+                    last_seen: entry.last_seen,
+                    rate: recent as f64 / self.window.as_secs_f64(),
+                }
+            })
+            .collect();
+        entries.sort_by(|a, b| {
+            b.rate
+                .total_cmp(&a.rate)
+                .then_with(|| a.address.cmp(&b.address))
+        });
+        entries
+    }
+
+    /// Returns the number of tracked addresses.
+    pub fn len(&self) -> usize {
+        self.addresses.len()
+    }
+
+    /// Returns `true` if no addresses are tracked.
+    pub fn is_empty(&self) -> bool {
+        self.addresses.is_empty()
+    }
+
+    /// Forgets all addresses.
+    pub fn clear(&mut self) {
+        self.addresses.clear();
+    }
+
+    fn evict_least_recently_seen(&mut self) {
+        let oldest = self
+            .addresses
+            .iter()
+            .min_by_key(|(_, entry)| entry.last_seen)
+            .map(|(address, _)| address.clone());
+        if let Some(address) = oldest {
+            self.addresses.remove(&address);
+        }
+    }
+}
+
+/// Formats a [snapshot](AddressStats::snapshot) in the Prometheus text exposition format.
+///
+/// Three metrics are written, each labelled with the address: the counters
+/// `osc_messages_total` and `osc_bytes_total`, and the gauge `osc_messages_per_second`.
+///
+/// # Example
+///
+/// ```
+/// use rosc::stats::{self, AddressStats};
+/// use rosc::OscMessage;
+/// use std::time::{Duration, Instant};
+///
+/// let mut address_stats = AddressStats::new(Duration::from_secs(1), 100);
+/// let now = Instant::now();
+/// address_stats.record(&OscMessage::from("/ping"), now);
+///
+/// let text = stats::prometheus_text(&address_stats.snapshot(now));
+/// assert!(text.contains("osc_messages_total{address=\"/ping\"} 1\n"));
+/// assert!(text.contains("osc_bytes_total{address=\"/ping\"} 12\n"));
+/// ```
+#[cfg(feature = "std")]
+pub fn prometheus_text(entries: &[AddressStatEntry]) -> String {
+    let mut out = String::new();
+    write_metric(
+        &mut out,
+        entries,
+        ("osc_messages_total", "counter"),
+        "Messages received per OSC address.",
+        |entry| entry.count as f64,
+    );
+    write_metric(
+        &mut out,
+        entries,
+        ("osc_bytes_total", "counter"),
+        "Encoded size of the messages received per OSC address.",
+        |entry| entry.bytes as f64,
+    );
+    write_metric(
+        &mut out,
+        entries,
+        ("osc_messages_per_second", "gauge"),
+        "Recent rate of messages received per OSC address.",
+        |entry| entry.rate,
+    );
+    out
+}
+
+/// Writes the samples of a metric of the given name and type for every entry.
+#[cfg(feature = "std")]
+fn write_metric<F>(
+    out: &mut String,
+    entries: &[AddressStatEntry],
+    (name, kind): (&str, &str),
+    help: &str,
+    value: F,
+) where
+    F: Fn(&AddressStatEntry) -> f64,
+{
+    // Writing to a String never fails
+    let _ = writeln!(out, "# HELP {} {}", name, help);
+    let _ = writeln!(out, "# TYPE {} {}", name, kind);
+    for entry in entries {
+        let _ = writeln!(
+            out,
+            "{}{{address=\"{}\"}} {}",
+            name,
+            escape_label_value(&entry.address),
+            value(entry)
+        );
+    }
+}
+
+/// Escapes backslashes, double quotes and line feeds, as required in Prometheus label values.
+#[cfg(feature = "std")]
+fn escape_label_value(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
+
+/// Returns the size of `msg` once encoded, without encoding it.
+#[cfg(feature = "std")]
+fn encoded_message_size(msg: &OscMessage) -> usize {
+    /// Returns the number of type tags and the size of the data of `args`.
+    fn tags_and_data(args: &[OscType]) -> (usize, usize) {
+        args.iter().fold((0, 0), |(tags, data), arg| match arg {
+            OscType::Array(array) => {
+                let (array_tags, array_data) = tags_and_data(&array.content);
+                (tags + array_tags + 2, data + array_data)
+            }
+            OscType::String(s) => (tags + 1, data + pad_to_4(s.len() + 1)),
+            OscType::Blob(blob) => (tags + 1, data + 4 + pad_to_4(blob.len())),
+            _ => (tags + 1, data + arg.fixed_data_size().unwrap_or_default()),
+        })
+    }
+
+    let (tags, data) = tags_and_data(&msg.args);
+    pad_to_4(msg.addr.len() + 1) + pad_to_4(tags + 2) + data
+}
@@ -1,18 +1,20 @@
 use crate::errors;
-#[cfg(feature = "std")]
+use core::convert::TryFrom;
 use core::fmt::{self, Display};
-use core::{iter::FromIterator, result};
+use core::{cmp::Ordering, iter::FromIterator, mem, result, time::Duration};
 
 #[cfg(feature = "std")]
 use std::{
-    convert::{TryFrom, TryInto},
-    time::{Duration, SystemTime, UNIX_EPOCH},
+    convert::TryInto,
+    time::{SystemTime, UNIX_EPOCH},
 };
 
 use crate::alloc::{
     string::{String, ToString},
     vec::Vec,
 };
+#[cfg(feature = "base64")]
+use base64::Engine;
 
 /// A time tag in OSC message consists of two 32-bit integers where the first one denotes the number of seconds since 1900-01-01 and the second the fractions of a second.
 /// For details on its semantics see http://opensoundcontrol.org/node/3/#timetags
@@ -61,9 +63,139 @@ pub struct OscTime {
     pub fractional: u32,
 }
 
-#[cfg(feature = "std")]
+impl OscTime {
+    /// The special time tag value indicating that a bundle should be processed immediately.
+    pub const IMMEDIATE: OscTime = OscTime {
+        seconds: 0,
+        fractional: 1,
+    };
+
+    /// Returns `true` if this is the special [`IMMEDIATE`](OscTime::IMMEDIATE) time tag.
+    pub fn is_immediate(&self) -> bool {
+        *self == OscTime::IMMEDIATE
+    }
+
+    /// The all-ones time tag, which some implementations use to mean "never schedule".
+    ///
+    /// This is a convention, not part of the OSC specification. rosc encodes and decodes this
+    /// value like any other time tag, so applications following the convention have to check for
+    /// it themselves, e.g. using [`is_never`](OscTime::is_never).
+    pub const NEVER: OscTime = OscTime {
+        seconds: u32::MAX,
+        fractional: u32::MAX,
+    };
+
+    /// Returns `true` if this is the all-ones [`NEVER`](OscTime::NEVER) time tag.
+    pub fn is_never(&self) -> bool {
+        *self == OscTime::NEVER
+    }
+
+    /// Returns `true` if this time tag is either immediate or lies within `window` of `now`, in
+    /// either direction.
+    ///
+    /// This can be used to reject bundles with absurd time tags, e.g. caused by clock skew or
+    /// garbage data, before scheduling them.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rosc::OscTime;
+    /// use std::time::Duration;
+    ///
+    /// let now = OscTime::from((3_900_000_000, 0));
+    /// let window = Duration::from_secs(60);
+    ///
+    /// assert!(OscTime::IMMEDIATE.is_reasonable(now, window));
+    /// assert!(OscTime::from((3_900_000_030, 0)).is_reasonable(now, window));
+    /// assert!(!OscTime::from((3_900_003_600, 0)).is_reasonable(now, window));
+    /// ```
+    pub fn is_reasonable(&self, now: OscTime, window: Duration) -> bool {
+        if self.is_immediate() {
+            return true;
+        }
+
+        let distance = self.to_fixed_point().abs_diff(now.to_fixed_point());
+        // Convert the window to the same 32.32 fixed point format, saturating on overflow.
+        let window_fractional = ((window.subsec_nanos() as u64) << 32) / 1_000_000_000;
+        let window = window
+            .as_secs()
+            .checked_mul(1 << 32)
+            .and_then(|seconds| seconds.checked_add(window_fractional))
+            .unwrap_or(u64::MAX);
+
+        distance <= window
+    }
+
+    /// Like [`is_reasonable`](OscTime::is_reasonable), but compares against the current system
+    /// time.
+    ///
+    /// Returns `false` if the current system time cannot be represented as an `OscTime`.
+    #[cfg(feature = "std")]
+    pub fn is_reasonable_now(&self, window: Duration) -> bool {
+        match OscTime::try_from(SystemTime::now()) {
+            Ok(now) => self.is_reasonable(now, window),
+            Err(_) => false,
+        }
+    }
+
+    /// Converts a time given as seconds and nanoseconds since the Unix epoch
+    /// (`1970-01-01 00:00:00 UTC`) into a time tag, e.g. from a real-time clock.
+    ///
+    /// Unlike the conversions from [`SystemTime`](std::time::SystemTime), this only uses integer
+    /// arithmetic and is available without the `std` feature. Nanoseconds exceeding a second are
+    /// carried over into the seconds. Like NTP timestamps, the seconds wrap around at the end of
+    /// the first NTP era in 2036, see [`to_unix_secs_nanos`](OscTime::to_unix_secs_nanos).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rosc::OscTime;
+    ///
+    /// let time = OscTime::from_unix_secs_nanos(1_700_000_000, 500_000_000);
+    /// assert_eq!(time, OscTime::from((3_908_988_800, 1 << 31)));
+    /// assert_eq!(time.to_unix_secs_nanos(), (1_700_000_000, 500_000_000));
+    /// ```
+    pub fn from_unix_secs_nanos(secs: u64, nanos: u32) -> OscTime {
+        let secs = secs.wrapping_add(u64::from(nanos / 1_000_000_000));
+        let nanos = u64::from(nanos % 1_000_000_000);
+        OscTime {
+            seconds: secs.wrapping_add(OscTime::UNIX_OFFSET) as u32,
+            // Rounded to the nearest fraction, which is always less than a full second
+            fractional: (((nanos << 32) + 500_000_000) / 1_000_000_000) as u32,
+        }
+    }
+
+    /// Converts this time tag into seconds and nanoseconds since the Unix epoch, the inverse of
+    /// [`from_unix_secs_nanos`](OscTime::from_unix_secs_nanos).
+    ///
+    /// Time tags before the Unix epoch are taken to be in the second NTP era, which begins in
+    /// 2036, so that Unix times up to the year 2106 round trip. The nanoseconds are rounded to
+    /// the nearest one.
+    pub fn to_unix_secs_nanos(&self) -> (u64, u32) {
+        let seconds = u64::from(self.seconds);
+        let secs = if seconds >= OscTime::UNIX_OFFSET {
+            seconds - OscTime::UNIX_OFFSET
+        } else {
+            seconds + (1 << 32) - OscTime::UNIX_OFFSET
+        };
+        // Rounding may carry over into the seconds
+        let nanos = ((u64::from(self.fractional) * 1_000_000_000) + (1 << 31)) >> 32;
+        let total = secs * 1_000_000_000 + nanos;
+        (total / 1_000_000_000, (total % 1_000_000_000) as u32)
+    }
+
+    /// Returns the time tag as a single 32.32 fixed point number.
+    fn to_fixed_point(self) -> u64 {
+        ((self.seconds as u64) << 32) | self.fractional as u64
+    }
+}
+
 impl OscTime {
     const UNIX_OFFSET: u64 = 2_208_988_800; // From RFC 5905
+}
+
+#[cfg(feature = "std")]
+impl OscTime {
     const TWO_POW_32: f64 = (u32::MAX as f64) + 1.0; // Number of bits in a `u32`
     const ONE_OVER_TWO_POW_32: f64 = 1.0 / OscTime::TWO_POW_32;
     const NANOS_PER_SECOND: f64 = 1.0e9;
@@ -149,6 +281,23 @@ impl std::error::Error for OscTimeError {}
 
 /// see OSC Type Tag String: [OSC Spec. 1.0](http://opensoundcontrol.org/spec-1_0)
 /// padding: zero bytes (n*4)
+///
+/// # Conversions
+///
+/// `From` conversions are implemented for the Rust types matching each variant. Note that an
+/// `f64` converts to a `Double` (type tag `d`), not a `Float` (type tag `f`), which many OSC
+/// implementations don't support. So `0.1.into()` with an unsuffixed float literal produces a
+/// `Double`. To send an `f64` value as a `Float`, use [`OscType::float32`], which fails if the
+/// value can't be represented exactly, or [`OscType::float32_lossy`].
+///
+/// ```
+/// use rosc::OscType;
+///
+/// assert_eq!(OscType::from(0.5), OscType::Double(0.5));
+/// assert_eq!(OscType::from(0.5f32), OscType::Float(0.5));
+/// assert_eq!(OscType::float32(0.5), Ok(OscType::Float(0.5)));
+/// assert!(OscType::float32(0.1).is_err());
+/// ```
 #[derive(Clone, Debug, PartialEq)]
 pub enum OscType {
     Int(i32),
@@ -200,6 +349,287 @@ value_impl! {
     (midi, Midi, OscMidiMessage),
     (bool, Bool, bool)
 }
+
+macro_rules! nullable_impl {
+    ($(($name:ident, $variant:ident, $ty:ty)),*) => {
+        $(
+        impl OscType {
+            #[doc = concat!(
+                "Returns `Some(Some(_))` for a `", stringify!($variant), "`, `Some(None)` for ",
+                "`Nil` and `None` for any other type.\n\n",
+                "This distinguishes an explicit null argument from an argument of the wrong type."
+            )]
+            pub fn $name(self) -> Option<Option<$ty>> {
+                match self {
+                    OscType::$variant(v) => Some(Some(v)),
+                    OscType::Nil => Some(None),
+                    _ => None
+                }
+            }
+        }
+        )*
+    }
+}
+nullable_impl! {
+    (nullable_int, Int, i32),
+    (nullable_float, Float, f32),
+    (nullable_string, String, String),
+    (nullable_blob, Blob, Vec<u8>),
+    (nullable_array, Array, OscArray),
+    (nullable_long, Long, i64),
+    (nullable_double, Double, f64),
+    (nullable_char, Char, char),
+    (nullable_color, Color, OscColor),
+    (nullable_midi, Midi, OscMidiMessage),
+    (nullable_bool, Bool, bool),
+    (nullable_time, Time, OscTime)
+}
+
+impl OscType {
+    /// Returns `true` if this is an explicit null argument (`Nil`).
+    pub fn is_nil(&self) -> bool {
+        matches!(self, OscType::Nil)
+    }
+
+    /// Compares two arguments like `==`, but allowing `Float` and `Double` values, including those
+    /// inside arrays, to differ within `tol`.
+    ///
+    /// Values of different types are never equal, even a `Float` and a `Double`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use rosc::{FloatTolerance, OscType};
+    ///
+    /// let tol = FloatTolerance {
+    ///     absolute: 1e-3,
+    ///     relative: 0.0,
+    /// };
+    /// assert!(OscType::Float(0.5001).approx_eq(&OscType::Float(0.5), tol));
+    /// assert!(!OscType::Float(0.502).approx_eq(&OscType::Float(0.5), tol));
+    /// assert!(!OscType::Float(0.5).approx_eq(&OscType::Double(0.5), tol));
+    /// ```
+    pub fn approx_eq(&self, other: &OscType, tol: FloatTolerance) -> bool {
+        match (self, other) {
+            (OscType::Float(a), OscType::Float(b)) => tol.accepts(f64::from(*a), f64::from(*b)),
+            (OscType::Double(a), OscType::Double(b)) => tol.accepts(*a, *b),
+            (OscType::Array(a), OscType::Array(b)) => {
+                a.content.len() == b.content.len()
+                    && a.content
+                        .iter()
+                        .zip(&b.content)
+                        .all(|(a, b)| a.approx_eq(b, tol))
+            }
+            _ => self == other,
+        }
+    }
+}
+
+impl OscType {
+    /// Returns the name of the variant, e.g. `"Int"` for `OscType::Int(0)`, for diagnostics.
+    pub fn variant_name(&self) -> &'static str {
+        match self {
+            OscType::Int(_) => "Int",
+            OscType::Float(_) => "Float",
+            OscType::String(_) => "String",
+            OscType::Blob(_) => "Blob",
+            OscType::Time(_) => "Time",
+            OscType::Long(_) => "Long",
+            OscType::Double(_) => "Double",
+            OscType::Char(_) => "Char",
+            OscType::Color(_) => "Color",
+            OscType::Midi(_) => "Midi",
+            OscType::Bool(_) => "Bool",
+            OscType::Array(_) => "Array",
+            OscType::Nil => "Nil",
+            OscType::Inf => "Inf",
+        }
+    }
+
+    /// Returns the encoded size of the argument data of this variant if it is the same for all
+    /// values, see [`FIXED_DATA_SIZES`].
+    pub fn fixed_data_size(&self) -> Option<usize> {
+        match self {
+            OscType::Array(_) => None,
+            _ => TypeTag::of(self).fixed_payload_size(),
+        }
+    }
+}
+
+/// The encoded size in bytes of the argument data of each [`OscType`] variant with a fixed size,
+/// by [variant name](OscType::variant_name).
+///
+/// The type tag is not included. `String`, `Blob` and `Array` arguments vary in size and are not
+/// listed.
+pub const FIXED_DATA_SIZES: [(&str, usize); 11] = [
+    ("Int", 4),
+    ("Float", 4),
+    ("Time", 8),
+    ("Long", 8),
+    ("Double", 8),
+    ("Char", 4),
+    ("Color", 4),
+    ("Midi", 4),
+    ("Bool", 0),
+    ("Nil", 0),
+    ("Inf", 0),
+];
+
+/// An OSC type tag, whose discriminant is the ASCII character it is encoded as.
+///
+/// This is the single source of truth about type tags for the encoder and decoder. Every
+/// [`OscType`] argument is encoded with one tag, see [`TypeTag::of`], except arrays, which are
+/// enclosed in [`ArrayStart`](TypeTag::ArrayStart) and [`ArrayEnd`](TypeTag::ArrayEnd).
+///
+/// # Example
+///
+/// ```
+/// use rosc::TypeTag;
+///
+/// assert_eq!(TypeTag::from_char('h'), Some(TypeTag::Long));
+/// assert_eq!(TypeTag::Long.to_char(), 'h');
+/// assert_eq!(TypeTag::Long.fixed_payload_size(), Some(8));
+/// assert_eq!(TypeTag::from_char('x'), None);
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[repr(u8)]
+pub enum TypeTag {
+    Int = b'i',
+    Float = b'f',
+    String = b's',
+    Blob = b'b',
+    Time = b't',
+    Long = b'h',
+    Double = b'd',
+    Char = b'c',
+    Color = b'r',
+    Midi = b'm',
+    True = b'T',
+    False = b'F',
+    Nil = b'N',
+    Inf = b'I',
+    ArrayStart = b'[',
+    ArrayEnd = b']',
+}
+
+impl TypeTag {
+    /// All type tags.
+    pub const ALL: [TypeTag; 16] = [
+        TypeTag::Int,
+        TypeTag::Float,
+        TypeTag::String,
+        TypeTag::Blob,
+        TypeTag::Time,
+        TypeTag::Long,
+        TypeTag::Double,
+        TypeTag::Char,
+        TypeTag::Color,
+        TypeTag::Midi,
+        TypeTag::True,
+        TypeTag::False,
+        TypeTag::Nil,
+        TypeTag::Inf,
+        TypeTag::ArrayStart,
+        TypeTag::ArrayEnd,
+    ];
+
+    /// Looks up the tag encoded as `c`, returning `None` if it isn't a supported type tag.
+    pub fn from_char(c: char) -> Option<TypeTag> {
+        TYPE_TAG_LOOKUP.get(c as usize).copied().flatten()
+    }
+
+    /// Returns the character this tag is encoded as.
+    pub fn to_char(self) -> char {
+        self as u8 as char
+    }
+
+    /// Returns the tag that `arg` is encoded with, or [`ArrayStart`](TypeTag::ArrayStart) for
+    /// arrays.
+    pub fn of(arg: &OscType) -> TypeTag {
+        match arg {
+            OscType::Int(_) => TypeTag::Int,
+            OscType::Float(_) => TypeTag::Float,
+            OscType::String(_) => TypeTag::String,
+            OscType::Blob(_) => TypeTag::Blob,
+            OscType::Time(_) => TypeTag::Time,
+            OscType::Long(_) => TypeTag::Long,
+            OscType::Double(_) => TypeTag::Double,
+            OscType::Char(_) => TypeTag::Char,
+            OscType::Color(_) => TypeTag::Color,
+            OscType::Midi(_) => TypeTag::Midi,
+            OscType::Bool(true) => TypeTag::True,
+            OscType::Bool(false) => TypeTag::False,
+            OscType::Array(_) => TypeTag::ArrayStart,
+            OscType::Nil => TypeTag::Nil,
+            OscType::Inf => TypeTag::Inf,
+        }
+    }
+
+    /// Returns the size in bytes of the argument data following this tag, or `None` for strings
+    /// and blobs, whose size depends on their content. Tags without data have a size of 0.
+    pub fn fixed_payload_size(self) -> Option<usize> {
+        match self {
+            TypeTag::Int | TypeTag::Float | TypeTag::Char | TypeTag::Color | TypeTag::Midi => {
+                Some(4)
+            }
+            TypeTag::Time | TypeTag::Long | TypeTag::Double => Some(8),
+            TypeTag::String | TypeTag::Blob => None,
+            TypeTag::True
+            | TypeTag::False
+            | TypeTag::Nil
+            | TypeTag::Inf
+            | TypeTag::ArrayStart
+            | TypeTag::ArrayEnd => Some(0),
+        }
+    }
+
+    /// Returns `true` if this tag is followed by argument data.
+    pub fn has_payload(self) -> bool {
+        self.fixed_payload_size() != Some(0)
+    }
+}
+
+impl fmt::Display for TypeTag {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.to_char())
+    }
+}
+
+/// Maps ASCII characters to the type tags they encode.
+const TYPE_TAG_LOOKUP: [Option<TypeTag>; 128] = {
+    let mut lookup = [None; 128];
+    let mut i = 0;
+    while i < TypeTag::ALL.len() {
+        lookup[TypeTag::ALL[i] as usize] = Some(TypeTag::ALL[i]);
+        i += 1;
+    }
+    lookup
+};
+
+/// How far floating point arguments may differ in approximate comparisons, see
+/// [`OscType::approx_eq`].
+///
+/// Two values are considered equal if they are equal, if their difference is at most `absolute`,
+/// or if it is at most `relative` times the larger magnitude of the two. `NaN` is never equal to
+/// anything. The default tolerance of zero makes comparisons exact.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct FloatTolerance {
+    /// The allowed absolute difference.
+    pub absolute: f64,
+    /// The allowed difference relative to the larger magnitude of the values.
+    pub relative: f64,
+}
+
+impl FloatTolerance {
+    fn accepts(&self, a: f64, b: f64) -> bool {
+        if a == b {
+            return true;
+        }
+        let diff = (a - b).abs();
+        diff <= self.absolute || diff <= self.relative * a.abs().max(b.abs())
+    }
+}
+
 impl From<(u32, u32)> for OscType {
     fn from(time: (u32, u32)) -> Self {
         OscType::Time(time.into())
@@ -215,6 +645,59 @@ impl TryFrom<SystemTime> for OscType {
     }
 }
 
+impl From<OscTime> for OscType {
+    fn from(time: OscTime) -> Self {
+        OscType::Time(time)
+    }
+}
+
+/// Extracts the time of a `Time` argument, e.g. to use it as a bundle time tag. Any other
+/// argument is returned as the error.
+impl TryFrom<OscType> for OscTime {
+    type Error = OscType;
+
+    fn try_from(arg: OscType) -> result::Result<OscTime, OscType> {
+        match arg {
+            OscType::Time(time) => Ok(time),
+            other => Err(other),
+        }
+    }
+}
+
+#[cfg(feature = "base64")]
+impl OscType {
+    /// Returns the data of a `Blob` argument as a standard, padded base64 string, or `None` for
+    /// any other type. Useful for logging blobs or storing them in text formats.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use rosc::OscType;
+    ///
+    /// let blob = OscType::Blob(vec![0, 1, 255]);
+    /// assert_eq!(blob.blob_to_base64(), Some("AAH/".to_string()));
+    /// assert_eq!(OscType::blob_from_base64("AAH/").unwrap(), blob);
+    /// ```
+    pub fn blob_to_base64(&self) -> Option<String> {
+        match self {
+            OscType::Blob(data) => Some(base64::engine::general_purpose::STANDARD.encode(data)),
+            _ => None,
+        }
+    }
+
+    /// Creates a `Blob` argument from a standard, padded base64 string, the inverse of
+    /// [`OscType::blob_to_base64`].
+    ///
+    /// An [`OscError::BadArg`](errors::OscError::BadArg) is returned if `encoded` isn't valid
+    /// base64.
+    pub fn blob_from_base64(encoded: &str) -> result::Result<OscType, errors::OscError> {
+        base64::engine::general_purpose::STANDARD
+            .decode(encoded)
+            .map(OscType::Blob)
+            .map_err(|err| errors::OscError::BadArg(format!("Invalid base64 blob: {}", err)))
+    }
+}
+
 impl OscType {
     pub fn time(self) -> Option<OscTime> {
         match self {
@@ -222,12 +705,161 @@ impl OscType {
             _ => None,
         }
     }
+
+    /// Returns a reference to the time of a `Time` argument, or `None` for any other type.
+    ///
+    /// A `Time` argument holds the same [`OscTime`] as a bundle's time tag, and both are
+    /// encoded identically, so one can be used as the other:
+    ///
+    /// ```
+    /// use rosc::{OscBundle, OscTime, OscType};
+    ///
+    /// let arg = OscType::from(OscTime::from((3_900_000_000, 0)));
+    /// let bundle = OscBundle {
+    ///     timetag: *arg.as_time().unwrap(),
+    ///     content: vec![],
+    /// };
+    /// assert_eq!(OscType::from(bundle.timetag), arg);
+    /// ```
+    pub fn as_time(&self) -> Option<&OscTime> {
+        match self {
+            OscType::Time(time) => Some(time),
+            _ => None,
+        }
+    }
+
+    /// Creates a `Float` from an `f64`, failing if the value can't be represented exactly as an
+    /// `f32`.
+    ///
+    /// Infinities and NaN are converted as well, since they exist in both types.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rosc::OscType;
+    ///
+    /// assert_eq!(OscType::float32(-2.25), Ok(OscType::Float(-2.25)));
+    ///
+    /// let err = OscType::float32(0.1).unwrap_err();
+    /// assert_eq!(err.value(), 0.1);
+    /// assert_eq!(err.nearest(), 0.1f32);
+    /// ```
+    pub fn float32(value: f64) -> result::Result<OscType, PrecisionLoss> {
+        let float = value as f32;
+        if f64::from(float) == value || value.is_nan() {
+            Ok(OscType::Float(float))
+        } else {
+            Err(PrecisionLoss { value })
+        }
+    }
+
+    /// Creates a `Float` from an `f64`, rounding it to the nearest `f32`.
+    ///
+    /// Values too large for an `f32` become infinite.
+    pub fn float32_lossy(value: f64) -> OscType {
+        OscType::Float(value as f32)
+    }
+}
+
+/// An error returned by [`OscType::float32`] if a value can't be represented exactly as an `f32`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct PrecisionLoss {
+    value: f64,
+}
+
+impl PrecisionLoss {
+    /// Returns the value that was to be converted.
+    pub fn value(&self) -> f64 {
+        self.value
+    }
+
+    /// Returns the `f32` nearest to the value, as returned by [`OscType::float32_lossy`].
+    pub fn nearest(&self) -> f32 {
+        self.value as f32
+    }
+}
+
+impl Display for PrecisionLoss {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{} cannot be represented exactly as a 32 bit float",
+            self.value
+        )
+    }
 }
+
+#[cfg(feature = "std")]
+impl std::error::Error for PrecisionLoss {}
 impl<'a> From<&'a str> for OscType {
     fn from(string: &'a str) -> Self {
         OscType::String(string.to_string())
     }
 }
+/// A single argument borrowed from an encoded message, as passed to a
+/// [`DecodeSink`](crate::decoder::DecodeSink).
+///
+/// Unlike [`OscType`], strings and blobs borrow the encoded data, so no argument allocates.
+/// Arrays have no variant of their own, since their elements are passed one by one.
+#[derive(Clone, Debug, PartialEq)]
+pub enum OscTypeRef<'a> {
+    Int(i32),
+    Float(f32),
+    String(&'a str),
+    Blob(&'a [u8]),
+    Time(OscTime),
+    Long(i64),
+    Double(f64),
+    Char(char),
+    Color(OscColor),
+    Midi(OscMidiMessage),
+    Bool(bool),
+    Nil,
+    Inf,
+}
+
+impl OscTypeRef<'_> {
+    /// Converts the argument into an owned one, copying strings and blobs.
+    pub fn to_osc_type(&self) -> OscType {
+        match *self {
+            OscTypeRef::Int(x) => OscType::Int(x),
+            OscTypeRef::Float(x) => OscType::Float(x),
+            OscTypeRef::String(x) => OscType::String(x.to_string()),
+            OscTypeRef::Blob(x) => OscType::Blob(x.to_vec()),
+            OscTypeRef::Time(x) => OscType::Time(x),
+            OscTypeRef::Long(x) => OscType::Long(x),
+            OscTypeRef::Double(x) => OscType::Double(x),
+            OscTypeRef::Char(x) => OscType::Char(x),
+            OscTypeRef::Color(ref x) => OscType::Color(x.clone()),
+            OscTypeRef::Midi(ref x) => OscType::Midi(x.clone()),
+            OscTypeRef::Bool(x) => OscType::Bool(x),
+            OscTypeRef::Nil => OscType::Nil,
+            OscTypeRef::Inf => OscType::Inf,
+        }
+    }
+
+    /// Like [`OscPacket::swap_numeric_bytes`], for a single argument.
+    pub(crate) fn swap_numeric_bytes(self) -> Self {
+        match self {
+            OscTypeRef::Int(x) => OscTypeRef::Int(x.swap_bytes()),
+            OscTypeRef::Long(x) => OscTypeRef::Long(x.swap_bytes()),
+            OscTypeRef::Float(x) => OscTypeRef::Float(f32::from_bits(x.to_bits().swap_bytes())),
+            OscTypeRef::Double(x) => OscTypeRef::Double(f64::from_bits(x.to_bits().swap_bytes())),
+            OscTypeRef::Time(time) => OscTypeRef::Time(OscTime {
+                seconds: time.seconds.swap_bytes(),
+                fractional: time.fractional.swap_bytes(),
+            }),
+            other => other,
+        }
+    }
+}
+
+impl<'a> From<OscTypeRef<'a>> for OscType {
+    fn from(arg: OscTypeRef<'a>) -> Self {
+        arg.to_osc_type()
+    }
+}
+
 /// Represents the parts of a Midi message. Mainly used for
 /// tunneling midi over a network using the OSC protocol.
 #[derive(Clone, Debug, PartialEq, Eq)]
@@ -246,6 +878,208 @@ pub enum OscPacket {
     Bundle(OscBundle),
 }
 
+impl OscPacket {
+    /// Wraps `packet` in a bundle containing only that packet, scheduled at `time`.
+    pub fn scheduled(packet: OscPacket, time: OscTime) -> OscPacket {
+        OscPacket::Bundle(OscBundle {
+            timetag: time,
+            content: vec![packet],
+        })
+    }
+
+    /// If this packet is a bundle containing exactly one message, returns that message together
+    /// with the bundle's time tag.
+    ///
+    /// Only a single level is unwrapped: a bundle whose only element is another bundle returns
+    /// `None`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rosc::{OscMessage, OscTime};
+    ///
+    /// let time = OscTime::from((3_900_000_000, 0));
+    /// let packet = OscMessage::from("/play").at(time);
+    ///
+    /// let (msg, timetag) = packet.unwrap_single().unwrap();
+    /// assert_eq!(msg.addr, "/play");
+    /// assert_eq!(timetag, time);
+    /// ```
+    pub fn unwrap_single(&self) -> Option<(&OscMessage, OscTime)> {
+        match self {
+            OscPacket::Bundle(OscBundle { timetag, content }) => match content.as_slice() {
+                [OscPacket::Message(msg)] => Some((msg, *timetag)),
+                _ => None,
+            },
+            OscPacket::Message(_) => None,
+        }
+    }
+
+    /// Returns an iterator over all messages in this packet, depth first, each together with its
+    /// effective time tag.
+    ///
+    /// The effective time tag of a message is the time tag of the innermost bundle containing
+    /// it, so a nested bundle's time tag overrides the one of its enclosing bundle, even if the
+    /// latter is [immediate](OscTime::IMMEDIATE). A message which isn't part of any bundle is
+    /// reported as immediate.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rosc::{OscBundle, OscMessage, OscPacket, OscTime};
+    ///
+    /// let later = OscTime::from((3_900_000_000, 0));
+    /// let packet = OscPacket::Bundle(OscBundle {
+    ///     timetag: OscTime::IMMEDIATE,
+    ///     content: vec![
+    ///         OscPacket::Message(OscMessage::from("/now")),
+    ///         OscMessage::from("/later").at(later),
+    ///     ],
+    /// });
+    ///
+    /// let times: Vec<_> = packet.flatten().map(|(time, msg)| (time, msg.addr.as_str())).collect();
+    /// assert_eq!(times, vec![(OscTime::IMMEDIATE, "/now"), (later, "/later")]);
+    /// ```
+    pub fn flatten(&self) -> Flatten<'_> {
+        match self {
+            OscPacket::Message(msg) => Flatten {
+                single: Some(msg),
+                stack: Vec::new(),
+            },
+            OscPacket::Bundle(bundle) => Flatten {
+                single: None,
+                stack: vec![(bundle.timetag, bundle.content.iter())],
+            },
+        }
+    }
+
+    /// Returns a fingerprint of the structure of this packet, ignoring argument values and time
+    /// tags.
+    ///
+    /// The fingerprint covers the addresses and type tags of all messages, and the nesting of
+    /// bundles. Two packets of the same shape, e.g. messages to the same address with different
+    /// float values, share a fingerprint, which makes it useful for deduplication and caching.
+    /// Since booleans are encoded as type tags, `true` and `false` arguments are distinguished.
+    ///
+    /// The fingerprint is computed with 64 bit FNV-1a, so it is stable across platforms and
+    /// program runs, but it is not suitable where collisions could be provoked maliciously.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rosc::{OscMessage, OscPacket, OscType};
+    ///
+    /// let fader = |value| {
+    ///     OscPacket::Message(OscMessage {
+    ///         addr: "/fader/1".to_string(),
+    ///         args: vec![OscType::Float(value)],
+    ///     })
+    /// };
+    /// assert_eq!(fader(0.1).structural_hash(), fader(0.9).structural_hash());
+    /// ```
+    pub fn structural_hash(&self) -> u64 {
+        let mut hash = Fnv1a::new();
+        self.hash_structure(&mut hash);
+        hash.finish()
+    }
+
+    fn hash_structure(&self, hash: &mut Fnv1a) {
+        fn hash_args(args: &[OscType], hash: &mut Fnv1a) {
+            for arg in args {
+                hash.write(&[TypeTag::of(arg) as u8]);
+                if let OscType::Array(array) = arg {
+                    hash_args(&array.content, hash);
+                    hash.write(b"]");
+                }
+            }
+        }
+
+        match self {
+            OscPacket::Message(msg) => {
+                // The address is null terminated just like when encoded, so that it can't run
+                // into the type tags
+                hash.write(msg.addr.as_bytes());
+                hash.write(b"\0,");
+                hash_args(&msg.args, hash);
+                hash.write(b"\0");
+            }
+            OscPacket::Bundle(bundle) => {
+                hash.write(b"#bundle\0");
+                hash.write(&(bundle.content.len() as u64).to_be_bytes());
+                for packet in &bundle.content {
+                    packet.hash_structure(hash);
+                }
+            }
+        }
+    }
+}
+
+/// An iterator over the messages of a packet and their effective time tags, see
+/// [`OscPacket::flatten`].
+#[derive(Clone, Debug)]
+pub struct Flatten<'a> {
+    single: Option<&'a OscMessage>,
+    stack: Vec<(OscTime, core::slice::Iter<'a, OscPacket>)>,
+}
+
+impl<'a> Iterator for Flatten<'a> {
+    type Item = (OscTime, &'a OscMessage);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if let Some(msg) = self.single.take() {
+            return Some((OscTime::IMMEDIATE, msg));
+        }
+
+        loop {
+            let (timetag, next) = match self.stack.last_mut() {
+                Some((timetag, content)) => (*timetag, content.next()),
+                None => return None,
+            };
+            match next {
+                Some(OscPacket::Message(msg)) => return Some((timetag, msg)),
+                Some(OscPacket::Bundle(bundle)) => {
+                    self.stack.push((bundle.timetag, bundle.content.iter()))
+                }
+                None => {
+                    self.stack.pop();
+                }
+            }
+        }
+    }
+}
+
+/// The 64 bit FNV-1a hash function, used for stable fingerprints.
+pub(crate) struct Fnv1a(u64);
+
+impl Fnv1a {
+    pub(crate) fn new() -> Self {
+        Fnv1a(0xcbf2_9ce4_8422_2325)
+    }
+
+    pub(crate) fn write(&mut self, bytes: &[u8]) {
+        for &byte in bytes {
+            self.0 ^= u64::from(byte);
+            self.0 = self.0.wrapping_mul(0x0000_0100_0000_01b3);
+        }
+    }
+
+    pub(crate) fn finish(&self) -> u64 {
+        self.0
+    }
+}
+
+impl From<OscMessage> for OscPacket {
+    fn from(msg: OscMessage) -> Self {
+        OscPacket::Message(msg)
+    }
+}
+
+impl From<OscBundle> for OscPacket {
+    fn from(bundle: OscBundle) -> Self {
+        OscPacket::Bundle(bundle)
+    }
+}
+
 /// An OSC message consists of an address and
 /// zero or more arguments. The address should
 /// specify an element of your Instrument (or whatever
@@ -258,6 +1092,428 @@ pub struct OscMessage {
     pub args: Vec<OscType>,
 }
 
+impl OscMessage {
+    /// Wraps this message in a single-element bundle scheduled at `time`.
+    ///
+    /// See [`OscPacket::unwrap_single`] for the inverse operation.
+    pub fn at(self, time: OscTime) -> OscPacket {
+        OscPacket::scheduled(OscPacket::Message(self), time)
+    }
+
+    /// Replaces every `Float` and `Double` argument, including those inside arrays, with the
+    /// result of `f`.
+    ///
+    /// `Float` arguments are passed to `f` as `f64`, and its result is converted back to `f32`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rosc::{OscMessage, OscType};
+    ///
+    /// let mut msg = OscMessage {
+    ///     addr: "/gain".to_string(),
+    ///     args: vec![OscType::Float(0.5), OscType::Int(2), OscType::Double(0.25)],
+    /// };
+    /// msg.map_floats(|value| value * 2.0);
+    /// assert_eq!(
+    ///     msg.args,
+    ///     vec![OscType::Float(1.0), OscType::Int(2), OscType::Double(0.5)]
+    /// );
+    /// ```
+    pub fn map_floats<F: FnMut(f64) -> f64>(&mut self, mut f: F) {
+        for_each_arg_mut(&mut self.args, &mut |arg| match arg {
+            OscType::Float(x) => *x = f(f64::from(*x)) as f32,
+            OscType::Double(x) => *x = f(*x),
+            _ => (),
+        });
+    }
+
+    /// Replaces every `Int` and `Long` argument, including those inside arrays, with the result
+    /// of `f`.
+    ///
+    /// `Int` arguments are passed to `f` as `i64`, and its result is truncated back to `i32`.
+    pub fn map_ints<F: FnMut(i64) -> i64>(&mut self, mut f: F) {
+        for_each_arg_mut(&mut self.args, &mut |arg| match arg {
+            OscType::Int(x) => *x = f(i64::from(*x)) as i32,
+            OscType::Long(x) => *x = f(*x),
+            _ => (),
+        });
+    }
+
+    /// Returns the number of arguments, counting the elements of arrays instead of the arrays
+    /// themselves, recursively.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use rosc::{OscArray, OscMessage, OscType};
+    ///
+    /// let msg = OscMessage {
+    ///     addr: "/points".to_string(),
+    ///     args: vec![
+    ///         OscType::Int(2),
+    ///         OscType::Array(OscArray {
+    ///             content: vec![OscType::Float(0.5), OscType::Float(1.0)],
+    ///         }),
+    ///     ],
+    /// };
+    /// assert_eq!(msg.total_arg_count(), 3);
+    /// ```
+    pub fn total_arg_count(&self) -> usize {
+        count_args(&self.args)
+    }
+
+    /// Appends an argument.
+    pub fn push_arg(&mut self, arg: OscType) {
+        self.args.push(arg);
+    }
+
+    /// Inserts an argument at `index`, shifting all arguments after it to the right.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index` is greater than the number of arguments.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use rosc::{OscMessage, OscType};
+    ///
+    /// let mut msg = OscMessage {
+    ///     addr: "/note".to_string(),
+    ///     args: vec![OscType::Int(60)],
+    /// };
+    /// // Tag the message with the ID of its sender
+    /// msg.insert_arg(0, OscType::String("controller-1".to_string()));
+    /// assert_eq!(
+    ///     msg.args,
+    ///     vec![OscType::String("controller-1".to_string()), OscType::Int(60)]
+    /// );
+    /// assert_eq!(msg.remove_arg(0), Some(OscType::String("controller-1".to_string())));
+    /// ```
+    pub fn insert_arg(&mut self, index: usize, arg: OscType) {
+        self.args.insert(index, arg);
+    }
+
+    /// Appends all arguments that `args` converts into, see [`IntoOscArgs`].
+    pub fn push_all<A: IntoOscArgs>(&mut self, args: A) {
+        self.args.extend(args.into_osc_args());
+    }
+
+    /// Removes and returns the argument at `index`, shifting all arguments after it to the left.
+    /// Returns `None` if there is no argument at `index`.
+    pub fn remove_arg(&mut self, index: usize) -> Option<OscType> {
+        if index < self.args.len() {
+            Some(self.args.remove(index))
+        } else {
+            None
+        }
+    }
+
+    /// Compares two messages, requiring equal addresses and arguments that are equal according
+    /// to [`OscType::approx_eq`].
+    ///
+    /// See [`assert_osc_approx_eq!`](crate::assert_osc_approx_eq) for an assertion that reports
+    /// which argument differs.
+    pub fn approx_eq(&self, other: &OscMessage, tol: FloatTolerance) -> bool {
+        self.addr == other.addr
+            && self.args.len() == other.args.len()
+            && self
+                .args
+                .iter()
+                .zip(&other.args)
+                .all(|(a, b)| a.approx_eq(b, tol))
+    }
+}
+
+/// Conversion of a value into any number of OSC arguments, for appending application types to a
+/// message using [`OscMessage::push_all`].
+///
+/// # Example
+///
+/// ```
+/// use rosc::{IntoOscArgs, OscMessage, OscType};
+///
+/// struct Position {
+///     x: f32,
+///     y: f32,
+/// }
+///
+/// impl IntoOscArgs for Position {
+///     fn into_osc_args(self) -> Vec<OscType> {
+///         vec![OscType::Float(self.x), OscType::Float(self.y)]
+///     }
+/// }
+///
+/// let mut msg = OscMessage::from("/cursor");
+/// msg.push_all(Position { x: 0.25, y: 0.5 });
+/// assert_eq!(msg.args, vec![OscType::Float(0.25), OscType::Float(0.5)]);
+/// ```
+pub trait IntoOscArgs {
+    /// Converts `self` into the arguments to append, in order.
+    fn into_osc_args(self) -> Vec<OscType>;
+}
+
+impl IntoOscArgs for OscType {
+    fn into_osc_args(self) -> Vec<OscType> {
+        vec![self]
+    }
+}
+
+impl IntoOscArgs for Vec<OscType> {
+    fn into_osc_args(self) -> Vec<OscType> {
+        self
+    }
+}
+
+/// Conversion of a single argument into a plain Rust value, for the elements of tuples
+/// implementing [`FromOscArgs`].
+///
+/// No coercion between types takes place, e.g. a `Float` argument isn't converted into an `f64`.
+pub trait FromOscArg: Sized {
+    /// The name of the expected variant, as returned by [`OscType::variant_name`], for errors.
+    const EXPECTED: &'static str;
+
+    /// Converts `arg`, returning `None` if it has a different type.
+    fn from_osc_arg(arg: &OscType) -> Option<Self>;
+}
+
+macro_rules! from_osc_arg_impl {
+    ($(($variant:ident, $ty:ty)),*) => {
+        $(
+        impl FromOscArg for $ty {
+            const EXPECTED: &'static str = stringify!($variant);
+
+            fn from_osc_arg(arg: &OscType) -> Option<Self> {
+                match arg {
+                    OscType::$variant(v) => Some(v.clone()),
+                    _ => None,
+                }
+            }
+        }
+        )*
+    }
+}
+from_osc_arg_impl! {
+    (Int, i32),
+    (Float, f32),
+    (String, String),
+    (Blob, Vec<u8>),
+    (Time, OscTime),
+    (Long, i64),
+    (Double, f64),
+    (Char, char),
+    (Color, OscColor),
+    (Midi, OscMidiMessage),
+    (Bool, bool),
+    (Array, OscArray)
+}
+
+impl FromOscArg for OscType {
+    const EXPECTED: &'static str = "any argument";
+
+    fn from_osc_arg(arg: &OscType) -> Option<Self> {
+        Some(arg.clone())
+    }
+}
+
+/// Conversion of the arguments of a message into a plain Rust value, the reverse of
+/// [`IntoOscArgs`].
+///
+/// It is implemented for tuples of up to 8 elements implementing [`FromOscArg`], which expect
+/// exactly as many arguments, and for `Vec<OscType>`, which accepts any arguments. A wrong number
+/// of arguments is reported as [`OscError::WrongArgCount`](errors::OscError::WrongArgCount), an
+/// argument of the wrong type as [`OscError::WrongArgType`](errors::OscError::WrongArgType)
+/// with its position.
+///
+/// # Example
+///
+/// ```
+/// use rosc::{FromOscArgs, OscError, OscMessage, OscType};
+///
+/// let msg = OscMessage {
+///     addr: "/fader".to_string(),
+///     args: vec![OscType::Int(3), OscType::Float(0.8)],
+/// };
+/// let (id, value): (i32, f32) = FromOscArgs::from_args(&msg.args).unwrap();
+/// assert_eq!((id, value), (3, 0.8));
+///
+/// match <(i32, i32)>::from_args(&msg.args) {
+///     Err(OscError::WrongArgType { index: 1, .. }) => (),
+///     other => panic!("unexpected result {:?}", other),
+/// }
+/// ```
+pub trait FromOscArgs: Sized {
+    /// Converts `args`, returning an error describing the first argument that doesn't fit.
+    fn from_args(args: &[OscType]) -> result::Result<Self, errors::OscError>;
+}
+
+impl FromOscArgs for Vec<OscType> {
+    fn from_args(args: &[OscType]) -> result::Result<Self, errors::OscError> {
+        Ok(args.to_vec())
+    }
+}
+
+/// Converts the argument at `index`, which must exist, for `FromOscArgs`.
+fn from_arg_at<T: FromOscArg>(
+    args: &[OscType],
+    index: usize,
+) -> result::Result<T, errors::OscError> {
+    T::from_osc_arg(&args[index]).ok_or_else(|| errors::OscError::WrongArgType {
+        index,
+        expected: T::EXPECTED,
+        found: args[index].variant_name(),
+    })
+}
+
+macro_rules! tuple_from_osc_args_impl {
+    ($(($len:expr; $($index:tt $ty:ident),*)),*) => {
+        $(
+        impl<$($ty: FromOscArg),*> FromOscArgs for ($($ty,)*) {
+            fn from_args(args: &[OscType]) -> result::Result<Self, errors::OscError> {
+                if args.len() != $len {
+                    return Err(errors::OscError::WrongArgCount {
+                        expected: $len,
+                        found: args.len(),
+                    });
+                }
+                Ok(($(from_arg_at::<$ty>(args, $index)?,)*))
+            }
+        }
+        )*
+    }
+}
+tuple_from_osc_args_impl! {
+    (1; 0 A),
+    (2; 0 A, 1 B),
+    (3; 0 A, 1 B, 2 C),
+    (4; 0 A, 1 B, 2 C, 3 D),
+    (5; 0 A, 1 B, 2 C, 3 D, 4 E),
+    (6; 0 A, 1 B, 2 C, 3 D, 4 E, 5 F),
+    (7; 0 A, 1 B, 2 C, 3 D, 4 E, 5 F, 6 G),
+    (8; 0 A, 1 B, 2 C, 3 D, 4 E, 5 F, 6 G, 7 H)
+}
+
+impl OscPacket {
+    /// Reverses the bytes of every `Int`, `Long`, `Float`, `Double` and `Time` argument, see
+    /// [`NumericByteOrder`]. The seconds and the fraction of a time tag are swapped separately.
+    pub(crate) fn swap_numeric_bytes(&mut self) {
+        match self {
+            OscPacket::Message(msg) => for_each_arg_mut(&mut msg.args, &mut |arg| match arg {
+                OscType::Int(x) => *x = x.swap_bytes(),
+                OscType::Long(x) => *x = x.swap_bytes(),
+                OscType::Float(x) => *x = f32::from_bits(x.to_bits().swap_bytes()),
+                OscType::Double(x) => *x = f64::from_bits(x.to_bits().swap_bytes()),
+                OscType::Time(time) => {
+                    time.seconds = time.seconds.swap_bytes();
+                    time.fractional = time.fractional.swap_bytes();
+                }
+                _ => (),
+            }),
+            OscPacket::Bundle(bundle) => bundle
+                .content
+                .iter_mut()
+                .for_each(OscPacket::swap_numeric_bytes),
+        }
+    }
+}
+
+/// The byte order of numeric argument data.
+///
+/// The OSC specification requires big-endian data, so anything but the default is a workaround
+/// for nonconforming peers, like firmware that writes its native little-endian values. It only
+/// applies to the data of `Int`, `Long`, `Float`, `Double` and `Time` arguments. Sizes,
+/// addresses, type tags and bundle time tags are always big-endian, as are all other arguments.
+/// The seconds and the fraction of a `Time` argument are each stored in this byte order, seconds
+/// first.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum NumericByteOrder {
+    /// Big-endian, as required by the OSC specification.
+    #[default]
+    Big,
+    /// Little-endian, which is **not** valid OSC.
+    Little,
+}
+
+/// A named preset of what the OSC specification allows, for pinning encoding, decoding and
+/// matching to one version of it.
+///
+/// The presets are turned into options by
+/// [`DecodeOptions::for_profile`](crate::decoder::DecodeOptions::for_profile),
+/// [`EncodeOptions::for_profile`](crate::encoder::EncodeOptions::for_profile),
+/// `address::PatternSyntax::for_profile` and
+/// [`Framing::for_profile`](crate::framing::Framing::for_profile). The fields of the options can
+/// then be adjusted individually.
+///
+/// # Example
+///
+/// ```
+/// use rosc::decoder::{self, DecodeOptions};
+/// use rosc::{encoder, OscArray, OscMessage, OscPacket, OscType, SpecProfile};
+///
+/// let packet = OscPacket::Message(OscMessage {
+///     addr: "/chord".to_string(),
+///     args: vec![OscType::Array(OscArray {
+///         content: vec![OscType::Int(60), OscType::Int(64)],
+///     })],
+/// });
+/// let bytes = encoder::encode(&packet).unwrap();
+///
+/// let osc_1_0 = DecodeOptions::for_profile(SpecProfile::Osc10Strict);
+/// assert!(decoder::decode_udp_with_options(&bytes, &osc_1_0).is_err());
+/// let osc_1_1 = DecodeOptions::for_profile(SpecProfile::Osc11);
+/// assert!(decoder::decode_udp_with_options(&bytes, &osc_1_1).is_ok());
+/// ```
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum SpecProfile {
+    /// Only what OSC 1.0 requires: `i`, `f`, `s` and `b` arguments with zero padding, address
+    /// patterns without `//` and length-prefixed streams.
+    Osc10Strict,
+    /// OSC 1.1: the required `i`, `f`, `s`, `b`, `t`, `T`, `F`, `N` and `I` arguments as well as
+    /// arrays, with zero padding, `//` in address patterns and SLIP-framed streams.
+    Osc11,
+    /// Everything this crate supports, which is the default of all options.
+    #[default]
+    Permissive,
+}
+
+impl SpecProfile {
+    /// Returns the type tags allowed by the profile, or `None` if all type tags supported by
+    /// this crate are allowed. Arrays are only allowed if `[` is included.
+    pub fn allowed_type_tags(self) -> Option<&'static str> {
+        match self {
+            SpecProfile::Osc10Strict => Some("ifsb"),
+            SpecProfile::Osc11 => Some("ifsbtTFNI[]"),
+            SpecProfile::Permissive => None,
+        }
+    }
+
+    /// Returns whether the profile requires padding bytes to be zero.
+    pub fn strict_padding(self) -> bool {
+        self != SpecProfile::Permissive
+    }
+}
+
+/// Returns the number of arguments in `args`, counting array elements instead of arrays.
+fn count_args(args: &[OscType]) -> usize {
+    args.iter()
+        .map(|arg| match arg {
+            OscType::Array(array) => count_args(&array.content),
+            _ => 1,
+        })
+        .sum()
+}
+
+/// Calls `f` with every argument in `args`, recursing into arrays.
+fn for_each_arg_mut(args: &mut [OscType], f: &mut dyn FnMut(&mut OscType)) {
+    for arg in args {
+        match arg {
+            OscType::Array(array) => for_each_arg_mut(&mut array.content, f),
+            _ => f(arg),
+        }
+    }
+}
+
 /// An OSC bundle contains zero or more OSC packets
 /// and a time tag. The contained packets *should* be
 /// applied at the given time tag.
@@ -267,6 +1523,81 @@ pub struct OscBundle {
     pub content: Vec<OscPacket>,
 }
 
+impl OscBundle {
+    /// Removes and returns the elements that are due at `now`, keeping the others in the bundle.
+    ///
+    /// A nested bundle is due if its own time tag is not later than `now`. Messages don't have a
+    /// time tag of their own, so they are due if this bundle's time tag is not later than `now`.
+    /// Nested bundles are returned as a whole, without looking at their content. The relative
+    /// order of both the returned and the remaining elements is preserved.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rosc::{OscBundle, OscMessage, OscPacket, OscTime};
+    ///
+    /// let mut bundle = OscBundle {
+    ///     timetag: OscTime::from((100, 0)),
+    ///     content: vec![
+    ///         OscPacket::Message(OscMessage::from("/now")),
+    ///         OscMessage::from("/later").at(OscTime::from((200, 0))),
+    ///     ],
+    /// };
+    ///
+    /// let ready = bundle.drain_ready(OscTime::from((150, 0)));
+    /// assert_eq!(ready, vec![OscPacket::Message(OscMessage::from("/now"))]);
+    /// assert_eq!(bundle.content.len(), 1);
+    /// ```
+    pub fn drain_ready(&mut self, now: OscTime) -> Vec<OscPacket> {
+        let timetag = self.timetag;
+        let (ready, pending) = mem::take(&mut self.content)
+            .into_iter()
+            .partition(|packet| match packet {
+                OscPacket::Message(_) => timetag <= now,
+                OscPacket::Bundle(bundle) => bundle.timetag <= now,
+            });
+        self.content = pending;
+        ready
+    }
+
+    /// Creates a bundle whose time tag is the earliest time tag of the bundles nested in
+    /// `content`, at any depth.
+    ///
+    /// The OSC specification requires a nested bundle not to be scheduled earlier than the bundle
+    /// containing it, which this guarantees. Messages in `content` are scheduled at the resulting
+    /// time tag. If `content` contains no bundles, the time tag is [`OscTime::IMMEDIATE`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rosc::{OscBundle, OscMessage, OscTime};
+    ///
+    /// let bundle = OscBundle::with_earliest_timetag(vec![
+    ///     OscMessage::from("/b").at(OscTime::from((200, 0))),
+    ///     OscMessage::from("/a").at(OscTime::from((100, 0))),
+    /// ]);
+    /// assert_eq!(bundle.timetag, OscTime::from((100, 0)));
+    /// ```
+    pub fn with_earliest_timetag(content: Vec<OscPacket>) -> OscBundle {
+        let timetag = earliest_timetag(&content).unwrap_or(OscTime::IMMEDIATE);
+        OscBundle { timetag, content }
+    }
+}
+
+/// Returns the earliest time tag of the bundles in `content`, including nested ones.
+fn earliest_timetag(content: &[OscPacket]) -> Option<OscTime> {
+    content
+        .iter()
+        .filter_map(|packet| match packet {
+            OscPacket::Message(_) => None,
+            OscPacket::Bundle(bundle) => Some(match earliest_timetag(&bundle.content) {
+                Some(nested) => nested.min(bundle.timetag),
+                None => bundle.timetag,
+            }),
+        })
+        .min()
+}
+
 /// An RGBA color.
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub struct OscColor {
@@ -282,6 +1613,110 @@ pub struct OscArray {
     pub content: Vec<OscType>,
 }
 
+impl OscType {
+    /// Compares two arguments using a total, deterministic order.
+    ///
+    /// Arguments of different types are ordered by type, in the order of the variants of
+    /// `OscType`: `Int < Float < String < Blob < Time < Long < Double < Char < Color < Midi < Bool
+    /// < Array < Nil < Inf`. Arguments of the same type are ordered by value:
+    ///
+    /// - numbers numerically, with floats ordered by `total_cmp`, so `-0.0 < 0.0` and NaNs are
+    ///   ordered as well,
+    /// - strings and blobs lexicographically by their bytes,
+    /// - time tags, colors and MIDI messages by their fields in declaration order,
+    /// - `false < true`,
+    /// - arrays lexicographically, comparing their elements recursively.
+    ///
+    /// Two arguments compare as equal exactly if they are bitwise identical, so unlike `==`, a
+    /// NaN equals itself while `0.0` and `-0.0` differ.
+    pub fn canonical_cmp(&self, other: &OscType) -> Ordering {
+        fn rank(arg: &OscType) -> u8 {
+            match arg {
+                OscType::Int(_) => 0,
+                OscType::Float(_) => 1,
+                OscType::String(_) => 2,
+                OscType::Blob(_) => 3,
+                OscType::Time(_) => 4,
+                OscType::Long(_) => 5,
+                OscType::Double(_) => 6,
+                OscType::Char(_) => 7,
+                OscType::Color(_) => 8,
+                OscType::Midi(_) => 9,
+                OscType::Bool(_) => 10,
+                OscType::Array(_) => 11,
+                OscType::Nil => 12,
+                OscType::Inf => 13,
+            }
+        }
+
+        match (self, other) {
+            (OscType::Int(a), OscType::Int(b)) => a.cmp(b),
+            (OscType::Float(a), OscType::Float(b)) => a.total_cmp(b),
+            (OscType::String(a), OscType::String(b)) => a.cmp(b),
+            (OscType::Blob(a), OscType::Blob(b)) => a.cmp(b),
+            (OscType::Time(a), OscType::Time(b)) => a.cmp(b),
+            (OscType::Long(a), OscType::Long(b)) => a.cmp(b),
+            (OscType::Double(a), OscType::Double(b)) => a.total_cmp(b),
+            (OscType::Char(a), OscType::Char(b)) => a.cmp(b),
+            (OscType::Color(a), OscType::Color(b)) => {
+                (a.red, a.green, a.blue, a.alpha).cmp(&(b.red, b.green, b.blue, b.alpha))
+            }
+            (OscType::Midi(a), OscType::Midi(b)) => {
+                (a.port, a.status, a.data1, a.data2).cmp(&(b.port, b.status, b.data1, b.data2))
+            }
+            (OscType::Bool(a), OscType::Bool(b)) => a.cmp(b),
+            (OscType::Array(a), OscType::Array(b)) => {
+                for (a, b) in a.content.iter().zip(&b.content) {
+                    match a.canonical_cmp(b) {
+                        Ordering::Equal => (),
+                        ordering => return ordering,
+                    }
+                }
+                a.content.len().cmp(&b.content.len())
+            }
+            _ => rank(self).cmp(&rank(other)),
+        }
+    }
+}
+
+impl OscArray {
+    /// Sorts the elements of the array by [`OscType::canonical_cmp`].
+    ///
+    /// Since only bitwise identical elements compare as equal, the result is deterministic even
+    /// though the sort is unstable.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rosc::{OscArray, OscType};
+    ///
+    /// let mut scenes: OscArray = vec![7, 3, 7, 1].into_iter().collect();
+    /// scenes.sort_unstable_by_canonical();
+    /// scenes.dedup_canonical();
+    /// assert_eq!(scenes, vec![1, 3, 7].into_iter().collect());
+    /// assert!(scenes.contains(&OscType::Int(3)));
+    /// ```
+    pub fn sort_unstable_by_canonical(&mut self) {
+        self.content.sort_unstable_by(OscType::canonical_cmp);
+    }
+
+    /// Removes consecutive bitwise identical elements, see [`OscType::canonical_cmp`].
+    ///
+    /// Like [`Vec::dedup`], this only removes all duplicates if the array is sorted.
+    pub fn dedup_canonical(&mut self) {
+        self.content
+            .dedup_by(|a, b| a.canonical_cmp(b) == Ordering::Equal);
+    }
+
+    /// Returns `true` if the array contains an element bitwise identical to `arg`, see
+    /// [`OscType::canonical_cmp`].
+    pub fn contains(&self, arg: &OscType) -> bool {
+        self.content
+            .iter()
+            .any(|element| element.canonical_cmp(arg) == Ordering::Equal)
+    }
+}
+
 impl<T: Into<OscType>> FromIterator<T> for OscArray {
     fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> OscArray {
         OscArray {
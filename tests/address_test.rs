@@ -1,8 +1,8 @@
 extern crate rosc;
 
 #[cfg(feature = "std")]
-use rosc::address::{verify_address, Matcher};
-use rosc::address::{verify_address_pattern, OscAddress};
+use rosc::address::{verify_address, Matcher, MatcherCache, PatternSet};
+use rosc::address::{verify_address_pattern, OscAddress, OscPattern};
 
 #[cfg(feature = "std")]
 #[test]
@@ -447,3 +447,286 @@ fn test_verify_address_pattern() {
     verify_address_pattern("/{foo").expect_err("Should not be valid");
     verify_address_pattern("/foo{,").expect_err("Should not be valid");
 }
+
+#[cfg(feature = "std")]
+#[test]
+fn test_matcher_cache() {
+    let mut cache = MatcherCache::new(2);
+    assert!(cache.is_empty());
+    assert_eq!(cache.capacity(), 2);
+
+    let address = OscAddress::new(String::from("/oscillator/1/frequency")).unwrap();
+    assert!(cache
+        .get_or_compile("/oscillator/*/frequency")
+        .expect("Should be valid")
+        .match_address(&address));
+    assert!(!cache
+        .get_or_compile("/oscillator/*/phase")
+        .expect("Should be valid")
+        .match_address(&address));
+    assert_eq!(cache.len(), 2);
+
+    // A cache hit must not grow the cache
+    assert_eq!(
+        cache
+            .get_or_compile("/oscillator/*/frequency")
+            .expect("Should be valid")
+            .pattern,
+        "/oscillator/*/frequency"
+    );
+    assert_eq!(cache.len(), 2);
+
+    // Invalid patterns are reported and not cached
+    cache
+        .get_or_compile("/[b-a]")
+        .expect_err("Should not be valid");
+    assert_eq!(cache.len(), 2);
+
+    cache.clear();
+    assert!(cache.is_empty());
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn test_matcher_cache_evicts_least_recently_used() {
+    let mut cache = MatcherCache::new(2);
+    cache.get_or_compile("/a").expect("Should be valid");
+    cache.get_or_compile("/b").expect("Should be valid");
+    // Touch "/a" so "/b" becomes the least recently used entry
+    cache.get_or_compile("/a").expect("Should be valid");
+    cache.get_or_compile("/c").expect("Should be valid");
+    assert_eq!(cache.len(), 2);
+    assert!(cache.contains("/a"));
+    assert!(!cache.contains("/b"));
+    assert!(cache.contains("/c"));
+
+    // Recompiling "/b" evicts "/a", which is now the least recently used entry
+    cache.get_or_compile("/b").expect("Should be valid");
+    assert!(!cache.contains("/a"));
+    assert!(cache.contains("/b"));
+    assert!(cache.contains("/c"));
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn test_matcher_cache_reorders_middle_entry() {
+    let mut cache = MatcherCache::new(3);
+    for pattern in ["/a", "/b", "/c"] {
+        cache.get_or_compile(pattern).expect("Should be valid");
+    }
+    // Touch "/b", which is neither the newest nor the oldest entry
+    cache.get_or_compile("/b").expect("Should be valid");
+    cache.get_or_compile("/d").expect("Should be valid");
+    cache.get_or_compile("/e").expect("Should be valid");
+    assert!(!cache.contains("/a"));
+    assert!(cache.contains("/b"));
+    assert!(!cache.contains("/c"));
+    assert!(cache.contains("/d"));
+    assert!(cache.contains("/e"));
+
+    // The order of use starts over after clearing
+    cache.clear();
+    for pattern in ["/f", "/g", "/h", "/i"] {
+        cache.get_or_compile(pattern).expect("Should be valid");
+    }
+    assert_eq!(cache.len(), 3);
+    assert!(!cache.contains("/f"));
+    assert!(cache.contains("/i"));
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn test_matcher_cache_zero_capacity() {
+    let mut cache = MatcherCache::new(0);
+    assert_eq!(cache.capacity(), 1);
+    cache.get_or_compile("/a").expect("Should be valid");
+    cache.get_or_compile("/b").expect("Should be valid");
+    assert_eq!(cache.len(), 1);
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn test_validated_address_types() {
+    use rosc::OscMessage;
+    use std::convert::TryFrom;
+
+    let address = OscAddress::try_from("/oscillator/1/frequency").expect("Should be valid");
+    assert_eq!(&*address, "/oscillator/1/frequency");
+    assert_eq!(address.as_str(), "/oscillator/1/frequency");
+    assert!(address.starts_with("/oscillator"));
+    assert_eq!(
+        OscAddress::try_from(String::from("/oscillator/1/frequency")).unwrap(),
+        address
+    );
+
+    // Clones share the same string
+    let clone = address.clone();
+    assert_eq!(clone.as_str().as_ptr(), address.as_str().as_ptr());
+
+    // Patterns are not valid addresses, but valid patterns
+    OscAddress::try_from("/oscillator/*").expect_err("Should not be valid");
+    OscAddress::try_from(String::from("oscillator")).expect_err("Should not be valid");
+    let pattern = OscPattern::try_from("/oscillator/*").expect("Should be valid");
+    OscPattern::try_from("/oscillator/[b-a]").expect_err("Should not be valid");
+    OscPattern::new(String::from("")).expect_err("Should not be valid");
+
+    let matcher = Matcher::new(&pattern).expect("Should be valid");
+    assert!(matcher.match_address(&OscAddress::try_from("/oscillator/2").unwrap()));
+
+    // Both can be used to construct messages
+    assert_eq!(OscMessage::from(address).addr, "/oscillator/1/frequency");
+    assert_eq!(OscMessage::from(pattern).addr, "/oscillator/*");
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn test_matcher_long_literal_runs() {
+    let matches = |pattern: &str, address: &str| {
+        Matcher::new(pattern)
+            .unwrap()
+            .match_address(&OscAddress::new(address.to_string()).unwrap())
+    };
+    let address = "/show/scenes/act2/cue17/fixtures/moving/spot3/intensity";
+
+    assert!(matches(address, address));
+    assert!(!matches(
+        address,
+        "/show/scenes/act2/cue17/fixtures/moving/spot3/intensitx"
+    ));
+    assert!(!matches(
+        address,
+        "/show/scenes/act2/cue17/fixtures/moving/spot3"
+    ));
+
+    // Literal runs following a wildcard, within and across address parts
+    assert!(matches(
+        "/show/*/act2/cue17/fixtures/*/spot3/intensity",
+        address
+    ));
+    assert!(matches(
+        "/show/sc*es/act2/cue17/fixtures/moving/spot3/intensity",
+        address
+    ));
+    assert!(matches(
+        "/show/scenes/act2/cue*/fixtures/moving/spot?/intensity",
+        address
+    ));
+    assert!(!matches(
+        "/show/*/cue17/fixtures/moving/spot3/intensity",
+        address
+    ));
+    assert!(!matches("/show/scenes/*/moving/spot3/intensity", address));
+    assert!(matches(
+        "/show/scenes/act[0-9]/cue17/fixtures/{moving,static}/spot3/intensity",
+        address
+    ));
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn test_matcher_pathological_patterns() {
+    use std::time::{Duration, Instant};
+
+    let matches = |pattern: &str, address: &str| {
+        Matcher::new(pattern)
+            .unwrap()
+            .match_address(&OscAddress::new(address.to_string()).unwrap())
+    };
+
+    // A backtracking matcher tries every way of splitting the address between the wildcards,
+    // or between the alternatives, before giving up
+    let start = Instant::now();
+    let address = format!("/{}", "a".repeat(200));
+    assert!(!matches(&format!("/{}b", "*a".repeat(20)), &address));
+    assert!(matches(&format!("/{}", "*a".repeat(20)), &address));
+    assert!(!matches(&format!("/{}b", "{a,aa}".repeat(40)), &address));
+    assert!(matches(&format!("/{}", "{a,aa}".repeat(100)), &address));
+    assert!(!matches(&format!("/{}b", "[a]".repeat(40)), &address));
+    assert!(start.elapsed() < Duration::from_secs(2));
+
+    // Cases which need to consider more than the first or last possible match of a component
+    assert!(matches("/{a,ab}c", "/abc"));
+    assert!(matches("/*ab", "/abab"));
+    assert!(matches("/*a?", "/xaa"));
+    assert!(matches("/*a*a", "/aaa"));
+    assert!(matches("/[a-c]c", "/abc"));
+    assert!(!matches("/[!0-9]", "/a/b"));
+    assert!(matches("/*/b", "/a/b"));
+    assert!(!matches("/*b", "/a/b"));
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn test_pattern_set_matches_like_matchers() {
+    let patterns = [
+        "/mixer/1/gain",
+        "/mixer/*/gain",
+        "/mixer/[0-9]/{gain,pan}",
+        "/mixer/1/*",
+        "/*",
+        "/*/1/gain",
+        "/mixer",
+        "/mixer/1/gain/fine",
+        "/fx/reverb/?ize",
+        "/fx/{reverb,delay}/mix",
+        "/fx/reverb/mix",
+    ];
+    let addresses = [
+        "/mixer/1/gain",
+        "/mixer/2/pan",
+        "/mixer/12/gain",
+        "/mixer",
+        "/mixer/1",
+        "/mixer/1/gain/fine",
+        "/fx/reverb/size",
+        "/fx/reverb/mix",
+        "/fx/delay/mix",
+        "/other",
+    ];
+
+    let set = PatternSet::from_iter(&patterns).unwrap();
+    assert_eq!(set.len(), patterns.len());
+    assert_eq!(set.get(2).unwrap().pattern, patterns[2]);
+    assert!(set.get(patterns.len()).is_none());
+    for address in addresses.iter() {
+        let address = OscAddress::new(address.to_string()).unwrap();
+        let expected: Vec<usize> = patterns
+            .iter()
+            .enumerate()
+            .filter(|(_, pattern)| Matcher::new(pattern).unwrap().match_address(&address))
+            .map(|(i, _)| i)
+            .collect();
+        assert_eq!(
+            set.matches(&address).collect::<Vec<_>>(),
+            expected,
+            "{}",
+            address
+        );
+    }
+
+    let address = OscAddress::new("/mixer/1/gain".to_string()).unwrap();
+    assert_eq!(
+        set.matches(&address).collect::<Vec<_>>(),
+        vec![0, 1, 2, 3, 5]
+    );
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn test_pattern_set_errors() {
+    let errors = PatternSet::from_iter(vec![
+        "/ok".to_string(),
+        "missing/slash".to_string(),
+        "/also/ok".to_string(),
+        "/bad/{choice".to_string(),
+    ])
+    .unwrap_err();
+    assert_eq!(errors.len(), 2);
+    assert_eq!(errors[0].0, 1);
+    assert_eq!(errors[1].0, 3);
+
+    let set = PatternSet::from_iter(Vec::<&str>::new()).unwrap();
+    assert!(set.is_empty());
+    let address = OscAddress::new("/any".to_string()).unwrap();
+    assert_eq!(set.matches(&address).count(), 0);
+}
@@ -0,0 +1,601 @@
+//! Compile-time checks of the public API of the codec types.
+//!
+//! Every item is used with concrete types and every function is coerced to a function pointer
+//! with its full signature, so that removing or renaming an item, or changing a signature, fails
+//! to compile here rather than in downstream crates. Matches are exhaustive on purpose. Extend
+//! this file whenever public API is added. It checks the default `std` API surface.
+#![cfg(feature = "std")]
+// Spelling out full signatures is the point of this file
+#![allow(clippy::type_complexity)]
+
+#[cfg(feature = "midly")]
+extern crate midly;
+extern crate nom;
+extern crate rosc;
+#[macro_use]
+extern crate static_assertions;
+
+use nom::error::ErrorKind;
+use rosc::address::{Matcher, MatcherCache, OscAddress, OscPattern, PatternSet, PatternSyntax};
+use rosc::debug::{self, NoRedaction, PatternRedactor, Redaction, Redactor};
+use rosc::decoder::{
+    self, BundleStats, DecodeOptions, DecodeSink, LenientReader, MessageView, Signature,
+};
+use rosc::encoder::{
+    self, BundleLayout, EncodeOptions, LazyBundleEncoder, MessageLayout, PacketLayout, PeerProfile,
+    PreparedMessage,
+};
+use rosc::framing::{self, Framing};
+use rosc::signature::{CoercionMode, TypeSignature};
+use rosc::stats::{self, AddressStatEntry, AddressStats, Stats};
+use rosc::{
+    ExactnessError, FloatTolerance, FromOscArg, FromOscArgs, IntoOscArgs, NumericByteOrder,
+    OscArray, OscBundle, OscColor, OscError, OscMessage, OscMidiMessage, OscPacket,
+    OscPacketEditor, OscTime, OscTimeError, OscType, OscTypeRef, PrecisionLoss, SpecProfile,
+    TypeTag,
+};
+use std::convert::TryFrom;
+use std::time::{Duration, Instant, SystemTime};
+
+// Losing any of these would silently break sending packets between threads
+assert_impl_all!(OscType: Send, Sync, Unpin, Clone, PartialEq);
+assert_impl_all!(OscMessage: Send, Sync, Unpin, Clone, PartialEq);
+assert_impl_all!(OscBundle: Send, Sync, Unpin, Clone, PartialEq);
+assert_impl_all!(OscPacket: Send, Sync, Unpin, Clone, PartialEq);
+assert_impl_all!(OscTime: Send, Sync, Unpin, Copy, Eq, Ord);
+assert_impl_all!(OscColor: Send, Sync, Unpin, Clone, Eq);
+assert_impl_all!(OscMidiMessage: Send, Sync, Unpin, Clone, Eq);
+assert_impl_all!(OscArray: Send, Sync, Unpin, Clone, PartialEq);
+assert_impl_all!(OscTypeRef: Send, Sync, Unpin, Clone, PartialEq);
+assert_impl_all!(TypeTag: Send, Sync, Unpin, Copy, Eq, std::hash::Hash, std::fmt::Display);
+assert_impl_all!(NumericByteOrder: Send, Sync, Unpin, Copy, Eq, Default);
+assert_impl_all!(SpecProfile: Send, Sync, Unpin, Copy, Eq, std::hash::Hash, Default);
+assert_impl_all!(PatternSyntax: Send, Sync, Unpin, Copy, Eq, std::hash::Hash, Default);
+assert_impl_all!(OscError: Send, Sync, Unpin, std::error::Error);
+assert_impl_all!(ExactnessError: Send, Sync, Unpin, std::error::Error);
+assert_impl_all!(OscTimeError: Send, Sync, Unpin, std::error::Error);
+assert_impl_all!(FloatTolerance: Send, Sync, Unpin, Copy, Default, PartialEq);
+assert_impl_all!(PrecisionLoss: Send, Sync, Unpin, std::error::Error);
+
+assert_impl_all!(OscAddress: Send, Sync, Unpin, Clone, Eq);
+assert_impl_all!(OscPattern: Send, Sync, Unpin, Clone, Eq);
+assert_impl_all!(Matcher: Send, Sync, Unpin, Clone);
+assert_impl_all!(MatcherCache: Send, Sync, Unpin);
+assert_impl_all!(PatternSet: Send, Sync, Unpin, Clone);
+assert_impl_all!(AddressStats: Send, Sync, Unpin, Clone);
+assert_impl_all!(AddressStatEntry: Send, Sync, Unpin, Clone, PartialEq);
+
+assert_impl_all!(DecodeOptions: Send, Sync, Unpin, Clone, Default, Eq);
+assert_impl_all!(EncodeOptions: Send, Sync, Unpin, Clone, Default, Eq);
+assert_impl_all!(PreparedMessage: Send, Sync, Unpin, Clone, Eq);
+assert_impl_all!(Redaction: Send, Sync, Unpin, Clone, PartialEq);
+assert_impl_all!(NoRedaction: Send, Sync, Unpin, Copy, Redactor);
+assert_impl_all!(PatternRedactor: Send, Sync, Unpin, Clone, Redactor);
+assert_impl_all!(Signature: Send, Sync, Unpin, Clone);
+assert_impl_all!(TypeSignature: Send, Sync, Unpin, Clone, Eq, std::hash::Hash);
+assert_impl_all!(CoercionMode: Send, Sync, Unpin, Copy, Eq);
+assert_impl_all!(LenientReader: Send, Sync, Unpin, Clone);
+assert_impl_all!(BundleStats: Send, Sync, Unpin, Clone);
+assert_impl_all!(MessageView<'static>: Send, Unpin);
+assert_impl_all!(PacketLayout: Send, Sync, Unpin, Clone, Eq);
+assert_impl_all!(LazyBundleEncoder<'static>: Unpin, std::fmt::Debug);
+assert_impl_all!(Framing: Send, Sync, Unpin, Copy, Eq, std::hash::Hash);
+assert_impl_all!(PeerProfile: Send, Sync, Unpin, Clone);
+assert_impl_all!(Stats: Send, Sync, Unpin, Clone, Default);
+
+#[test]
+fn test_type_constructors() {
+    let time = OscTime {
+        seconds: 1,
+        fractional: 2,
+    };
+    let color = OscColor {
+        red: 1,
+        green: 2,
+        blue: 3,
+        alpha: 4,
+    };
+    let midi = OscMidiMessage {
+        port: 1,
+        status: 2,
+        data1: 3,
+        data2: 4,
+    };
+    let array = OscArray {
+        content: vec![OscType::Int(1)],
+    };
+
+    let args = vec![
+        OscType::Int(1),
+        OscType::Float(1.0),
+        OscType::String("s".to_string()),
+        OscType::Blob(vec![1]),
+        OscType::Time(time),
+        OscType::Long(1),
+        OscType::Double(1.0),
+        OscType::Char('c'),
+        OscType::Color(color.clone()),
+        OscType::Midi(midi.clone()),
+        OscType::Bool(true),
+        OscType::Array(array.clone()),
+        OscType::Nil,
+        OscType::Inf,
+    ];
+    for arg in &args {
+        match arg {
+            OscType::Int(_)
+            | OscType::Float(_)
+            | OscType::String(_)
+            | OscType::Blob(_)
+            | OscType::Time(_)
+            | OscType::Long(_)
+            | OscType::Double(_)
+            | OscType::Char(_)
+            | OscType::Color(_)
+            | OscType::Midi(_)
+            | OscType::Bool(_)
+            | OscType::Array(_)
+            | OscType::Nil
+            | OscType::Inf => (),
+        }
+    }
+
+    let msg = OscMessage {
+        addr: "/a".to_string(),
+        args,
+    };
+    let bundle = OscBundle {
+        timetag: time,
+        content: vec![OscPacket::Message(msg.clone())],
+    };
+    for packet in &[
+        OscPacket::Message(msg.clone()),
+        OscPacket::Bundle(bundle.clone()),
+    ] {
+        match packet {
+            OscPacket::Message(_) | OscPacket::Bundle(_) => (),
+        }
+    }
+    for order in &[NumericByteOrder::Big, NumericByteOrder::Little] {
+        match order {
+            NumericByteOrder::Big | NumericByteOrder::Little => (),
+        }
+    }
+
+    let _: OscPacket = msg.clone().into();
+    let _: OscPacket = bundle.into();
+    let _: OscMessage = "/a".into();
+    let _: OscMessage = "/a".to_string().into();
+    let _: OscArray = vec![1i32, 2].into_iter().collect();
+    let _: OscTime = (1u32, 2u32).into();
+    let _: (u32, u32) = time.into();
+    let _: SystemTime = OscTime::from((3_900_000_000, 0)).into();
+    let _: Result<OscTime, OscTimeError> = OscTime::try_from(SystemTime::now());
+    let _: Result<OscTime, OscType> = OscTime::try_from(OscType::Nil);
+    let _ = osc_msg_and_bundle_macros();
+}
+
+fn osc_msg_and_bundle_macros() -> OscPacket {
+    let msg = rosc::osc_msg!("/a", 1i32, "s");
+    OscPacket::Bundle(rosc::osc_bundle!((0, 1); OscPacket::Message(msg)))
+}
+
+#[test]
+fn test_type_methods() {
+    let _: OscTime = OscTime::IMMEDIATE;
+    let _: OscTime = OscTime::NEVER;
+    let _: fn(&OscTime) -> bool = OscTime::is_immediate;
+    let _: fn(&OscTime) -> bool = OscTime::is_never;
+    let _: fn(&OscTime, OscTime, std::time::Duration) -> bool = OscTime::is_reasonable;
+    let _: fn(&OscTime, std::time::Duration) -> bool = OscTime::is_reasonable_now;
+    let _: fn(u64, u32) -> OscTime = OscTime::from_unix_secs_nanos;
+    let _: fn(&OscTime) -> (u64, u32) = OscTime::to_unix_secs_nanos;
+
+    let _: fn(OscType) -> Option<i32> = OscType::int;
+    let _: fn(OscType) -> Option<f32> = OscType::float;
+    let _: fn(OscType) -> Option<String> = OscType::string;
+    let _: fn(OscType) -> Option<Vec<u8>> = OscType::blob;
+    let _: fn(OscType) -> Option<OscTime> = OscType::time;
+    let _: fn(OscType) -> Option<i64> = OscType::long;
+    let _: fn(OscType) -> Option<f64> = OscType::double;
+    let _: fn(OscType) -> Option<char> = OscType::char;
+    let _: fn(OscType) -> Option<OscColor> = OscType::color;
+    let _: fn(OscType) -> Option<OscMidiMessage> = OscType::midi;
+    let _: fn(OscType) -> Option<bool> = OscType::bool;
+    let _: fn(OscType) -> Option<OscArray> = OscType::array;
+    let _: fn(&OscType) -> bool = OscType::is_nil;
+    let _: fn(&OscType) -> &'static str = OscType::variant_name;
+    let _: fn(&OscType) -> Option<usize> = OscType::fixed_data_size;
+    let _: [(&str, usize); 11] = rosc::FIXED_DATA_SIZES;
+    let _: fn(char) -> Option<TypeTag> = TypeTag::from_char;
+    let _: fn(TypeTag) -> char = TypeTag::to_char;
+    let _: fn(&OscType) -> TypeTag = TypeTag::of;
+    let _: fn(TypeTag) -> Option<usize> = TypeTag::fixed_payload_size;
+    let _: fn(TypeTag) -> bool = TypeTag::has_payload;
+    let _: [TypeTag; 16] = TypeTag::ALL;
+    match TypeTag::Int {
+        TypeTag::Int
+        | TypeTag::Float
+        | TypeTag::String
+        | TypeTag::Blob
+        | TypeTag::Time
+        | TypeTag::Long
+        | TypeTag::Double
+        | TypeTag::Char
+        | TypeTag::Color
+        | TypeTag::Midi
+        | TypeTag::True
+        | TypeTag::False
+        | TypeTag::Nil
+        | TypeTag::Inf
+        | TypeTag::ArrayStart
+        | TypeTag::ArrayEnd => (),
+    }
+    let _: fn(&OscType) -> Option<&OscTime> = OscType::as_time;
+    let _: fn(&OscTypeRef<'static>) -> OscType = OscTypeRef::to_osc_type;
+    let _: fn(OscTypeRef<'static>) -> OscType = OscType::from;
+    match OscTypeRef::Nil {
+        OscTypeRef::Int(_)
+        | OscTypeRef::Float(_)
+        | OscTypeRef::String(_)
+        | OscTypeRef::Blob(_)
+        | OscTypeRef::Time(_)
+        | OscTypeRef::Long(_)
+        | OscTypeRef::Double(_)
+        | OscTypeRef::Char(_)
+        | OscTypeRef::Color(_)
+        | OscTypeRef::Midi(_)
+        | OscTypeRef::Bool(_)
+        | OscTypeRef::Nil
+        | OscTypeRef::Inf => (),
+    }
+    #[cfg(feature = "base64")]
+    {
+        let _: fn(&OscType) -> Option<String> = OscType::blob_to_base64;
+        let _: fn(&str) -> Result<OscType, OscError> = OscType::blob_from_base64;
+    }
+    #[cfg(feature = "midly")]
+    {
+        let _: for<'a> fn(&midly::live::LiveEvent<'a>, u8) -> Result<OscMidiMessage, OscError> =
+            OscMidiMessage::from_live_event;
+        let _: fn(&OscMidiMessage) -> Result<midly::live::LiveEvent<'static>, OscError> =
+            OscMidiMessage::to_live_event;
+    }
+    let _: fn(f64) -> Result<OscType, PrecisionLoss> = OscType::float32;
+    let _: fn(f64) -> OscType = OscType::float32_lossy;
+    let _: fn(&OscType, &OscType) -> std::cmp::Ordering = OscType::canonical_cmp;
+    let _: fn(&PrecisionLoss) -> f64 = PrecisionLoss::value;
+    let _: fn(&PrecisionLoss) -> f32 = PrecisionLoss::nearest;
+
+    let _: fn(&mut OscArray) = OscArray::sort_unstable_by_canonical;
+    let _: fn(&mut OscArray) = OscArray::dedup_canonical;
+    let _: fn(&OscArray, &OscType) -> bool = OscArray::contains;
+
+    let _: fn(OscMessage, OscTime) -> OscPacket = OscMessage::at;
+    let _: fn(&mut OscMessage, fn(f64) -> f64) = OscMessage::map_floats;
+    let _: fn(&mut OscMessage, fn(i64) -> i64) = OscMessage::map_ints;
+    let _: fn(&OscMessage) -> usize = OscMessage::total_arg_count;
+    let _: fn(&mut OscMessage, OscType) = OscMessage::push_arg;
+    let _: fn(&mut OscMessage, usize, OscType) = OscMessage::insert_arg;
+    let _: fn(&mut OscMessage, usize) -> Option<OscType> = OscMessage::remove_arg;
+    let _: fn(&mut OscMessage, OscType) = OscMessage::push_all::<OscType>;
+    let _: fn(&mut OscMessage, Vec<OscType>) = OscMessage::push_all::<Vec<OscType>>;
+    let _: fn(OscType) -> Vec<OscType> = <OscType as IntoOscArgs>::into_osc_args;
+    let _: fn(Vec<OscType>) -> Vec<OscType> = <Vec<OscType> as IntoOscArgs>::into_osc_args;
+    let _: fn(&[OscType]) -> Result<Vec<OscType>, OscError> = Vec::<OscType>::from_args;
+    let _: fn(&[OscType]) -> Result<(i32,), OscError> = <(i32,)>::from_args;
+    let _: fn(
+        &[OscType],
+    ) -> Result<(i32, f32, String, Vec<u8>, OscTime, i64, f64, char), OscError> =
+        FromOscArgs::from_args;
+    let _: fn(&[OscType]) -> Result<(OscColor, OscMidiMessage, bool, OscArray, OscType), OscError> =
+        FromOscArgs::from_args;
+    let _: fn(&OscType) -> Option<i32> = <i32 as FromOscArg>::from_osc_arg;
+    let _: &'static str = <i32 as FromOscArg>::EXPECTED;
+    let _: fn(&OscMessage, &OscMessage, FloatTolerance) -> bool = OscMessage::approx_eq;
+    let _: fn(&OscType, &OscType, FloatTolerance) -> bool = OscType::approx_eq;
+    let FloatTolerance {
+        absolute: _,
+        relative: _,
+    } = FloatTolerance::default();
+    let _: fn(&mut OscBundle, OscTime) -> Vec<OscPacket> = OscBundle::drain_ready;
+    let _: fn(Vec<OscPacket>) -> OscBundle = OscBundle::with_earliest_timetag;
+
+    let _: fn(OscPacket, OscTime) -> OscPacket = OscPacket::scheduled;
+    let _: fn(&OscPacket) -> Option<(&OscMessage, OscTime)> = OscPacket::unwrap_single;
+    let _: fn(&OscPacket) -> u64 = OscPacket::structural_hash;
+    let packet = OscPacket::Message(OscMessage::from("/a"));
+    let _: Vec<(OscTime, &OscMessage)> = packet.flatten().collect();
+
+    let _: fn(OscPacket) -> OscPacketEditor = OscPacketEditor::new;
+    let _: fn(&OscPacketEditor) -> bool = OscPacketEditor::is_changed;
+    let _: fn(OscPacketEditor) -> OscPacket = OscPacketEditor::finish;
+}
+
+#[test]
+fn test_errors() {
+    let errors = vec![
+        OscError::StringError(String::from_utf8(vec![0xff]).unwrap_err()),
+        OscError::ReadError(ErrorKind::Eof),
+        OscError::BadChar('c'),
+        OscError::BadPacket(""),
+        OscError::BadMessage(""),
+        OscError::BadString(""),
+        OscError::BadArg(String::new()),
+        OscError::BadBundle(String::new()),
+        OscError::BadAddressPattern(String::new()),
+        OscError::BadAddress(String::new()),
+        OscError::RegexError(String::new()),
+        OscError::BufferTooSmall {
+            needed: 1,
+            available: 0,
+        },
+        OscError::NeedMoreData { needed: 1 },
+        OscError::UnsupportedControlPacket(String::new()),
+        OscError::TooManyArgs { count: 2, max: 1 },
+        OscError::MemoryBudgetExceeded {
+            budget: 1,
+            needed: 2,
+        },
+        OscError::WrongArgCount {
+            expected: 2,
+            found: 1,
+        },
+        OscError::WrongArgType {
+            index: 0,
+            expected: "Int",
+            found: "Float",
+        },
+        OscError::Aborted,
+        OscError::Unimplemented,
+    ];
+    for error in &errors {
+        match error {
+            OscError::StringError(_)
+            | OscError::ReadError(_)
+            | OscError::BadChar(_)
+            | OscError::BadPacket(_)
+            | OscError::BadMessage(_)
+            | OscError::BadString(_)
+            | OscError::BadArg(_)
+            | OscError::BadBundle(_)
+            | OscError::BadAddressPattern(_)
+            | OscError::BadAddress(_)
+            | OscError::RegexError(_)
+            | OscError::BufferTooSmall { .. }
+            | OscError::NeedMoreData { .. }
+            | OscError::UnsupportedControlPacket(_)
+            | OscError::TooManyArgs { .. }
+            | OscError::MemoryBudgetExceeded { .. }
+            | OscError::WrongArgCount { .. }
+            | OscError::WrongArgType { .. }
+            | OscError::Aborted
+            | OscError::Unimplemented => assert!(!error.to_string().is_empty()),
+        }
+    }
+
+    for error in &[
+        ExactnessError::Invalid(OscError::Unimplemented),
+        ExactnessError::Mismatch { offset: 0 },
+    ] {
+        match error {
+            ExactnessError::Invalid(_) | ExactnessError::Mismatch { .. } => {
+                assert!(!error.to_string().is_empty())
+            }
+        }
+    }
+}
+
+/// Implements every method of `DecodeSink` with its full signature.
+struct NullSink;
+
+impl DecodeSink for NullSink {
+    fn on_message_start(&mut self, _addr: &str) {}
+    fn on_arg(&mut self, _arg: OscTypeRef) {}
+    fn on_array_start(&mut self) {}
+    fn on_array_end(&mut self) {}
+    fn on_message_end(&mut self) {}
+    fn on_bundle_start(&mut self, _timetag: OscTime) {}
+    fn on_bundle_end(&mut self) {}
+}
+
+#[test]
+fn test_decoder() {
+    let _: usize = decoder::MTU;
+    let _: usize = decoder::MAX_BUNDLE_DEPTH;
+    let options = DecodeOptions {
+        require_leading_slash: true,
+        numeric_byte_order: NumericByteOrder::Big,
+        max_args: 65536,
+        allowed_type_tags: None,
+        strict_padding: false,
+        timetag_as_arg: false,
+        max_owned_bytes: None,
+    };
+    assert_eq!(options, DecodeOptions::default());
+    let _: fn(SpecProfile) -> DecodeOptions = DecodeOptions::for_profile;
+    let _: fn(SpecProfile) -> Option<&'static str> = SpecProfile::allowed_type_tags;
+    let _: fn(SpecProfile) -> bool = SpecProfile::strict_padding;
+    match SpecProfile::Permissive {
+        SpecProfile::Osc10Strict | SpecProfile::Osc11 | SpecProfile::Permissive => (),
+    }
+    let _: fn(&str, PatternSyntax) -> Result<Matcher, OscError> = Matcher::with_syntax;
+    let _: fn(SpecProfile) -> PatternSyntax = PatternSyntax::for_profile;
+    match PatternSyntax::Osc10 {
+        PatternSyntax::Osc10 | PatternSyntax::Osc11 => (),
+    }
+
+    let _: fn(&[u8]) -> Result<(&[u8], OscPacket), OscError> = decoder::decode_udp;
+    let _: for<'a> fn(&'a [u8], &DecodeOptions) -> Result<(&'a [u8], OscPacket), OscError> =
+        decoder::decode_udp_with_options;
+    let _: for<'a> fn(&'a [u8], &mut Stats) -> Result<(&'a [u8], OscPacket), OscError> =
+        decoder::decode_udp_with_stats;
+    let _: fn(Vec<u8>) -> Result<OscPacket, OscError> = decoder::decode_owned;
+    let _: fn(&[u8]) -> Result<OscPacket, ExactnessError> = decoder::decode_exact;
+    let _: fn(&[u8]) -> Result<Option<&str>, OscError> = decoder::peek_address;
+    let _: fn(&[u8], &mut Vec<OscPacket>) -> Result<usize, OscError> = decoder::decode_framed_into;
+    let _: fn(&[u8], &mut OscMessage) -> Result<(), OscError> = decoder::decode_message_into;
+    let _: fn(&[u8]) -> Result<BundleStats, OscError> = decoder::bundle_stats;
+    let _: fn(&[u8]) -> Result<(&[u8], Option<OscPacket>), OscError> = decoder::decode_tcp;
+    let _: for<'a> fn(&'a [u8], &DecodeOptions) -> Result<(&'a [u8], Option<OscPacket>), OscError> =
+        decoder::decode_tcp_with_options;
+    let _: fn(&[u8]) -> Result<(&[u8], Vec<OscPacket>), OscError> = decoder::decode_tcp_vec;
+    let _: for<'a> fn(&'a [u8], &DecodeOptions) -> Result<(&'a [u8], Vec<OscPacket>), OscError> =
+        decoder::decode_tcp_vec_with_options;
+    let _: fn(&[u8]) -> Result<(Signature, usize), OscError> = decoder::decode_signature;
+    let _: fn(&[u8], &Signature, usize) -> Result<OscType, OscError> = decoder::decode_arg_at;
+    let _: fn(&[u8], usize) -> Option<usize> = decoder::resync;
+
+    let _: fn(usize) -> LenientReader = LenientReader::new;
+    let _: fn(usize, DecodeOptions) -> LenientReader = LenientReader::with_options;
+    let _: fn(&mut LenientReader, &[u8]) = LenientReader::push;
+    let _: fn(&mut LenientReader) -> Option<OscPacket> = LenientReader::next_packet;
+    let _: fn(&LenientReader) -> u64 = LenientReader::skipped_bytes;
+    let _: fn(&LenientReader) -> u64 = LenientReader::resyncs;
+    let _: fn(&mut LenientReader, &mut NullSink) -> bool = LenientReader::next_packet_to_sink;
+    let _: for<'a> fn(&'a [u8], &mut NullSink) -> Result<&'a [u8], OscError> =
+        decoder::decode_to_sink;
+    let _: for<'a> fn(&'a [u8], &DecodeOptions, &mut NullSink) -> Result<&'a [u8], OscError> =
+        decoder::decode_to_sink_with_options;
+
+    let bytes = encoder::encode(&OscPacket::Message(OscMessage::from("/a"))).unwrap();
+    let (signature, _) = decoder::decode_signature(&bytes).unwrap();
+    let _: &str = &signature.address;
+    let _: &TypeSignature = &signature.type_tags;
+    let _: Vec<&str> = signature.arg_type_tags();
+
+    let _: fn(&str) -> Result<TypeSignature, OscError> = TypeSignature::parse;
+    let _: fn(&TypeSignature) -> &str = TypeSignature::as_str;
+    let _: fn(&TypeSignature, &TypeSignature, CoercionMode) -> bool =
+        TypeSignature::is_compatible_with;
+    match CoercionMode::Exact {
+        CoercionMode::Exact | CoercionMode::Relaxed => (),
+    }
+
+    let view: MessageView = MessageView::new(&bytes).unwrap();
+    let _: &str = view.addr();
+    let _: &str = view.tags();
+    let _: usize = view.arg_count();
+    let _: Result<OscType, OscError> = view.arg(0);
+    let _: Result<OscMessage, OscError> = view.to_message();
+
+    let BundleStats {
+        element_count: _,
+        max_element_size: _,
+        total_size: _,
+        depth_estimate: _,
+        consistent: _,
+    } = decoder::bundle_stats(&bytes).unwrap();
+}
+
+#[test]
+fn test_encoder() {
+    let options = EncodeOptions {
+        numeric_byte_order: NumericByteOrder::Big,
+        allowed_type_tags: None,
+    };
+    assert_eq!(options, EncodeOptions::default());
+    let _: fn(SpecProfile) -> EncodeOptions = EncodeOptions::for_profile;
+
+    let _: fn(&OscPacket) -> rosc::Result<Vec<u8>> = encoder::encode;
+    let _: fn(&OscPacket) -> rosc::Result<Vec<u8>> = encoder::encode_validated;
+    let _: fn(&OscPacket, usize) -> rosc::Result<Vec<u8>> = encoder::encode_with_capacity;
+    let _: fn(&OscPacket, &mut Vec<u8>) -> rosc::Result<usize> = encoder::encode_into;
+    let _: fn(&OscPacket, &EncodeOptions) -> rosc::Result<Vec<u8>> = encoder::encode_with_options;
+    let _: fn(&OscPacket, &mut Stats) -> rosc::Result<Vec<u8>> = encoder::encode_with_stats;
+    let _: fn(Duration, usize) -> AddressStats = AddressStats::new;
+    let _: fn(&mut AddressStats, &OscMessage, Instant) = AddressStats::record;
+    let _: fn(&mut AddressStats, &str, usize, Instant) = AddressStats::record_size;
+    let _: fn(&AddressStats, Instant) -> Vec<AddressStatEntry> = AddressStats::snapshot;
+    let _: fn(&AddressStats) -> usize = AddressStats::len;
+    let _: fn(&AddressStats) -> bool = AddressStats::is_empty;
+    let _: fn(&mut AddressStats) = AddressStats::clear;
+    let _: fn(&[AddressStatEntry]) -> String = stats::prometheus_text;
+    let AddressStatEntry {
+        address: _,
+        count: _,
+        bytes: _,
+        last_seen: _,
+        rate: _,
+    } = AddressStatEntry {
+        address: String::new(),
+        count: 0,
+        bytes: 0,
+        last_seen: Instant::now(),
+        rate: 0.0,
+    };
+    let _: fn(&OscPacket, &mut Vec<u8>, fn() -> bool) -> rosc::Result<usize> =
+        encoder::encode_with_deadline;
+    let _: usize = encoder::DEADLINE_CHECK_INTERVAL;
+    let _: fn(&OscPacket, Vec<u8>) -> std::io::Result<usize> = encoder::encode_to_writer;
+    let _: fn(&OscPacket, &mut [u8]) -> rosc::Result<usize> = encoder::encode_into_slice;
+    let _: fn(&OscPacket) -> rosc::Result<(Vec<u8>, PacketLayout)> = encoder::encode_with_layout;
+    let _: fn(&mut [u8], OscTime) -> rosc::Result<()> = encoder::patch_timetag;
+    let _: fn(&mut [u8], OscTime) -> rosc::Result<()> = encoder::patch_timetag_recursive;
+    let _: fn(&OscPacket, &PeerProfile) -> rosc::Result<Vec<u8>> = encoder::encode_for_peer;
+    let _: fn(OscTime) -> LazyBundleEncoder<'static> = LazyBundleEncoder::new;
+    let _: for<'a> fn(
+        &'a mut LazyBundleEncoder<'static>,
+        OscPacket,
+    ) -> &'a mut LazyBundleEncoder<'static> = LazyBundleEncoder::add;
+    let _: fn(&LazyBundleEncoder<'static>) -> usize = LazyBundleEncoder::len;
+    let _: fn(&LazyBundleEncoder<'static>) -> bool = LazyBundleEncoder::is_empty;
+    let _: fn(LazyBundleEncoder<'static>, &mut Vec<u8>) -> rosc::Result<usize> =
+        LazyBundleEncoder::encode_into;
+    let _: fn(LazyBundleEncoder<'static>) -> rosc::Result<Vec<u8>> = LazyBundleEncoder::encode;
+    let mut lazy = LazyBundleEncoder::new(OscTime::IMMEDIATE);
+    let _: &mut LazyBundleEncoder = lazy.add_lazy(|out: &mut Vec<u8>| Ok(out.len()));
+    let _: fn() -> PeerProfile = PeerProfile::strict_osc_1_0;
+    let _: fn() -> PeerProfile = PeerProfile::liblo;
+    let _: fn() -> PeerProfile = PeerProfile::minimal_embedded;
+    let _: fn(&'static str) -> Vec<u8> = encoder::encode_string::<&'static str>;
+    let _: fn(&str, &mut Vec<u8>) -> usize = encoder::encode_string_into;
+    let _: fn(&str, Vec<OscType>) -> rosc::Result<Vec<u8>> =
+        encoder::encode_message_iter::<Vec<OscType>>;
+    let _: fn(&str, &[TypeTag]) -> rosc::Result<PreparedMessage> = PreparedMessage::new;
+    let _: fn(&PreparedMessage, &[OscType], &mut Vec<u8>) -> rosc::Result<usize> =
+        PreparedMessage::encode_values;
+    let _: fn(&[u8], &NoRedaction) -> String = debug::annotated_dump_redacted;
+    let _: fn(&mut String, &[u8], &(dyn Redactor + 'static)) -> std::fmt::Result =
+        debug::write_annotated_dump_redacted;
+    let _: fn(&OscPacket, &PatternRedactor) -> OscPacket = debug::redact_packet;
+    let _: fn(&[&str]) -> rosc::Result<PatternRedactor> = PatternRedactor::new;
+    let _: &str = debug::MASK;
+    match Redaction::Keep {
+        Redaction::Keep | Redaction::Mask | Redaction::Replace(_) => (),
+    }
+    const _: usize = encoder::pad_to_4(1);
+    const _: Option<usize> = encoder::checked_pad_to_4(1);
+    const _: bool = encoder::is_aligned_4(4);
+
+    let (_, layout) =
+        encoder::encode_with_layout(&OscPacket::Message(OscMessage::from("/a"))).unwrap();
+    match layout {
+        PacketLayout::Message(MessageLayout {
+            ref address_range,
+            ref type_tags_range,
+            ref arg_ranges,
+        }) => {
+            let _ = (address_range, type_tags_range, arg_ranges);
+        }
+        PacketLayout::Bundle(BundleLayout {
+            ref timetag_range,
+            ref element_ranges,
+        }) => {
+            let _ = (timetag_range, element_ranges);
+        }
+    }
+}
+
+#[test]
+fn test_framing() {
+    let _: fn(&OscPacket, Framing) -> rosc::Result<Vec<u8>> = framing::encode_framed;
+    let _: fn(&[u8], Framing) -> rosc::Result<(usize, OscPacket)> = framing::decode_framed;
+    let _: fn(&[u8], Framing, &DecodeOptions) -> rosc::Result<(usize, OscPacket)> =
+        framing::decode_framed_with_options;
+    let _: fn(&[u8], Framing, usize) -> usize = framing::resume_hint;
+    let _: fn(SpecProfile) -> Framing = Framing::for_profile;
+    match Framing::Datagram {
+        Framing::Datagram | Framing::LengthPrefixed | Framing::Slip => (),
+    }
+}
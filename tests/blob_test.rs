@@ -0,0 +1,66 @@
+extern crate rosc;
+
+use rosc::{blob, decoder, encoder, OscMessage, OscPacket, OscType};
+
+// Two f32 samples (1.0, -0.5) and the same values as i16 PCM (16384, -8192)
+const F32_BE: [u8; 8] = [0x3f, 0x80, 0x00, 0x00, 0xbf, 0x00, 0x00, 0x00];
+const F32_LE: [u8; 8] = [0x00, 0x00, 0x80, 0x3f, 0x00, 0x00, 0x00, 0xbf];
+const I16_BE: [u8; 4] = [0x40, 0x00, 0xe0, 0x00];
+const I16_LE: [u8; 4] = [0x00, 0x40, 0x00, 0xe0];
+
+#[test]
+fn test_blob_as_samples() {
+    assert_eq!(blob::blob_as_f32_be(&F32_BE).unwrap(), vec![1.0, -0.5]);
+    assert_eq!(blob::blob_as_f32_le(&F32_LE).unwrap(), vec![1.0, -0.5]);
+    assert_eq!(blob::blob_as_i16_be(&I16_BE).unwrap(), vec![16384, -8192]);
+    assert_eq!(blob::blob_as_i16_le(&I16_LE).unwrap(), vec![16384, -8192]);
+
+    assert_eq!(
+        blob::iter_f32_be(&F32_BE).unwrap().collect::<Vec<_>>(),
+        vec![1.0, -0.5]
+    );
+    assert_eq!(
+        blob::iter_i16_le(&I16_LE).unwrap().collect::<Vec<_>>(),
+        vec![16384, -8192]
+    );
+
+    // Empty blobs contain no samples
+    assert!(blob::blob_as_f32_be(&[]).unwrap().is_empty());
+    assert_eq!(blob::iter_i16_be(&[]).unwrap().count(), 0);
+}
+
+#[test]
+fn test_blob_as_samples_odd_length() {
+    assert!(blob::blob_as_f32_be(&F32_BE[..7]).is_err());
+    assert!(blob::blob_as_f32_le(&F32_LE[..6]).is_err());
+    assert!(blob::iter_f32_be(&F32_BE[..5]).is_err());
+    assert!(blob::blob_as_i16_be(&I16_BE[..3]).is_err());
+    assert!(blob::iter_i16_le(&I16_LE[..1]).is_err());
+
+    // Lengths that are a multiple of 2 but not 4 are only valid for i16 samples
+    assert!(blob::blob_as_f32_be(&[0u8; 6]).is_err());
+    assert_eq!(blob::blob_as_i16_be(&[0u8; 6]).unwrap(), vec![0, 0, 0]);
+}
+
+#[test]
+fn test_blob_samples_round_trip() {
+    assert_eq!(blob::blob_from_f32_be(&[1.0, -0.5]), F32_BE);
+    assert_eq!(blob::blob_from_f32_le(&[1.0, -0.5]), F32_LE);
+    assert_eq!(blob::blob_from_i16_be(&[16384, -8192]), I16_BE);
+    assert_eq!(blob::blob_from_i16_le(&[16384, -8192]), I16_LE);
+
+    let samples: Vec<f32> = (0..64).map(|i| (i as f32 / 8.0).sin()).collect();
+    let packet = OscPacket::Message(OscMessage {
+        addr: "/samples".to_string(),
+        args: vec![OscType::Blob(blob::blob_from_f32_be(&samples))],
+    });
+    let bytes = encoder::encode(&packet).unwrap();
+
+    match decoder::decode_udp(&bytes).unwrap().1 {
+        OscPacket::Message(msg) => match &msg.args[0] {
+            OscType::Blob(data) => assert_eq!(blob::blob_as_f32_be(data).unwrap(), samples),
+            _ => panic!("Expected a blob!"),
+        },
+        _ => panic!("Expected an OscMessage!"),
+    }
+}
@@ -0,0 +1,70 @@
+#![cfg(feature = "std")]
+
+extern crate rosc;
+
+use rosc::buffer::BufferPool;
+use rosc::{decoder, encoder, OscMessage, OscPacket, OscType};
+
+#[test]
+fn test_buffer_pool_reuse() {
+    let pool = BufferPool::new(2, 64);
+    assert_eq!(pool.idle_count(), 0);
+
+    let mut buf = pool.get();
+    assert_eq!(buf.len(), 64);
+    let ptr = buf.as_ptr();
+    buf.truncate(10);
+    drop(buf);
+    assert_eq!(pool.idle_count(), 1);
+
+    // The same allocation is handed out again, with its length restored
+    let buf = pool.get();
+    assert_eq!(buf.as_ptr(), ptr);
+    assert_eq!(buf.len(), 64);
+    assert_eq!(pool.idle_count(), 0);
+}
+
+#[test]
+fn test_buffer_pool_exhaustion() {
+    let pool = BufferPool::new(2, 64);
+
+    // More buffers than the pool keeps can be taken at once
+    let buffers: Vec<_> = (0..5).map(|_| pool.get()).collect();
+    assert!(buffers.iter().all(|buf| buf.len() == 64));
+    assert_eq!(pool.idle_count(), 0);
+
+    // Only up to max_buffers are kept when they are returned
+    drop(buffers);
+    assert_eq!(pool.idle_count(), 2);
+
+    // Detached buffers don't return
+    let buf = pool.get().into_inner();
+    assert_eq!(buf.len(), 64);
+    assert_eq!(pool.idle_count(), 1);
+
+    // Clones share the same buffers
+    let clone = pool.clone();
+    drop(clone.get());
+    assert_eq!(pool.idle_count(), 1);
+}
+
+#[test]
+fn test_buffer_pool_decode() {
+    let pool = BufferPool::new(1, decoder::MTU);
+    let packet = OscPacket::Message(OscMessage {
+        addr: "/pooled".to_string(),
+        args: vec![OscType::Int(42), OscType::String("buffer".to_string())],
+    });
+    let bytes = encoder::encode(&packet).unwrap();
+
+    for _ in 0..3 {
+        let mut buf = pool.get();
+        buf[..bytes.len()].copy_from_slice(&bytes);
+        buf.truncate(bytes.len());
+
+        let (remainder, decoded) = decoder::decode_udp(&buf).unwrap();
+        assert!(remainder.is_empty());
+        assert_eq!(decoded, packet);
+    }
+    assert_eq!(pool.idle_count(), 1);
+}
@@ -0,0 +1,8 @@
+extern crate rosc;
+
+#[test]
+fn test_self_test() {
+    if let Err(failure) = rosc::self_test() {
+        panic!("Self test failed: {}", failure);
+    }
+}
@@ -0,0 +1,350 @@
+extern crate rosc;
+
+use rosc::{debug, encoder};
+use rosc::{
+    OscArray, OscBundle, OscColor, OscMessage, OscMidiMessage, OscPacket, OscTime, OscType,
+};
+
+/// The same packet as used by `rosc::self_test`, using every argument type.
+fn golden_bytes() -> Vec<u8> {
+    encoder::encode(&OscPacket::Bundle(OscBundle {
+        timetag: OscTime::from((0x0102_0304, 0x0506_0708)),
+        content: vec![OscPacket::Message(OscMessage {
+            addr: "/self/test".to_string(),
+            args: vec![
+                OscType::Int(-2),
+                OscType::Float(-1.5),
+                OscType::String("osc".to_string()),
+                OscType::Blob(vec![1, 2, 3, 4, 5]),
+                OscType::Time(OscTime::from((0x89ab_cdef, 0x0123_4567))),
+                OscType::Long(0x0102_0304_0506_0708),
+                OscType::Double(-0.75),
+                OscType::Char('λ'),
+                OscType::Color(OscColor {
+                    red: 1,
+                    green: 2,
+                    blue: 3,
+                    alpha: 4,
+                }),
+                OscType::Midi(OscMidiMessage {
+                    port: 5,
+                    status: 6,
+                    data1: 7,
+                    data2: 8,
+                }),
+                OscType::Bool(true),
+                OscType::Bool(false),
+                OscType::Nil,
+                OscType::Inf,
+                OscType::Array(OscArray {
+                    content: vec![OscType::Int(7), OscType::Float(0.5)],
+                }),
+            ],
+        })],
+    }))
+    .unwrap()
+}
+
+const GOLDEN_DUMP: &str = "\
+0000  23 62 75 6e  bundle
+0004  64 6c 65 00
+0008  01 02 03 04  timetag (16909060, 84281096)
+000c  05 06 07 08
+0010  00 00 00 64  bundle elem size 100
+0014  2f 73 65 6c    addr '/self/test' + 2 pad
+0018  66 2f 74 65
+001c  73 74 00 00
+0020  2c 69 66 73    tags ',ifsbthdcrmTFNI[if]' + 1 pad
+0024  62 74 68 64
+0028  63 72 6d 54
+002c  46 4e 49 5b
+0030  69 66 5d 00
+0034  ff ff ff fe    arg0 i32 = -2
+0038  bf c0 00 00    arg1 f32 = -1.5
+003c  6f 73 63 00    arg2 str 'osc' + 1 pad
+0040  00 00 00 05    arg3 blob len 5
+0044  01 02 03 04    arg3 blob data + 3 pad
+0048  05 00 00 00
+004c  89 ab cd ef    arg4 time (2309737967, 19088743)
+0050  01 23 45 67
+0054  01 02 03 04    arg5 i64 = 72623859790382856
+0058  05 06 07 08
+005c  bf e8 00 00    arg6 f64 = -0.75
+0060  00 00 00 00
+0064  00 00 03 bb    arg7 char 'λ'
+0068  01 02 03 04    arg8 color [1, 2, 3, 4]
+006c  05 06 07 08    arg9 midi [5, 6, 7, 8]
+0070                 arg10 true
+0070                 arg11 false
+0070                 arg12 nil
+0070                 arg13 inf
+0070                 arg14 array start
+0070  00 00 00 07    arg14[0] i32 = 7
+0074  3f 00 00 00    arg14[1] f32 = 0.5
+0078                 arg14 array end
+";
+
+#[test]
+fn test_annotated_dump_golden() {
+    let bytes = golden_bytes();
+    assert_eq!(debug::annotated_dump(&bytes), GOLDEN_DUMP);
+
+    let mut written = String::new();
+    debug::write_annotated_dump(&mut written, &bytes).unwrap();
+    assert_eq!(written, GOLDEN_DUMP);
+}
+
+#[test]
+fn test_annotated_dump_truncated() {
+    let bytes = golden_bytes();
+    assert_eq!(
+        debug::annotated_dump(&bytes[..50]),
+        "\
+0000  23 62 75 6e  bundle
+0004  64 6c 65 00
+0008  01 02 03 04  timetag (16909060, 84281096)
+000c  05 06 07 08
+0010  00 00 00 64  error: bundle element size 100 exceeds the 30 bytes left
+0014  2f 73 65 6c
+0018  66 2f 74 65
+001c  73 74 00 00
+0020  2c 69 66 73
+0024  62 74 68 64
+0028  63 72 6d 54
+002c  46 4e 49 5b
+0030  69 66
+"
+    );
+}
+
+#[test]
+fn test_annotated_dump_invalid() {
+    // Replace the type tag of the string argument
+    let mut bytes = golden_bytes();
+    assert_eq!(bytes[0x23], b's');
+    bytes[0x23] = b'S';
+    let dump = debug::annotated_dump(&bytes);
+    let lines: Vec<&str> = dump.lines().collect();
+    let golden: Vec<&str> = GOLDEN_DUMP.lines().collect();
+    assert_eq!(&lines[..8], &golden[..8]);
+    assert_eq!(
+        lines[8],
+        "0020  2c 69 66 53    tags ',ifSbthdcrmTFNI[if]' + 1 pad"
+    );
+    assert_eq!(&lines[9..15], &golden[9..15]);
+    assert_eq!(lines[15], "003c  6f 73 63 00  error: unknown type tag 'S'");
+    assert_eq!(lines[16], "0040  00 00 00 05");
+    assert_eq!(lines.len(), 15 + (bytes.len() - 0x3c) / 4);
+
+    // Trailing bytes, and data that isn't a packet at all
+    let mut bytes = encoder::encode(&OscPacket::Message(OscMessage::from("/a"))).unwrap();
+    bytes.extend([1, 2]);
+    assert_eq!(
+        debug::annotated_dump(&bytes),
+        "\
+0000  2f 61 00 00  addr '/a' + 2 pad
+0004  2c 00 00 00  tags ',' + 3 pad
+0008  01 02        trailing bytes
+"
+    );
+    assert_eq!(
+        debug::annotated_dump(b"#bun"),
+        "0000  23 62 75 6e  error: invalid bundle tag\n"
+    );
+    assert_eq!(
+        debug::annotated_dump(b""),
+        "0000               error: empty packet\n"
+    );
+}
+
+/// A login message carrying a token, in a bundle.
+fn login_bytes(token: &str) -> Vec<u8> {
+    encoder::encode(&OscPacket::Bundle(OscBundle {
+        timetag: OscTime::from((0, 1)),
+        content: vec![
+            OscPacket::Message(OscMessage {
+                addr: "/auth/login".to_string(),
+                args: vec![
+                    OscType::String("user".to_string()),
+                    OscType::String(token.to_string()),
+                    OscType::Int(7),
+                ],
+            }),
+            OscPacket::Message(OscMessage {
+                addr: "/mixer/name".to_string(),
+                args: vec![OscType::String("main".to_string())],
+            }),
+        ],
+    }))
+    .unwrap()
+}
+
+/// Whether `dump` shows `token`, either as text or as the hex bytes of the start of it.
+fn shows_token(dump: &str, token: &str) -> bool {
+    let hex: Vec<String> = token
+        .bytes()
+        .take(4)
+        .map(|b| format!("{:02x}", b))
+        .collect();
+    dump.contains(token) || dump.contains(&hex.join(" "))
+}
+
+#[test]
+fn test_annotated_dump_redacted() {
+    use rosc::debug::{NoRedaction, Redaction, Redactor};
+
+    struct ReplaceToken;
+    impl Redactor for ReplaceToken {
+        fn redact(&self, _addr: &str, index: usize, arg: &OscType) -> Redaction {
+            match arg {
+                OscType::String(s) if index == 1 && s.starts_with("tok") => {
+                    Redaction::Replace(OscType::Nil)
+                }
+                _ => Redaction::Keep,
+            }
+        }
+    }
+
+    let token = "tok-3f9a1c";
+    let bytes = login_bytes(token);
+    assert!(shows_token(&debug::annotated_dump(&bytes), token));
+
+    // Keeping everything dumps the original bytes
+    assert_eq!(
+        debug::annotated_dump_redacted(&bytes, &NoRedaction),
+        debug::annotated_dump(&bytes)
+    );
+
+    let dump = debug::annotated_dump_redacted(&bytes, &ReplaceToken);
+    assert!(!shows_token(&dump, token));
+    assert!(dump.contains("tags ',sNi'"));
+    assert!(dump.contains("arg0 str 'user'"));
+    assert!(dump.contains("arg1 nil"));
+
+    // Packets that cannot be decoded aren't shown at all
+    let truncated = &bytes[..bytes.len() - 24];
+    let dump = debug::annotated_dump_redacted(truncated, &NoRedaction);
+    assert!(dump.starts_with("error: "));
+    assert!(!shows_token(&dump, token));
+    assert_eq!(dump.lines().count(), 1);
+}
+
+#[test]
+#[cfg(feature = "std")]
+fn test_pattern_redactor() {
+    use rosc::debug::{self, PatternRedactor};
+
+    let token = "tok-3f9a1c";
+    let bytes = login_bytes(token);
+    let redactor = PatternRedactor::new(&["/auth/*"]).unwrap();
+
+    let dump = debug::annotated_dump_redacted(&bytes, &redactor);
+    assert!(!shows_token(&dump, token));
+    assert!(!dump.contains("'user'"));
+    assert!(dump.contains("arg1 str '***'"));
+    assert!(dump.contains("arg2 i32 = 7"));
+    assert!(dump.contains("arg0 str 'main'"));
+
+    let (_, packet) = rosc::decoder::decode_udp(&bytes).unwrap();
+    let redacted = debug::redact_packet(&packet, &redactor);
+    assert!(!format!("{:?}", redacted).contains(token));
+    assert_eq!(
+        redacted,
+        rosc::decoder::decode_udp(&encoder::encode(&redacted).unwrap())
+            .unwrap()
+            .1
+    );
+
+    // Strings in arrays are masked as well
+    let in_array = encoder::encode(&OscPacket::Message(OscMessage {
+        addr: "/auth/session".to_string(),
+        args: vec![
+            OscType::Array(OscArray {
+                content: vec![
+                    OscType::Int(1),
+                    OscType::Array(OscArray {
+                        content: vec![OscType::String(token.to_string())],
+                    }),
+                ],
+            }),
+            OscType::Array(OscArray {
+                content: vec![OscType::Int(2)],
+            }),
+        ],
+    }))
+    .unwrap();
+    assert!(shows_token(&debug::annotated_dump(&in_array), token));
+    let dump = debug::annotated_dump_redacted(&in_array, &redactor);
+    assert!(!shows_token(&dump, token));
+    assert!(dump.contains("arg0[0] i32 = 1"));
+    assert!(dump.contains("arg0[1][0] str '***'"));
+    assert!(dump.contains("arg1[0] i32 = 2"));
+
+    assert!(PatternRedactor::new(&["/auth/[a-"]).is_err());
+}
+
+#[test]
+fn test_redact_packet_debug() {
+    use rosc::debug::{Redaction, Redactor};
+
+    /// Masks strings, and replaces arrays with a copy in which their strings are masked.
+    struct MaskStrings;
+
+    fn mask(arg: &OscType) -> OscType {
+        match arg {
+            OscType::String(_) => OscType::String(debug::MASK.to_string()),
+            OscType::Array(array) => OscType::Array(OscArray {
+                content: array.content.iter().map(mask).collect(),
+            }),
+            _ => arg.clone(),
+        }
+    }
+
+    impl Redactor for MaskStrings {
+        fn redact(&self, _addr: &str, _index: usize, arg: &OscType) -> Redaction {
+            match arg {
+                OscType::String(_) => Redaction::Mask,
+                OscType::Array(_) => Redaction::Replace(mask(arg)),
+                _ => Redaction::Keep,
+            }
+        }
+    }
+
+    let token = "tok-3f9a1c";
+    let (_, login) = rosc::decoder::decode_udp(&login_bytes(token)).unwrap();
+    let packet = OscPacket::Bundle(OscBundle {
+        timetag: OscTime::from((0, 1)),
+        content: vec![
+            login,
+            OscPacket::Message(OscMessage {
+                addr: "/auth/session".to_string(),
+                args: vec![
+                    OscType::Int(1),
+                    OscType::Array(OscArray {
+                        content: vec![
+                            OscType::String(token.to_string()),
+                            OscType::Array(OscArray {
+                                content: vec![OscType::Int(2), OscType::String(token.to_string())],
+                            }),
+                        ],
+                    }),
+                ],
+            }),
+        ],
+    });
+    assert!(format!("{:?}", packet).contains(token));
+
+    let redacted = debug::redact_packet(&packet, &MaskStrings);
+    let shown = format!("{:?}", redacted);
+    assert!(!shown.contains(token));
+    assert!(shown.contains(debug::MASK));
+    assert!(!format!("{:#?}", redacted).contains(token));
+
+    #[cfg(feature = "std")]
+    {
+        let redactor = debug::PatternRedactor::new(&["/auth/*"]).unwrap();
+        let redacted = debug::redact_packet(&packet, &redactor);
+        assert!(!format!("{:?}", redacted).contains(token));
+        assert!(!format!("{:#?}", redacted).contains(token));
+    }
+}
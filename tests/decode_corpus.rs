@@ -0,0 +1,37 @@
+extern crate rosc;
+
+use rosc::decoder;
+
+use std::fs;
+use std::panic;
+use std::path::Path;
+
+/// Decodes every file in `tests/corpus` with all decoder entry points. Decoding may succeed or
+/// fail, but must never panic.
+///
+/// To pin an input found by fuzzing, add it to `tests/corpus` as a raw `.bin` file.
+#[test]
+fn test_decode_corpus_never_panics() {
+    let dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/corpus");
+    let mut files: Vec<_> = fs::read_dir(&dir)
+        .unwrap()
+        .map(|entry| entry.unwrap().path())
+        .filter(|path| path.extension().is_some_and(|ext| ext == "bin"))
+        .collect();
+    files.sort();
+    assert!(!files.is_empty(), "No corpus files in {}", dir.display());
+
+    let panicked: Vec<_> = files
+        .iter()
+        .filter(|path| {
+            let bytes = fs::read(path).unwrap();
+            panic::catch_unwind(|| {
+                let _ = decoder::decode_udp(&bytes);
+                let _ = decoder::decode_tcp(&bytes);
+                let _ = decoder::decode_tcp_vec(&bytes);
+            })
+            .is_err()
+        })
+        .collect();
+    assert!(panicked.is_empty(), "Decoding panicked for {:?}", panicked);
+}
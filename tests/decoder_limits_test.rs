@@ -0,0 +1,311 @@
+//! Tests of decoding limits, using a global allocator that records the largest allocation and
+//! the number of bytes allocated by each thread.
+//!
+//! Every test in this binary shares the allocator, so they must not run concurrently with
+//! anything that allocates a lot.
+
+extern crate rosc;
+
+use rosc::decoder::{self, DecodeOptions};
+use rosc::{
+    encoder, OscArray, OscBundle, OscError, OscMessage, OscMidiMessage, OscPacket, OscTime, OscType,
+};
+
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::cell::Cell;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+struct MaxAllocation;
+
+static MAX_ALLOCATION: AtomicUsize = AtomicUsize::new(0);
+
+thread_local! {
+    /// The number of bytes allocated and not yet freed by this thread. Memory freed by another
+    /// thread than the one allocating it makes this meaningless, which the tests avoid.
+    static LIVE_BYTES: Cell<isize> = const { Cell::new(0) };
+}
+
+fn add_live_bytes(bytes: isize) {
+    // Allocations while the thread is being torn down aren't of interest
+    let _ = LIVE_BYTES.try_with(|live| live.set(live.get() + bytes));
+}
+
+unsafe impl GlobalAlloc for MaxAllocation {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        MAX_ALLOCATION.fetch_max(layout.size(), Ordering::SeqCst);
+        add_live_bytes(layout.size() as isize);
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        add_live_bytes(-(layout.size() as isize));
+        System.dealloc(ptr, layout)
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        MAX_ALLOCATION.fetch_max(new_size, Ordering::SeqCst);
+        add_live_bytes(new_size as isize - layout.size() as isize);
+        System.realloc(ptr, layout, new_size)
+    }
+}
+
+#[global_allocator]
+static ALLOCATOR: MaxAllocation = MaxAllocation;
+
+/// Returns the size of the largest allocation made by `f`.
+fn max_allocation<T, F: FnOnce() -> T>(f: F) -> (T, usize) {
+    MAX_ALLOCATION.store(0, Ordering::SeqCst);
+    let result = f();
+    (result, MAX_ALLOCATION.load(Ordering::SeqCst))
+}
+
+/// Returns the number of bytes allocated by `f` and still held by its result.
+fn retained_bytes<T, F: FnOnce() -> T>(f: F) -> (T, usize) {
+    let before = LIVE_BYTES.with(Cell::get);
+    let result = f();
+    (result, (LIVE_BYTES.with(Cell::get) - before) as usize)
+}
+
+/// A message with `n` `T` tags, which have no data.
+fn many_bools(n: usize) -> Vec<u8> {
+    let mut bytes = encoder::encode_string("/flags");
+    bytes.extend(encoder::encode_string(format!(",{}", "T".repeat(n))));
+    bytes
+}
+
+#[test]
+fn test_max_args() {
+    let bytes = many_bools(100_000);
+    let options = DecodeOptions {
+        max_args: 1000,
+        ..DecodeOptions::default()
+    };
+
+    // Only the type tags themselves are copied before the limit is checked, nothing in
+    // proportion to the number of arguments
+    let (result, max) = max_allocation(|| decoder::decode_udp_with_options(&bytes, &options));
+    match result {
+        Err(OscError::TooManyArgs {
+            count: 100_000,
+            max: 1000,
+        }) => (),
+        other => panic!("Expected TooManyArgs, got {:?}", other),
+    }
+    assert!(max <= bytes.len(), "allocated {} bytes", max);
+
+    // Without the limit, the arguments take much more memory than the message
+    let unlimited = DecodeOptions {
+        max_args: usize::MAX,
+        ..DecodeOptions::default()
+    };
+    let (result, max) = max_allocation(|| decoder::decode_udp_with_options(&bytes, &unlimited));
+    assert_eq!(
+        result.unwrap().1,
+        OscPacket::Message(OscMessage {
+            addr: "/flags".to_string(),
+            args: vec![OscType::Bool(true); 100_000],
+        })
+    );
+    assert!(max >= 100_000 * std::mem::size_of::<OscType>());
+
+    // Elements of arrays count, the arrays themselves don't
+    let msg = OscPacket::Message(OscMessage {
+        addr: "/nested".to_string(),
+        args: vec![
+            OscType::Int(1),
+            OscType::Array(OscArray {
+                content: vec![
+                    OscType::Nil,
+                    OscType::Array(OscArray {
+                        content: vec![OscType::Nil],
+                    }),
+                ],
+            }),
+        ],
+    });
+    let bytes = encoder::encode(&msg).unwrap();
+    let exact = DecodeOptions {
+        max_args: 3,
+        ..DecodeOptions::default()
+    };
+    assert_eq!(
+        decoder::decode_udp_with_options(&bytes, &exact).unwrap().1,
+        msg
+    );
+    let below = DecodeOptions {
+        max_args: 2,
+        ..DecodeOptions::default()
+    };
+    match decoder::decode_udp_with_options(&bytes, &below) {
+        Err(OscError::TooManyArgs { count: 3, max: 2 }) => (),
+        other => panic!("Expected TooManyArgs, got {:?}", other),
+    }
+
+    // Within a bundle, the error isn't swallowed
+    let bundle = encoder::encode(&OscPacket::Bundle(OscBundle {
+        timetag: OscTime::IMMEDIATE,
+        content: vec![OscPacket::Message(OscMessage::from("/a")), msg],
+    }))
+    .unwrap();
+    match decoder::decode_udp_with_options(&bundle, &below) {
+        Err(OscError::TooManyArgs { count: 3, max: 2 }) => (),
+        other => panic!("Expected TooManyArgs, got {:?}", other),
+    }
+}
+
+#[test]
+fn test_default_max_args() {
+    assert_eq!(DecodeOptions::default().max_args, 65536);
+
+    // A million arguments in a 1 MB message are rejected by default, before the arguments would
+    // take dozens of megabytes
+    let bytes = many_bools(1_000_000);
+    let (result, max) = max_allocation(|| decoder::decode_udp(&bytes));
+    match result {
+        Err(OscError::TooManyArgs {
+            count: 1_000_000,
+            max: 65536,
+        }) => (),
+        other => panic!("Expected TooManyArgs, got {:?}", other),
+    }
+    assert!(max <= bytes.len(), "allocated {} bytes", max);
+
+    let bytes = many_bools(65536);
+    assert!(decoder::decode_udp(&bytes).is_ok());
+}
+
+/// Decodes `bytes` with a budget of 0, returning the estimate of the error.
+fn owned_bytes_estimate(bytes: &[u8]) -> usize {
+    let options = DecodeOptions {
+        max_owned_bytes: Some(0),
+        ..DecodeOptions::default()
+    };
+    match decoder::decode_udp_with_options(bytes, &options) {
+        Err(OscError::MemoryBudgetExceeded { budget: 0, needed }) => needed,
+        other => panic!("Expected MemoryBudgetExceeded, got {:?}", other),
+    }
+}
+
+#[test]
+fn test_max_owned_bytes() {
+    // 64 KB of medium strings
+    let msg = OscPacket::Message(OscMessage {
+        addr: "/strings".to_string(),
+        args: vec![OscType::String("x".repeat(60)); 1000],
+    });
+    let bytes = encoder::encode(&msg).unwrap();
+    let needed = owned_bytes_estimate(&bytes);
+    assert_eq!(
+        needed,
+        "/strings".len() + 1000 * (60 + std::mem::size_of::<OscType>())
+    );
+
+    let options = DecodeOptions {
+        max_owned_bytes: Some(needed - 1),
+        ..DecodeOptions::default()
+    };
+    // Only the estimate itself allocates before the budget is checked
+    let (result, max) = max_allocation(|| decoder::decode_udp_with_options(&bytes, &options));
+    match result {
+        Err(OscError::MemoryBudgetExceeded { budget, needed: n }) => {
+            assert_eq!((budget, n), (needed - 1, needed))
+        }
+        other => panic!("Expected MemoryBudgetExceeded, got {:?}", other),
+    }
+    assert!(max < 100, "allocated {} bytes", max);
+
+    let options = DecodeOptions {
+        max_owned_bytes: Some(needed),
+        ..DecodeOptions::default()
+    };
+    assert_eq!(
+        decoder::decode_udp_with_options(&bytes, &options)
+            .unwrap()
+            .1,
+        msg
+    );
+
+    // Within a bundle, the whole packet is rejected
+    let bundle = encoder::encode(&OscPacket::Bundle(OscBundle {
+        timetag: OscTime::IMMEDIATE,
+        content: vec![OscPacket::Message(OscMessage::from("/a")), msg],
+    }))
+    .unwrap();
+    assert_eq!(
+        owned_bytes_estimate(&bundle),
+        needed + "/a".len() + 2 * std::mem::size_of::<OscPacket>()
+    );
+
+    // Invalid packets fail as usual
+    let truncated = &bytes[..bytes.len() - 4];
+    assert_eq!(
+        decoder::decode_udp_with_options(truncated, &options)
+            .unwrap_err()
+            .to_string(),
+        decoder::decode_udp(truncated).unwrap_err().to_string()
+    );
+}
+
+#[test]
+fn test_max_owned_bytes_estimate() {
+    let packets = vec![
+        OscPacket::Message(OscMessage {
+            addr: "/strings".to_string(),
+            args: vec![OscType::String("medium length string".to_string()); 500],
+        }),
+        OscPacket::Message(OscMessage {
+            addr: "/mixer/channel/1/eq/band/3".to_string(),
+            args: vec![
+                OscType::Float(0.5),
+                OscType::Int(3),
+                OscType::String("peak".to_string()),
+                OscType::Bool(true),
+            ],
+        }),
+        OscPacket::Message(OscMessage {
+            addr: "/blobs".to_string(),
+            args: vec![OscType::Blob(vec![7; 300]); 50],
+        }),
+        OscPacket::Message(OscMessage {
+            addr: "/arrays".to_string(),
+            args: vec![
+                OscType::Array(OscArray {
+                    content: vec![OscType::Int(1), OscType::Int(2), OscType::Int(3)],
+                });
+                100
+            ],
+        }),
+        OscPacket::Bundle(OscBundle {
+            timetag: OscTime::from((1, 2)),
+            content: vec![
+                OscPacket::Message(OscMessage {
+                    addr: "/note/on".to_string(),
+                    args: vec![
+                        OscType::Midi(OscMidiMessage {
+                            port: 0,
+                            status: 0x90,
+                            data1: 60,
+                            data2: 100,
+                        }),
+                        OscType::String("piano".to_string()),
+                    ],
+                });
+                40
+            ],
+        }),
+    ];
+
+    for packet in packets {
+        let bytes = encoder::encode(&packet).unwrap();
+        let estimate = owned_bytes_estimate(&bytes);
+        let (decoded, actual) = retained_bytes(|| decoder::decode_udp(&bytes).unwrap().1);
+        assert_eq!(decoded, packet);
+        assert!(
+            estimate <= actual && actual <= 2 * estimate,
+            "estimated {} bytes, actually {} for {:?}",
+            estimate,
+            actual,
+            packet
+        );
+    }
+}
@@ -4,6 +4,7 @@ extern crate rosc;
 use byteorder::{BigEndian, ByteOrder};
 use std::mem;
 
+use rosc::signature::{CoercionMode, TypeSignature};
 use rosc::{decoder, encoder, OscBundle, OscPacket, OscTime, OscType};
 
 #[test]
@@ -74,6 +75,31 @@ fn test_decode_udp_empty_bundle() {
     }
 }
 
+#[test]
+fn test_decode_udp_nested_empty_bundles() {
+    let empty = OscPacket::Bundle(OscBundle {
+        timetag: OscTime::from((1, 0)),
+        content: vec![],
+    });
+    let packet = OscPacket::Bundle(OscBundle {
+        timetag: OscTime::from((4, 2)),
+        content: vec![
+            empty.clone(),
+            OscPacket::Bundle(OscBundle {
+                timetag: OscTime::from((2, 0)),
+                content: vec![empty.clone()],
+            }),
+            OscPacket::Message(rosc::OscMessage::from("/after/empty")),
+            empty,
+        ],
+    });
+
+    let encoded = encoder::encode(&packet).unwrap();
+    let (rest, decoded) = decoder::decode_udp(&encoded).unwrap();
+    assert!(rest.is_empty());
+    assert_eq!(packet, decoded);
+}
+
 #[test]
 fn test_decode_udp_args() {
     // /another/valid/address/123 ,fdih 3.1415 3.14159265359 12345678i32
@@ -160,3 +186,834 @@ fn test_decode_udp_args() {
         _ => panic!("Expected an OSC message!"),
     }
 }
+
+#[test]
+fn test_decode_owned() {
+    let packet = OscPacket::Message(rosc::OscMessage {
+        addr: "/some/blob".to_string(),
+        args: vec![OscType::Blob(vec![1, 2, 3, 4, 5]), OscType::Int(42)],
+    });
+    let bytes = encoder::encode(&packet).unwrap();
+    assert_eq!(decoder::decode_owned(bytes).unwrap(), packet);
+
+    assert!(decoder::decode_owned(vec![]).is_err());
+}
+
+#[test]
+fn test_decode_udp_aligned_blob() {
+    // A blob whose size is a multiple of 4 must not be followed by any padding
+    for size in 0..=12u8 {
+        let blob: Vec<u8> = (0..size).collect();
+        let packet = OscPacket::Message(rosc::OscMessage {
+            addr: "/blob".to_string(),
+            args: vec![OscType::Blob(blob), OscType::Int(7)],
+        });
+        let bytes = encoder::encode(&packet).unwrap();
+        let (remainder, decoded) = decoder::decode_udp(&bytes).unwrap();
+        assert!(remainder.is_empty());
+        assert_eq!(decoded, packet);
+    }
+}
+
+fn nested_bundles(depth: usize) -> OscPacket {
+    let mut packet = OscPacket::Bundle(OscBundle {
+        timetag: OscTime::from((0, 1)),
+        content: vec![],
+    });
+    for _ in 1..depth {
+        packet = OscPacket::Bundle(OscBundle {
+            timetag: OscTime::from((0, 1)),
+            content: vec![packet],
+        });
+    }
+    packet
+}
+
+#[test]
+fn test_decode_udp_max_bundle_depth() {
+    let packet = nested_bundles(decoder::MAX_BUNDLE_DEPTH);
+    let bytes = encoder::encode(&packet).unwrap();
+    assert_eq!(decoder::decode_udp(&bytes).unwrap().1, packet);
+
+    let bytes = encoder::encode(&nested_bundles(decoder::MAX_BUNDLE_DEPTH + 1)).unwrap();
+    match decoder::decode_udp(&bytes) {
+        Err(rosc::OscError::BadBundle(_)) => (),
+        other => panic!("Expected a BadBundle error, got {:?}", other),
+    }
+}
+
+#[test]
+fn test_decode_udp_empty_address() {
+    let mut bytes = vec![0u8; 4];
+    bytes.extend(encoder::encode_string(",i"));
+    bytes.extend(42i32.to_be_bytes());
+
+    // Strict by default
+    match decoder::decode_udp(&bytes) {
+        Err(rosc::OscError::BadAddress(_)) => (),
+        other => panic!("Expected a BadAddress error, got {:?}", other),
+    }
+    let strict = decoder::DecodeOptions {
+        require_leading_slash: true,
+        ..decoder::DecodeOptions::default()
+    };
+    assert!(decoder::decode_udp_with_options(&bytes, &strict).is_err());
+
+    let lenient = decoder::DecodeOptions {
+        require_leading_slash: false,
+        ..decoder::DecodeOptions::default()
+    };
+    let (rest, packet) = decoder::decode_udp_with_options(&bytes, &lenient).unwrap();
+    assert!(rest.is_empty());
+    assert_eq!(
+        packet,
+        OscPacket::Message(rosc::OscMessage {
+            addr: String::new(),
+            args: vec![OscType::Int(42)],
+        })
+    );
+
+    // Options apply to bundle elements and TCP framing as well
+    let mut element = bytes.clone();
+    let mut bundle = encoder::encode(&OscPacket::Bundle(OscBundle {
+        timetag: OscTime::from((1, 2)),
+        content: vec![],
+    }))
+    .unwrap();
+    bundle.extend((element.len() as u32).to_be_bytes());
+    bundle.append(&mut element);
+    let mut framed = (bundle.len() as u32).to_be_bytes().to_vec();
+    framed.extend(&bundle);
+
+    match decoder::decode_tcp_vec_with_options(&framed, &lenient)
+        .unwrap()
+        .1
+        .as_slice()
+    {
+        [OscPacket::Bundle(decoded)] => assert_eq!(decoded.content, vec![packet]),
+        other => panic!("Expected a single bundle, got {:?}", other),
+    }
+}
+
+#[test]
+fn test_decode_udp_symbol_not_mistaken_for_string() {
+    // There is no symbol type yet. A symbol argument must be rejected rather than silently decoded
+    // as a string, which a forwarding proxy would then re-encode with a different type tag.
+    let mut bytes = encoder::encode_string("/symbol");
+    bytes.extend(encoder::encode_string(",sS"));
+    bytes.extend(encoder::encode_string("string"));
+    bytes.extend(encoder::encode_string("symbol"));
+
+    match decoder::decode_udp(&bytes) {
+        Err(rosc::OscError::BadArg(_)) => (),
+        other => panic!("Expected a BadArg error, got {:?}", other),
+    }
+}
+
+#[test]
+fn test_decode_signature_and_single_arg() {
+    let args = vec![
+        OscType::String("variable length".to_string()),
+        OscType::Blob(vec![1, 2, 3, 4, 5]),
+        OscType::Array(rosc::OscArray {
+            content: vec![
+                OscType::Bool(true),
+                OscType::Long(7),
+                OscType::String("x".into()),
+            ],
+        }),
+        OscType::Double(2.5),
+        OscType::Nil,
+        OscType::Int(-9),
+    ];
+    let packet = OscPacket::Message(rosc::OscMessage {
+        addr: "/six/args".to_string(),
+        args: args.clone(),
+    });
+    let bytes = encoder::encode(&packet).unwrap();
+
+    let (signature, offset) = decoder::decode_signature(&bytes).unwrap();
+    assert_eq!(signature.address, "/six/args");
+    assert_eq!(signature.type_tags.as_str(), "sb[Ths]dNi");
+    assert_eq!(
+        signature.arg_type_tags(),
+        vec!["s", "b", "[Ths]", "d", "N", "i"]
+    );
+    assert_eq!(offset, 12 + 12);
+    let expected = TypeSignature::parse("sb[Ths]dNi").unwrap();
+    assert!(signature
+        .type_tags
+        .is_compatible_with(&expected, CoercionMode::Exact));
+
+    let full = match decoder::decode_udp(&bytes).unwrap().1 {
+        OscPacket::Message(msg) => msg.args,
+        _ => panic!("Expected an OscMessage!"),
+    };
+    assert_eq!(
+        decoder::decode_arg_at(&bytes, &signature, 3).unwrap(),
+        full[3]
+    );
+    for (n, arg) in args.iter().enumerate() {
+        assert_eq!(&decoder::decode_arg_at(&bytes, &signature, n).unwrap(), arg);
+    }
+    assert!(decoder::decode_arg_at(&bytes, &signature, 6).is_err());
+
+    // Truncated argument data
+    assert!(decoder::decode_arg_at(&bytes[..bytes.len() - 2], &signature, 5).is_err());
+}
+
+#[test]
+fn test_decode_signature_invalid() {
+    let message = |tags: &str| {
+        let mut bytes = encoder::encode_string("/addr");
+        bytes.extend(encoder::encode_string(tags));
+        bytes
+    };
+
+    assert!(decoder::decode_signature(&message(",i[f")).is_err());
+    assert!(decoder::decode_signature(&message(",i]")).is_err());
+    assert!(decoder::decode_signature(&message("i")).is_err());
+    assert!(decoder::decode_signature(&message(",[[i]f]")).is_ok());
+
+    let bundle = encoder::encode(&OscPacket::Bundle(OscBundle {
+        timetag: OscTime::from((0, 1)),
+        content: vec![],
+    }))
+    .unwrap();
+    assert!(decoder::decode_signature(&bundle).is_err());
+}
+
+#[test]
+fn test_decode_udp_exact_bundle_tag() {
+    let valid = encoder::encode(&OscPacket::Bundle(OscBundle {
+        timetag: OscTime::from((1, 2)),
+        content: vec![],
+    }))
+    .unwrap();
+    assert_eq!(&valid[..8], b"#bundle\0");
+    assert!(decoder::decode_udp(&valid).is_ok());
+
+    // Replace the null terminator of the tag, or the tag itself
+    for tag in [&b"#bundlex"[..], b"#bundl\0\0", b"#Bundle\0", b"bundle\0\0"] {
+        let mut bytes = valid.clone();
+        bytes[..8].copy_from_slice(tag);
+        if let Ok((_, OscPacket::Bundle(_))) = decoder::decode_udp(&bytes) {
+            panic!("{:?} was decoded as a bundle", tag);
+        }
+    }
+
+    // "#bundlex" followed by a null terminator is a single, invalid string
+    let mut bytes = encoder::encode_string("#bundlex");
+    bytes.extend([0, 0, 0, 1, 0, 0, 0, 2]);
+    assert!(decoder::decode_udp(&bytes).is_err());
+    assert!(decoder::decode_udp(b"#bundle").is_err());
+}
+
+#[test]
+fn test_message_view() {
+    let msg = rosc::OscMessage {
+        addr: "/mixed".to_string(),
+        args: vec![
+            OscType::Int(1),
+            OscType::String("seven".to_string()),
+            OscType::Double(0.25),
+            OscType::Blob(vec![9; 6]),
+            OscType::Array(rosc::OscArray {
+                content: vec![OscType::String("ab".into()), OscType::Float(1.5)],
+            }),
+            OscType::Bool(false),
+            OscType::Long(-3),
+        ],
+    };
+    let bytes = encoder::encode(&OscPacket::Message(msg.clone())).unwrap();
+
+    let view = decoder::MessageView::new(&bytes).unwrap();
+    assert_eq!(view.addr(), "/mixed");
+    assert_eq!(view.tags(), "isdb[sf]Fh");
+    assert_eq!(view.arg_count(), 7);
+
+    // Out of order and repeated access
+    for &n in &[6, 3, 0, 4, 6, 1, 5, 2, 3] {
+        assert_eq!(view.arg(n).unwrap(), msg.args[n]);
+    }
+    assert!(view.arg(7).is_err());
+    assert_eq!(view.to_message().unwrap(), msg);
+
+    // Truncated argument data is only detected when reaching it
+    let view = decoder::MessageView::new(&bytes[..bytes.len() - 4]).unwrap();
+    assert_eq!(view.arg(3).unwrap(), msg.args[3]);
+    assert!(view.arg(6).is_err());
+    assert!(view.to_message().is_err());
+
+    assert!(decoder::MessageView::new(&encoder::encode_string("/no/tags")).is_err());
+}
+
+#[test]
+fn test_numeric_byte_order_little() {
+    use rosc::decoder::DecodeOptions;
+    use rosc::encoder::EncodeOptions;
+    use rosc::{NumericByteOrder, OscArray, OscMessage};
+
+    let packet = OscPacket::Bundle(OscBundle {
+        timetag: OscTime::from((1, 2)),
+        content: vec![OscPacket::Message(OscMessage {
+            addr: "/firmware".to_string(),
+            args: vec![
+                OscType::Int(0x0102_0304),
+                OscType::Long(0x0102_0304_0506_0708),
+                OscType::Float(f32::from_bits(0x0102_0304)),
+                OscType::Double(f64::from_bits(0x0102_0304_0506_0708)),
+                OscType::Time(OscTime::from((0x0102_0304, 0x0506_0708))),
+                OscType::Char('a'),
+                OscType::Array(OscArray {
+                    content: vec![OscType::Int(0x0102_0304), "s".into()],
+                }),
+            ],
+        })],
+    });
+
+    let little_encode = EncodeOptions {
+        numeric_byte_order: NumericByteOrder::Little,
+        ..EncodeOptions::default()
+    };
+    let little_decode = DecodeOptions {
+        numeric_byte_order: NumericByteOrder::Little,
+        ..DecodeOptions::default()
+    };
+    let bytes = encoder::encode_with_options(&packet, &little_encode).unwrap();
+
+    // The bundle's own time tag and the sizes stay big-endian
+    assert_eq!(&bytes[8..16], &[0, 0, 0, 1, 0, 0, 0, 2]);
+    let data = 20 + 12 + 12;
+    assert_eq!(&bytes[data..data + 4], &[4, 3, 2, 1]);
+    assert_eq!(&bytes[data + 4..data + 12], &[8, 7, 6, 5, 4, 3, 2, 1]);
+    assert_eq!(&bytes[data + 12..data + 16], &[4, 3, 2, 1]);
+    assert_eq!(&bytes[data + 16..data + 24], &[8, 7, 6, 5, 4, 3, 2, 1]);
+    assert_eq!(&bytes[data + 24..data + 32], &[4, 3, 2, 1, 8, 7, 6, 5]);
+    assert_eq!(&bytes[data + 32..data + 36], &[0, 0, 0, b'a']);
+    assert_eq!(&bytes[data + 36..data + 40], &[4, 3, 2, 1]);
+    assert_eq!(&bytes[data + 40..], b"s\0\0\0");
+
+    let decoded = decoder::decode_udp_with_options(&bytes, &little_decode)
+        .unwrap()
+        .1;
+    assert_eq!(decoded, packet);
+
+    // By default, the same bytes are read as big-endian
+    assert_ne!(decoder::decode_udp(&bytes).unwrap().1, packet);
+    let default_bytes = encoder::encode(&packet).unwrap();
+    assert_eq!(
+        encoder::encode_with_options(&packet, &EncodeOptions::default()).unwrap(),
+        default_bytes
+    );
+    assert_eq!(
+        DecodeOptions::default().numeric_byte_order,
+        NumericByteOrder::Big
+    );
+    assert_eq!(
+        decoder::decode_udp_with_options(&default_bytes, &DecodeOptions::default())
+            .unwrap()
+            .1,
+        packet
+    );
+}
+
+#[test]
+fn test_timetag_as_arg() {
+    use rosc::decoder::DecodeOptions;
+    use rosc::OscMessage;
+
+    let outer = OscTime::from((10, 0));
+    let inner = OscTime::from((5, 0));
+    let note = |args: Vec<OscType>| {
+        OscPacket::Message(OscMessage {
+            addr: "/note".to_string(),
+            args,
+        })
+    };
+    let packet = OscPacket::Bundle(OscBundle {
+        timetag: outer,
+        content: vec![
+            note(vec![OscType::Int(60)]),
+            OscPacket::Bundle(OscBundle {
+                timetag: inner,
+                content: vec![note(vec![])],
+            }),
+        ],
+    });
+    let bytes = encoder::encode(&packet).unwrap();
+    let options = DecodeOptions {
+        timetag_as_arg: true,
+        ..DecodeOptions::default()
+    };
+
+    assert_eq!(
+        decoder::decode_udp_with_options(&bytes, &options)
+            .unwrap()
+            .1,
+        OscPacket::Bundle(OscBundle {
+            timetag: outer,
+            content: vec![
+                note(vec![OscType::Time(outer), OscType::Int(60)]),
+                OscPacket::Bundle(OscBundle {
+                    timetag: inner,
+                    content: vec![note(vec![OscType::Time(inner)])],
+                }),
+            ],
+        })
+    );
+    // Disabled by default
+    assert_eq!(decoder::decode_udp(&bytes).unwrap().1, packet);
+
+    // Messages outside of bundles are unchanged
+    let message = note(vec![OscType::Int(60)]);
+    let bytes = encoder::encode(&message).unwrap();
+    assert_eq!(
+        decoder::decode_udp_with_options(&bytes, &options)
+            .unwrap()
+            .1,
+        message
+    );
+
+    // The inserted argument doesn't count towards the limits
+    let limited = DecodeOptions {
+        max_args: 0,
+        allowed_type_tags: Some(String::new()),
+        strict_padding: true,
+        ..options
+    };
+    let empty = OscPacket::Bundle(OscBundle {
+        timetag: outer,
+        content: vec![note(vec![])],
+    });
+    let bytes = encoder::encode(&empty).unwrap();
+    assert_eq!(
+        decoder::decode_udp_with_options(&bytes, &limited)
+            .unwrap()
+            .1,
+        OscPacket::Bundle(OscBundle {
+            timetag: outer,
+            content: vec![note(vec![OscType::Time(outer)])],
+        })
+    );
+}
+
+#[test]
+fn test_bundle_stats() {
+    let message = |addr: &str| OscPacket::Message(rosc::OscMessage::from(addr));
+    let packet = OscPacket::Bundle(OscBundle {
+        timetag: OscTime::from((1, 0)),
+        content: vec![
+            message("/a"),
+            OscPacket::Bundle(OscBundle {
+                timetag: OscTime::from((2, 0)),
+                content: vec![message("/b"), nested_bundles(3)],
+            }),
+            message("/c"),
+        ],
+    });
+    let bytes = encoder::encode(&packet).unwrap();
+
+    let stats = decoder::bundle_stats(&bytes).unwrap();
+    assert_eq!(stats.element_count, 3);
+    // The nested bundle: header, message "/b" and three nested empty bundles
+    assert_eq!(
+        stats.max_element_size,
+        16 + (4 + 8) + (4 + 16 + 4 + 16 + 4 + 16)
+    );
+    assert_eq!(stats.total_size, bytes.len());
+    assert_eq!(stats.depth_estimate, 5);
+    assert!(stats.consistent);
+
+    // An empty bundle
+    let empty = encoder::encode(&nested_bundles(1)).unwrap();
+    let stats = decoder::bundle_stats(&empty).unwrap();
+    assert_eq!((stats.element_count, stats.max_element_size), (0, 0));
+    assert_eq!((stats.total_size, stats.depth_estimate), (16, 1));
+
+    // Deep nesting is only examined up to the limit
+    let deep = encoder::encode(&nested_bundles(decoder::MAX_BUNDLE_DEPTH + 10)).unwrap();
+    let stats = decoder::bundle_stats(&deep).unwrap();
+    assert_eq!(stats.depth_estimate, decoder::MAX_BUNDLE_DEPTH + 1);
+    assert!(stats.consistent);
+}
+
+#[test]
+fn test_bundle_stats_inconsistent() {
+    let packet = OscPacket::Bundle(OscBundle {
+        timetag: OscTime::from((1, 0)),
+        content: vec![OscPacket::Message(rosc::OscMessage::from("/first"))],
+    });
+    let bytes = encoder::encode(&packet).unwrap();
+
+    // The size of the only element claims more bytes than there are
+    let mut lying = bytes.clone();
+    BigEndian::write_u32(&mut lying[16..20], 1000);
+    let stats = decoder::bundle_stats(&lying).unwrap();
+    assert_eq!(stats.element_count, 1);
+    assert_eq!(stats.max_element_size, bytes.len() - 20);
+    assert_eq!(stats.total_size, bytes.len());
+    assert!(!stats.consistent);
+
+    // Unaligned size, and an input ending within the next size field
+    let mut unaligned = bytes.clone();
+    BigEndian::write_u32(&mut unaligned[16..20], 7);
+    assert!(!decoder::bundle_stats(&unaligned).unwrap().consistent);
+    let mut truncated = bytes.clone();
+    truncated.extend([0, 0]);
+    let stats = decoder::bundle_stats(&truncated).unwrap();
+    assert_eq!(stats.element_count, 1);
+    assert!(!stats.consistent);
+    assert!(!decoder::bundle_stats(&bytes[..12]).unwrap().consistent);
+
+    // A message is a single element without any bundles, and empty input is an error
+    let message = encoder::encode(&OscPacket::Message(rosc::OscMessage::from("/m"))).unwrap();
+    let stats = decoder::bundle_stats(&message).unwrap();
+    assert_eq!(
+        stats,
+        decoder::BundleStats {
+            element_count: 1,
+            max_element_size: message.len(),
+            total_size: message.len(),
+            depth_estimate: 0,
+            consistent: true,
+        }
+    );
+    assert!(decoder::bundle_stats(&[]).is_err());
+}
+
+#[test]
+fn test_decode_udp_truncated_bundle_needs_more_data() {
+    let packet = OscPacket::Bundle(OscBundle {
+        timetag: OscTime::from((1, 0)),
+        content: vec![
+            OscPacket::Message(rosc::OscMessage::from("/first")),
+            OscPacket::Bundle(OscBundle {
+                timetag: OscTime::from((2, 0)),
+                content: vec![OscPacket::Message(rosc::OscMessage::from("/second"))],
+            }),
+        ],
+    });
+    let bytes = encoder::encode(&packet).unwrap();
+    assert_eq!(decoder::decode_udp(&bytes).unwrap().1, packet);
+
+    // Within the last element of the nested bundle
+    match decoder::decode_udp(&bytes[..bytes.len() - 6]) {
+        Err(rosc::OscError::NeedMoreData { needed }) => assert_eq!(needed, 6),
+        other => panic!("Expected NeedMoreData, got {:?}", other),
+    }
+    // Within the size of the second element
+    match decoder::decode_udp(&bytes[..16 + 4 + 12 + 1]) {
+        Err(rosc::OscError::NeedMoreData { needed }) => assert_eq!(needed, 3),
+        other => panic!("Expected NeedMoreData, got {:?}", other),
+    }
+
+    // Any truncation within an element is reported the same way
+    for len in 21..bytes.len() {
+        match decoder::decode_udp(&bytes[..len]) {
+            Err(rosc::OscError::NeedMoreData { needed }) => assert!(needed > 0),
+            Ok((_, OscPacket::Bundle(bundle))) if len == 16 + 4 + 12 => {
+                assert_eq!(bundle.content.len(), 1)
+            }
+            other => panic!("Unexpected result for {} bytes: {:?}", len, other),
+        }
+    }
+}
+
+#[test]
+fn test_decode_udp_zero_length_bundle_element() {
+    let message = encoder::encode(&OscPacket::Message(rosc::OscMessage::from("/after"))).unwrap();
+    let mut bytes = b"#bundle\0\0\0\0\0\0\0\0\x01".to_vec();
+    bytes.extend_from_slice(&[0, 0, 0, 0]);
+    bytes.extend((message.len() as u32).to_be_bytes());
+    bytes.extend_from_slice(&message);
+
+    match decoder::decode_udp(&bytes) {
+        Err(rosc::OscError::BadBundle(_)) => (),
+        other => panic!("Expected BadBundle, got {:?}", other),
+    }
+    // Also within a nested bundle, and as the only element
+    let mut outer = b"#bundle\0\0\0\0\0\0\0\0\x01".to_vec();
+    outer.extend((bytes.len() as u32).to_be_bytes());
+    outer.extend_from_slice(&bytes);
+    assert!(decoder::decode_udp(&outer).is_err());
+    assert!(decoder::decode_udp(&bytes[..20]).is_err());
+
+    struct Ignore;
+    impl decoder::DecodeSink for Ignore {
+        fn on_message_start(&mut self, _addr: &str) {}
+        fn on_arg(&mut self, _arg: rosc::OscTypeRef) {}
+        fn on_array_start(&mut self) {}
+        fn on_array_end(&mut self) {}
+        fn on_message_end(&mut self) {}
+        fn on_bundle_start(&mut self, _timetag: OscTime) {}
+        fn on_bundle_end(&mut self) {}
+    }
+    match decoder::decode_to_sink(&outer, &mut Ignore) {
+        Err(rosc::OscError::BadBundle(_)) => (),
+        other => panic!("Expected BadBundle, got {:?}", other),
+    }
+
+    let stats = decoder::bundle_stats(&bytes).unwrap();
+    assert_eq!(stats.element_count, 2);
+    assert!(!stats.consistent);
+}
+
+#[test]
+fn test_decode_framed_into_reused_vec() {
+    fn framed(packets: &[OscPacket]) -> Vec<u8> {
+        let mut bytes = vec![];
+        for packet in packets {
+            let frame = encoder::encode(packet).unwrap();
+            bytes.extend_from_slice(&(frame.len() as u32).to_be_bytes());
+            bytes.extend_from_slice(&frame);
+        }
+        bytes
+    }
+    let message = |addr: &str| OscPacket::Message(rosc::OscMessage::from(addr));
+
+    let mut out = Vec::new();
+    let first = vec![message("/a"), message("/b"), message("/c")];
+    assert_eq!(
+        decoder::decode_framed_into(&framed(&first), &mut out).unwrap(),
+        3
+    );
+    assert_eq!(out, first);
+    let capacity = out.capacity();
+
+    let second = vec![message("/d")];
+    assert_eq!(
+        decoder::decode_framed_into(&framed(&second), &mut out).unwrap(),
+        1
+    );
+    assert_eq!(out, second);
+    assert_eq!(out.capacity(), capacity);
+
+    // An incomplete trailing frame is left undecoded
+    let mut bytes = framed(&first);
+    bytes.truncate(bytes.len() - 6);
+    assert_eq!(decoder::decode_framed_into(&bytes, &mut out).unwrap(), 2);
+    assert_eq!(out, first[..2]);
+}
+
+#[test]
+fn test_decode_control_packets() {
+    let control_tag = |bytes: &[u8]| match decoder::decode_udp(bytes) {
+        Err(rosc::OscError::UnsupportedControlPacket(tag)) => tag,
+        other => panic!("unexpected result {:?}", other),
+    };
+
+    let mut time = encoder::encode_string("#time");
+    time.extend_from_slice(&[0, 0, 0, 1, 0, 0, 0, 2]);
+    assert_eq!(control_tag(&time), "#time");
+    assert_eq!(control_tag(b"#garbage"), "#garbage");
+    assert_eq!(control_tag(b"#bundl"), "#bundl");
+    assert_eq!(control_tag(&[b'#', 0xff, 0]), "#\u{fffd}");
+    match decoder::peek_address(&time) {
+        Err(rosc::OscError::UnsupportedControlPacket(tag)) => assert_eq!(tag, "#time"),
+        other => panic!("unexpected result {:?}", other),
+    }
+
+    // Bundles still take the normal path
+    let bundle = OscPacket::Bundle(OscBundle {
+        timetag: OscTime::from((1, 2)),
+        content: vec![],
+    });
+    let bytes = encoder::encode(&bundle).unwrap();
+    assert_eq!(decoder::decode_udp(&bytes).unwrap().1, bundle);
+}
+
+#[test]
+fn test_decode_message_into_reuses_allocations() {
+    let long = rosc::OscMessage {
+        addr: "/a/rather/long/address/for/the/first/message".to_string(),
+        args: vec![
+            OscType::Int(1),
+            OscType::Array(vec![2i32, 3].into_iter().collect()),
+            OscType::String("four".to_string()),
+            OscType::Bool(true),
+        ],
+    };
+    let short = rosc::OscMessage {
+        addr: "/b".to_string(),
+        args: vec![OscType::Float(0.5)],
+    };
+
+    let mut msg = rosc::OscMessage::from("/");
+    let bytes = encoder::encode(&OscPacket::Message(long.clone())).unwrap();
+    decoder::decode_message_into(&bytes, &mut msg).unwrap();
+    assert_eq!(msg, long);
+    let (addr_ptr, addr_capacity) = (msg.addr.as_ptr(), msg.addr.capacity());
+    let (args_ptr, args_capacity) = (msg.args.as_ptr(), msg.args.capacity());
+
+    let bytes = encoder::encode(&OscPacket::Message(short.clone())).unwrap();
+    decoder::decode_message_into(&bytes, &mut msg).unwrap();
+    assert_eq!(msg, short);
+    assert_eq!(
+        (msg.addr.as_ptr(), msg.addr.capacity()),
+        (addr_ptr, addr_capacity)
+    );
+    assert_eq!(
+        (msg.args.as_ptr(), msg.args.capacity()),
+        (args_ptr, args_capacity)
+    );
+
+    let bundle = encoder::encode(&OscPacket::Bundle(OscBundle {
+        timetag: OscTime::from((0, 1)),
+        content: vec![],
+    }))
+    .unwrap();
+    assert!(decoder::decode_message_into(&bundle, &mut msg).is_err());
+}
+
+#[test]
+fn test_resync() {
+    let msg = encoder::encode(&OscPacket::Message(rosc::OscMessage::from("/a/b"))).unwrap();
+    let bundle = encoder::encode(&OscPacket::Bundle(OscBundle {
+        timetag: OscTime::from((0, 1)),
+        content: vec![],
+    }))
+    .unwrap();
+
+    assert_eq!(decoder::resync(&msg, 0), Some(0));
+    // The second slash of the address isn't followed by type tags
+    assert_eq!(decoder::resync(&msg, 1), None);
+    assert_eq!(decoder::resync(&bundle, 0), Some(0));
+    assert_eq!(decoder::resync(&bundle, 1), None);
+
+    let mut bytes = b"/x\0\0/\x01\xff#bun#xyz".to_vec();
+    let start = bytes.len();
+    bytes.extend_from_slice(&bundle);
+    assert_eq!(decoder::resync(&bytes, 0), Some(start));
+
+    // Candidates cut off at the end are returned
+    assert_eq!(decoder::resync(b"xx#bun", 0), Some(2));
+    assert_eq!(decoder::resync(b"xx/abc", 0), Some(2));
+    assert_eq!(decoder::resync(b"xx/abc\0\0", 0), Some(2));
+    assert_eq!(decoder::resync(b"xx/abc\0\0s", 0), None);
+}
+
+#[test]
+fn test_lenient_reader_recovers_after_garbage() {
+    fn frame(packet: &OscPacket) -> Vec<u8> {
+        let bytes = encoder::encode(packet).unwrap();
+        let mut frame = (bytes.len() as u32).to_be_bytes().to_vec();
+        frame.extend(bytes);
+        frame
+    }
+
+    let packets: Vec<OscPacket> = (0..4)
+        .map(|i| {
+            OscPacket::Message(rosc::OscMessage {
+                addr: format!("/track/{}", i),
+                args: vec![OscType::Int(i)],
+            })
+        })
+        .collect();
+    let bundle = OscPacket::Bundle(OscBundle {
+        timetag: OscTime::from((0, 1)),
+        content: vec![packets[0].clone()],
+    });
+
+    let mut stream = frame(&packets[0]);
+    // Garbage between frames
+    stream.extend_from_slice(b"\x00\x13garbage/with/slashes#bund");
+    stream.extend(frame(&packets[1]));
+    // A frame whose packet is corrupt
+    let mut corrupt = frame(&packets[2]);
+    let len = corrupt.len();
+    assert_eq!(&corrupt[len - 8..len - 6], b",i");
+    corrupt[len - 7] = b'q';
+    stream.extend(corrupt);
+    stream.extend(frame(&bundle));
+    // A length prefix that lies, followed by a valid frame
+    stream.extend_from_slice(&[0, 0, 0xff, 0xf0]);
+    stream.extend(frame(&packets[3]));
+
+    // Feed the stream in small chunks to exercise waiting for more data
+    let mut reader = decoder::LenientReader::new(1024);
+    let mut decoded = vec![];
+    for chunk in stream.chunks(3) {
+        reader.push(chunk);
+        while let Some(packet) = reader.next_packet() {
+            decoded.push(packet);
+        }
+    }
+
+    assert_eq!(
+        decoded,
+        vec![
+            packets[0].clone(),
+            packets[1].clone(),
+            bundle,
+            packets[3].clone()
+        ]
+    );
+    assert!(reader.skipped_bytes() > 0);
+    assert!(reader.resyncs() > 0);
+}
+
+#[test]
+fn test_decode_exact() {
+    use rosc::ExactnessError;
+
+    let clean = OscPacket::Bundle(OscBundle {
+        timetag: OscTime::from((0, 1)),
+        content: vec![OscPacket::Message(rosc::OscMessage {
+            addr: "/mixer/level".to_string(),
+            args: vec![
+                OscType::Float(0.5),
+                OscType::String("abc".to_string()),
+                OscType::Array(rosc::OscArray {
+                    content: vec![OscType::Int(1), OscType::Bool(true)],
+                }),
+                OscType::Blob(vec![1, 2, 3]),
+            ],
+        })],
+    });
+    let bytes = encoder::encode(&clean).unwrap();
+    assert_eq!(decoder::decode_exact(&bytes).unwrap(), clean);
+
+    // Dirty padding of the blob, which is the last argument
+    let mut dirty = bytes.clone();
+    let blob_padding = dirty.len() - 1;
+    assert_eq!(dirty[blob_padding], 0);
+    dirty[blob_padding] = 0xaa;
+    assert_eq!(decoder::decode_udp(&dirty).unwrap().1, clean);
+    match decoder::decode_exact(&dirty) {
+        Err(ExactnessError::Mismatch { offset }) => assert_eq!(offset, blob_padding),
+        other => panic!("Expected a mismatch, got {:?}", other),
+    }
+
+    // Trailing bytes, which after a bundle read as an empty element instead
+    let message = encoder::encode(&OscPacket::Message(rosc::OscMessage::from("/m"))).unwrap();
+    let mut trailing = message.clone();
+    trailing.extend_from_slice(&[0, 0, 0, 0]);
+    match decoder::decode_exact(&trailing) {
+        Err(ExactnessError::Mismatch { offset }) => assert_eq!(offset, message.len()),
+        other => panic!("Expected a mismatch, got {:?}", other),
+    }
+    let mut trailing = bytes.clone();
+    trailing.extend_from_slice(&[0, 0, 0, 0]);
+    match decoder::decode_exact(&trailing) {
+        Err(ExactnessError::Invalid(_)) => (),
+        other => panic!("Expected an error, got {:?}", other),
+    }
+
+    match decoder::decode_exact(&[]) {
+        Err(ExactnessError::Invalid(_)) => (),
+        other => panic!("Expected an error, got {:?}", other),
+    }
+}
+
+#[test]
+fn test_decode_udp_unterminated_array() {
+    // Previously decoded as the elements of the array alone, dropping the first argument
+    let mut bytes = encoder::encode_string("/a");
+    bytes.extend(encoder::encode_string(",i[i"));
+    bytes.extend([0, 0, 0, 1, 0, 0, 0, 2]);
+    match decoder::decode_udp(&bytes) {
+        Err(rosc::OscError::BadMessage(_)) => (),
+        other => panic!("Expected BadMessage, got {:?}", other),
+    }
+}
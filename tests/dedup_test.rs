@@ -0,0 +1,108 @@
+#![cfg(feature = "std")]
+
+extern crate rosc;
+
+use rosc::dedup::Window;
+use rosc::{OscBundle, OscMessage, OscPacket, OscType};
+use std::net::SocketAddr;
+use std::time::{Duration, Instant};
+
+fn fader(value: f32) -> OscPacket {
+    OscPacket::Message(OscMessage {
+        addr: "/fader/1".to_string(),
+        args: vec![OscType::Float(value)],
+    })
+}
+
+#[test]
+fn test_dedup_window() {
+    let mut window = Window::new(Duration::from_millis(50), 16);
+    let start = Instant::now();
+    let at = |ms| start + Duration::from_millis(ms);
+
+    assert!(!window.check_and_insert(&fader(0.5), None, at(0)));
+    assert!(window.check_and_insert(&fader(0.5), None, at(1)));
+    assert!(window.check_and_insert(&fader(0.5), None, at(50)));
+    assert_eq!(window.len(), 1);
+
+    // Outside of the window, the packet is new again
+    assert!(!window.check_and_insert(&fader(0.5), None, at(51)));
+    assert!(window.check_and_insert(&fader(0.5), None, at(52)));
+}
+
+#[test]
+fn test_dedup_window_structural_collisions() {
+    let mut window = Window::new(Duration::from_secs(1), 16);
+    let now = Instant::now();
+
+    // Same structural hash, different values
+    assert_eq!(fader(0.1).structural_hash(), fader(0.2).structural_hash());
+    assert!(!window.check_and_insert(&fader(0.1), None, now));
+    assert!(!window.check_and_insert(&fader(0.2), None, now));
+    assert!(window.check_and_insert(&fader(0.1), None, now));
+    assert!(window.check_and_insert(&fader(0.2), None, now));
+
+    // Bundles are compared including their time tags
+    let bundle = |seconds| {
+        OscPacket::Bundle(OscBundle {
+            timetag: (seconds, 0).into(),
+            content: vec![fader(0.1)],
+        })
+    };
+    assert!(!window.check_and_insert(&bundle(1), None, now));
+    assert!(!window.check_and_insert(&bundle(2), None, now));
+    assert!(window.check_and_insert(&bundle(1), None, now));
+    assert_eq!(window.len(), 4);
+}
+
+#[test]
+fn test_dedup_window_many_values_of_one_address() {
+    // All of these share their address and type tags, so they must not share a bucket, or every
+    // check would compare against every remembered packet
+    let count = 20_000;
+    let mut window = Window::new(Duration::from_secs(1), count);
+    let now = Instant::now();
+
+    for i in 0..count {
+        assert!(!window.check_and_insert(&fader(i as f32), None, now));
+    }
+    assert_eq!(window.len(), count);
+    for i in 0..count {
+        assert!(window.check_and_insert(&fader(i as f32), None, now));
+    }
+    assert!(!window.check_and_insert(&fader(-1.0), None, now));
+}
+
+#[test]
+fn test_dedup_window_sources() {
+    let mut window = Window::new(Duration::from_secs(1), 16);
+    let now = Instant::now();
+    let a: SocketAddr = "10.0.0.1:9000".parse().unwrap();
+    let b: SocketAddr = "10.0.0.2:9000".parse().unwrap();
+
+    assert!(!window.check_and_insert(&fader(0.5), Some(a), now));
+    assert!(!window.check_and_insert(&fader(0.5), Some(b), now));
+    assert!(!window.check_and_insert(&fader(0.5), None, now));
+    assert!(window.check_and_insert(&fader(0.5), Some(a), now));
+    assert!(window.check_and_insert(&fader(0.5), Some(b), now));
+}
+
+#[test]
+fn test_dedup_window_capacity() {
+    let mut window = Window::new(Duration::from_secs(1), 3);
+    let now = Instant::now();
+
+    for i in 0..4 {
+        assert!(!window.check_and_insert(&fader(i as f32), None, now));
+    }
+    assert_eq!(window.len(), 3);
+    // The oldest packet was evicted, the others are still remembered
+    assert!(!window.check_and_insert(&fader(0.0), None, now));
+    assert!(window.check_and_insert(&fader(2.0), None, now));
+    assert!(window.check_and_insert(&fader(3.0), None, now));
+
+    let mut disabled = Window::new(Duration::from_secs(1), 0);
+    assert!(!disabled.check_and_insert(&fader(0.0), None, now));
+    assert!(!disabled.check_and_insert(&fader(0.0), None, now));
+    assert!(disabled.is_empty());
+}
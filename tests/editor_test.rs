@@ -0,0 +1,119 @@
+extern crate rosc;
+
+use rosc::{OscBundle, OscMessage, OscPacket, OscPacketEditor, OscTime, OscType};
+
+fn blob_message() -> OscPacket {
+    OscPacket::Message(OscMessage {
+        addr: "/sample".to_string(),
+        args: vec![OscType::Int(1), OscType::Blob(vec![7; 1 << 20])],
+    })
+}
+
+fn blob_ptr(packet: &OscPacket) -> *const u8 {
+    match packet {
+        OscPacket::Message(msg) => match &msg.args[1] {
+            OscType::Blob(blob) => blob.as_ptr(),
+            _ => panic!("Expected a blob"),
+        },
+        OscPacket::Bundle(bundle) => blob_ptr(&bundle.content[0]),
+    }
+}
+
+#[test]
+fn test_editor_tracks_changes() {
+    let packet = blob_message();
+
+    let mut editor = OscPacketEditor::new(packet.clone());
+    editor
+        .set_addr("/sample")
+        .unwrap()
+        .set_arg(0, OscType::Int(1))
+        .unwrap();
+    assert!(!editor.is_changed());
+    assert_eq!(editor.finish(), packet);
+
+    // Setting a value back to the original undoes the change
+    let mut editor = OscPacketEditor::new(packet.clone());
+    editor.set_arg(0, OscType::Int(2)).unwrap();
+    assert!(editor.is_changed());
+    editor.set_arg(0, OscType::Int(1)).unwrap();
+    assert!(!editor.is_changed());
+    assert_eq!(editor.finish(), packet);
+}
+
+#[test]
+fn test_editor_moves_untouched_blob() {
+    let packet = blob_message();
+    let blob = blob_ptr(&packet);
+
+    let mut editor = OscPacketEditor::new(packet);
+    editor.set_arg(0, OscType::Int(2)).unwrap();
+    let edited = editor.finish();
+    assert_eq!(blob_ptr(&edited), blob);
+    match edited {
+        OscPacket::Message(msg) => {
+            assert_eq!(msg.addr, "/sample");
+            assert_eq!(msg.args[0], OscType::Int(2));
+            assert_eq!(msg.args[1], OscType::Blob(vec![7; 1 << 20]));
+        }
+        OscPacket::Bundle(_) => panic!("Expected a message"),
+    }
+}
+
+#[test]
+fn test_editor_edits_owned_packet_in_place() {
+    let packet = OscPacket::Bundle(OscBundle {
+        timetag: OscTime::IMMEDIATE,
+        content: vec![blob_message()],
+    });
+    let blob = blob_ptr(&packet);
+
+    let mut editor = OscPacketEditor::new(packet);
+    editor
+        .set_timetag(OscTime::from((1, 0)))
+        .unwrap()
+        .edit_element(0, |element| {
+            element.set_addr("/renamed")?.set_arg(0, OscType::Int(3))?;
+            Ok(())
+        })
+        .unwrap();
+    let edited = editor.finish();
+    assert_eq!(blob_ptr(&edited), blob);
+
+    match edited {
+        OscPacket::Bundle(bundle) => {
+            assert_eq!(bundle.timetag, OscTime::from((1, 0)));
+            match &bundle.content[0] {
+                OscPacket::Message(msg) => {
+                    assert_eq!(msg.addr, "/renamed");
+                    assert_eq!(msg.args[0], OscType::Int(3));
+                }
+                OscPacket::Bundle(_) => panic!("Expected a message"),
+            }
+        }
+        OscPacket::Message(_) => panic!("Expected a bundle"),
+    }
+}
+
+#[test]
+fn test_editor_errors() {
+    let message = blob_message();
+    let bundle = OscPacket::Bundle(OscBundle {
+        timetag: OscTime::IMMEDIATE,
+        content: vec![message.clone()],
+    });
+
+    let mut editor = OscPacketEditor::new(message.clone());
+    assert!(editor.set_arg(2, OscType::Nil).is_err());
+    assert!(editor.set_timetag(OscTime::IMMEDIATE).is_err());
+    assert!(editor.edit_element(0, |_| Ok(())).is_err());
+
+    let mut editor = OscPacketEditor::new(bundle.clone());
+    assert!(editor.set_addr("/a").is_err());
+    assert!(editor.edit_element(1, |_| Ok(())).is_err());
+    assert!(editor
+        .edit_element(0, |element| element.set_arg(5, OscType::Nil).map(|_| ()))
+        .is_err());
+    assert!(!editor.is_changed());
+    assert_eq!(editor.finish(), bundle);
+}
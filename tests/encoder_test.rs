@@ -1,7 +1,9 @@
 extern crate rosc;
 
 use rosc::{decoder, encoder};
-use rosc::{OscArray, OscBundle, OscColor, OscMessage, OscMidiMessage, OscPacket, OscType};
+use rosc::{
+    OscArray, OscBundle, OscColor, OscMessage, OscMidiMessage, OscPacket, OscTime, OscType,
+};
 
 #[test]
 fn test_encode_message_wo_args() {
@@ -154,3 +156,1014 @@ fn test_encode_bundle() {
     let dec_bundle = decoder::decode_udp(&enc_bundle).unwrap().1;
     assert_eq!(root_bundle, dec_bundle);
 }
+
+#[test]
+fn test_encode_into_uninit() {
+    use std::mem::MaybeUninit;
+
+    let packet = OscPacket::Bundle(OscBundle {
+        timetag: (1234, 4321).into(),
+        content: vec![OscPacket::Message(OscMessage {
+            addr: "/osc/1/freq".to_string(),
+            args: vec![440i32.into(), "sine".into()],
+        })],
+    });
+    let expected = encoder::encode(&packet).unwrap();
+
+    // Buffer with spare room: only the prefix is initialized and returned
+    let mut frame = [MaybeUninit::<u8>::uninit(); 128];
+    let bytes = encoder::encode_into_uninit(&packet, &mut frame).unwrap();
+    assert_eq!(bytes, &expected[..]);
+    assert_eq!(decoder::decode_udp(bytes).unwrap().1, packet);
+
+    // Exactly sized buffer
+    let mut frame = vec![MaybeUninit::<u8>::uninit(); expected.len()];
+    let bytes = encoder::encode_into_uninit(&packet, &mut frame).unwrap();
+    assert_eq!(bytes, &expected[..]);
+}
+
+#[test]
+fn test_encode_into_uninit_all_types() {
+    use std::mem::MaybeUninit;
+
+    let message = OscPacket::Message(OscMessage {
+        addr: "/all/types".to_string(),
+        args: vec![
+            OscType::Int(-2),
+            OscType::Float(-1.5),
+            OscType::String("osc".to_string()),
+            OscType::Blob(vec![1, 2, 3, 4, 5]),
+            OscType::Blob(vec![1, 2, 3, 4]),
+            OscType::Time((1, 2).into()),
+            OscType::Long(1 << 40),
+            OscType::Double(0.5),
+            OscType::Char('€'),
+            OscType::Color(OscColor {
+                red: 1,
+                green: 2,
+                blue: 3,
+                alpha: 4,
+            }),
+            OscType::Midi(OscMidiMessage {
+                port: 0,
+                status: 0x80,
+                data1: 64,
+                data2: 0,
+            }),
+            OscType::Bool(true),
+            OscType::Bool(false),
+            OscType::Nil,
+            OscType::Inf,
+            OscType::Array(OscArray {
+                content: vec![OscType::Int(7), OscType::Int(8)],
+            }),
+            OscType::Array(OscArray {
+                content: vec![
+                    OscType::String("in".to_string()),
+                    OscType::Array(OscArray { content: vec![] }),
+                ],
+            }),
+        ],
+    });
+    let packets = vec![
+        OscPacket::Message(OscMessage::from("/a")),
+        OscPacket::Message(OscMessage::from("/abc")),
+        OscPacket::Bundle(OscBundle {
+            timetag: (1, 2).into(),
+            content: vec![],
+        }),
+        OscPacket::Bundle(OscBundle {
+            timetag: (3, 4).into(),
+            content: vec![message.clone(), nested_bundle_packet()],
+        }),
+        message,
+    ];
+
+    for packet in &packets {
+        let expected = encoder::encode(packet).unwrap();
+        let mut frame = vec![MaybeUninit::<u8>::uninit(); expected.len()];
+        let bytes = encoder::encode_into_uninit(packet, &mut frame).unwrap();
+        assert_eq!(bytes, &expected[..]);
+    }
+}
+
+#[test]
+fn test_encode_into_uninit_too_small() {
+    use std::mem::MaybeUninit;
+
+    let packet = OscPacket::Message(OscMessage {
+        addr: "/some/addr".to_string(),
+        args: vec![1i32.into()],
+    });
+    let needed = encoder::encode(&packet).unwrap().len();
+
+    let mut frame = vec![MaybeUninit::new(0xaau8); needed - 1];
+    match encoder::encode_into_uninit(&packet, &mut frame) {
+        Err(rosc::OscError::BufferTooSmall {
+            needed: n,
+            available,
+        }) => {
+            assert_eq!(n, needed);
+            assert_eq!(available, needed - 1);
+        }
+        other => panic!("Expected BufferTooSmall, got {:?}", other),
+    }
+    // The buffer must not have been written to
+    assert!(frame.iter().all(|b| unsafe { b.assume_init() } == 0xaa));
+}
+
+#[test]
+fn test_encode_message_iter() {
+    let args = vec![
+        OscType::Int(4),
+        OscType::Float(0.5),
+        "text".into(),
+        OscType::Nil,
+        vec![1u8, 2, 3].into(),
+        OscArray {
+            content: vec![1i32.into(), true.into()],
+        }
+        .into(),
+    ];
+    let msg_packet = OscPacket::Message(OscMessage {
+        addr: "/iter/addr".to_string(),
+        args: args.clone(),
+    });
+    let expected = encoder::encode(&msg_packet).unwrap();
+
+    // Owned arguments from a transient iterator
+    let encoded = encoder::encode_message_iter("/iter/addr", args.iter().cloned()).unwrap();
+    assert_eq!(encoded, expected);
+
+    // Borrowed arguments
+    let encoded = encoder::encode_message_iter("/iter/addr", &args).unwrap();
+    assert_eq!(encoded, expected);
+
+    // No arguments at all
+    assert_eq!(
+        encoder::encode_message_iter("/iter/addr", std::iter::empty::<OscType>()).unwrap(),
+        encoder::encode(&OscPacket::Message(OscMessage::from("/iter/addr"))).unwrap()
+    );
+}
+
+#[test]
+fn test_encode_rejects_bundle_tag_address() {
+    let msg = OscMessage {
+        addr: "#bundle".to_string(),
+        args: vec![1i32.into()],
+    };
+    match encoder::encode_validated(&OscPacket::Message(msg.clone())) {
+        Err(rosc::OscError::BadAddress(_)) => (),
+        other => panic!("Expected BadAddress, got {:?}", other),
+    }
+
+    // Also when nested inside a bundle
+    let bundle = OscPacket::Bundle(OscBundle {
+        timetag: (1, 0).into(),
+        content: vec![OscPacket::Message(msg)],
+    });
+    assert!(encoder::encode_validated(&bundle).is_err());
+
+    // Only the validating path rejects them
+    assert!(encoder::encode(&bundle).is_ok());
+    let valid = OscPacket::Message(OscMessage::from("/bundle"));
+    assert_eq!(
+        encoder::encode_validated(&valid).unwrap(),
+        encoder::encode(&valid).unwrap()
+    );
+}
+
+#[test]
+fn test_encode_for_peer_default_profile() {
+    use rosc::encoder::PeerProfile;
+
+    let packet = OscPacket::Message(OscMessage {
+        addr: "/ünïcode".to_string(),
+        args: vec![
+            OscType::Double(0.5),
+            OscArray {
+                content: vec![1i32.into(), 2i32.into()],
+            }
+            .into(),
+        ],
+    });
+    assert_eq!(
+        encoder::encode_for_peer(&packet, &PeerProfile::default()).unwrap(),
+        encoder::encode(&packet).unwrap()
+    );
+}
+
+#[test]
+fn test_encode_for_peer_strict_osc_1_0() {
+    use rosc::encoder::PeerProfile;
+
+    let profile = PeerProfile::strict_osc_1_0();
+    let packet = OscPacket::Bundle(OscBundle {
+        timetag: (1, 0).into(),
+        content: vec![OscPacket::Message(OscMessage {
+            addr: "/xy".to_string(),
+            args: vec![
+                OscArray {
+                    content: vec![0.25f32.into(), 0.75f32.into()],
+                }
+                .into(),
+                "label".into(),
+            ],
+        })],
+    });
+    let flattened = OscPacket::Bundle(OscBundle {
+        timetag: (1, 0).into(),
+        content: vec![OscPacket::Message(OscMessage {
+            addr: "/xy".to_string(),
+            args: vec![0.25f32.into(), 0.75f32.into(), "label".into()],
+        })],
+    });
+    assert_eq!(
+        encoder::encode_for_peer(&packet, &profile).unwrap(),
+        encoder::encode(&flattened).unwrap()
+    );
+
+    // Nonstandard types and non-ASCII strings are rejected
+    let msg = |args: Vec<OscType>| {
+        OscPacket::Message(OscMessage {
+            addr: "/x".to_string(),
+            args,
+        })
+    };
+    assert!(encoder::encode_for_peer(&msg(vec![OscType::Long(1)]), &profile).is_err());
+    assert!(encoder::encode_for_peer(&msg(vec![true.into()]), &profile).is_err());
+    assert!(encoder::encode_for_peer(&msg(vec!["grüße".into()]), &profile).is_err());
+
+    // Arrays are rejected if they're neither flattened nor allowed
+    let profile = PeerProfile {
+        flatten_arrays: false,
+        ..PeerProfile::strict_osc_1_0()
+    };
+    assert!(encoder::encode_for_peer(&packet, &profile).is_err());
+}
+
+#[test]
+fn test_encode_for_peer_liblo() {
+    use rosc::encoder::PeerProfile;
+
+    let profile = PeerProfile::liblo();
+    let packet = OscPacket::Message(OscMessage {
+        addr: "/grüße".to_string(),
+        args: vec![
+            OscType::Long(1),
+            OscType::Double(0.5),
+            true.into(),
+            OscType::Nil,
+            (1, 2).into(),
+        ],
+    });
+    assert_eq!(
+        encoder::encode_for_peer(&packet, &profile).unwrap(),
+        encoder::encode(&packet).unwrap()
+    );
+
+    let packet = OscPacket::Message(OscMessage {
+        addr: "/color".to_string(),
+        args: vec![OscColor {
+            red: 1,
+            green: 2,
+            blue: 3,
+            alpha: 4,
+        }
+        .into()],
+    });
+    assert!(encoder::encode_for_peer(&packet, &profile).is_err());
+}
+
+#[test]
+fn test_encode_for_peer_minimal_embedded() {
+    use rosc::encoder::PeerProfile;
+
+    let profile = PeerProfile::minimal_embedded();
+    let msg = |text: String| {
+        OscPacket::Message(OscMessage {
+            addr: "/display".to_string(),
+            args: vec![OscType::Int(1), text.into()],
+        })
+    };
+
+    let fits = msg("x".repeat(480));
+    assert!(encoder::encode(&fits).unwrap().len() <= 512);
+    assert!(encoder::encode_for_peer(&fits, &profile).is_ok());
+
+    let too_large = msg("x".repeat(500));
+    assert!(encoder::encode(&too_large).unwrap().len() > 512);
+    assert!(encoder::encode_for_peer(&too_large, &profile).is_err());
+
+    let blob = OscPacket::Message(OscMessage {
+        addr: "/display".to_string(),
+        args: vec![vec![1u8, 2, 3].into()],
+    });
+    assert!(encoder::encode_for_peer(&blob, &profile).is_err());
+}
+
+#[test]
+fn test_encode_into_slice_after_header() {
+    let packet = OscPacket::Message(OscMessage {
+        addr: "/osc/1/freq".to_string(),
+        args: vec![440i32.into()],
+    });
+    let expected = encoder::encode(&packet).unwrap();
+
+    const HEADER: usize = 6;
+    let mut buf = [0xffu8; 64];
+    buf[..HEADER].copy_from_slice(b"HEADER");
+    let len = encoder::encode_into_slice(&packet, &mut buf[HEADER..]).unwrap();
+
+    assert_eq!(len, expected.len());
+    assert_eq!(&buf[..HEADER], b"HEADER");
+    assert_eq!(&buf[HEADER..HEADER + len], &expected[..]);
+    // Bytes after the packet are untouched
+    assert!(buf[HEADER + len..].iter().all(|&b| b == 0xff));
+    assert_eq!(
+        decoder::decode_udp(&buf[HEADER..HEADER + len]).unwrap().1,
+        packet
+    );
+
+    // Too small: nothing is written
+    let mut small = vec![0xffu8; expected.len() - 1];
+    match encoder::encode_into_slice(&packet, &mut small) {
+        Err(rosc::OscError::BufferTooSmall { needed, available }) => {
+            assert_eq!(needed, expected.len());
+            assert_eq!(available, small.len());
+        }
+        other => panic!("Expected BufferTooSmall, got {:?}", other),
+    }
+    assert!(small.iter().all(|&b| b == 0xff));
+
+    // Nested bundles are written in place as well
+    let packet = nested_bundle_packet();
+    let expected = encoder::encode(&packet).unwrap();
+    let mut buf = vec![0xffu8; HEADER + expected.len()];
+    let len = encoder::encode_into_slice(&packet, &mut buf[HEADER..]).unwrap();
+    assert_eq!(len, expected.len());
+    assert_eq!(&buf[HEADER..], &expected[..]);
+}
+
+fn large_bundle(messages: i32) -> OscPacket {
+    OscPacket::Bundle(OscBundle {
+        timetag: (4, 2).into(),
+        content: (0..messages)
+            .map(|i| {
+                OscPacket::Message(OscMessage {
+                    addr: "/osc/1/freq".to_string(),
+                    args: vec![OscType::Int(i)],
+                })
+            })
+            .collect(),
+    })
+}
+
+#[test]
+fn test_encode_with_deadline() {
+    let packet = large_bundle(100);
+    let expected = encoder::encode(&packet).unwrap();
+
+    let mut calls = 0;
+    let mut out = b"prefix".to_vec();
+    let len = encoder::encode_with_deadline(&packet, &mut out, || {
+        calls += 1;
+        true
+    })
+    .unwrap();
+
+    // 101 packets: the bundle and its messages
+    assert_eq!(calls, 101usize.div_ceil(encoder::DEADLINE_CHECK_INTERVAL));
+    assert_eq!(len, expected.len());
+    assert_eq!(&out[..6], b"prefix");
+    assert_eq!(&out[6..], &expected[..]);
+}
+
+#[test]
+fn test_encode_with_deadline_aborted() {
+    let packet = large_bundle(100);
+    let expected = encoder::encode(&packet).unwrap();
+
+    let mut calls = 0;
+    let mut out = vec![];
+    let result = encoder::encode_with_deadline(&packet, &mut out, || {
+        calls += 1;
+        calls < 3
+    });
+
+    match result {
+        Err(rosc::OscError::Aborted) => (),
+        other => panic!("Expected encoding to be aborted, got {:?}", other),
+    }
+    assert_eq!(calls, 3);
+    // Encoding stopped somewhere in the middle of the bundle
+    assert!(!out.is_empty());
+    assert!(out.len() < expected.len());
+    assert_eq!(&out[..], &expected[..out.len()]);
+}
+
+#[test]
+fn test_encode_with_layout_message() {
+    let args = vec![
+        OscType::Int(42),
+        OscType::String("hello".to_string()),
+        OscType::Bool(true),
+        OscType::Blob(vec![1, 2, 3, 4, 5]),
+        OscType::Array(OscArray {
+            content: vec![OscType::Double(1.5), OscType::Nil, OscType::Long(-7)],
+        }),
+        OscType::Float(0.5),
+    ];
+    let tags = ["i", "s", "T", "b", "[dNh]", "f"];
+    let packet = OscPacket::Message(OscMessage {
+        addr: "/layout/test".to_string(),
+        args: args.clone(),
+    });
+
+    let (bytes, layout) = encoder::encode_with_layout(&packet).unwrap();
+    assert_eq!(bytes, encoder::encode(&packet).unwrap());
+    let layout = match layout {
+        encoder::PacketLayout::Message(layout) => layout,
+        _ => panic!("Expected a message layout!"),
+    };
+
+    assert_eq!(
+        &bytes[layout.address_range.clone()],
+        &encoder::encode_string("/layout/test")[..]
+    );
+    assert_eq!(
+        &bytes[layout.type_tags_range.clone()],
+        &encoder::encode_string(",isTb[dNh]f")[..]
+    );
+    assert_eq!(layout.arg_ranges.len(), args.len());
+    assert_eq!(layout.arg_ranges[2].len(), 0);
+    assert_eq!(layout.arg_ranges.last().unwrap().end, bytes.len());
+
+    // Decode each argument on its own by combining it with the address and its type tag
+    for ((range, tag), arg) in layout.arg_ranges.iter().zip(&tags).zip(&args) {
+        let mut single = bytes[layout.address_range.clone()].to_vec();
+        single.extend(encoder::encode_string(format!(",{}", tag)));
+        single.extend(&bytes[range.clone()]);
+
+        match decoder::decode_udp(&single).unwrap().1 {
+            OscPacket::Message(msg) => assert_eq!(msg.args, vec![arg.clone()]),
+            _ => panic!("Expected an OscMessage!"),
+        }
+    }
+
+    // Strip the type tags and re-attach them, as a transport sending them out of band would
+    let data = layout.arg_data_range();
+    assert_eq!(data.start, layout.type_tags_range.end);
+    assert_eq!(data.end, bytes.len());
+    let stripped: Vec<u8> = bytes[layout.address_range.clone()]
+        .iter()
+        .chain(&bytes[data])
+        .cloned()
+        .collect();
+    let tags_len = layout.type_tags_range.len();
+    let mut reattached = stripped[..layout.address_range.end].to_vec();
+    reattached.extend(&bytes[layout.type_tags_range.clone()]);
+    reattached.extend(&stripped[layout.address_range.end..]);
+    assert_eq!(reattached.len(), stripped.len() + tags_len);
+    assert_eq!(decoder::decode_udp(&reattached).unwrap().1, packet);
+
+    let (_, empty) = encoder::encode_with_layout(&OscPacket::Message(OscMessage {
+        addr: "/flags".to_string(),
+        args: vec![OscType::Bool(false), OscType::Nil],
+    }))
+    .unwrap();
+    match empty {
+        encoder::PacketLayout::Message(layout) => {
+            assert_eq!(layout.arg_data_range(), 12..12);
+        }
+        _ => panic!("Expected a message layout!"),
+    }
+}
+
+#[test]
+fn test_encode_with_layout_bundle() {
+    let content = vec![
+        OscPacket::Message(OscMessage {
+            addr: "/first".to_string(),
+            args: vec![OscType::Int(1)],
+        }),
+        OscPacket::Bundle(OscBundle {
+            timetag: (5, 6).into(),
+            content: vec![OscPacket::Message(OscMessage::from("/nested"))],
+        }),
+        OscPacket::Bundle(OscBundle {
+            timetag: (7, 8).into(),
+            content: vec![],
+        }),
+        OscPacket::Message(OscMessage::from("/last")),
+    ];
+    let packet = OscPacket::Bundle(OscBundle {
+        timetag: (3, 4).into(),
+        content: content.clone(),
+    });
+
+    let (bytes, layout) = encoder::encode_with_layout(&packet).unwrap();
+    assert_eq!(bytes, encoder::encode(&packet).unwrap());
+    let layout = match layout {
+        encoder::PacketLayout::Bundle(layout) => layout,
+        _ => panic!("Expected a bundle layout!"),
+    };
+
+    assert_eq!(layout.timetag_range, 8..16);
+    assert_eq!(&bytes[8..16], &[0, 0, 0, 3, 0, 0, 0, 4]);
+    assert_eq!(layout.element_ranges.last().unwrap().end, bytes.len());
+
+    let decoded: Vec<OscPacket> = layout
+        .element_ranges
+        .iter()
+        .map(|range| decoder::decode_udp(&bytes[range.clone()]).unwrap().1)
+        .collect();
+    assert_eq!(decoded, content);
+}
+
+fn nested_bundle_packet() -> OscPacket {
+    OscPacket::Bundle(OscBundle {
+        timetag: (1, 0).into(),
+        content: vec![
+            OscPacket::Message(OscMessage {
+                addr: "/first".to_string(),
+                args: vec![OscType::Int(1)],
+            }),
+            OscPacket::Bundle(OscBundle {
+                timetag: (2, 0).into(),
+                content: vec![
+                    OscPacket::Bundle(OscBundle {
+                        timetag: (3, 0).into(),
+                        content: vec![OscPacket::Message(OscMessage::from("/deep"))],
+                    }),
+                    // Walking continues after a nested bundle
+                    OscPacket::Message(OscMessage::from("/bundle")),
+                ],
+            }),
+        ],
+    })
+}
+
+fn with_timetags(packet: &OscPacket, outer: OscTime, nested: Option<OscTime>) -> OscPacket {
+    match packet {
+        OscPacket::Message(_) => packet.clone(),
+        OscPacket::Bundle(bundle) => OscPacket::Bundle(OscBundle {
+            timetag: outer,
+            content: bundle
+                .content
+                .iter()
+                .map(|element| match (element, nested) {
+                    (OscPacket::Bundle(_), Some(time)) => with_timetags(element, time, nested),
+                    _ => element.clone(),
+                })
+                .collect(),
+        }),
+    }
+}
+
+#[test]
+fn test_patch_timetag() {
+    let packet = nested_bundle_packet();
+    let original = encoder::encode(&packet).unwrap();
+    let new_time = OscTime::from((3_900_000_000, 12345));
+
+    let mut bytes = original.clone();
+    encoder::patch_timetag(&mut bytes, new_time).unwrap();
+    assert_eq!(&bytes[..8], &original[..8]);
+    assert_eq!(&bytes[16..], &original[16..]);
+    assert_eq!(
+        decoder::decode_udp(&bytes).unwrap().1,
+        with_timetags(&packet, new_time, None)
+    );
+
+    let mut bytes = original.clone();
+    encoder::patch_timetag_recursive(&mut bytes, new_time).unwrap();
+    assert_eq!(bytes.len(), original.len());
+    assert_eq!(
+        decoder::decode_udp(&bytes).unwrap().1,
+        with_timetags(&packet, new_time, Some(new_time))
+    );
+    // Re-encoding the expected packet yields identical bytes
+    assert_eq!(
+        bytes,
+        encoder::encode(&with_timetags(&packet, new_time, Some(new_time))).unwrap()
+    );
+}
+
+#[test]
+fn test_patch_timetag_invalid() {
+    let new_time = OscTime::from((1, 2));
+
+    let mut msg = encoder::encode(&OscPacket::Message(OscMessage::from("/bundle"))).unwrap();
+    let original = msg.clone();
+    assert!(encoder::patch_timetag(&mut msg, new_time).is_err());
+    assert!(encoder::patch_timetag_recursive(&mut msg, new_time).is_err());
+    assert_eq!(msg, original);
+
+    let mut short = encoder::encode_string("#bundle");
+    assert!(encoder::patch_timetag(&mut short, new_time).is_err());
+
+    // Corrupt the size of the nested bundle so that it exceeds the outer bundle
+    let mut bytes = encoder::encode(&nested_bundle_packet()).unwrap();
+    let original = bytes.clone();
+    let nested_size_pos = 16 + 4 + 16;
+    bytes[nested_size_pos..nested_size_pos + 4].copy_from_slice(&1000u32.to_be_bytes());
+    let corrupted = bytes.clone();
+    assert!(encoder::patch_timetag_recursive(&mut bytes, new_time).is_err());
+    assert_eq!(bytes, corrupted);
+    assert_ne!(bytes, original);
+}
+
+/// A writer which can't seek and accepts at most 3 bytes per call.
+#[cfg(feature = "std")]
+struct Trickle(Vec<u8>);
+
+#[cfg(feature = "std")]
+impl std::io::Write for Trickle {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        let len = buf.len().min(3);
+        self.0.extend(&buf[..len]);
+        Ok(len)
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+#[test]
+#[cfg(feature = "std")]
+fn test_encode_to_writer() {
+    let packet = nested_bundle_packet();
+    let expected = encoder::encode(&packet).unwrap();
+
+    // Seekable writer, with existing content
+    let mut cursor = std::io::Cursor::new(b"header".to_vec());
+    cursor.set_position(6);
+    let len = encoder::encode_to_writer(&packet, &mut cursor).unwrap();
+    assert_eq!(len, expected.len());
+    assert_eq!(&cursor.get_ref()[..6], b"header");
+    assert_eq!(&cursor.get_ref()[6..], &expected[..]);
+
+    // Non-seekable writer doing short writes
+    let mut trickle = Trickle(vec![]);
+    assert_eq!(
+        encoder::encode_to_writer(&packet, &mut trickle).unwrap(),
+        expected.len()
+    );
+    assert_eq!(trickle.0, expected);
+}
+
+#[test]
+fn test_encode_with_capacity() {
+    let packet = nested_bundle_packet();
+    let expected = encoder::encode(&packet).unwrap();
+
+    for &hint in &[0, 1, expected.len(), 4096] {
+        let bytes = encoder::encode_with_capacity(&packet, hint).unwrap();
+        assert_eq!(bytes, expected);
+        assert!(bytes.capacity() >= hint);
+    }
+}
+
+#[test]
+fn test_encode_numeric_arrays() {
+    let array = |content: Vec<OscType>| OscType::Array(OscArray { content });
+    let msg = OscMessage {
+        addr: "/n".to_string(),
+        args: vec![
+            array(vec![OscType::Int(1), OscType::Int(-2)]),
+            array(vec![OscType::Float(0.5)]),
+            array(vec![OscType::Long(3), OscType::Long(i64::MIN)]),
+            array(vec![OscType::Double(-0.25), OscType::Double(1e300)]),
+            // Mixed and nested arrays are encoded element by element
+            array(vec![OscType::Int(4), OscType::Float(5.0)]),
+            array(vec![OscType::Int(6), array(vec![OscType::Int(7)])]),
+            array(vec![]),
+        ],
+    };
+
+    let mut expected = encoder::encode_string("/n");
+    expected.extend(encoder::encode_string(",[ii][f][hh][dd][if][i[i]][]"));
+    expected.extend(1i32.to_be_bytes());
+    expected.extend((-2i32).to_be_bytes());
+    expected.extend(0.5f32.to_be_bytes());
+    expected.extend(3i64.to_be_bytes());
+    expected.extend(i64::MIN.to_be_bytes());
+    expected.extend((-0.25f64).to_be_bytes());
+    expected.extend(1e300f64.to_be_bytes());
+    expected.extend(4i32.to_be_bytes());
+    expected.extend(5.0f32.to_be_bytes());
+    expected.extend(6i32.to_be_bytes());
+    expected.extend(7i32.to_be_bytes());
+
+    let packet = OscPacket::Message(msg);
+    let bytes = encoder::encode(&packet).unwrap();
+    assert_eq!(bytes, expected);
+    assert_eq!(decoder::decode_udp(&bytes).unwrap().1, packet);
+}
+
+#[test]
+fn test_float_bit_patterns_round_trip() {
+    let f32_bits = [
+        (-0.0f32).to_bits(),
+        0x7fa0_0001, // signaling NaN
+        0x7fc0_0000, // quiet NaN
+        0xffc0_1234, // negative quiet NaN with payload
+    ];
+    let f64_bits = [
+        (-0.0f64).to_bits(),
+        0x7ff4_0000_0000_0001, // signaling NaN
+        0x7ff8_0000_0000_0000, // quiet NaN
+        0xfff8_0000_0000_1234, // negative quiet NaN with payload
+    ];
+
+    let args = || {
+        f32_bits
+            .iter()
+            .map(|&bits| OscType::Float(f32::from_bits(bits)))
+            .chain(
+                f64_bits
+                    .iter()
+                    .map(|&bits| OscType::Double(f64::from_bits(bits))),
+            )
+    };
+    // Both as separate arguments and in arrays, which are encoded in bulk
+    let packet = OscPacket::Message(OscMessage {
+        addr: "/bits".to_string(),
+        args: args()
+            .chain(vec![
+                OscType::Array(args().take(f32_bits.len()).collect()),
+                OscType::Array(args().skip(f32_bits.len()).collect()),
+            ])
+            .collect(),
+    });
+
+    let decoded = match decoder::decode_udp(&encoder::encode(&packet).unwrap())
+        .unwrap()
+        .1
+    {
+        OscPacket::Message(msg) => msg.args,
+        _ => panic!("Expected an OscMessage!"),
+    };
+    let mut flat = vec![];
+    for arg in decoded {
+        match arg {
+            OscType::Array(array) => flat.extend(array.content),
+            arg => flat.push(arg),
+        }
+    }
+
+    let expected: Vec<u64> = f32_bits
+        .iter()
+        .map(|&bits| u64::from(bits))
+        .chain(f64_bits.iter().cloned())
+        .collect();
+    let actual: Vec<u64> = flat
+        .iter()
+        .map(|arg| match *arg {
+            OscType::Float(x) => u64::from(x.to_bits()),
+            OscType::Double(x) => x.to_bits(),
+            ref other => panic!("Unexpected argument {:?}", other),
+        })
+        .collect();
+    assert_eq!(actual.len(), 2 * expected.len());
+    assert_eq!(&actual[..expected.len()], &expected[..]);
+    assert_eq!(&actual[expected.len()..], &expected[..]);
+}
+
+#[test]
+fn test_encode_time_arg_like_bundle_timetag() {
+    use std::convert::TryFrom;
+
+    let time = OscTime::from((0x89ab_cdef, 0x0123_4567));
+    let msg = encoder::encode(&OscPacket::Message(OscMessage {
+        addr: "/t".to_string(),
+        args: vec![OscType::from(time)],
+    }))
+    .unwrap();
+    let bundle = encoder::encode(&OscPacket::Bundle(OscBundle {
+        timetag: time,
+        content: vec![],
+    }))
+    .unwrap();
+
+    assert_eq!(&msg[4..8], b",t\0\0");
+    assert_eq!(msg.len(), 16);
+    assert_eq!(&msg[8..], &bundle[8..16]);
+    assert_eq!(&msg[8..], &[0x89, 0xab, 0xcd, 0xef, 0x01, 0x23, 0x45, 0x67]);
+
+    assert_eq!(OscTime::try_from(OscType::Time(time)), Ok(time));
+    assert_eq!(OscTime::try_from(OscType::Nil), Err(OscType::Nil));
+    assert_eq!(OscType::Time(time).as_time(), Some(&time));
+    assert_eq!(OscType::Int(1).as_time(), None);
+}
+
+#[test]
+fn test_never_timetag_round_trip() {
+    assert!(OscTime::NEVER.is_never());
+    assert!(!OscTime::IMMEDIATE.is_never());
+    assert!(!OscTime::from((u32::MAX, u32::MAX - 1)).is_never());
+
+    let packet = OscPacket::Bundle(OscBundle {
+        timetag: OscTime::NEVER,
+        content: vec![OscPacket::Message(OscMessage {
+            addr: "/t".to_string(),
+            args: vec![OscType::Time(OscTime::NEVER)],
+        })],
+    });
+    let bytes = encoder::encode(&packet).unwrap();
+    assert_eq!(&bytes[8..16], &[0xff; 8]);
+    assert_eq!(&bytes[bytes.len() - 8..], &[0xff; 8]);
+
+    match decoder::decode_udp(&bytes).unwrap().1 {
+        OscPacket::Bundle(bundle) => {
+            assert!(bundle.timetag.is_never());
+            assert_eq!(bundle.timetag.seconds, u32::MAX);
+            assert_eq!(bundle.timetag.fractional, u32::MAX);
+        }
+        OscPacket::Message(_) => panic!("expected a bundle"),
+    }
+    assert_eq!(decoder::decode_udp(&bytes).unwrap().1, packet);
+}
+
+#[test]
+fn test_encode_string_into_matches_encode_string() {
+    for len in 0..=16 {
+        let s: String = "abcdefghijklmnop"[..len].to_string();
+        let expected = encoder::encode_string(s.clone());
+        assert_eq!(expected.len() % 4, 0);
+        assert!(expected.len() > len && expected.len() <= len + 4);
+
+        // Appending must not disturb what is already in the buffer
+        let mut out = vec![0xaa; 3];
+        assert_eq!(encoder::encode_string_into(&s, &mut out), expected.len());
+        assert_eq!(&out[..3], &[0xaa; 3]);
+        assert_eq!(&out[3..], &expected[..]);
+    }
+}
+
+#[test]
+fn test_lazy_bundle_encoder_mixed_elements() {
+    let message = |i: i32| {
+        OscPacket::Message(OscMessage {
+            addr: format!("/item/{}", i),
+            args: vec![OscType::Int(i), OscType::String("x".repeat(i as usize))],
+        })
+    };
+    let nested = OscPacket::Bundle(OscBundle {
+        timetag: OscTime::from((5, 6)),
+        content: vec![message(9)],
+    });
+
+    let mut lazy = encoder::LazyBundleEncoder::new(OscTime::from((1, 2)));
+    lazy.add(message(0))
+        .add_lazy(|out| encoder::encode_into(&message(1), out))
+        .add_lazy(|out| {
+            let mut inner = encoder::LazyBundleEncoder::new(OscTime::from((5, 6)));
+            inner.add_lazy(|out| encoder::encode_into(&message(9), out));
+            inner.encode_into(out)
+        })
+        .add_lazy(|out| {
+            let bytes = encoder::encode_message_iter("/item/3", vec![OscType::Int(3)])?;
+            out.extend_from_slice(&bytes);
+            Ok(bytes.len())
+        })
+        .add(message(4));
+    assert_eq!(lazy.len(), 5);
+
+    let mut out = b"prefix".to_vec();
+    let len = lazy.encode_into(&mut out).unwrap();
+    assert_eq!(&out[..6], b"prefix");
+    assert_eq!(len, out.len() - 6);
+
+    let eager = OscPacket::Bundle(OscBundle {
+        timetag: OscTime::from((1, 2)),
+        content: vec![
+            message(0),
+            message(1),
+            nested,
+            OscPacket::Message(OscMessage {
+                addr: "/item/3".to_string(),
+                args: vec![OscType::Int(3)],
+            }),
+            message(4),
+        ],
+    });
+    assert_eq!(&out[6..], &encoder::encode(&eager).unwrap()[..]);
+
+    let empty = encoder::LazyBundleEncoder::new(OscTime::from((1, 2)));
+    assert!(empty.is_empty());
+    assert_eq!(
+        empty.encode().unwrap(),
+        encoder::encode(&OscPacket::Bundle(OscBundle {
+            timetag: OscTime::from((1, 2)),
+            content: vec![],
+        }))
+        .unwrap()
+    );
+}
+
+#[test]
+fn test_lazy_bundle_encoder_errors() {
+    let mut bad_size = encoder::LazyBundleEncoder::new(OscTime::IMMEDIATE);
+    bad_size.add_lazy(|out| {
+        out.extend_from_slice(b"/a\0");
+        Ok(3)
+    });
+    match bad_size.encode() {
+        Err(rosc::OscError::BadBundle(_)) => (),
+        other => panic!("Expected BadBundle, got {:?}", other),
+    }
+
+    let mut empty_element = encoder::LazyBundleEncoder::new(OscTime::IMMEDIATE);
+    empty_element.add_lazy(|_| Ok(0));
+    assert!(empty_element.encode().is_err());
+
+    // Errors of elements are passed on
+    let mut failing = encoder::LazyBundleEncoder::new(OscTime::IMMEDIATE);
+    failing.add(OscPacket::Message(OscMessage::from("/ok")));
+    failing.add_lazy(|_| Err(rosc::OscError::BadAddress("/bad".to_string())));
+    match failing.encode() {
+        Err(rosc::OscError::BadAddress(_)) => (),
+        other => panic!("Expected BadAddress, got {:?}", other),
+    }
+}
+
+#[test]
+fn test_prepared_message() {
+    use rosc::encoder::PreparedMessage;
+    use rosc::{OscError, TypeTag};
+
+    let tags = [
+        TypeTag::Int,
+        TypeTag::String,
+        TypeTag::ArrayStart,
+        TypeTag::Float,
+        TypeTag::ArrayStart,
+        TypeTag::ArrayEnd,
+        TypeTag::ArrayEnd,
+        TypeTag::True,
+        TypeTag::Blob,
+    ];
+    let prepared = PreparedMessage::new("/voice/1", &tags).unwrap();
+    let values = |i: i32| {
+        vec![
+            OscType::Int(i),
+            OscType::String("x".repeat(i as usize)),
+            OscType::Array(OscArray {
+                content: vec![
+                    OscType::Float(i as f32),
+                    OscType::Array(OscArray { content: vec![] }),
+                ],
+            }),
+            OscType::Bool(true),
+            OscType::Blob(vec![0xab; i as usize]),
+        ]
+    };
+
+    // Appends to what is already in the buffer
+    let mut out = vec![0xff];
+    for i in 0..6 {
+        out.truncate(1);
+        let expected = encoder::encode(&OscPacket::Message(OscMessage {
+            addr: "/voice/1".to_string(),
+            args: values(i),
+        }))
+        .unwrap();
+        assert_eq!(
+            prepared.encode_values(&values(i), &mut out).unwrap(),
+            expected.len()
+        );
+        assert_eq!(out[1..], expected[..]);
+    }
+
+    // No arguments at all
+    let ping = PreparedMessage::new("/ping", &[]).unwrap();
+    let mut out = vec![];
+    ping.encode_values(&[], &mut out).unwrap();
+    assert_eq!(out, b"/ping\0\0\0,\0\0\0");
+
+    // Mismatching values leave the buffer untouched
+    let mut out = vec![1, 2, 3];
+    let mut wrong = values(1);
+    wrong[3] = OscType::Bool(false);
+    let mut short = values(1);
+    short.pop();
+    let mut long = values(1);
+    long.push(OscType::Nil);
+    let mut nested = values(1);
+    nested[2] = OscType::Array(OscArray {
+        content: vec![OscType::Float(1.0)],
+    });
+    for wrong in &[wrong, short, long, nested, vec![]] {
+        match prepared.encode_values(wrong, &mut out) {
+            Err(OscError::BadArg(_)) => (),
+            other => panic!("unexpected result {:?}", other),
+        }
+        assert_eq!(out, vec![1, 2, 3]);
+    }
+
+    assert!(PreparedMessage::new("/a", &[TypeTag::ArrayStart]).is_err());
+    assert!(PreparedMessage::new("/a", &[TypeTag::ArrayEnd, TypeTag::ArrayStart]).is_err());
+}
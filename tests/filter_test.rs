@@ -0,0 +1,204 @@
+#![cfg(feature = "std")]
+
+extern crate rosc;
+
+use rosc::filter::{self, AddressFilter, Verdict};
+use rosc::{decoder, encoder, OscBundle, OscMessage, OscPacket, OscTime, OscType};
+
+/// Encodes a message with a string argument, then replaces the string with invalid UTF-8.
+fn corrupt_message(addr: &str) -> Vec<u8> {
+    let mut bytes = encoder::encode(&OscPacket::Message(OscMessage {
+        addr: addr.to_string(),
+        args: vec![OscType::String("abc".to_string())],
+    }))
+    .unwrap();
+    let len = bytes.len();
+    bytes[len - 4..len - 1].copy_from_slice(&[0xff, 0xfe, 0xfd]);
+    bytes
+}
+
+fn bundle_bytes(elements: &[Vec<u8>]) -> Vec<u8> {
+    let mut bytes = encoder::encode(&OscPacket::Bundle(OscBundle {
+        timetag: OscTime::from((1, 2)),
+        content: vec![],
+    }))
+    .unwrap();
+    for element in elements {
+        bytes.extend_from_slice(&(element.len() as u32).to_be_bytes());
+        bytes.extend_from_slice(element);
+    }
+    bytes
+}
+
+#[test]
+fn test_check() {
+    let filter =
+        AddressFilter::new(&["/mixer/*", "/transport/{play,stop}"], &["/mixer/master"]).unwrap();
+    assert_eq!(filter.check("/mixer/1"), Verdict::Allow);
+    assert_eq!(filter.check("/transport/stop"), Verdict::Allow);
+    assert_eq!(filter.check("/mixer/master"), Verdict::Deny);
+    assert_eq!(filter.check("/transport/record"), Verdict::Deny);
+    assert_eq!(filter.check("not an address"), Verdict::Deny);
+
+    let deny_only = AddressFilter::new(&[], &["/admin/*"]).unwrap();
+    assert_eq!(deny_only.check("/anything"), Verdict::Allow);
+    assert_eq!(deny_only.check("/admin/reset"), Verdict::Deny);
+
+    assert!(AddressFilter::new(&["no slash"], &[]).is_err());
+}
+
+#[test]
+fn test_denied_message_args_are_not_decoded() {
+    let filter = AddressFilter::new(&["/public/*"], &[]).unwrap();
+
+    let denied = corrupt_message("/private/x");
+    assert!(decoder::decode_udp(&denied).is_err());
+    assert_eq!(filter::decode_udp_filtered(&denied, &filter).unwrap(), None);
+
+    // Allowed messages are fully decoded, including their errors
+    let allowed = corrupt_message("/public/x");
+    assert!(filter::decode_udp_filtered(&allowed, &filter).is_err());
+}
+
+#[test]
+fn test_denied_bundle_elements_are_skipped() {
+    let filter = AddressFilter::new(&[], &["/private/*"]).unwrap();
+    let allowed = OscMessage {
+        addr: "/public/x".to_string(),
+        args: vec![OscType::Int(7)],
+    };
+    let allowed_bytes = encoder::encode(&OscPacket::Message(allowed.clone())).unwrap();
+
+    let inner = bundle_bytes(&[corrupt_message("/private/b"), allowed_bytes.clone()]);
+    let bytes = bundle_bytes(&[corrupt_message("/private/a"), allowed_bytes, inner]);
+
+    assert_eq!(
+        filter::decode_udp_filtered(&bytes, &filter).unwrap(),
+        Some(OscPacket::Bundle(OscBundle {
+            timetag: OscTime::from((1, 2)),
+            content: vec![
+                OscPacket::Message(allowed.clone()),
+                OscPacket::Bundle(OscBundle {
+                    timetag: OscTime::from((1, 2)),
+                    content: vec![OscPacket::Message(allowed)],
+                }),
+            ],
+        }))
+    );
+}
+
+#[test]
+fn test_filtered_truncated_bundle() {
+    let filter = AddressFilter::new(&[], &[]).unwrap();
+    let mut bytes = bundle_bytes(&[corrupt_message("/a")]);
+    bytes.truncate(bytes.len() - 4);
+    match filter::decode_udp_filtered(&bytes, &filter) {
+        Err(rosc::OscError::NeedMoreData { needed }) => assert_eq!(needed, 4),
+        other => panic!("unexpected result {:?}", other),
+    }
+}
+
+#[test]
+fn test_filtered_timetag_as_arg() {
+    let options = decoder::DecodeOptions {
+        timetag_as_arg: true,
+        ..decoder::DecodeOptions::default()
+    };
+    let packet = OscPacket::Bundle(OscBundle {
+        timetag: OscTime::from((3, 4)),
+        content: vec![
+            OscPacket::Message(OscMessage {
+                addr: "/public/level".to_string(),
+                args: vec![OscType::Float(0.5)],
+            }),
+            OscPacket::Bundle(OscBundle {
+                timetag: OscTime::from((5, 6)),
+                content: vec![
+                    OscPacket::Message(OscMessage::from("/public/inner")),
+                    OscPacket::Message(OscMessage::from("/private/inner")),
+                ],
+            }),
+        ],
+    });
+    let bytes = encoder::encode(&packet).unwrap();
+
+    let allow_all = AddressFilter::new(&[], &[]).unwrap();
+    let decoded = decoder::decode_udp_with_options(&bytes, &options)
+        .unwrap()
+        .1;
+    assert_eq!(
+        filter::decode_udp_filtered_with_options(&bytes, &allow_all, &options).unwrap(),
+        Some(decoded)
+    );
+
+    let public = AddressFilter::new(&["/public/*"], &[]).unwrap();
+    assert_eq!(
+        filter::decode_udp_filtered_with_options(&bytes, &public, &options).unwrap(),
+        Some(OscPacket::Bundle(OscBundle {
+            timetag: OscTime::from((3, 4)),
+            content: vec![
+                OscPacket::Message(OscMessage {
+                    addr: "/public/level".to_string(),
+                    args: vec![OscType::Time(OscTime::from((3, 4))), OscType::Float(0.5)],
+                }),
+                OscPacket::Bundle(OscBundle {
+                    timetag: OscTime::from((5, 6)),
+                    content: vec![OscPacket::Message(OscMessage {
+                        addr: "/public/inner".to_string(),
+                        args: vec![OscType::Time(OscTime::from((5, 6)))],
+                    })],
+                }),
+            ],
+        }))
+    );
+
+    // A message that isn't in a bundle is unchanged
+    let message = encoder::encode(&OscPacket::Message(OscMessage::from("/public/a"))).unwrap();
+    assert_eq!(
+        filter::decode_udp_filtered_with_options(&message, &public, &options).unwrap(),
+        Some(OscPacket::Message(OscMessage::from("/public/a")))
+    );
+}
+
+#[test]
+fn test_filtered_max_owned_bytes() {
+    let strings = |addr: &str| {
+        encoder::encode(&OscPacket::Message(OscMessage {
+            addr: addr.to_string(),
+            args: vec![OscType::String("x".repeat(200))],
+        }))
+        .unwrap()
+    };
+    let bytes = bundle_bytes(&[strings("/public/a"), strings("/private/b")]);
+    let options = decoder::DecodeOptions {
+        max_owned_bytes: Some(300),
+        ..decoder::DecodeOptions::default()
+    };
+
+    // Each message fits the budget, but both together don't
+    let allow_all = AddressFilter::new(&[], &[]).unwrap();
+    let unfiltered = decoder::decode_udp_with_options(&bytes, &options).unwrap_err();
+    let filtered =
+        filter::decode_udp_filtered_with_options(&bytes, &allow_all, &options).unwrap_err();
+    match (&unfiltered, &filtered) {
+        (
+            rosc::OscError::MemoryBudgetExceeded { .. },
+            rosc::OscError::MemoryBudgetExceeded { .. },
+        ) => assert_eq!(unfiltered.to_string(), filtered.to_string()),
+        other => panic!("unexpected result {:?}", other),
+    }
+
+    // Denied messages don't count
+    let public = AddressFilter::new(&["/public/*"], &[]).unwrap();
+    let decoded = filter::decode_udp_filtered_with_options(&bytes, &public, &options).unwrap();
+    match decoded {
+        Some(OscPacket::Bundle(bundle)) => assert_eq!(bundle.content.len(), 1),
+        other => panic!("unexpected result {:?}", other),
+    }
+
+    // The budget also applies to nested bundles
+    let nested = bundle_bytes(&[strings("/public/a"), bundle_bytes(&[strings("/public/b")])]);
+    assert!(filter::decode_udp_filtered_with_options(&nested, &public, &options).is_err());
+    let unlimited = decoder::DecodeOptions::default();
+    assert!(filter::decode_udp_filtered_with_options(&nested, &public, &unlimited).is_ok());
+}
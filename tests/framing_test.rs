@@ -0,0 +1,251 @@
+extern crate rosc;
+
+use rosc::framing::{self, Framing};
+use rosc::{decoder, encoder, OscBundle, OscError, OscMessage, OscPacket, OscTime, OscType};
+
+fn packets() -> Vec<OscPacket> {
+    vec![
+        OscPacket::Message(OscMessage::from("/ping")),
+        OscPacket::Message(OscMessage {
+            addr: "/escapes".to_string(),
+            // Blob data containing the SLIP end and escape bytes
+            args: vec![
+                OscType::Blob(vec![0xc0, 0xdb, 0xdc, 0xdd, 0xc0]),
+                OscType::Int(-64),
+            ],
+        }),
+        OscPacket::Bundle(OscBundle {
+            timetag: OscTime::from((0xc0c0_c0c0, 0xdbdb_dbdb)),
+            content: vec![OscPacket::Message(OscMessage::from("/in/bundle"))],
+        }),
+    ]
+}
+
+#[test]
+fn test_datagram_framing_matches_udp() {
+    for packet in packets() {
+        let bytes = framing::encode_framed(&packet, Framing::Datagram).unwrap();
+        assert_eq!(bytes, encoder::encode(&packet).unwrap());
+
+        assert_eq!(
+            framing::decode_framed(&bytes, Framing::Datagram).unwrap(),
+            (bytes.len(), decoder::decode_udp(&bytes).unwrap().1)
+        );
+    }
+
+    // Bytes after a message are not part of the packet
+    let bytes = encoder::encode(&packets()[0]).unwrap();
+    let mut trailing = bytes.clone();
+    trailing.extend_from_slice(&[1, 2, 3, 4]);
+    assert_eq!(
+        framing::decode_framed(&trailing, Framing::Datagram).unwrap(),
+        (bytes.len(), packets()[0].clone())
+    );
+}
+
+#[test]
+fn test_length_prefixed_framing_matches_tcp() {
+    let mut stream = vec![];
+    for packet in packets() {
+        let bytes = framing::encode_framed(&packet, Framing::LengthPrefixed).unwrap();
+        let encoded = encoder::encode(&packet).unwrap();
+        assert_eq!(bytes[..4], (encoded.len() as u32).to_be_bytes());
+        assert_eq!(bytes[4..], encoded[..]);
+        stream.extend(bytes);
+    }
+
+    let (remainder, decoded) = decoder::decode_tcp_vec(&stream).unwrap();
+    assert!(remainder.is_empty());
+    assert_eq!(decoded, packets());
+
+    let mut offset = 0;
+    for packet in packets() {
+        let (len, decoded) =
+            framing::decode_framed(&stream[offset..], Framing::LengthPrefixed).unwrap();
+        assert_eq!(decoded, packet);
+        let (remainder, _) = decoder::decode_tcp(&stream[offset..]).unwrap();
+        assert_eq!(remainder.len(), stream.len() - offset - len);
+        offset += len;
+    }
+    assert_eq!(offset, stream.len());
+
+    // Incomplete frames
+    match framing::decode_framed(&stream[..2], Framing::LengthPrefixed) {
+        Err(OscError::NeedMoreData { needed: 2 }) => (),
+        other => panic!("Expected NeedMoreData, got {:?}", other),
+    }
+    match framing::decode_framed(&stream[..10], Framing::LengthPrefixed) {
+        Err(OscError::NeedMoreData { needed: 6 }) => (),
+        other => panic!("Expected NeedMoreData, got {:?}", other),
+    }
+    assert_eq!(
+        decoder::decode_tcp(&stream[..10]).unwrap(),
+        (&stream[..10], None)
+    );
+}
+
+#[test]
+fn test_length_prefixed_framing_limits_packet_to_frame() {
+    // A bundle whose frame ends within its element
+    let bundle = encoder::encode(&packets()[2]).unwrap();
+    let mut stream = ((bundle.len() - 4) as u32).to_be_bytes().to_vec();
+    stream.extend(&bundle);
+    match framing::decode_framed(&stream, Framing::LengthPrefixed) {
+        Err(OscError::BadPacket(_)) => (),
+        other => panic!("Expected BadPacket, got {:?}", other),
+    }
+    assert!(decoder::decode_tcp(&stream).is_err());
+}
+
+#[test]
+fn test_slip_framing() {
+    let mut stream = vec![];
+    for packet in packets() {
+        let bytes = framing::encode_framed(&packet, Framing::Slip).unwrap();
+        assert_eq!(bytes[0], 0xc0);
+        assert_eq!(bytes[bytes.len() - 1], 0xc0);
+        assert!(!bytes[1..bytes.len() - 1].contains(&0xc0));
+        stream.extend(bytes);
+    }
+
+    // The blob of the second packet is escaped
+    let escaped = framing::encode_framed(&packets()[1], Framing::Slip).unwrap();
+    let encoded = encoder::encode(&packets()[1]).unwrap();
+    assert_eq!(escaped.len(), encoded.len() + 2 + 4);
+    assert!(escaped
+        .windows(8)
+        .any(|w| w == [0xdb, 0xdc, 0xdb, 0xdd, 0xdc, 0xdd, 0xdb, 0xdc]));
+
+    let mut offset = 0;
+    for packet in packets() {
+        let (len, decoded) = framing::decode_framed(&stream[offset..], Framing::Slip).unwrap();
+        assert_eq!(decoded, packet);
+        offset += len;
+    }
+    assert_eq!(offset, stream.len());
+
+    // Single end bytes between packets, as sent by some implementations
+    let single: Vec<u8> = encoder::encode(&packets()[0])
+        .unwrap()
+        .into_iter()
+        .chain(Some(0xc0))
+        .collect();
+    assert_eq!(
+        framing::decode_framed(&single, Framing::Slip).unwrap(),
+        (single.len(), packets()[0].clone())
+    );
+
+    // Incomplete and corrupt frames
+    for incomplete in &[&[][..], &[0xc0, 0xc0], &stream[..5]] {
+        match framing::decode_framed(incomplete, Framing::Slip) {
+            Err(OscError::NeedMoreData { .. }) => (),
+            other => panic!("Expected NeedMoreData, got {:?}", other),
+        }
+    }
+    match framing::decode_framed(&[0xc0, b'/', 0xdb, b'a', 0xc0], Framing::Slip) {
+        Err(OscError::BadPacket(_)) => (),
+        other => panic!("Expected BadPacket, got {:?}", other),
+    }
+}
+
+/// Decodes every packet of `stream`, skipping corrupt frames using `framing::resume_hint`.
+/// Returns the packets and the number of times decoding had to resync.
+fn decode_stream(mut bytes: &[u8], framing: Framing, max_scan: usize) -> (Vec<OscPacket>, usize) {
+    let mut decoded = vec![];
+    let mut resyncs = 0;
+    while !bytes.is_empty() {
+        match framing::decode_framed(bytes, framing) {
+            Ok((len, packet)) => {
+                decoded.push(packet);
+                bytes = &bytes[len..];
+            }
+            Err(OscError::NeedMoreData { .. }) => break,
+            Err(_) => {
+                resyncs += 1;
+                bytes = &bytes[framing::resume_hint(bytes, framing, max_scan)..];
+            }
+        }
+    }
+    (decoded, resyncs)
+}
+
+#[test]
+fn test_length_prefixed_resume_after_corrupt_length() {
+    let packets = packets();
+    let frame =
+        |packet: &OscPacket| framing::encode_framed(packet, Framing::LengthPrefixed).unwrap();
+
+    // Lengths that cut the packet off, including an empty frame
+    for corrupt_len in [4u32, 2, 0] {
+        let mut stream = frame(&packets[0]);
+        stream[..4].copy_from_slice(&corrupt_len.to_be_bytes());
+        stream.extend(frame(&packets[1]));
+        stream.extend(frame(&packets[2]));
+
+        let (decoded, resyncs) = decode_stream(&stream, Framing::LengthPrefixed, 1024);
+        assert_eq!(decoded, packets[1..], "length {}", corrupt_len);
+        assert_eq!(resyncs, 1, "length {}", corrupt_len);
+    }
+
+    // Garbage between frames
+    let mut stream = frame(&packets[0]);
+    stream.extend_from_slice(b"\x00\x00\x00\x08/garbage");
+    stream.extend(frame(&packets[2]));
+    let (decoded, resyncs) = decode_stream(&stream, Framing::LengthPrefixed, 1024);
+    assert_eq!(decoded, vec![packets[0].clone(), packets[2].clone()]);
+    assert_eq!(resyncs, 1);
+}
+
+#[test]
+fn test_length_prefixed_resume_hint_limits_scan() {
+    let packet = OscPacket::Message(OscMessage::from("/ping"));
+    // Zero lengths, which are never plausible
+    let mut stream = vec![0; 64];
+    stream.extend(framing::encode_framed(&packet, Framing::LengthPrefixed).unwrap());
+
+    assert_eq!(
+        framing::resume_hint(&stream, Framing::LengthPrefixed, 1024),
+        64
+    );
+    assert_eq!(
+        framing::resume_hint(&stream, Framing::LengthPrefixed, 16),
+        16
+    );
+    let (decoded, resyncs) = decode_stream(&stream, Framing::LengthPrefixed, 16);
+    assert_eq!(decoded, vec![packet]);
+    assert_eq!(resyncs, 4);
+
+    // The last 4 bytes might be the start of a length prefix
+    assert_eq!(
+        framing::resume_hint(&[0xff; 10], Framing::LengthPrefixed, 1024),
+        6
+    );
+    assert_eq!(
+        framing::resume_hint(&[0xff; 2], Framing::LengthPrefixed, 1024),
+        1
+    );
+    assert_eq!(framing::resume_hint(&[], Framing::LengthPrefixed, 1024), 0);
+}
+
+#[test]
+fn test_slip_resume_after_corrupt_frame() {
+    let packets = packets();
+    let mut stream = framing::encode_framed(&packets[0], Framing::Slip).unwrap();
+    // An invalid escape sequence, then a frame that isn't a packet
+    stream.extend_from_slice(&[0xc0, b'/', 0xdb, b'x', 0xc0]);
+    stream.extend_from_slice(&[0xc0, b'/', b'a', 0xc0]);
+    for packet in &packets[1..] {
+        stream.extend(framing::encode_framed(packet, Framing::Slip).unwrap());
+    }
+
+    let (decoded, resyncs) = decode_stream(&stream, Framing::Slip, 1024);
+    assert_eq!(decoded, packets);
+    assert_eq!(resyncs, 2);
+
+    assert_eq!(
+        framing::resume_hint(&[0xc0, 0xc0, 1, 2, 0xc0, 3], Framing::Slip, 0),
+        4
+    );
+    assert_eq!(framing::resume_hint(&[1, 2, 3], Framing::Slip, 0), 3);
+    assert_eq!(framing::resume_hint(&[1, 2, 3], Framing::Datagram, 0), 3);
+}
@@ -0,0 +1,123 @@
+#![cfg(feature = "std")]
+
+extern crate rosc;
+
+use rosc::address::{Matcher, OscAddress};
+use rosc::gen::{ArgSpec, FloatDist, Generator};
+use rosc::{decoder, encoder, OscPacket, OscTime, OscType};
+
+fn generator(seed: u64) -> Generator {
+    let mut gen = Generator::from_seed(seed);
+    gen.entry(
+        "/mixer/[1-8]/{gain,pan}",
+        vec![
+            ArgSpec::Float(FloatDist::Uniform {
+                min: -1.0,
+                max: 1.0,
+            }),
+            ArgSpec::Int { min: 0, max: 3 },
+        ],
+    )
+    .unwrap()
+    .entry(
+        "/track/*/name?",
+        vec![
+            ArgSpec::alphanumeric(1, 16),
+            ArgSpec::Blob {
+                min_len: 0,
+                max_len: 8,
+            },
+            ArgSpec::Long {
+                min: i64::MIN,
+                max: i64::MAX,
+            },
+        ],
+    )
+    .unwrap()
+    .entry(
+        "/meter/[!a-z]",
+        vec![
+            ArgSpec::Double(FloatDist::Normal {
+                mean: 10.0,
+                std_dev: 2.0,
+            }),
+            ArgSpec::Fixed(OscType::Nil),
+        ],
+    )
+    .unwrap();
+    gen
+}
+
+#[test]
+fn test_generator_matches_entries() {
+    let entries = [
+        ("/mixer/[1-8]/{gain,pan}", "fi"),
+        ("/track/*/name?", "sbh"),
+        ("/meter/[!a-z]", "dN"),
+    ];
+    let mut counts = [0; 3];
+
+    for msg in generator(7).take(300) {
+        let address = OscAddress::new(msg.addr.clone()).unwrap();
+        let index = entries
+            .iter()
+            .position(|&(pattern, _)| Matcher::new(pattern).unwrap().match_address(&address))
+            .unwrap_or_else(|| panic!("{} matches no entry", msg.addr));
+        counts[index] += 1;
+
+        let bytes = encoder::encode(&OscPacket::Message(msg.clone())).unwrap();
+        let (signature, _) = decoder::decode_signature(&bytes).unwrap();
+        assert_eq!(signature.type_tags.as_str(), entries[index].1);
+
+        match &msg.args[..] {
+            [OscType::Float(gain), OscType::Int(mode)] => {
+                assert!((-1.0..1.0).contains(gain));
+                assert!((0..=3).contains(mode));
+            }
+            [OscType::String(name), OscType::Blob(blob), OscType::Long(_)] => {
+                assert!((1..=16).contains(&name.len()));
+                assert!(name.chars().all(|c| c.is_ascii_alphanumeric()));
+                assert!(blob.len() <= 8);
+            }
+            [OscType::Double(level), OscType::Nil] => assert!(level.is_finite()),
+            args => panic!("Unexpected arguments {:?}", args),
+        }
+    }
+    assert!(counts.iter().all(|&count| count > 50), "{:?}", counts);
+}
+
+#[test]
+fn test_generator_is_deterministic() {
+    let first: Vec<_> = generator(1).take(20).collect();
+    assert_eq!(first, generator(1).take(20).collect::<Vec<_>>());
+    assert_ne!(first, generator(2).take(20).collect::<Vec<_>>());
+
+    let bundle = generator(1).bundle(OscTime::IMMEDIATE, 20);
+    assert_eq!(bundle.timetag, OscTime::IMMEDIATE);
+    assert_eq!(
+        bundle.content,
+        first
+            .into_iter()
+            .map(OscPacket::Message)
+            .collect::<Vec<_>>()
+    );
+}
+
+#[test]
+fn test_generator_rejects_invalid_entries() {
+    let mut gen = Generator::from_seed(0);
+    assert!(gen.entry("no/slash", vec![]).is_err());
+    assert!(gen
+        .entry("/a", vec![ArgSpec::Int { min: 1, max: 0 }])
+        .is_err());
+    assert!(gen
+        .entry(
+            "/a",
+            vec![ArgSpec::String {
+                min_len: 0,
+                max_len: 1,
+                charset: String::new(),
+            }],
+        )
+        .is_err());
+}
@@ -0,0 +1,323 @@
+extern crate rosc;
+
+use rosc::{debug, decoder, encoder};
+use rosc::{
+    OscArray, OscBundle, OscColor, OscMessage, OscMidiMessage, OscPacket, OscTime, OscType,
+};
+
+use std::collections::BTreeSet;
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Set to rewrite the golden files from the table in `golden_packets` instead of comparing them,
+/// after an intended change of the wire format.
+const UPDATE_VAR: &str = "ROSC_UPDATE_GOLDEN";
+
+fn golden_dir() -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/golden")
+}
+
+fn message(addr: &str, args: Vec<OscType>) -> OscPacket {
+    OscPacket::Message(OscMessage {
+        addr: addr.to_string(),
+        args,
+    })
+}
+
+/// The packets that each file in `tests/golden` encodes, by file name without the `.osc`.
+///
+/// To add a case, add it here and run the tests with `ROSC_UPDATE_GOLDEN=1` once, then check the
+/// new file, e.g. with `rosc::debug::annotated_dump`, before committing it.
+fn golden_packets() -> Vec<(&'static str, OscPacket)> {
+    vec![
+        ("no_args", message("/ping", vec![])),
+        (
+            "int",
+            message(
+                "/int",
+                vec![
+                    OscType::Int(0),
+                    OscType::Int(-1),
+                    OscType::Int(i32::MAX),
+                    OscType::Int(i32::MIN),
+                ],
+            ),
+        ),
+        (
+            "float",
+            message(
+                "/float",
+                vec![
+                    OscType::Float(0.0),
+                    OscType::Float(-1.5),
+                    OscType::Float(f32::INFINITY),
+                ],
+            ),
+        ),
+        (
+            "string_padding",
+            message(
+                "/strings",
+                vec![
+                    "".into(),
+                    "a".into(),
+                    "ab".into(),
+                    "abc".into(),
+                    "abcd".into(),
+                ],
+            ),
+        ),
+        (
+            "blob_padding",
+            message(
+                "/blobs",
+                (0..5u8)
+                    .map(|len| OscType::Blob((1..=len).collect()))
+                    .collect(),
+            ),
+        ),
+        (
+            "time",
+            message(
+                "/time",
+                vec![
+                    OscType::Time(OscTime::IMMEDIATE),
+                    OscType::Time(OscTime::from((0x89ab_cdef, 0x0123_4567))),
+                ],
+            ),
+        ),
+        (
+            "long",
+            message(
+                "/long",
+                vec![OscType::Long(0x0102_0304_0506_0708), OscType::Long(-2)],
+            ),
+        ),
+        (
+            "double",
+            message(
+                "/double",
+                vec![OscType::Double(0.25), OscType::Double(-1e300)],
+            ),
+        ),
+        (
+            "char",
+            message("/char", vec![OscType::Char('x'), OscType::Char('€')]),
+        ),
+        (
+            "color",
+            message(
+                "/color",
+                vec![OscType::Color(OscColor {
+                    red: 0x11,
+                    green: 0x22,
+                    blue: 0x33,
+                    alpha: 0x44,
+                })],
+            ),
+        ),
+        (
+            "midi",
+            message(
+                "/midi",
+                vec![OscType::Midi(OscMidiMessage {
+                    port: 1,
+                    status: 0x90,
+                    data1: 60,
+                    data2: 127,
+                })],
+            ),
+        ),
+        (
+            "flags",
+            message(
+                "/flags",
+                vec![
+                    OscType::Bool(true),
+                    OscType::Bool(false),
+                    OscType::Nil,
+                    OscType::Inf,
+                ],
+            ),
+        ),
+        (
+            "array",
+            message(
+                "/array",
+                vec![
+                    OscType::Int(1),
+                    OscType::Array(OscArray {
+                        content: vec![
+                            OscType::String("in".to_string()),
+                            OscType::Array(OscArray { content: vec![] }),
+                            OscType::Array(OscArray {
+                                content: vec![OscType::Nil, OscType::Float(2.0)],
+                            }),
+                        ],
+                    }),
+                    OscType::Int(3),
+                ],
+            ),
+        ),
+        (
+            "all_types",
+            message(
+                "/all/types",
+                vec![
+                    OscType::Int(-2),
+                    OscType::Float(-1.5),
+                    OscType::String("osc".to_string()),
+                    OscType::Blob(vec![1, 2, 3, 4, 5]),
+                    OscType::Time(OscTime::from((1, 2))),
+                    OscType::Long(1 << 40),
+                    OscType::Double(0.5),
+                    OscType::Char('c'),
+                    OscType::Color(OscColor {
+                        red: 1,
+                        green: 2,
+                        blue: 3,
+                        alpha: 4,
+                    }),
+                    OscType::Midi(OscMidiMessage {
+                        port: 0,
+                        status: 0x80,
+                        data1: 64,
+                        data2: 0,
+                    }),
+                    OscType::Bool(true),
+                    OscType::Bool(false),
+                    OscType::Nil,
+                    OscType::Inf,
+                    OscType::Array(OscArray {
+                        content: vec![OscType::Int(7)],
+                    }),
+                ],
+            ),
+        ),
+        (
+            "empty_bundle",
+            OscPacket::Bundle(OscBundle {
+                timetag: OscTime::IMMEDIATE,
+                content: vec![],
+            }),
+        ),
+        (
+            "nested_bundle",
+            OscPacket::Bundle(OscBundle {
+                timetag: OscTime::from((0x0102_0304, 0x0506_0708)),
+                content: vec![
+                    message("/first", vec![OscType::Int(1)]),
+                    OscPacket::Bundle(OscBundle {
+                        timetag: OscTime::from((0x0a0b_0c0d, 0)),
+                        content: vec![
+                            message("/inner", vec![OscType::String("deep".to_string())]),
+                            OscPacket::Bundle(OscBundle {
+                                timetag: OscTime::IMMEDIATE,
+                                content: vec![],
+                            }),
+                        ],
+                    }),
+                    message("/last", vec![]),
+                ],
+            }),
+        ),
+    ]
+}
+
+/// Describes how `actual` differs from the golden `expected` bytes.
+fn describe_mismatch(expected: &[u8], actual: &[u8]) -> String {
+    let offset = expected
+        .iter()
+        .zip(actual)
+        .position(|(a, b)| a != b)
+        .unwrap_or_else(|| expected.len().min(actual.len()));
+    format!(
+        "first difference at offset {:#x}\nexpected:\n{}actual:\n{}",
+        offset,
+        debug::annotated_dump(expected),
+        debug::annotated_dump(actual)
+    )
+}
+
+#[test]
+fn test_golden_encode() {
+    let update = env::var_os(UPDATE_VAR).is_some();
+    let mut failures = vec![];
+    for (name, packet) in golden_packets() {
+        let path = golden_dir().join(format!("{}.osc", name));
+        let encoded = encoder::encode(&packet).unwrap();
+        if update {
+            fs::write(&path, &encoded).unwrap();
+            continue;
+        }
+
+        match fs::read(&path) {
+            Ok(golden) if golden == encoded => (),
+            Ok(golden) => failures.push(format!(
+                "{}: {}",
+                name,
+                describe_mismatch(&golden, &encoded)
+            )),
+            Err(err) => failures.push(format!("{}: {}", path.display(), err)),
+        }
+    }
+    assert!(
+        failures.is_empty(),
+        "Encoding differs from the golden files (set {} to update them):\n{}",
+        UPDATE_VAR,
+        failures.join("\n")
+    );
+}
+
+#[test]
+fn test_golden_decode() {
+    let mut failures = vec![];
+    for (name, packet) in golden_packets() {
+        let path = golden_dir().join(format!("{}.osc", name));
+        // A missing file is reported by `test_golden_encode`
+        let golden = match fs::read(&path) {
+            Ok(golden) => golden,
+            Err(_) => continue,
+        };
+
+        match decoder::decode_udp(&golden) {
+            Ok((remainder, decoded)) if decoded == packet && remainder.is_empty() => (),
+            Ok((remainder, decoded)) => failures.push(format!(
+                "{}: decoded {:?} with {} bytes left, expected {:?}",
+                name,
+                decoded,
+                remainder.len(),
+                packet
+            )),
+            Err(err) => failures.push(format!("{}: {}", name, err)),
+        }
+    }
+    assert!(
+        failures.is_empty(),
+        "Decoding the golden files failed:\n{}",
+        failures.join("\n")
+    );
+}
+
+#[test]
+fn test_golden_files_have_packets() {
+    let names: BTreeSet<String> = golden_packets()
+        .into_iter()
+        .map(|(name, _)| name.to_string())
+        .collect();
+    let files: BTreeSet<String> = fs::read_dir(golden_dir())
+        .unwrap()
+        .map(|entry| entry.unwrap().path())
+        .filter(|path| path.extension().is_some_and(|ext| ext == "osc"))
+        .map(|path| path.file_stem().unwrap().to_string_lossy().into_owned())
+        .collect();
+
+    let orphans: Vec<_> = files.difference(&names).collect();
+    assert!(
+        orphans.is_empty(),
+        "Golden files without a packet: {:?}",
+        orphans
+    );
+    assert_eq!(names.len(), golden_packets().len(), "Duplicate names");
+}
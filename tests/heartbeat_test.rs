@@ -0,0 +1,165 @@
+#![cfg(feature = "std")]
+
+extern crate rosc;
+
+use rosc::heartbeat::{Heartbeat, DEFAULT_ADDRESS, MAX_REORDER};
+use rosc::{OscMessage, OscType};
+use std::time::{Duration, Instant};
+
+fn secs(s: u64) -> Duration {
+    Duration::from_secs(s)
+}
+
+#[test]
+fn test_heartbeat_due_ping() {
+    let start = Instant::now();
+    let mut heartbeat = Heartbeat::new(secs(1), secs(3));
+    assert_eq!(heartbeat.address(), DEFAULT_ADDRESS);
+
+    let ping = heartbeat
+        .due_ping(start)
+        .expect("First ping is due immediately");
+    assert_eq!(ping.addr, "/rosc/ping");
+    assert_eq!(ping.args, vec![OscType::Long(0)]);
+
+    assert!(heartbeat.due_ping(start).is_none());
+    assert!(heartbeat
+        .due_ping(start + Duration::from_millis(999))
+        .is_none());
+    assert_eq!(
+        heartbeat.due_ping(start + secs(1)).unwrap().args,
+        vec![OscType::Long(1)]
+    );
+    assert_eq!(
+        heartbeat.due_ping(start + secs(5)).unwrap().args,
+        vec![OscType::Long(2)]
+    );
+    assert!(heartbeat.due_ping(start + secs(5)).is_none());
+}
+
+#[test]
+fn test_heartbeat_missed_beats_and_recovery() {
+    let start = Instant::now();
+    let mut sender = Heartbeat::with_address("/alive", secs(1), secs(3));
+    let mut receiver = Heartbeat::with_address("/alive", secs(1), secs(3));
+    assert!(!receiver.is_alive(start));
+
+    // Pings 0 and 1 arrive
+    for t in 0..2 {
+        let ping = sender.due_ping(start + secs(t)).unwrap();
+        assert!(receiver.observe(&ping, start + secs(t)));
+    }
+    assert!(receiver.is_alive(start + secs(1)));
+    assert_eq!(receiver.missed_count(), 0);
+
+    // Pings 2 to 5 get lost, so the peer is considered dead after the timeout
+    for t in 2..6 {
+        sender.due_ping(start + secs(t)).unwrap();
+    }
+    assert!(receiver.is_alive(start + secs(4)));
+    assert!(!receiver.is_alive(start + secs(5)));
+
+    // Ping 6 arrives again
+    let ping = sender.due_ping(start + secs(6)).unwrap();
+    assert!(receiver.observe(&ping, start + secs(6)));
+    assert!(receiver.is_alive(start + secs(6)));
+    assert_eq!(receiver.missed_count(), 4);
+    assert_eq!(receiver.restart_count(), 0);
+}
+
+#[test]
+fn test_heartbeat_detects_restart() {
+    let start = Instant::now();
+    let mut receiver = Heartbeat::new(secs(1), secs(3));
+
+    let mut sender = Heartbeat::new(secs(1), secs(3));
+    for t in 0..3 {
+        let ping = sender.due_ping(start + secs(t)).unwrap();
+        receiver.observe(&ping, start + secs(t));
+    }
+
+    // The peer restarts and counts from zero again
+    let mut sender = Heartbeat::new(secs(1), secs(3));
+    let ping = sender.due_ping(start + secs(3)).unwrap();
+    assert!(receiver.observe(&ping, start + secs(3)));
+    assert_eq!(receiver.restart_count(), 1);
+    assert_eq!(receiver.missed_count(), 0);
+    assert!(receiver.is_alive(start + secs(3)));
+}
+
+#[test]
+fn test_heartbeat_duplicated_pings() {
+    let start = Instant::now();
+    let mut sender = Heartbeat::new(secs(1), secs(3));
+    let mut receiver = Heartbeat::new(secs(1), secs(3));
+
+    for t in 0..3 {
+        let ping = sender.due_ping(start + secs(t)).unwrap();
+        assert!(receiver.observe(&ping, start + secs(t)));
+        assert!(receiver.observe(&ping, start + secs(t)));
+    }
+    assert_eq!(receiver.restart_count(), 0);
+    assert_eq!(receiver.missed_count(), 0);
+}
+
+#[test]
+fn test_heartbeat_huge_sequence_jumps() {
+    let start = Instant::now();
+    let mut receiver = Heartbeat::new(secs(1), secs(3));
+    let ping = |sequence| OscMessage {
+        addr: DEFAULT_ADDRESS.to_string(),
+        args: vec![OscType::Long(sequence)],
+    };
+
+    // The missed count saturates instead of overflowing
+    for &sequence in &[i64::MIN, i64::MAX, 0, i64::MAX] {
+        assert!(receiver.observe(&ping(sequence), start));
+    }
+    assert_eq!(receiver.missed_count(), u64::MAX);
+    assert_eq!(receiver.restart_count(), 1);
+}
+
+#[test]
+fn test_heartbeat_reordered_pings() {
+    let start = Instant::now();
+    let mut sender = Heartbeat::new(secs(1), secs(3));
+    let mut receiver = Heartbeat::new(secs(1), secs(3));
+    let pings: Vec<_> = (0..6)
+        .map(|t| sender.due_ping(start + secs(t)).unwrap())
+        .collect();
+
+    // Ping 2 arrives after ping 4, and then once more
+    for &i in &[0, 1, 3, 4] {
+        assert!(receiver.observe(&pings[i], start + secs(i as u64)));
+    }
+    assert_eq!(receiver.missed_count(), 1);
+    assert!(receiver.observe(&pings[2], start + secs(4)));
+    assert!(receiver.observe(&pings[2], start + secs(4)));
+    assert!(receiver.observe(&pings[5], start + secs(5)));
+    assert_eq!(receiver.missed_count(), 0);
+    assert_eq!(receiver.restart_count(), 0);
+
+    // A ping far behind the newest one is a restart, even if it isn't 0
+    let mut receiver = Heartbeat::new(secs(1), secs(3));
+    for t in 0..(MAX_REORDER as u64 + 10) {
+        receiver.observe(&sender.due_ping(start + secs(6 + t)).unwrap(), start);
+    }
+    assert!(receiver.observe(&pings[1], start));
+    assert_eq!(receiver.restart_count(), 1);
+}
+
+#[test]
+fn test_heartbeat_ignores_other_messages() {
+    let start = Instant::now();
+    let mut receiver = Heartbeat::new(secs(1), secs(3));
+
+    assert!(!receiver.observe(&OscMessage::from("/synth/freq"), start));
+    // Right address, but no sequence number
+    assert!(!receiver.observe(&OscMessage::from(DEFAULT_ADDRESS), start));
+    let msg = OscMessage {
+        addr: DEFAULT_ADDRESS.to_string(),
+        args: vec![OscType::Int(1)],
+    };
+    assert!(!receiver.observe(&msg, start));
+    assert!(!receiver.is_alive(start));
+}
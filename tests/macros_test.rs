@@ -0,0 +1,142 @@
+#[macro_use]
+extern crate rosc;
+
+use rosc::{OscArray, OscBundle, OscMessage, OscPacket, OscTime, OscType};
+
+#[test]
+fn test_osc_msg() {
+    assert_eq!(osc_msg!("/empty"), OscMessage::from("/empty"));
+    assert_eq!(osc_msg!("/trailing", 1,), osc_msg!("/trailing", 1));
+
+    let name = String::from("saw");
+    let samples = vec![4u8, 5, 6];
+    let msg = osc_msg!(
+        format!("/synth/{}", 1),
+        440.0f32,
+        2.5,
+        1,
+        -7i64,
+        true,
+        'x',
+        "sine",
+        name.clone(),
+        (1, 2),
+        OscArray {
+            content: vec![OscType::Int(1)],
+        },
+        int(3.9),
+        long(5),
+        float(880),
+        double(0.5f32),
+        string("str"),
+        blob(samples),
+        blob(&samples[..1]),
+        blob([7, 8]),
+        nil,
+        inf,
+    );
+
+    assert_eq!(
+        msg,
+        OscMessage {
+            addr: "/synth/1".to_string(),
+            args: vec![
+                OscType::Float(440.0),
+                OscType::Double(2.5),
+                OscType::Int(1),
+                OscType::Long(-7),
+                OscType::Bool(true),
+                OscType::Char('x'),
+                OscType::String("sine".to_string()),
+                OscType::String("saw".to_string()),
+                OscType::Time(OscTime::from((1, 2))),
+                OscType::Array(OscArray {
+                    content: vec![OscType::Int(1)],
+                }),
+                OscType::Int(3),
+                OscType::Long(5),
+                OscType::Float(880.0),
+                OscType::Double(0.5),
+                OscType::String("str".to_string()),
+                OscType::Blob(vec![4, 5, 6]),
+                OscType::Blob(vec![4]),
+                OscType::Blob(vec![7, 8]),
+                OscType::Nil,
+                OscType::Inf,
+            ],
+        }
+    );
+}
+
+#[test]
+fn test_osc_bundle() {
+    let later = OscTime::from((3_900_000_000, 0));
+    let bundle = osc_bundle![OscTime::IMMEDIATE;
+        osc_msg!("/play", 1),
+        osc_bundle![later; osc_msg!("/stop")],
+        osc_bundle![(5, 6);],
+        OscPacket::Message(OscMessage::from("/packet")),
+    ];
+
+    assert_eq!(
+        bundle,
+        OscBundle {
+            timetag: OscTime::IMMEDIATE,
+            content: vec![
+                OscPacket::Message(OscMessage {
+                    addr: "/play".to_string(),
+                    args: vec![OscType::Int(1)],
+                }),
+                OscPacket::Bundle(OscBundle {
+                    timetag: later,
+                    content: vec![OscPacket::Message(OscMessage::from("/stop"))],
+                }),
+                OscPacket::Bundle(OscBundle {
+                    timetag: OscTime::from((5, 6)),
+                    content: vec![],
+                }),
+                OscPacket::Message(OscMessage::from("/packet")),
+            ],
+        }
+    );
+}
+
+#[test]
+fn test_assert_osc_approx_eq() {
+    use rosc::FloatTolerance;
+    use std::panic;
+
+    let tol = FloatTolerance {
+        absolute: 0.01,
+        relative: 0.0,
+    };
+    assert_osc_approx_eq!(
+        osc_msg!("/xy", 0.5f32, 1, 0.25),
+        osc_msg!("/xy", 0.505f32, 1, 0.255),
+        tol
+    );
+
+    let failure = |left: OscMessage, right: OscMessage| {
+        let result = panic::catch_unwind(|| assert_osc_approx_eq!(left, right, tol));
+        let payload = result.unwrap_err();
+        payload.downcast_ref::<String>().unwrap().clone()
+    };
+
+    let message = failure(osc_msg!("/xy", 0.5f32, 1), osc_msg!("/xy", 0.6f32, 1));
+    assert!(
+        message.starts_with(
+            "assertion `left ≈ right` failed: argument 0 differs: Float(0.5) vs Float(0.6)\n"
+        ),
+        "{}",
+        message
+    );
+    assert!(message.contains("\n  left: OscMessage { addr: \"/xy\""));
+    assert!(message.contains("\n right: OscMessage { addr: \"/xy\""));
+
+    let message = failure(osc_msg!("/xy", 1, 0.5), osc_msg!("/xy", 2, 0.5));
+    assert!(message.contains("argument 0 differs: Int(1) vs Int(2)"));
+    let message = failure(osc_msg!("/x", 1), osc_msg!("/y", 1));
+    assert!(message.contains("addresses differ: \"/x\" vs \"/y\""));
+    let message = failure(osc_msg!("/xy", 1), osc_msg!("/xy", 1, 2));
+    assert!(message.contains("argument counts differ: 1 vs 2"));
+}
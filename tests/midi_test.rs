@@ -0,0 +1,157 @@
+#![cfg(feature = "midly")]
+
+extern crate midly;
+extern crate rosc;
+
+use midly::live::{LiveEvent, SystemRealtime};
+use midly::{MidiMessage, PitchBend};
+use rosc::{encoder, OscError, OscMessage, OscMidiMessage, OscPacket, OscType};
+use std::convert::TryFrom;
+
+fn channel_messages() -> Vec<(LiveEvent<'static>, [u8; 3])> {
+    let event = |channel: u8, message| LiveEvent::Midi {
+        channel: channel.into(),
+        message,
+    };
+    vec![
+        (
+            event(
+                0,
+                MidiMessage::NoteOn {
+                    key: 60.into(),
+                    vel: 127.into(),
+                },
+            ),
+            [0x90, 60, 127],
+        ),
+        (
+            event(
+                15,
+                MidiMessage::NoteOff {
+                    key: 61.into(),
+                    vel: 0.into(),
+                },
+            ),
+            [0x8f, 61, 0],
+        ),
+        (
+            event(
+                3,
+                MidiMessage::Controller {
+                    controller: 7.into(),
+                    value: 100.into(),
+                },
+            ),
+            [0xb3, 7, 100],
+        ),
+        (
+            event(9, MidiMessage::ProgramChange { program: 42.into() }),
+            [0xc9, 42, 0],
+        ),
+        (
+            event(
+                1,
+                MidiMessage::PitchBend {
+                    bend: PitchBend(0x2abc.into()),
+                },
+            ),
+            [0xe1, 0x3c, 0x55],
+        ),
+        (
+            event(
+                1,
+                MidiMessage::PitchBend {
+                    bend: PitchBend::max_raw_value(),
+                },
+            ),
+            [0xe1, 0x7f, 0x7f],
+        ),
+    ]
+}
+
+#[test]
+fn test_live_event_round_trip() {
+    for (event, [status, data1, data2]) in channel_messages() {
+        let msg = OscMidiMessage::from_live_event(&event, 5).unwrap();
+        assert_eq!(
+            msg,
+            OscMidiMessage {
+                port: 5,
+                status,
+                data1,
+                data2,
+            }
+        );
+        assert_eq!(msg.to_live_event().unwrap(), event);
+        assert_eq!(LiveEvent::try_from(&msg).unwrap(), event);
+        assert_eq!(
+            OscMidiMessage::try_from(event).unwrap(),
+            OscMidiMessage { port: 0, ..msg }
+        );
+    }
+}
+
+#[test]
+fn test_live_event_wire_bytes() {
+    for (event, _) in channel_messages() {
+        let mut midi_bytes = vec![];
+        event.write_std(&mut midi_bytes).unwrap();
+
+        // The MIDI argument carries the port followed by the bytes midly writes, padded with a
+        // zero for messages with a single data byte
+        let msg = OscMidiMessage::from_live_event(&event, 7).unwrap();
+        let osc_bytes = encoder::encode(&OscPacket::Message(OscMessage {
+            addr: "/midi".to_string(),
+            args: vec![OscType::Midi(msg)],
+        }))
+        .unwrap();
+        let arg = &osc_bytes[osc_bytes.len() - 4..];
+        assert_eq!(arg[0], 7);
+        assert_eq!(&arg[1..1 + midi_bytes.len()], &midi_bytes[..]);
+        assert!(arg[1 + midi_bytes.len()..].iter().all(|&b| b == 0));
+
+        assert_eq!(LiveEvent::parse(&midi_bytes).unwrap(), event);
+    }
+}
+
+#[test]
+fn test_live_event_errors() {
+    match OscMidiMessage::from_live_event(&LiveEvent::Realtime(SystemRealtime::TimingClock), 0) {
+        Err(OscError::BadArg(_)) => (),
+        other => panic!("Expected BadArg, got {:?}", other),
+    }
+    let sysex = LiveEvent::parse(&[0xf0, 1, 2, 0xf7]).unwrap();
+    assert!(OscMidiMessage::try_from(sysex).is_err());
+
+    let invalid = [
+        // System messages
+        [0xf0, 0, 0],
+        [0xf8, 0, 0],
+        // Running status, without a status byte
+        [0x40, 0, 0],
+        // Data bytes with the top bit set
+        [0x90, 0x80, 0],
+        [0xb0, 1, 0xff],
+    ];
+    for [status, data1, data2] in invalid.iter().copied() {
+        let msg = OscMidiMessage {
+            port: 0,
+            status,
+            data1,
+            data2,
+        };
+        match msg.to_live_event() {
+            Err(OscError::BadArg(_)) => (),
+            other => panic!("Expected BadArg for {:?}, got {:?}", msg, other),
+        }
+    }
+
+    // The unused second data byte of a program change is ignored
+    let msg = OscMidiMessage {
+        port: 0,
+        status: 0xc0,
+        data1: 1,
+        data2: 0xff,
+    };
+    assert!(msg.to_live_event().is_ok());
+}
@@ -0,0 +1,275 @@
+extern crate rosc;
+
+use rosc::decoder::{self, DecodeOptions};
+use rosc::encoder::{self, EncodeOptions};
+use rosc::framing::Framing;
+use rosc::{
+    NumericByteOrder, OscArray, OscBundle, OscError, OscMessage, OscPacket, OscTime, OscType,
+    SpecProfile,
+};
+
+const PROFILES: [SpecProfile; 3] = [
+    SpecProfile::Osc10Strict,
+    SpecProfile::Osc11,
+    SpecProfile::Permissive,
+];
+
+fn chord() -> OscPacket {
+    OscPacket::Message(OscMessage {
+        addr: "/chord".to_string(),
+        args: vec![OscType::Array(OscArray {
+            content: vec![OscType::Int(60), OscType::Int(64), OscType::Int(67)],
+        })],
+    })
+}
+
+#[test]
+fn test_default_profile_is_permissive() {
+    assert_eq!(SpecProfile::default(), SpecProfile::Permissive);
+    assert_eq!(
+        DecodeOptions::for_profile(SpecProfile::Permissive),
+        DecodeOptions::default()
+    );
+    assert_eq!(
+        EncodeOptions::for_profile(SpecProfile::Permissive),
+        EncodeOptions::default()
+    );
+}
+
+#[test]
+fn test_decode_arrays_by_profile() {
+    let bytes = encoder::encode(&chord()).unwrap();
+
+    let strict = DecodeOptions::for_profile(SpecProfile::Osc10Strict);
+    match decoder::decode_udp_with_options(&bytes, &strict) {
+        Err(OscError::BadArg(_)) => (),
+        other => panic!("unexpected result {:?}", other),
+    }
+    for profile in &[SpecProfile::Osc11, SpecProfile::Permissive] {
+        let options = DecodeOptions::for_profile(*profile);
+        assert_eq!(
+            decoder::decode_udp_with_options(&bytes, &options)
+                .unwrap()
+                .1,
+            chord()
+        );
+    }
+
+    // A rejected message fails its enclosing bundle instead of just ending it
+    let bundle = encoder::encode(&OscPacket::Bundle(OscBundle {
+        timetag: OscTime::from((0, 1)),
+        content: vec![OscPacket::Message(OscMessage::from("/before")), chord()],
+    }))
+    .unwrap();
+    assert!(decoder::decode_udp_with_options(&bundle, &strict).is_err());
+
+    // Overriding the type tags of a profile
+    let with_arrays = DecodeOptions {
+        allowed_type_tags: Some("ifsb[]".to_string()),
+        ..DecodeOptions::for_profile(SpecProfile::Osc10Strict)
+    };
+    assert!(decoder::decode_udp_with_options(&bytes, &with_arrays).is_ok());
+}
+
+#[test]
+fn test_decode_type_tags_by_profile() {
+    let long = encoder::encode(&OscPacket::Message(OscMessage {
+        addr: "/long".to_string(),
+        args: vec![OscType::Long(1)],
+    }))
+    .unwrap();
+    let flags = encoder::encode(&OscPacket::Message(OscMessage {
+        addr: "/flags".to_string(),
+        args: vec![
+            OscType::Bool(true),
+            OscType::Bool(false),
+            OscType::Nil,
+            OscType::Inf,
+        ],
+    }))
+    .unwrap();
+
+    let decodes = |bytes: &[u8], profile| {
+        decoder::decode_udp_with_options(bytes, &DecodeOptions::for_profile(profile)).is_ok()
+    };
+    assert!(!decodes(&long, SpecProfile::Osc10Strict));
+    assert!(!decodes(&long, SpecProfile::Osc11));
+    assert!(decodes(&long, SpecProfile::Permissive));
+    assert!(!decodes(&flags, SpecProfile::Osc10Strict));
+    assert!(decodes(&flags, SpecProfile::Osc11));
+    assert!(decodes(&flags, SpecProfile::Permissive));
+}
+
+#[test]
+fn test_decode_strict_padding() {
+    let padded = b"/ping\0\0\0,\0\0\0";
+    let nonzero = b"/ping\0\xff\0,\0\0\0";
+
+    for profile in &PROFILES {
+        let options = DecodeOptions::for_profile(*profile);
+        assert!(decoder::decode_udp_with_options(padded, &options).is_ok());
+        let result = decoder::decode_udp_with_options(nonzero, &options);
+        match profile {
+            SpecProfile::Permissive => assert!(result.is_ok()),
+            _ => match result {
+                Err(OscError::BadPacket(_)) => (),
+                other => panic!("unexpected result {:?}", other),
+            },
+        }
+    }
+
+    let lenient = DecodeOptions {
+        strict_padding: false,
+        ..DecodeOptions::for_profile(SpecProfile::Osc10Strict)
+    };
+    assert!(decoder::decode_udp_with_options(nonzero, &lenient).is_ok());
+
+    // Bytes following the packet aren't padding
+    let mut trailing = padded.to_vec();
+    trailing.extend_from_slice(b"\xff\xff\xff\xff");
+    let strict = DecodeOptions::for_profile(SpecProfile::Osc10Strict);
+    assert!(decoder::decode_udp_with_options(&trailing, &strict).is_ok());
+
+    // Numeric data is compared in the decoded byte order
+    let packet = OscPacket::Message(OscMessage {
+        addr: "/level".to_string(),
+        args: vec![OscType::Int(1), OscType::Float(0.5)],
+    });
+    let little_encode = EncodeOptions {
+        numeric_byte_order: NumericByteOrder::Little,
+        ..EncodeOptions::default()
+    };
+    let little_decode = DecodeOptions {
+        numeric_byte_order: NumericByteOrder::Little,
+        ..strict
+    };
+    let bytes = encoder::encode_with_options(&packet, &little_encode).unwrap();
+    assert_eq!(
+        decoder::decode_udp_with_options(&bytes, &little_decode)
+            .unwrap()
+            .1,
+        packet
+    );
+}
+
+#[test]
+fn test_decode_to_sink_by_profile() {
+    use rosc::decoder::DecodeSink;
+    use rosc::OscTypeRef;
+
+    struct Ignore;
+    impl DecodeSink for Ignore {
+        fn on_message_start(&mut self, _addr: &str) {}
+        fn on_arg(&mut self, _arg: OscTypeRef) {}
+        fn on_array_start(&mut self) {}
+        fn on_array_end(&mut self) {}
+        fn on_message_end(&mut self) {}
+        fn on_bundle_start(&mut self, _timetag: OscTime) {}
+        fn on_bundle_end(&mut self) {}
+    }
+
+    let strict = DecodeOptions::for_profile(SpecProfile::Osc10Strict);
+    let bytes = encoder::encode(&chord()).unwrap();
+    assert!(decoder::decode_to_sink_with_options(&bytes, &strict, &mut Ignore).is_err());
+    assert!(
+        decoder::decode_to_sink_with_options(b"/ping\0\xff\0,\0\0\0", &strict, &mut Ignore)
+            .is_err()
+    );
+    assert!(
+        decoder::decode_to_sink_with_options(b"/ping\0\0\0,\0\0\0", &strict, &mut Ignore).is_ok()
+    );
+}
+
+#[test]
+fn test_encode_by_profile() {
+    let strict = EncodeOptions::for_profile(SpecProfile::Osc10Strict);
+    match encoder::encode_with_options(&chord(), &strict) {
+        Err(OscError::BadArg(_)) => (),
+        other => panic!("unexpected result {:?}", other),
+    }
+    // Arguments of nested bundles are checked as well
+    let bundle = OscPacket::Bundle(OscBundle {
+        timetag: OscTime::from((0, 1)),
+        content: vec![OscPacket::Bundle(OscBundle {
+            timetag: OscTime::from((0, 1)),
+            content: vec![chord()],
+        })],
+    });
+    assert!(encoder::encode_with_options(&bundle, &strict).is_err());
+
+    let osc_1_1 = EncodeOptions::for_profile(SpecProfile::Osc11);
+    assert_eq!(
+        encoder::encode_with_options(&bundle, &osc_1_1).unwrap(),
+        encoder::encode(&bundle).unwrap()
+    );
+    let long = OscPacket::Message(OscMessage {
+        addr: "/long".to_string(),
+        args: vec![OscType::Long(1)],
+    });
+    assert!(encoder::encode_with_options(&long, &osc_1_1).is_err());
+    assert!(encoder::encode_with_options(&long, &EncodeOptions::default()).is_ok());
+}
+
+#[test]
+#[cfg(feature = "std")]
+fn test_pattern_syntax_by_profile() {
+    use rosc::address::{Matcher, OscAddress, PatternSyntax};
+
+    let matches = |pattern: &str, address: &str| {
+        Matcher::with_syntax(pattern, PatternSyntax::for_profile(SpecProfile::Osc11))
+            .unwrap()
+            .match_address(&OscAddress::new(address.to_string()).unwrap())
+    };
+
+    for profile in &[SpecProfile::Osc10Strict, SpecProfile::Permissive] {
+        let syntax = PatternSyntax::for_profile(*profile);
+        assert_eq!(syntax, PatternSyntax::Osc10);
+        assert!(Matcher::with_syntax("//volume", syntax).is_err());
+        assert!(Matcher::with_syntax("/mixer//volume", syntax).is_err());
+    }
+    assert_eq!(
+        PatternSyntax::for_profile(SpecProfile::Osc11),
+        PatternSyntax::Osc11
+    );
+
+    assert!(matches("//volume", "/volume"));
+    assert!(matches("//volume", "/mixer/volume"));
+    assert!(matches("//volume", "/mixer/1/volume"));
+    assert!(!matches("//volume", "/mixer/volumes"));
+    assert!(!matches("//volume", "/mixer/mastervolume"));
+    assert!(!matches("//volume", "/volume/1"));
+
+    assert!(matches("/mixer//gain", "/mixer/gain"));
+    assert!(matches("/mixer//gain", "/mixer/1/eq/gain"));
+    assert!(!matches("/mixer//gain", "/mixers/gain"));
+    assert!(!matches("/mixer//gain", "/fx/mixer/gain"));
+
+    assert!(matches("//{gain,pan}", "/ch/1/pan"));
+    assert!(matches("/ch//?", "/ch/1/2"));
+    assert!(!matches("/ch//?", "/ch/1/23"));
+    assert!(matches("//ch//mute", "/desk/ch/3/mute"));
+    // Patterns without `//` mean the same in both syntaxes
+    assert!(matches("/ch/*/mute", "/ch/3/mute"));
+    assert!(!matches("/ch/*/mute", "/ch/3/4/mute"));
+
+    for invalid in &["//", "/a//", "///a", "/a///b", "volume"] {
+        assert!(
+            Matcher::with_syntax(invalid, PatternSyntax::Osc11).is_err(),
+            "{:?}",
+            invalid
+        );
+    }
+}
+
+#[test]
+fn test_framing_by_profile() {
+    assert_eq!(
+        Framing::for_profile(SpecProfile::Osc10Strict),
+        Framing::LengthPrefixed
+    );
+    assert_eq!(Framing::for_profile(SpecProfile::Osc11), Framing::Slip);
+    assert_eq!(
+        Framing::for_profile(SpecProfile::Permissive),
+        Framing::LengthPrefixed
+    );
+}
@@ -0,0 +1,77 @@
+extern crate rosc;
+
+use rosc::{decoder, encoder, query, OscBundle, OscMessage, OscPacket, OscTime, OscType};
+
+#[test]
+fn test_list_request() {
+    let request = query::list_request();
+    assert_eq!(request.addr, "/");
+    assert!(request.args.is_empty());
+    assert!(query::is_list_request(&request));
+
+    assert_eq!(query::list_request_for("/a/b").addr, "/a/b/");
+    assert_eq!(query::list_request_for("/a/b/").addr, "/a/b/");
+    assert!(!query::is_list_request(&OscMessage::from("/a/b")));
+}
+
+#[test]
+fn test_list_round_trip() {
+    let request = query::list_request_for("/mixer");
+    let bytes = encoder::encode(&OscPacket::Message(request)).unwrap();
+
+    // Server side
+    let request = match decoder::decode_udp(&bytes).unwrap().1 {
+        OscPacket::Message(msg) => msg,
+        _ => panic!("Expected an OscMessage!"),
+    };
+    assert!(query::is_list_request(&request));
+    let reply = query::list_reply(&request, &["channel/", "master/", "mute"]);
+    let bytes = encoder::encode(&reply).unwrap();
+
+    // Client side
+    let reply = decoder::decode_udp(&bytes).unwrap().1;
+    assert_eq!(
+        query::parse_list_reply(&reply),
+        Some(vec![
+            "channel/".to_string(),
+            "master/".to_string(),
+            "mute".to_string()
+        ])
+    );
+}
+
+#[test]
+fn test_parse_list_reply() {
+    let names = |names: &[&str]| {
+        OscPacket::Message(OscMessage {
+            addr: "/".to_string(),
+            args: names.iter().map(|&name| name.into()).collect(),
+        })
+    };
+
+    // Replies split across several messages of a bundle are joined
+    let reply = OscPacket::Bundle(OscBundle {
+        timetag: OscTime::IMMEDIATE,
+        content: vec![names(&["a", "b"]), names(&[]), names(&["c/"])],
+    });
+    assert_eq!(
+        query::parse_list_reply(&reply),
+        Some(vec!["a".to_string(), "b".to_string(), "c/".to_string()])
+    );
+    assert_eq!(
+        query::parse_list_reply(&names(&["x"])),
+        Some(vec!["x".to_string()])
+    );
+
+    // Invalid replies
+    let invalid = OscPacket::Message(OscMessage {
+        addr: "/".to_string(),
+        args: vec!["a".into(), OscType::Int(1)],
+    });
+    assert_eq!(query::parse_list_reply(&invalid), None);
+    let nested = OscPacket::Bundle(OscBundle {
+        timetag: OscTime::IMMEDIATE,
+        content: vec![reply],
+    });
+    assert_eq!(query::parse_list_reply(&nested), None);
+}
@@ -0,0 +1,80 @@
+#![cfg(feature = "std")]
+
+extern crate rosc;
+
+use rosc::route::{split_by_address, AddressSplitter};
+use rosc::{OscMessage, OscType};
+
+fn msg(addr: &str, args: Vec<OscType>) -> OscMessage {
+    OscMessage {
+        addr: addr.to_string(),
+        args,
+    }
+}
+
+fn mixed_stream() -> Vec<OscMessage> {
+    vec![
+        msg("/fader/1", vec![OscType::Float(0.25)]),
+        msg("/mute/1", vec![OscType::Bool(true)]),
+        msg("/transport/play", vec![]),
+        msg("/fader/2", vec![OscType::Float(0.75)]),
+        // Matches the fader route, but has the wrong type
+        msg("/fader/1", vec![OscType::Int(1)]),
+        msg("/mute/1", vec![OscType::Bool(false)]),
+        msg("/unknown", vec![OscType::Int(7)]),
+    ]
+}
+
+#[test]
+fn test_split_by_address_callbacks() {
+    let mut faders = vec![];
+    let mut mutes = vec![];
+    let mut unmatched = vec![];
+    {
+        let mut splitter = AddressSplitter::new();
+        splitter
+            .route("/fader/*", |value: f32| faders.push(value))
+            .unwrap()
+            .route("/mute/1", |value: bool| mutes.push(value))
+            .unwrap()
+            .default_sink(|msg| unmatched.push(msg.addr));
+
+        split_by_address(mixed_stream(), &mut splitter);
+        assert_eq!(splitter.unmatched_count(), 2);
+        assert_eq!(splitter.rejected_count(), 1);
+    }
+
+    assert_eq!(faders, vec![0.25, 0.75]);
+    assert_eq!(mutes, vec![true, false]);
+    assert_eq!(unmatched, vec!["/transport/play", "/unknown"]);
+}
+
+#[test]
+fn test_split_by_address_channels() {
+    let mut splitter = AddressSplitter::new();
+    let faders = splitter.route_to_channel::<f32>("/fader/{1,2}").unwrap();
+    let fader_one = splitter.route_to_channel::<OscType>("/fader/1").unwrap();
+    let mutes = splitter.route_to_channel::<bool>("/mute/*").unwrap();
+
+    split_by_address(mixed_stream(), &mut splitter);
+    assert_eq!(splitter.unmatched_count(), 2);
+
+    assert_eq!(faders.try_iter().collect::<Vec<_>>(), vec![0.25, 0.75]);
+    assert_eq!(
+        fader_one.try_iter().collect::<Vec<_>>(),
+        vec![OscType::Float(0.25), OscType::Int(1)]
+    );
+    assert_eq!(mutes.try_iter().collect::<Vec<_>>(), vec![true, false]);
+}
+
+#[test]
+fn test_split_by_address_invalid() {
+    let mut splitter = AddressSplitter::new();
+    assert!(splitter.route("/fader/[b-a]", |_: f32| ()).is_err());
+
+    // Messages with an invalid address never match any route
+    let values = splitter.route_to_channel::<i32>("/*").unwrap();
+    splitter.dispatch(msg("not/an/address", vec![OscType::Int(1)]));
+    assert_eq!(splitter.unmatched_count(), 1);
+    assert!(values.try_recv().is_err());
+}
@@ -0,0 +1,60 @@
+extern crate rosc;
+
+use rosc::signature::{CoercionMode, TypeSignature};
+use rosc::OscError;
+
+fn sig(tags: &str) -> TypeSignature {
+    TypeSignature::parse(tags).unwrap()
+}
+
+#[test]
+fn test_parse_type_signature() {
+    assert_eq!(sig(",if[sb]T").as_str(), "if[sb]T");
+    assert_eq!(sig("if[sb]T"), sig(",if[sb]T"));
+    assert_eq!(sig(",").as_str(), "");
+    assert_eq!(sig("i[f]").to_string(), ",i[f]");
+
+    match TypeSignature::parse(",ix") {
+        Err(OscError::BadArg(_)) => (),
+        other => panic!("Expected BadArg, got {:?}", other),
+    }
+    for unbalanced in &[",[i", ",i]", ",][", ",[[f]"] {
+        match TypeSignature::parse(unbalanced) {
+            Err(OscError::BadMessage(_)) => (),
+            other => panic!("Expected BadMessage for {}, got {:?}", unbalanced, other),
+        }
+    }
+}
+
+#[test]
+fn test_exact_compatibility() {
+    assert!(sig(",sif").is_compatible_with(&sig(",sif"), CoercionMode::Exact));
+    assert!(sig(",").is_compatible_with(&sig(""), CoercionMode::Exact));
+    assert!(sig(",[ii]").is_compatible_with(&sig(",[ii]"), CoercionMode::Exact));
+
+    assert!(!sig(",si").is_compatible_with(&sig(",sf"), CoercionMode::Exact));
+    assert!(!sig(",T").is_compatible_with(&sig(",F"), CoercionMode::Exact));
+    assert!(!sig(",si").is_compatible_with(&sig(",sii"), CoercionMode::Exact));
+}
+
+#[test]
+fn test_relaxed_compatibility() {
+    // All numeric types are interchangeable, in both directions
+    for a in &["i", "h", "f", "d"] {
+        for b in &["i", "h", "f", "d"] {
+            assert!(sig(a).is_compatible_with(&sig(b), CoercionMode::Relaxed));
+        }
+    }
+    assert!(sig(",T").is_compatible_with(&sig(",F"), CoercionMode::Relaxed));
+    assert!(sig(",s[if]").is_compatible_with(&sig(",s[fd]"), CoercionMode::Relaxed));
+
+    // Other types still have to be equal
+    assert!(!sig(",s").is_compatible_with(&sig(",c"), CoercionMode::Relaxed));
+    assert!(!sig(",i").is_compatible_with(&sig(",s"), CoercionMode::Relaxed));
+    assert!(!sig(",i").is_compatible_with(&sig(",T"), CoercionMode::Relaxed));
+    assert!(!sig(",N").is_compatible_with(&sig(",I"), CoercionMode::Relaxed));
+    assert!(!sig(",i").is_compatible_with(&sig(",ii"), CoercionMode::Relaxed));
+    // Arrays aren't flattened
+    assert!(!sig(",[i]").is_compatible_with(&sig(",ii"), CoercionMode::Relaxed));
+    assert!(!sig(",[ii]").is_compatible_with(&sig(",[i]i"), CoercionMode::Relaxed));
+}
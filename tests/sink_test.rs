@@ -0,0 +1,354 @@
+extern crate rosc;
+
+use rosc::decoder::{self, DecodeOptions, DecodeSink};
+use rosc::encoder::{self, EncodeOptions};
+use rosc::{
+    NumericByteOrder, OscArray, OscBundle, OscColor, OscMessage, OscMidiMessage, OscPacket,
+    OscTime, OscType, OscTypeRef,
+};
+
+/// Rebuilds owned packets from the events of a sink, to compare them against `decode_udp`.
+#[derive(Default)]
+struct Rebuilder {
+    bundles: Vec<OscBundle>,
+    message: Option<OscMessage>,
+    arrays: Vec<OscArray>,
+    packets: Vec<OscPacket>,
+}
+
+impl Rebuilder {
+    fn push_arg(&mut self, arg: OscType) {
+        match self.arrays.last_mut() {
+            Some(array) => array.content.push(arg),
+            None => self.message.as_mut().unwrap().args.push(arg),
+        }
+    }
+
+    fn finish(&mut self, packet: OscPacket) {
+        match self.bundles.last_mut() {
+            Some(bundle) => bundle.content.push(packet),
+            None => self.packets.push(packet),
+        }
+    }
+}
+
+impl DecodeSink for Rebuilder {
+    fn on_message_start(&mut self, addr: &str) {
+        assert!(self.message.is_none());
+        self.message = Some(OscMessage {
+            addr: addr.to_string(),
+            args: vec![],
+        });
+    }
+
+    fn on_arg(&mut self, arg: OscTypeRef) {
+        self.push_arg(arg.into());
+    }
+
+    fn on_array_start(&mut self) {
+        self.arrays.push(OscArray { content: vec![] });
+    }
+
+    fn on_array_end(&mut self) {
+        let array = self.arrays.pop().unwrap();
+        self.push_arg(OscType::Array(array));
+    }
+
+    fn on_message_end(&mut self) {
+        assert!(self.arrays.is_empty());
+        let message = self.message.take().unwrap();
+        self.finish(OscPacket::Message(message));
+    }
+
+    fn on_bundle_start(&mut self, timetag: OscTime) {
+        self.bundles.push(OscBundle {
+            timetag,
+            content: vec![],
+        });
+    }
+
+    fn on_bundle_end(&mut self) {
+        let bundle = self.bundles.pop().unwrap();
+        self.finish(OscPacket::Bundle(bundle));
+    }
+}
+
+fn rebuild(bytes: &[u8], options: &DecodeOptions) -> Result<OscPacket, rosc::OscError> {
+    let mut rebuilder = Rebuilder::default();
+    decoder::decode_to_sink_with_options(bytes, options, &mut rebuilder)?;
+    assert!(rebuilder.bundles.is_empty());
+    assert_eq!(rebuilder.packets.len(), 1);
+    Ok(rebuilder.packets.pop().unwrap())
+}
+
+fn all_types() -> OscMessage {
+    OscMessage {
+        addr: "/all/types".to_string(),
+        args: vec![
+            OscType::Int(-4),
+            OscType::Float(0.5),
+            OscType::String("text".to_string()),
+            OscType::Blob(vec![1, 2, 3, 4, 5]),
+            OscType::Time(OscTime::from((1, 2))),
+            OscType::Long(1 << 40),
+            OscType::Double(0.25),
+            OscType::Char('x'),
+            OscType::Color(OscColor {
+                red: 1,
+                green: 2,
+                blue: 3,
+                alpha: 4,
+            }),
+            OscType::Midi(OscMidiMessage {
+                port: 1,
+                status: 0x90,
+                data1: 60,
+                data2: 127,
+            }),
+            OscType::Bool(true),
+            OscType::Bool(false),
+            OscType::Nil,
+            OscType::Inf,
+            OscType::Array(OscArray {
+                content: vec![
+                    OscType::Int(1),
+                    OscType::Array(OscArray { content: vec![] }),
+                    OscType::Array(OscArray {
+                        content: vec!["nested".into()],
+                    }),
+                ],
+            }),
+        ],
+    }
+}
+
+#[test]
+fn test_decode_to_sink_matches_decode_udp() {
+    let packets = vec![
+        OscPacket::Message(OscMessage::from("/no/args")),
+        OscPacket::Message(all_types()),
+        OscPacket::Bundle(OscBundle {
+            timetag: OscTime::from((0, 1)),
+            content: vec![],
+        }),
+        OscPacket::Bundle(OscBundle {
+            timetag: OscTime::from((3, 4)),
+            content: vec![
+                OscPacket::Message(all_types()),
+                OscPacket::Bundle(OscBundle {
+                    timetag: OscTime::from((5, 6)),
+                    content: vec![
+                        OscPacket::Message(OscMessage::from("/inner")),
+                        OscPacket::Bundle(OscBundle {
+                            timetag: OscTime::from((7, 8)),
+                            content: vec![],
+                        }),
+                    ],
+                }),
+                OscPacket::Message(OscMessage::from("/last")),
+            ],
+        }),
+    ];
+
+    for packet in packets {
+        let bytes = encoder::encode(&packet).unwrap();
+        let (remainder, decoded) = decoder::decode_udp(&bytes).unwrap();
+        assert_eq!(decoded, packet);
+
+        let mut rebuilder = Rebuilder::default();
+        assert_eq!(
+            decoder::decode_to_sink(&bytes, &mut rebuilder).unwrap(),
+            remainder
+        );
+        assert_eq!(rebuilder.packets, vec![packet]);
+    }
+}
+
+#[test]
+fn test_decode_to_sink_little_endian() {
+    let packet = OscPacket::Bundle(OscBundle {
+        timetag: OscTime::from((1, 2)),
+        content: vec![OscPacket::Message(all_types())],
+    });
+    let bytes = encoder::encode_with_options(
+        &packet,
+        &EncodeOptions {
+            numeric_byte_order: NumericByteOrder::Little,
+            ..EncodeOptions::default()
+        },
+    )
+    .unwrap();
+    let options = DecodeOptions {
+        numeric_byte_order: NumericByteOrder::Little,
+        ..DecodeOptions::default()
+    };
+
+    assert_eq!(rebuild(&bytes, &options).unwrap(), packet);
+    assert_eq!(
+        rebuild(&bytes, &DecodeOptions::default()).unwrap(),
+        decoder::decode_udp(&bytes).unwrap().1
+    );
+}
+
+#[test]
+fn test_decode_to_sink_drops_bundle_elements_like_decode_udp() {
+    let bundle = OscPacket::Bundle(OscBundle {
+        timetag: OscTime::from((0, 1)),
+        content: vec![
+            OscPacket::Message(OscMessage::from("/kept")),
+            OscPacket::Message(OscMessage {
+                addr: "/bad".to_string(),
+                args: vec![OscType::Int(1)],
+            }),
+            OscPacket::Message(OscMessage::from("/after")),
+        ],
+    });
+    let mut bytes = encoder::encode(&bundle).unwrap();
+    let tag = bytes.windows(4).position(|w| w == b",i\0\0").unwrap();
+    bytes[tag + 1] = b'q';
+
+    let decoded = decoder::decode_udp(&bytes).unwrap().1;
+    assert_eq!(
+        decoded,
+        OscPacket::Bundle(OscBundle {
+            timetag: OscTime::from((0, 1)),
+            content: vec![OscPacket::Message(OscMessage::from("/kept"))],
+        })
+    );
+    assert_eq!(rebuild(&bytes, &DecodeOptions::default()).unwrap(), decoded);
+}
+
+#[test]
+fn test_decode_to_sink_timetag_as_arg() {
+    let packet = OscPacket::Bundle(OscBundle {
+        timetag: OscTime::from((3, 4)),
+        content: vec![
+            OscPacket::Message(all_types()),
+            OscPacket::Bundle(OscBundle {
+                timetag: OscTime::from((5, 6)),
+                content: vec![OscPacket::Message(OscMessage::from("/inner"))],
+            }),
+        ],
+    });
+    let bytes = encoder::encode(&packet).unwrap();
+    let options = DecodeOptions {
+        timetag_as_arg: true,
+        ..DecodeOptions::default()
+    };
+
+    assert_eq!(
+        rebuild(&bytes, &options).unwrap(),
+        decoder::decode_udp_with_options(&bytes, &options)
+            .unwrap()
+            .1
+    );
+}
+
+/// Records that any event was received.
+#[derive(Default)]
+struct EventCount(usize);
+
+impl DecodeSink for EventCount {
+    fn on_message_start(&mut self, _addr: &str) {
+        self.0 += 1;
+    }
+    fn on_arg(&mut self, _arg: OscTypeRef) {
+        self.0 += 1;
+    }
+    fn on_array_start(&mut self) {
+        self.0 += 1;
+    }
+    fn on_array_end(&mut self) {
+        self.0 += 1;
+    }
+    fn on_message_end(&mut self) {
+        self.0 += 1;
+    }
+    fn on_bundle_start(&mut self, _timetag: OscTime) {
+        self.0 += 1;
+    }
+    fn on_bundle_end(&mut self) {
+        self.0 += 1;
+    }
+}
+
+#[test]
+fn test_decode_to_sink_errors_emit_no_events() {
+    let message = OscPacket::Message(all_types());
+    let mut bad_arg = encoder::encode(&message).unwrap();
+    // Cut off the last argument
+    bad_arg.truncate(bad_arg.len() - 4);
+
+    let mut too_deep = OscPacket::Message(OscMessage::from("/deep"));
+    for _ in 0..decoder::MAX_BUNDLE_DEPTH + 1 {
+        too_deep = OscPacket::Bundle(OscBundle {
+            timetag: OscTime::from((0, 1)),
+            content: vec![too_deep],
+        });
+    }
+    let too_deep = encoder::encode(&too_deep).unwrap();
+
+    let too_many_args = encoder::encode(&OscPacket::Message(OscMessage {
+        addr: "/many".to_string(),
+        args: vec![OscType::Nil; 3],
+    }))
+    .unwrap();
+    let limited = DecodeOptions {
+        max_args: 2,
+        ..DecodeOptions::default()
+    };
+
+    for (bytes, options) in [
+        (&bad_arg[..], DecodeOptions::default()),
+        (&too_deep[..], DecodeOptions::default()),
+        (&too_many_args[..], limited),
+        (&b""[..], DecodeOptions::default()),
+        (&b"#reply\0\0"[..], DecodeOptions::default()),
+        (&b"/a\xff\0,\0\0\0"[..], DecodeOptions::default()),
+        (&b"no/slash\0\0\0\0,\0\0\0"[..], DecodeOptions::default()),
+        (
+            &b"/a\0\0,i[i\0\0\0\0\0\0\0\0\0\0\0"[..],
+            DecodeOptions::default(),
+        ),
+        (&b"/a\0\0,]\0\0"[..], DecodeOptions::default()),
+    ] {
+        let mut events = EventCount::default();
+        assert_eq!(
+            decoder::decode_to_sink_with_options(bytes, &options, &mut events)
+                .unwrap_err()
+                .to_string(),
+            decoder::decode_udp_with_options(bytes, &options)
+                .unwrap_err()
+                .to_string()
+        );
+        assert_eq!(events.0, 0);
+    }
+}
+
+#[test]
+fn test_lenient_reader_to_sink() {
+    let packets = vec![
+        OscPacket::Message(all_types()),
+        OscPacket::Bundle(OscBundle {
+            timetag: OscTime::from((0, 1)),
+            content: vec![OscPacket::Message(OscMessage::from("/in/bundle"))],
+        }),
+    ];
+
+    let mut stream = vec![];
+    for packet in &packets {
+        let bytes = encoder::encode(packet).unwrap();
+        stream.extend((bytes.len() as u32).to_be_bytes());
+        stream.extend(bytes);
+        stream.extend_from_slice(b"\x00\x13garbage");
+    }
+
+    let mut reader = decoder::LenientReader::new(1024);
+    let mut rebuilder = Rebuilder::default();
+    for chunk in stream.chunks(5) {
+        reader.push(chunk);
+        while reader.next_packet_to_sink(&mut rebuilder) {}
+    }
+    assert_eq!(rebuilder.packets, packets);
+    assert!(reader.resyncs() > 0);
+}
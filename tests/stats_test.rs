@@ -0,0 +1,183 @@
+extern crate rosc;
+
+use rosc::stats::Stats;
+use rosc::{decoder, encoder, OscArray, OscBundle, OscMessage, OscPacket, OscType};
+
+#[test]
+fn test_stats_across_packets() {
+    let packets = vec![
+        OscPacket::Message(OscMessage::from("/a")),
+        OscPacket::Message(OscMessage {
+            addr: "/mixer/channel/1/amp".to_string(),
+            args: vec![OscType::Float(0.5), OscType::Blob(vec![0; 100])],
+        }),
+        OscPacket::Bundle(OscBundle {
+            timetag: (1, 0).into(),
+            content: vec![OscPacket::Message(OscMessage::from("/b"))],
+        }),
+    ];
+
+    let mut encode_stats = Stats::new();
+    let mut decode_stats = Stats::new();
+    assert_eq!(decode_stats.count(), 0);
+    assert_eq!(decode_stats.max_size(), None);
+    assert_eq!(decode_stats.min_size(), None);
+
+    let mut sizes = vec![];
+    for packet in &packets {
+        let bytes = encoder::encode_with_stats(packet, &mut encode_stats).unwrap();
+        decoder::decode_udp_with_stats(&bytes, &mut decode_stats).unwrap();
+        sizes.push(bytes.len());
+    }
+
+    assert_eq!(decode_stats, encode_stats);
+    assert_eq!(decode_stats.count(), 3);
+    assert_eq!(decode_stats.min_size(), sizes.iter().copied().min());
+    assert_eq!(decode_stats.max_size(), sizes.iter().copied().max());
+    assert_eq!(
+        decode_stats.total_size(),
+        sizes.iter().map(|&s| s as u64).sum::<u64>()
+    );
+
+    // Failed decodes are not recorded
+    assert!(decoder::decode_udp_with_stats(&[1, 2, 3, 4], &mut decode_stats).is_err());
+    assert_eq!(decode_stats.count(), 3);
+
+    decode_stats.reset();
+    assert_eq!(decode_stats, Stats::new());
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn test_address_stats_rates() {
+    use rosc::stats::AddressStats;
+    use std::time::{Duration, Instant};
+
+    let fast = OscMessage {
+        addr: "/mixer/fader".to_string(),
+        args: vec![
+            OscType::Float(0.5),
+            OscType::String("main".to_string()),
+            OscType::Array(OscArray {
+                content: vec![OscType::Int(1), OscType::Blob(vec![1, 2, 3])],
+            }),
+        ],
+    };
+    let slow = OscMessage::from("/transport/tick");
+    let fast_size = encoder::encode(&OscPacket::Message(fast.clone()))
+        .unwrap()
+        .len();
+
+    let mut stats = AddressStats::new(Duration::from_secs(1), 16);
+    let start = Instant::now();
+    // 100 messages per second to one address and 10 to another, for 5 seconds
+    for ms in (0..5000).step_by(10) {
+        let now = start + Duration::from_millis(ms);
+        stats.record(&fast, now);
+        if ms % 100 == 0 {
+            stats.record(&slow, now);
+        }
+    }
+
+    let end = start + Duration::from_millis(5000);
+    let snapshot = stats.snapshot(end);
+    assert_eq!(snapshot.len(), 2);
+    assert_eq!(snapshot[0].address, "/mixer/fader");
+    assert_eq!(snapshot[0].count, 500);
+    assert_eq!(snapshot[0].bytes, 500 * fast_size as u64);
+    assert_eq!(snapshot[0].last_seen, start + Duration::from_millis(4990));
+    assert!(
+        (snapshot[0].rate - 100.0).abs() < 10.0,
+        "{}",
+        snapshot[0].rate
+    );
+    assert_eq!(snapshot[1].address, "/transport/tick");
+    assert_eq!(snapshot[1].count, 50);
+    assert!(
+        (snapshot[1].rate - 10.0).abs() < 1.5,
+        "{}",
+        snapshot[1].rate
+    );
+
+    // Rates decay once the traffic stops
+    let snapshot = stats.snapshot(end + Duration::from_secs(2));
+    assert!(snapshot.iter().all(|entry| entry.rate == 0.0));
+    assert_eq!(snapshot[0].count, 500);
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn test_address_stats_eviction() {
+    use rosc::stats::AddressStats;
+    use std::time::{Duration, Instant};
+
+    let mut stats = AddressStats::new(Duration::from_secs(1), 2);
+    let start = Instant::now();
+    let at = |ms| start + Duration::from_millis(ms);
+    stats.record_size("/a", 8, at(0));
+    stats.record_size("/b", 8, at(1));
+    stats.record_size("/a", 8, at(2));
+    // "/b" is the least recently seen
+    stats.record_size("/c", 8, at(3));
+    assert_eq!(stats.len(), 2);
+
+    let mut addresses: Vec<String> = stats
+        .snapshot(at(3))
+        .into_iter()
+        .map(|entry| entry.address)
+        .collect();
+    addresses.sort();
+    assert_eq!(addresses, vec!["/a", "/c"]);
+
+    // An evicted address starts over
+    stats.record_size("/b", 8, at(4));
+    let b = stats
+        .snapshot(at(4))
+        .into_iter()
+        .find(|entry| entry.address == "/b")
+        .unwrap();
+    assert_eq!(b.count, 1);
+
+    stats.clear();
+    assert!(stats.is_empty());
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn test_prometheus_text() {
+    use rosc::stats::{self, AddressStatEntry};
+    use std::time::Instant;
+
+    let entries = vec![
+        AddressStatEntry {
+            address: "/a".to_string(),
+            count: 3,
+            bytes: 24,
+            last_seen: Instant::now(),
+            rate: 1.5,
+        },
+        AddressStatEntry {
+            address: "/quote\"back\\slash".to_string(),
+            count: 1,
+            bytes: 20,
+            last_seen: Instant::now(),
+            rate: 0.0,
+        },
+    ];
+    assert_eq!(
+        stats::prometheus_text(&entries),
+        "# HELP osc_messages_total Messages received per OSC address.\n\
+         # TYPE osc_messages_total counter\n\
+         osc_messages_total{address=\"/a\"} 3\n\
+         osc_messages_total{address=\"/quote\\\"back\\\\slash\"} 1\n\
+         # HELP osc_bytes_total Encoded size of the messages received per OSC address.\n\
+         # TYPE osc_bytes_total counter\n\
+         osc_bytes_total{address=\"/a\"} 24\n\
+         osc_bytes_total{address=\"/quote\\\"back\\\\slash\"} 20\n\
+         # HELP osc_messages_per_second Recent rate of messages received per OSC address.\n\
+         # TYPE osc_messages_per_second gauge\n\
+         osc_messages_per_second{address=\"/a\"} 1.5\n\
+         osc_messages_per_second{address=\"/quote\\\"back\\\\slash\"} 0\n"
+    );
+    assert_eq!(stats::prometheus_text(&[]).lines().count(), 6);
+}
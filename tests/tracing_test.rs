@@ -0,0 +1,119 @@
+//! Tests of the events emitted with the `tracing` feature.
+#![cfg(feature = "tracing")]
+
+extern crate rosc;
+extern crate tracing;
+
+use rosc::{decoder, encoder, OscBundle, OscMessage, OscPacket, OscTime};
+
+use std::fmt;
+use std::sync::{Arc, Mutex};
+use tracing::field::{Field, Visit};
+use tracing::span::{Attributes, Id, Record};
+use tracing::{Event, Level, Metadata, Subscriber};
+
+/// A subscriber that records the message and fields of every event as a string.
+#[derive(Clone, Default)]
+struct Recorder {
+    events: Arc<Mutex<Vec<String>>>,
+}
+
+struct EventVisitor(String);
+
+impl Visit for EventVisitor {
+    fn record_debug(&mut self, field: &Field, value: &dyn fmt::Debug) {
+        if !self.0.is_empty() {
+            self.0.push(' ');
+        }
+        if field.name() == "message" {
+            self.0.push_str(&format!("{:?}", value));
+        } else {
+            self.0.push_str(&format!("{}={:?}", field.name(), value));
+        }
+    }
+}
+
+impl Subscriber for Recorder {
+    fn enabled(&self, metadata: &Metadata) -> bool {
+        *metadata.level() == Level::TRACE
+    }
+
+    fn new_span(&self, _: &Attributes) -> Id {
+        Id::from_u64(1)
+    }
+
+    fn record(&self, _: &Id, _: &Record) {}
+
+    fn record_follows_from(&self, _: &Id, _: &Id) {}
+
+    fn event(&self, event: &Event) {
+        let mut visitor = EventVisitor(String::new());
+        event.record(&mut visitor);
+        self.events.lock().unwrap().push(visitor.0);
+    }
+
+    fn enter(&self, _: &Id) {}
+
+    fn exit(&self, _: &Id) {}
+}
+
+/// Runs `f` and returns the events it emitted.
+fn events<F: FnOnce()>(f: F) -> Vec<String> {
+    let recorder = Recorder::default();
+    tracing::subscriber::with_default(recorder.clone(), f);
+    let events = recorder.events.lock().unwrap().clone();
+    events
+}
+
+#[test]
+fn test_encode_events() {
+    let packet = OscPacket::Bundle(OscBundle {
+        timetag: OscTime::IMMEDIATE,
+        content: vec![OscPacket::Message(OscMessage::from("/ping"))],
+    });
+
+    assert_eq!(
+        events(|| {
+            encoder::encode(&packet).unwrap();
+        }),
+        vec!["encoded bundle of 32 bytes size=32"]
+    );
+    assert_eq!(
+        events(|| {
+            encoder::encode_into(&packet, &mut vec![]).unwrap();
+        }),
+        vec!["encoded bundle of 32 bytes size=32"]
+    );
+}
+
+#[test]
+fn test_decode_events() {
+    let bytes = encoder::encode(&OscPacket::Bundle(OscBundle {
+        timetag: OscTime::IMMEDIATE,
+        content: vec![
+            OscPacket::Message(OscMessage::from("/ping")),
+            OscPacket::Bundle(OscBundle {
+                timetag: OscTime::IMMEDIATE,
+                content: vec![],
+            }),
+        ],
+    }))
+    .unwrap();
+
+    assert_eq!(
+        events(|| {
+            decoder::decode_udp(&bytes).unwrap();
+        }),
+        vec![
+            "decoded bundle with 0 elements depth=2",
+            "decoded bundle with 2 elements depth=1",
+            "decoded packet size=52",
+        ]
+    );
+    assert_eq!(
+        events(|| {
+            decoder::decode_udp(b"ping").unwrap_err();
+        }),
+        vec!["failed to decode packet size=4 error=error reading from buffer: Eof"]
+    );
+}
@@ -1,6 +1,6 @@
 extern crate rosc;
 
-use rosc::{OscArray, OscType};
+use rosc::{OscArray, OscBundle, OscMessage, OscPacket, OscTime, OscType};
 
 #[test]
 fn test_osc_array_from_iter() {
@@ -14,3 +14,941 @@ fn test_osc_array_from_iter() {
         }
     );
 }
+
+#[test]
+fn test_osc_time_is_reasonable() {
+    use std::time::Duration;
+
+    let now = OscTime::from((3_900_000_000, 1 << 31));
+    let window = Duration::from_millis(1500);
+
+    // Immediate time tags are always accepted, regardless of the window
+    assert!(OscTime::IMMEDIATE.is_immediate());
+    assert!(OscTime::IMMEDIATE.is_reasonable(now, Duration::from_secs(0)));
+
+    // Near times in either direction
+    assert!(now.is_reasonable(now, Duration::from_secs(0)));
+    assert!(OscTime::from((3_900_000_001, 1 << 31)).is_reasonable(now, window));
+    assert!(OscTime::from((3_899_999_999, 1 << 31)).is_reasonable(now, window));
+    assert!(OscTime::from((3_900_000_002, 0)).is_reasonable(now, window));
+    assert!(!OscTime::from((3_900_000_002, 1)).is_reasonable(now, window));
+
+    // Far past and far future
+    assert!(!OscTime::from((3_800_000_000, 0)).is_reasonable(now, window));
+    assert!(!OscTime::from((u32::MAX, u32::MAX)).is_reasonable(now, window));
+    assert!(!OscTime::from((0, 0)).is_reasonable(now, window));
+
+    // Huge windows saturate instead of overflowing
+    assert!(OscTime::from((u32::MAX, u32::MAX)).is_reasonable(now, Duration::MAX));
+}
+
+#[test]
+fn test_osc_time_unix_secs_nanos() {
+    // The Unix epoch is 2208988800 seconds after the NTP epoch
+    assert_eq!(
+        OscTime::from_unix_secs_nanos(0, 0),
+        OscTime::from((2_208_988_800, 0))
+    );
+    assert_eq!(
+        OscTime::from((2_208_988_800, 0)).to_unix_secs_nanos(),
+        (0, 0)
+    );
+
+    // Fractions of a second
+    assert_eq!(
+        OscTime::from_unix_secs_nanos(1, 250_000_000),
+        OscTime::from((2_208_988_801, 1 << 30))
+    );
+    assert_eq!(
+        OscTime::from_unix_secs_nanos(1, 999_999_999),
+        OscTime::from((2_208_988_801, 4_294_967_292))
+    );
+    assert_eq!(
+        OscTime::from((0, 1 << 31)).to_unix_secs_nanos().1,
+        500_000_000
+    );
+    assert_eq!(
+        OscTime::from((2_208_988_800, u32::MAX)).to_unix_secs_nanos(),
+        (1, 0)
+    );
+
+    // Excess nanoseconds carry into the seconds
+    assert_eq!(
+        OscTime::from_unix_secs_nanos(1, 2_500_000_000),
+        OscTime::from_unix_secs_nanos(3, 500_000_000)
+    );
+
+    // The second NTP era starts in 2036
+    let era_1 = (1u64 << 32) - 2_208_988_800;
+    assert_eq!(
+        OscTime::from_unix_secs_nanos(era_1 + 5, 0),
+        OscTime::from((5, 0))
+    );
+    assert_eq!(OscTime::from((5, 0)).to_unix_secs_nanos(), (era_1 + 5, 0));
+
+    for &(secs, nanos) in &[
+        (0, 1),
+        (1_700_000_000, 123_456_789),
+        (4_000_000_000, 999_999_999),
+    ] {
+        let time = OscTime::from_unix_secs_nanos(secs, nanos);
+        assert_eq!(time.to_unix_secs_nanos(), (secs, nanos));
+
+        // Agrees with the conversion from `SystemTime`
+        #[cfg(feature = "std")]
+        {
+            use std::convert::TryFrom;
+            use std::time::{Duration, UNIX_EPOCH};
+
+            let system_time = UNIX_EPOCH + Duration::new(secs, nanos);
+            if let Ok(expected) = OscTime::try_from(system_time) {
+                assert_eq!(time, expected);
+            }
+        }
+    }
+}
+
+#[test]
+#[cfg(feature = "std")]
+fn test_osc_time_is_reasonable_now() {
+    use std::convert::TryFrom;
+    use std::time::{Duration, SystemTime};
+
+    let now = OscTime::try_from(SystemTime::now()).unwrap();
+    assert!(now.is_reasonable_now(Duration::from_secs(60)));
+    assert!(!OscTime::from((now.seconds - 3600, 0)).is_reasonable_now(Duration::from_secs(60)));
+}
+
+#[test]
+fn test_schedule_single_message() {
+    let time = OscTime::from((3_900_000_000, 42));
+    let msg = OscMessage {
+        addr: "/synth/freq".to_string(),
+        args: vec![OscType::Float(440.0)],
+    };
+
+    let packet = msg.clone().at(time);
+    assert_eq!(
+        packet,
+        OscPacket::Bundle(OscBundle {
+            timetag: time,
+            content: vec![OscPacket::Message(msg.clone())],
+        })
+    );
+    assert_eq!(packet.unwrap_single(), Some((&msg, time)));
+
+    // Plain messages and bundles with several elements are not unwrapped
+    assert_eq!(OscPacket::Message(msg.clone()).unwrap_single(), None);
+    let packet = OscPacket::Bundle(OscBundle {
+        timetag: time,
+        content: vec![
+            OscPacket::Message(msg.clone()),
+            OscPacket::Message(msg.clone()),
+        ],
+    });
+    assert_eq!(packet.unwrap_single(), None);
+    let packet = OscPacket::Bundle(OscBundle {
+        timetag: time,
+        content: vec![],
+    });
+    assert_eq!(packet.unwrap_single(), None);
+}
+
+#[test]
+fn test_unwrap_single_only_unwraps_one_level() {
+    let outer = OscTime::from((1, 0));
+    let inner = OscTime::from((2, 0));
+    let msg = OscMessage::from("/nested");
+
+    let packet = OscPacket::scheduled(msg.clone().at(inner), outer);
+    assert_eq!(packet.unwrap_single(), None);
+
+    match packet {
+        OscPacket::Bundle(bundle) => {
+            assert_eq!(bundle.timetag, outer);
+            assert_eq!(bundle.content[0].unwrap_single(), Some((&msg, inner)));
+        }
+        _ => panic!("Expected an OscBundle!"),
+    }
+}
+
+#[test]
+fn test_drain_ready() {
+    let msg = |addr: &str| OscPacket::Message(OscMessage::from(addr));
+    let nested = |time: (u32, u32), addr: &str| {
+        OscPacket::Bundle(OscBundle {
+            timetag: OscTime::from(time),
+            content: vec![msg(addr)],
+        })
+    };
+
+    let mut bundle = OscBundle {
+        timetag: OscTime::from((100, 0)),
+        content: vec![
+            nested((300, 0), "/future/1"),
+            msg("/message/1"),
+            nested((50, 0), "/past"),
+            nested((200, 0), "/now"),
+            nested((200, 1), "/future/2"),
+            msg("/message/2"),
+        ],
+    };
+
+    let now = OscTime::from((200, 0));
+    assert_eq!(
+        bundle.drain_ready(now),
+        vec![
+            msg("/message/1"),
+            nested((50, 0), "/past"),
+            nested((200, 0), "/now"),
+            msg("/message/2"),
+        ]
+    );
+    assert_eq!(
+        bundle.content,
+        vec![nested((300, 0), "/future/1"), nested((200, 1), "/future/2")]
+    );
+
+    // Nothing else is due yet
+    assert!(bundle.drain_ready(now).is_empty());
+    assert_eq!(bundle.content.len(), 2);
+
+    assert_eq!(bundle.drain_ready(OscTime::from((300, 0))).len(), 2);
+    assert!(bundle.content.is_empty());
+}
+
+#[test]
+fn test_drain_ready_future_bundle() {
+    // Messages in a bundle scheduled in the future are not due
+    let mut bundle = OscBundle {
+        timetag: OscTime::from((100, 0)),
+        content: vec![OscPacket::Message(OscMessage::from("/message"))],
+    };
+    assert!(bundle.drain_ready(OscTime::from((99, 0))).is_empty());
+    assert_eq!(bundle.content.len(), 1);
+    assert_eq!(bundle.drain_ready(OscTime::from((100, 0))).len(), 1);
+}
+
+#[test]
+fn test_structural_hash() {
+    let msg = |addr: &str, args: Vec<OscType>| {
+        OscPacket::Message(OscMessage {
+            addr: addr.to_string(),
+            args,
+        })
+    };
+    let hash = |packet: &OscPacket| packet.structural_hash();
+
+    // Same shape with different values share a fingerprint
+    let a = msg("/synth/1", vec![OscType::Float(0.1), OscType::Int(1)]);
+    let b = msg("/synth/1", vec![OscType::Float(0.9), OscType::Int(-5)]);
+    assert_eq!(hash(&a), hash(&b));
+    assert_eq!(
+        hash(&msg("/s", vec![OscType::String("a".into())])),
+        hash(&msg("/s", vec![OscType::String("long string".into())]))
+    );
+
+    // Different addresses, types or argument counts don't
+    assert_ne!(
+        hash(&a),
+        hash(&msg("/synth/2", vec![OscType::Float(0.1), OscType::Int(1)]))
+    );
+    assert_ne!(
+        hash(&a),
+        hash(&msg(
+            "/synth/1",
+            vec![OscType::Double(0.1), OscType::Int(1)]
+        ))
+    );
+    assert_ne!(hash(&a), hash(&msg("/synth/1", vec![OscType::Float(0.1)])));
+    assert_ne!(
+        hash(&msg("/a", vec![OscType::Bool(true)])),
+        hash(&msg("/a", vec![OscType::Bool(false)]))
+    );
+    assert_ne!(
+        hash(&msg(
+            "/a",
+            vec![
+                OscType::Array(OscArray { content: vec![] }),
+                OscType::Int(1)
+            ]
+        )),
+        hash(&msg(
+            "/a",
+            vec![OscType::Array(OscArray {
+                content: vec![OscType::Int(1)]
+            })]
+        ))
+    );
+
+    // Bundles ignore time tags, but not their structure
+    let bundle = |time: (u32, u32), content: Vec<OscPacket>| {
+        OscPacket::Bundle(OscBundle {
+            timetag: OscTime::from(time),
+            content,
+        })
+    };
+    assert_eq!(
+        hash(&bundle((1, 0), vec![a.clone(), b.clone()])),
+        hash(&bundle((2, 0), vec![b.clone(), a.clone()]))
+    );
+    assert_ne!(hash(&bundle((1, 0), vec![a.clone()])), hash(&a));
+    assert_ne!(
+        hash(&bundle((1, 0), vec![a.clone(), a.clone()])),
+        hash(&bundle(
+            (1, 0),
+            vec![bundle((1, 0), vec![a.clone()]), a.clone()]
+        ))
+    );
+
+    // The fingerprint is stable
+    assert_eq!(hash(&msg("/", vec![])), 0x6e14_799e_8674_0926);
+}
+
+#[test]
+fn test_float32() {
+    let smallest_subnormal = f64::from(f32::from_bits(1));
+
+    // Values that fit into an f32 exactly
+    for &value in &[
+        0.0,
+        -0.0,
+        1.0,
+        -2.25,
+        0.5,
+        16_777_216.0,
+        f64::from(f32::MAX),
+        smallest_subnormal,
+    ] {
+        match OscType::float32(value) {
+            Ok(OscType::Float(float)) => assert_eq!(f64::from(float), value),
+            other => panic!("Expected a Float for {}, got {:?}", value, other),
+        }
+    }
+    assert_eq!(
+        OscType::float32(f64::INFINITY),
+        Ok(OscType::Float(f32::INFINITY))
+    );
+    match OscType::float32(f64::NAN) {
+        Ok(OscType::Float(float)) => assert!(float.is_nan()),
+        other => panic!("Expected a NaN Float, got {:?}", other),
+    }
+
+    // Values that don't
+    for &value in &[0.1, 16_777_217.0, 1e39, smallest_subnormal / 2.0, f64::MAX] {
+        let err = OscType::float32(value).unwrap_err();
+        assert_eq!(err.value(), value);
+        assert_eq!(OscType::float32_lossy(value), OscType::Float(err.nearest()));
+    }
+    assert_eq!(OscType::float32_lossy(0.1), OscType::Float(0.1));
+    assert_eq!(OscType::float32_lossy(1e39), OscType::Float(f32::INFINITY));
+
+    // Plain conversions from f64 still produce a Double
+    assert_eq!(OscType::from(0.1), OscType::Double(0.1));
+}
+
+#[test]
+fn test_osc_array_canonical_order() {
+    let nested = |content: Vec<OscType>| OscType::Array(OscArray { content });
+    let mut array = OscArray {
+        content: vec![
+            OscType::String("b".to_string()),
+            OscType::Float(2.5),
+            nested(vec![OscType::Int(1), OscType::Int(2)]),
+            OscType::Int(10),
+            OscType::Float(-0.0),
+            OscType::Nil,
+            nested(vec![OscType::Int(1)]),
+            OscType::String("a".to_string()),
+            OscType::Int(-3),
+            OscType::Float(0.0),
+            nested(vec![OscType::Int(1), OscType::Float(0.0)]),
+            OscType::Int(10),
+            OscType::Float(f32::NAN),
+            nested(vec![OscType::Int(1), OscType::Int(2)]),
+            OscType::Bool(true),
+            OscType::Bool(false),
+            OscType::Float(f32::NAN),
+        ],
+    };
+
+    array.sort_unstable_by_canonical();
+    array.dedup_canonical();
+
+    let expected = vec![
+        OscType::Int(-3),
+        OscType::Int(10),
+        OscType::Float(-0.0),
+        OscType::Float(0.0),
+        OscType::Float(2.5),
+        OscType::Float(f32::NAN),
+        OscType::String("a".to_string()),
+        OscType::String("b".to_string()),
+        OscType::Bool(false),
+        OscType::Bool(true),
+        nested(vec![OscType::Int(1)]),
+        nested(vec![OscType::Int(1), OscType::Int(2)]),
+        nested(vec![OscType::Int(1), OscType::Float(0.0)]),
+        OscType::Nil,
+    ];
+    assert_eq!(array.content.len(), expected.len());
+    for (actual, expected) in array.content.iter().zip(&expected) {
+        assert_eq!(
+            actual.canonical_cmp(expected),
+            std::cmp::Ordering::Equal,
+            "{:?} != {:?}",
+            actual,
+            expected
+        );
+    }
+
+    // Sorting is deterministic regardless of the initial order
+    let mut reversed = OscArray {
+        content: array.content.iter().rev().cloned().collect(),
+    };
+    reversed.sort_unstable_by_canonical();
+    assert_eq!(format!("{:?}", reversed), format!("{:?}", array));
+
+    // Containment is bitwise
+    assert!(array.contains(&OscType::Float(f32::NAN)));
+    assert!(array.contains(&OscType::Float(-0.0)));
+    assert!(!array.contains(&OscType::Double(0.0)));
+    assert!(array.contains(&nested(vec![OscType::Int(1), OscType::Int(2)])));
+    assert!(!array.contains(&nested(vec![OscType::Int(2)])));
+}
+
+#[test]
+fn test_nullable_accessors() {
+    assert!(OscType::Nil.is_nil());
+    assert!(!OscType::Int(0).is_nil());
+    assert!(!OscType::Inf.is_nil());
+
+    assert_eq!(OscType::Int(5).nullable_int(), Some(Some(5)));
+    assert_eq!(OscType::Nil.nullable_int(), Some(None));
+    assert_eq!(OscType::Float(5.0).nullable_int(), None);
+    assert_eq!(OscType::Inf.nullable_int(), None);
+
+    // The plain accessors can't tell Nil from a type mismatch
+    assert_eq!(OscType::Nil.int(), None);
+    assert_eq!(OscType::Float(5.0).int(), None);
+
+    assert_eq!(
+        OscType::String("x".to_string()).nullable_string(),
+        Some(Some("x".to_string()))
+    );
+    assert_eq!(OscType::Nil.nullable_string(), Some(None));
+    assert_eq!(OscType::Nil.nullable_time(), Some(None));
+    assert_eq!(OscType::Bool(false).nullable_bool(), Some(Some(false)));
+    assert_eq!(OscType::Int(1).nullable_bool(), None);
+}
+
+#[test]
+fn test_flatten_uses_innermost_timetag() {
+    let future = OscTime::from((3_900_000_000, 0));
+    let farther = OscTime::from((3_900_000_100, 0));
+    let packet = OscPacket::Bundle(OscBundle {
+        timetag: OscTime::IMMEDIATE,
+        content: vec![
+            OscPacket::Message(OscMessage::from("/outer/1")),
+            OscPacket::Bundle(OscBundle {
+                timetag: future,
+                content: vec![
+                    OscPacket::Message(OscMessage::from("/inner/1")),
+                    OscMessage::from("/innermost").at(farther),
+                    OscPacket::Message(OscMessage::from("/inner/2")),
+                ],
+            }),
+            OscPacket::Bundle(OscBundle {
+                timetag: future,
+                content: vec![],
+            }),
+            OscPacket::Message(OscMessage::from("/outer/2")),
+        ],
+    });
+
+    let flattened: Vec<_> = packet
+        .flatten()
+        .map(|(time, msg)| (time, msg.addr.as_str()))
+        .collect();
+    assert_eq!(
+        flattened,
+        vec![
+            (OscTime::IMMEDIATE, "/outer/1"),
+            (future, "/inner/1"),
+            (farther, "/innermost"),
+            (future, "/inner/2"),
+            (OscTime::IMMEDIATE, "/outer/2"),
+        ]
+    );
+
+    // A message outside of any bundle is immediate
+    let msg = OscPacket::Message(OscMessage::from("/single"));
+    assert_eq!(
+        msg.flatten().map(|(time, _)| time).collect::<Vec<_>>(),
+        vec![OscTime::IMMEDIATE]
+    );
+}
+
+#[test]
+fn test_map_floats_and_ints() {
+    let mut msg = OscMessage {
+        addr: "/scale".to_string(),
+        args: vec![
+            OscType::Float(1.5),
+            OscType::Array(OscArray {
+                content: vec![
+                    OscType::Double(-2.0),
+                    OscType::Array(OscArray {
+                        content: vec![OscType::Float(0.25), OscType::Int(3)],
+                    }),
+                ],
+            }),
+            OscType::Long(i64::MAX),
+            OscType::String("1.0".to_string()),
+        ],
+    };
+
+    msg.map_floats(|value| value * 4.0);
+    msg.map_ints(|value| value.wrapping_add(1));
+    assert_eq!(
+        msg.args,
+        vec![
+            OscType::Float(6.0),
+            OscType::Array(OscArray {
+                content: vec![
+                    OscType::Double(-8.0),
+                    OscType::Array(OscArray {
+                        content: vec![OscType::Float(1.0), OscType::Int(4)],
+                    }),
+                ],
+            }),
+            OscType::Long(i64::MIN),
+            OscType::String("1.0".to_string()),
+        ]
+    );
+}
+
+#[test]
+fn test_total_arg_count() {
+    // ,i[i[ff]s]T
+    let msg = OscMessage {
+        addr: "/nested".to_string(),
+        args: vec![
+            OscType::Int(1),
+            OscType::Array(OscArray {
+                content: vec![
+                    OscType::Int(2),
+                    OscType::Array(OscArray {
+                        content: vec![OscType::Float(0.5), OscType::Float(1.5)],
+                    }),
+                    OscType::String("s".to_string()),
+                ],
+            }),
+            OscType::Bool(true),
+        ],
+    };
+    assert_eq!(msg.args.len(), 3);
+    assert_eq!(msg.total_arg_count(), 6);
+
+    let empty_array = OscMessage {
+        addr: "/empty".to_string(),
+        args: vec![OscType::Array(OscArray { content: vec![] })],
+    };
+    assert_eq!(empty_array.total_arg_count(), 0);
+    assert_eq!(OscMessage::from("/none").total_arg_count(), 0);
+}
+
+#[test]
+fn test_message_arg_helpers() {
+    let mut msg = OscMessage::from("/args");
+    assert_eq!(msg.remove_arg(0), None);
+
+    msg.push_arg(OscType::Int(1));
+    msg.push_arg(OscType::Int(3));
+    // At the start, in the middle and one past the end
+    msg.insert_arg(0, OscType::Int(0));
+    msg.insert_arg(2, OscType::Int(2));
+    msg.insert_arg(4, OscType::Int(4));
+    assert_eq!(msg.args, (0..5).map(OscType::Int).collect::<Vec<_>>());
+
+    assert_eq!(msg.remove_arg(5), None);
+    assert_eq!(msg.remove_arg(usize::MAX), None);
+    assert_eq!(msg.remove_arg(4), Some(OscType::Int(4)));
+    assert_eq!(msg.remove_arg(0), Some(OscType::Int(0)));
+    assert_eq!(msg.remove_arg(1), Some(OscType::Int(2)));
+    assert_eq!(msg.args, vec![OscType::Int(1), OscType::Int(3)]);
+}
+
+#[test]
+fn test_message_push_all() {
+    use rosc::{IntoOscArgs, OscColor};
+
+    /// A domain type encoding itself as a name followed by an RGBA color.
+    struct Light {
+        name: &'static str,
+        rgb: (u8, u8, u8),
+    }
+
+    impl IntoOscArgs for Light {
+        fn into_osc_args(self) -> Vec<OscType> {
+            vec![
+                self.name.into(),
+                OscType::Color(OscColor {
+                    red: self.rgb.0,
+                    green: self.rgb.1,
+                    blue: self.rgb.2,
+                    alpha: 255,
+                }),
+            ]
+        }
+    }
+
+    let mut msg = OscMessage::from("/lights");
+    msg.push_all(OscType::Int(2));
+    msg.push_all(Light {
+        name: "key",
+        rgb: (255, 200, 150),
+    });
+    msg.push_all(vec![OscType::Nil, OscType::Inf]);
+    msg.push_all(Vec::new());
+    assert_eq!(
+        msg.args,
+        vec![
+            OscType::Int(2),
+            "key".into(),
+            OscType::Color(OscColor {
+                red: 255,
+                green: 200,
+                blue: 150,
+                alpha: 255,
+            }),
+            OscType::Nil,
+            OscType::Inf,
+        ]
+    );
+}
+
+#[test]
+fn test_from_osc_args() {
+    use rosc::{FromOscArgs, OscError};
+
+    let msg = OscMessage {
+        addr: "/voice".to_string(),
+        args: vec![
+            OscType::Int(2),
+            OscType::String("saw".to_string()),
+            OscType::Float(0.75),
+        ],
+    };
+    let (id, shape, level): (i32, String, f32) = FromOscArgs::from_args(&msg.args).unwrap();
+    assert_eq!((id, shape.as_str(), level), (2, "saw", 0.75));
+    assert_eq!(Vec::<OscType>::from_args(&msg.args).unwrap(), msg.args);
+    let (_, _, any): (i32, String, OscType) = FromOscArgs::from_args(&msg.args).unwrap();
+    assert_eq!(any, OscType::Float(0.75));
+
+    // No coercion from a Float to an f64
+    let err = <(i32, String, f64)>::from_args(&msg.args).unwrap_err();
+    match err {
+        OscError::WrongArgType {
+            index: 2,
+            expected: "Double",
+            found: "Float",
+        } => (),
+        ref other => panic!("unexpected error {:?}", other),
+    }
+    assert_eq!(
+        err.to_string(),
+        "OSC argument 2: expected Double, found Float"
+    );
+
+    // The first mismatch is reported
+    match <(String, f32, f32)>::from_args(&msg.args) {
+        Err(OscError::WrongArgType {
+            index: 0,
+            expected: "String",
+            found: "Int",
+        }) => (),
+        other => panic!("unexpected result {:?}", other),
+    }
+
+    for args in &[&msg.args[..2], &[]] {
+        match <(i32, String, f32)>::from_args(args) {
+            Err(OscError::WrongArgCount { expected: 3, found }) => assert_eq!(found, args.len()),
+            other => panic!("unexpected result {:?}", other),
+        }
+    }
+    match <(i32, String)>::from_args(&msg.args) {
+        Err(OscError::WrongArgCount {
+            expected: 2,
+            found: 3,
+        }) => (),
+        other => panic!("unexpected result {:?}", other),
+    }
+}
+
+#[test]
+#[should_panic]
+fn test_message_insert_arg_out_of_bounds() {
+    let mut msg = OscMessage::from("/args");
+    msg.push_arg(OscType::Nil);
+    msg.insert_arg(2, OscType::Nil);
+}
+
+#[test]
+fn test_approx_eq_boundaries() {
+    use rosc::FloatTolerance;
+
+    let absolute = FloatTolerance {
+        absolute: 0.25,
+        relative: 0.0,
+    };
+    assert!(OscType::Double(1.0).approx_eq(&OscType::Double(1.25), absolute));
+    assert!(OscType::Double(1.25).approx_eq(&OscType::Double(1.0), absolute));
+    assert!(!OscType::Double(1.0).approx_eq(&OscType::Double(1.375), absolute));
+    assert!(OscType::Float(-0.125).approx_eq(&OscType::Float(0.125), absolute));
+
+    let relative = FloatTolerance {
+        absolute: 0.0,
+        relative: 0.5,
+    };
+    // The difference is relative to the larger magnitude
+    assert!(OscType::Double(100.0).approx_eq(&OscType::Double(50.0), relative));
+    assert!(!OscType::Double(100.0).approx_eq(&OscType::Double(49.0), relative));
+    assert!(OscType::Float(0.002).approx_eq(&OscType::Float(0.001), relative));
+    assert!(!OscType::Float(0.0).approx_eq(&OscType::Float(1e-30), relative));
+
+    // The default tolerance is exact
+    let exact = FloatTolerance::default();
+    assert!(OscType::Float(0.5).approx_eq(&OscType::Float(0.5), exact));
+    assert!(!OscType::Float(0.5).approx_eq(&OscType::Float(0.500_001), exact));
+    assert!(OscType::Double(f64::INFINITY).approx_eq(&OscType::Double(f64::INFINITY), exact));
+    assert!(!OscType::Double(f64::NAN).approx_eq(&OscType::Double(f64::NAN), absolute));
+
+    // Other types compare exactly, even if numeric
+    assert!(!OscType::Int(1).approx_eq(&OscType::Int(2), absolute));
+    assert!(!OscType::Float(1.0).approx_eq(&OscType::Double(1.0), absolute));
+    assert!(OscType::String("a".to_string()).approx_eq(&OscType::String("a".to_string()), exact));
+
+    let array = |content: Vec<OscType>| OscType::Array(OscArray { content });
+    assert!(
+        array(vec![OscType::Float(1.0), array(vec![OscType::Double(2.0)])]).approx_eq(
+            &array(vec![
+                OscType::Float(1.125),
+                array(vec![OscType::Double(2.25)])
+            ]),
+            absolute
+        )
+    );
+    assert!(
+        !array(vec![OscType::Float(1.0), array(vec![OscType::Double(2.0)])]).approx_eq(
+            &array(vec![OscType::Float(1.0), array(vec![OscType::Double(2.5)])]),
+            absolute
+        )
+    );
+    assert!(!array(vec![OscType::Float(1.0)]).approx_eq(
+        &array(vec![OscType::Float(1.0), OscType::Float(1.0)]),
+        absolute
+    ));
+
+    let msg = |addr: &str, args: Vec<OscType>| OscMessage {
+        addr: addr.to_string(),
+        args,
+    };
+    let left = msg("/a", vec![OscType::Int(1), OscType::Float(0.5)]);
+    assert!(left.approx_eq(
+        &msg("/a", vec![OscType::Int(1), OscType::Float(0.7)]),
+        absolute
+    ));
+    assert!(!left.approx_eq(
+        &msg("/b", vec![OscType::Int(1), OscType::Float(0.5)]),
+        absolute
+    ));
+    assert!(!left.approx_eq(&msg("/a", vec![OscType::Int(1)]), absolute));
+}
+
+#[test]
+fn test_variant_names_and_fixed_sizes() {
+    use rosc::{encoder, OscColor, OscMidiMessage, FIXED_DATA_SIZES};
+
+    let args = vec![
+        OscType::Int(0),
+        OscType::Float(0.0),
+        OscType::String("abc".to_string()),
+        OscType::Blob(vec![1]),
+        OscType::Time(OscTime::IMMEDIATE),
+        OscType::Long(0),
+        OscType::Double(0.0),
+        OscType::Char('x'),
+        OscType::Color(OscColor {
+            red: 1,
+            green: 2,
+            blue: 3,
+            alpha: 4,
+        }),
+        OscType::Midi(OscMidiMessage {
+            port: 1,
+            status: 2,
+            data1: 3,
+            data2: 4,
+        }),
+        OscType::Bool(true),
+        OscType::Array(OscArray { content: vec![] }),
+        OscType::Nil,
+        OscType::Inf,
+    ];
+    let names: Vec<&str> = args.iter().map(OscType::variant_name).collect();
+    assert_eq!(
+        names,
+        vec![
+            "Int", "Float", "String", "Blob", "Time", "Long", "Double", "Char", "Color", "Midi",
+            "Bool", "Array", "Nil", "Inf"
+        ]
+    );
+    assert_eq!(OscType::Bool(false).variant_name(), "Bool");
+
+    // The table agrees with what the encoder writes
+    let empty = encoder::encode(&OscPacket::Message(OscMessage::from("/size"))).unwrap();
+    for arg in &args {
+        let size = arg.fixed_data_size();
+        let listed = FIXED_DATA_SIZES
+            .iter()
+            .any(|(name, _)| *name == arg.variant_name());
+        assert_eq!(size.is_some(), listed);
+
+        let bytes = encoder::encode(&OscPacket::Message(OscMessage {
+            addr: "/size".to_string(),
+            args: vec![arg.clone()],
+        }))
+        .unwrap();
+        // One type tag still fits in the padding of ","
+        if let Some(size) = size {
+            assert_eq!(bytes.len() - empty.len(), size, "{:?}", arg);
+        }
+    }
+    assert_eq!(OscType::String("abc".to_string()).fixed_data_size(), None);
+}
+
+#[test]
+fn test_type_tags() {
+    use rosc::{encoder, OscColor, OscMidiMessage, TypeTag};
+
+    for tag in TypeTag::ALL.iter() {
+        assert_eq!(TypeTag::from_char(tag.to_char()), Some(*tag));
+        assert_eq!(tag.to_string(), tag.to_char().to_string());
+    }
+    assert_eq!(TypeTag::from_char('x'), None);
+    assert_eq!(TypeTag::from_char('é'), None);
+    assert!(!TypeTag::Nil.has_payload());
+    assert!(TypeTag::String.has_payload());
+
+    let args = vec![
+        OscType::Int(0),
+        OscType::Float(0.0),
+        OscType::String("abc".to_string()),
+        OscType::Blob(vec![1]),
+        OscType::Time(OscTime::IMMEDIATE),
+        OscType::Long(0),
+        OscType::Double(0.0),
+        OscType::Char('x'),
+        OscType::Color(OscColor {
+            red: 1,
+            green: 2,
+            blue: 3,
+            alpha: 4,
+        }),
+        OscType::Midi(OscMidiMessage {
+            port: 1,
+            status: 2,
+            data1: 3,
+            data2: 4,
+        }),
+        OscType::Bool(true),
+        OscType::Bool(false),
+        OscType::Array(OscArray { content: vec![] }),
+        OscType::Nil,
+        OscType::Inf,
+    ];
+    let empty = encoder::encode(&OscPacket::Message(OscMessage::from("/tag"))).unwrap();
+    for arg in &args {
+        let tag = TypeTag::of(arg);
+        let bytes = encoder::encode(&OscPacket::Message(OscMessage {
+            addr: "/tag".to_string(),
+            args: vec![arg.clone()],
+        }))
+        .unwrap();
+        // The type tag follows the padded address and the ","
+        assert_eq!(bytes[9] as char, tag.to_char(), "{:?}", arg);
+        if let Some(size) = tag.fixed_payload_size() {
+            // One type tag still fits in the padding of ","
+            assert_eq!(bytes.len() - empty.len(), size, "{:?}", arg);
+        }
+    }
+}
+
+#[test]
+fn test_bundle_with_earliest_timetag() {
+    let at = |secs: u32| OscTime::from((secs, 0));
+    let bundle = |timetag: OscTime, content: Vec<OscPacket>| {
+        OscPacket::Bundle(OscBundle { timetag, content })
+    };
+    let msg = |addr: &str| OscPacket::Message(OscMessage::from(addr));
+
+    let content = vec![
+        msg("/direct"),
+        bundle(at(300), vec![msg("/c")]),
+        bundle(at(200), vec![msg("/b"), bundle(at(250), vec![msg("/b2")])]),
+        // Nested deeper than its parent, against the specification
+        bundle(at(400), vec![bundle(at(150), vec![msg("/a")])]),
+    ];
+    let earliest = OscBundle::with_earliest_timetag(content.clone());
+    assert_eq!(earliest.timetag, at(150));
+    assert_eq!(earliest.content, content);
+
+    // Immediate bundles are the earliest
+    let content = vec![
+        bundle(at(100), vec![msg("/later")]),
+        bundle(OscTime::IMMEDIATE, vec![msg("/now")]),
+    ];
+    assert!(OscBundle::with_earliest_timetag(content)
+        .timetag
+        .is_immediate());
+    let all_immediate = vec![
+        bundle(OscTime::IMMEDIATE, vec![msg("/a")]),
+        bundle(OscTime::IMMEDIATE, vec![]),
+    ];
+    assert!(OscBundle::with_earliest_timetag(all_immediate)
+        .timetag
+        .is_immediate());
+
+    // Without nested bundles
+    assert!(OscBundle::with_earliest_timetag(vec![msg("/a")])
+        .timetag
+        .is_immediate());
+    assert!(OscBundle::with_earliest_timetag(vec![])
+        .timetag
+        .is_immediate());
+}
+
+#[cfg(feature = "base64")]
+#[test]
+fn test_blob_base64_round_trip() {
+    let data: Vec<u8> = vec![0, 0, 0x7f, 0x80, 0xfe, 0xff, 0, b'/', b'+'];
+    let blob = OscType::Blob(data.clone());
+    let encoded = blob.blob_to_base64().unwrap();
+    assert_eq!(encoded, "AAB/gP7/AC8r");
+    assert_eq!(OscType::blob_from_base64(&encoded).unwrap(), blob);
+
+    let all: Vec<u8> = (0..=255).collect();
+    let encoded = OscType::Blob(all.clone()).blob_to_base64().unwrap();
+    assert_eq!(
+        OscType::blob_from_base64(&encoded).unwrap(),
+        OscType::Blob(all)
+    );
+    assert_eq!(OscType::Blob(vec![]).blob_to_base64(), Some(String::new()));
+
+    assert_eq!(OscType::Int(1).blob_to_base64(), None);
+    match OscType::blob_from_base64("not base64!") {
+        Err(rosc::OscError::BadArg(_)) => (),
+        other => panic!("Expected BadArg, got {:?}", other),
+    }
+}